@@ -0,0 +1,53 @@
+use std::path::{Path, PathBuf};
+use std::{env, fs};
+
+const NATIVE_COMPILER_PACKAGE: &str = "cairo-native";
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    // Only native execution depends on the native compiler.
+    if env::var_os("CARGO_FEATURE_NATIVE").is_none() {
+        return;
+    }
+
+    let lock_file_path = find_lock_file().expect("Cargo.lock not found.");
+    println!("cargo:rerun-if-changed={}", lock_file_path.display());
+    let lock_file = fs::read_to_string(&lock_file_path).expect("Failed to read Cargo.lock.");
+    println!("cargo:rustc-env=NATIVE_COMPILER_VERSION={}", native_compiler_version(&lock_file));
+}
+
+/// Returns the lock file of the enclosing workspace.
+fn find_lock_file() -> Option<PathBuf> {
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").expect("CARGO_MANIFEST_DIR is not set.");
+    Path::new(&manifest_dir)
+        .ancestors()
+        .map(|dir| dir.join("Cargo.lock"))
+        .find(|lock_file_path| lock_file_path.exists())
+}
+
+/// Identifies the locked native compiler by its version and, for git dependencies, its commit.
+/// Path dependencies cannot be identified; their artifacts must be cleared manually when the
+/// compiler changes.
+fn native_compiler_version(lock_file: &str) -> String {
+    let package = lock_file
+        .split("[[package]]")
+        .find(|package| package_field(package, "name").as_deref() == Some(NATIVE_COMPILER_PACKAGE))
+        .unwrap_or_else(|| panic!("{NATIVE_COMPILER_PACKAGE} is not locked."));
+    let version = package_field(package, "version").expect("Locked package has no version.");
+
+    match package_field(package, "source") {
+        Some(source) if source.starts_with("git+") => {
+            let (_, commit) = source.rsplit_once('#').expect("Locked git source has no commit.");
+            format!("{NATIVE_COMPILER_PACKAGE}-{version}-{commit}")
+        }
+        Some(_) => format!("{NATIVE_COMPILER_PACKAGE}-{version}"),
+        None => format!("{NATIVE_COMPILER_PACKAGE}-{version}-local"),
+    }
+}
+
+fn package_field(package: &str, field: &str) -> Option<String> {
+    package.lines().find_map(|line| {
+        let value = line.strip_prefix(field)?.trim_start().strip_prefix('=')?.trim();
+        Some(value.trim_matches('"').to_string())
+    })
+}
@@ -0,0 +1,33 @@
+//! A worker process that executes classes compiled to native code on behalf of the sequencer, so
+//! that a crash of native code does not take the sequencer down with it.
+//! Spawned by [`blockifier::execution::native::worker::NativeWorker`], which talks to it over a
+//! dedicated channel.
+
+use std::io::{BufReader, BufWriter};
+use std::os::fd::FromRawFd;
+use std::os::unix::net::UnixStream;
+
+use blockifier::execution::errors::NativeWorkerError;
+use blockifier::execution::native::worker::run_worker;
+use blockifier::execution::native::worker_protocol::WORKER_CHANNEL_FD;
+use blockifier::execution::native::worker_sandbox::restrict_syscalls;
+
+fn main() {
+    // Before anything else; the rest of the sandbox is set up by the sequencer process.
+    if let Err(error) = restrict_syscalls() {
+        eprintln!("Native worker failed to sandbox itself: {error}");
+        std::process::exit(1);
+    }
+
+    // Safety: the sequencer process passes the channel at this descriptor, and nothing else owns
+    // it.
+    let channel = unsafe { UnixStream::from_raw_fd(WORKER_CHANNEL_FD) };
+    let result = channel
+        .try_clone()
+        .map_err(NativeWorkerError::from)
+        .and_then(|reader| run_worker(BufReader::new(reader), BufWriter::new(channel)));
+    if let Err(error) = result {
+        eprintln!("Native worker failed: {error}");
+        std::process::exit(1);
+    }
+}
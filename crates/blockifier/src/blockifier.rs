@@ -1,5 +1,9 @@
 pub mod block;
+pub mod call;
 pub mod config;
+pub mod fee_estimation;
+pub mod observer;
+pub mod simulation;
 pub mod stateful_validator;
 pub mod transaction_executor;
 #[cfg(test)]
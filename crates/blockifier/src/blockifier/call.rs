@@ -0,0 +1,145 @@
+use std::sync::Arc;
+
+use cairo_vm::vm::runners::cairo_runner::{ExecutionResources, RunResources};
+use starknet_api::core::{
+    ClassHash, CompiledClassHash, ContractAddress, EntryPointSelector, Nonce,
+};
+use starknet_api::deprecated_contract_class::EntryPointType;
+use starknet_api::state::StorageKey;
+use starknet_api::transaction::{Calldata, EventContent};
+use starknet_types_core::felt::Felt;
+
+use crate::context::{BlockContext, TransactionContext};
+use crate::execution::call_info::{CallInfo, Retdata};
+use crate::execution::contract_class::ContractClass;
+use crate::execution::entry_point::{
+    CallEntryPoint, CallType, EntryPointExecutionContext, EntryPointExecutionResult,
+};
+use crate::state::cached_state::{CachedState, StorageEntry};
+use crate::state::state_api::{StateReader, StateResult};
+use crate::transaction::objects::{DeprecatedTransactionInfo, TransactionInfo};
+
+#[cfg(test)]
+#[path = "call_test.rs"]
+mod test;
+
+/// Execution limits of a call; unset limits default to those of an invoke transaction.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CallLimits {
+    pub initial_gas: Option<u64>,
+    pub max_n_steps: Option<usize>,
+}
+
+/// An event emitted during a call, by the given contract.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CallEvent {
+    pub from_address: ContractAddress,
+    pub content: EventContent,
+}
+
+#[derive(Debug)]
+pub struct CallOutput {
+    pub retdata: Retdata,
+    /// The events emitted by the call and its inner calls, in emission order.
+    pub events: Vec<CallEvent>,
+    pub call_info: CallInfo,
+}
+
+/// Calls the given external entry point, outside of any transaction (e.g., to run a view
+/// function), on top of the given state.
+/// The state is only read; writes of the call are discarded once it returns.
+/// Note that a failed Cairo 1 call (i.e., one that panicked) is returned as such, rather than as an
+/// error.
+pub fn call<S: StateReader + ?Sized>(
+    state_reader: &S,
+    contract_address: ContractAddress,
+    entry_point_selector: EntryPointSelector,
+    calldata: Calldata,
+    block_context: &BlockContext,
+    limits: CallLimits,
+) -> EntryPointExecutionResult<CallOutput> {
+    let tx_context = Arc::new(TransactionContext {
+        block_context: block_context.clone(),
+        tx_info: TransactionInfo::Deprecated(DeprecatedTransactionInfo::default()),
+    });
+    let limit_steps_by_resources = false;
+    let mut context = EntryPointExecutionContext::new_invoke(tx_context, limit_steps_by_resources)
+        .expect("Step limits do not depend on resources.");
+    if let Some(max_n_steps) = limits.max_n_steps {
+        context.vm_run_resources = RunResources::new(max_n_steps);
+    }
+
+    let call = CallEntryPoint {
+        class_hash: None,
+        code_address: Some(contract_address),
+        entry_point_type: EntryPointType::External,
+        entry_point_selector,
+        calldata,
+        storage_address: contract_address,
+        caller_address: ContractAddress::default(),
+        call_type: CallType::Call,
+        initial_gas: limits
+            .initial_gas
+            .unwrap_or_else(|| block_context.versioned_constants.tx_initial_gas()),
+    };
+    let mut state = CachedState::new(ReadOnlyState(state_reader));
+    let call_info = call.execute(&mut state, &mut ExecutionResources::default(), &mut context)?;
+
+    let mut events: Vec<(usize, CallEvent)> = call_info
+        .iter()
+        .flat_map(|inner_call_info| {
+            inner_call_info.execution.events.iter().map(|ordered_event| {
+                let event = CallEvent {
+                    from_address: inner_call_info.call.storage_address,
+                    content: ordered_event.event.clone(),
+                };
+                (ordered_event.order, event)
+            })
+        })
+        .collect();
+    events.sort_by_key(|(order, _)| *order);
+
+    Ok(CallOutput {
+        retdata: call_info.execution.retdata.clone(),
+        events: events.into_iter().map(|(_, event)| event).collect(),
+        call_info,
+    })
+}
+
+// Exposes a shared reference to a state reader as a state reader, so that it can be wrapped by a
+// `CachedState` without being written to.
+struct ReadOnlyState<'a, S: StateReader + ?Sized>(&'a S);
+
+impl<S: StateReader + ?Sized> StateReader for ReadOnlyState<'_, S> {
+    fn get_storage_at(
+        &self,
+        contract_address: ContractAddress,
+        key: StorageKey,
+    ) -> StateResult<Felt> {
+        self.0.get_storage_at(contract_address, key)
+    }
+
+    fn get_nonce_at(&self, contract_address: ContractAddress) -> StateResult<Nonce> {
+        self.0.get_nonce_at(contract_address)
+    }
+
+    fn get_storage_values(&self, storage_entries: &[StorageEntry]) -> StateResult<Vec<Felt>> {
+        self.0.get_storage_values(storage_entries)
+    }
+
+    fn get_nonces(&self, contract_addresses: &[ContractAddress]) -> StateResult<Vec<Nonce>> {
+        self.0.get_nonces(contract_addresses)
+    }
+
+    fn get_class_hash_at(&self, contract_address: ContractAddress) -> StateResult<ClassHash> {
+        self.0.get_class_hash_at(contract_address)
+    }
+
+    fn get_compiled_contract_class(&self, class_hash: ClassHash) -> StateResult<ContractClass> {
+        self.0.get_compiled_contract_class(class_hash)
+    }
+
+    fn get_compiled_class_hash(&self, class_hash: ClassHash) -> StateResult<CompiledClassHash> {
+        self.0.get_compiled_class_hash(class_hash)
+    }
+}
@@ -0,0 +1,94 @@
+use pretty_assertions::assert_eq;
+use starknet_api::transaction::{Calldata, EventContent, EventData, EventKey};
+use starknet_api::{calldata, felt};
+
+use crate::abi::abi_utils::selector_from_name;
+use crate::blockifier::call::{call, CallEvent, CallLimits};
+use crate::context::BlockContext;
+use crate::execution::call_info::Retdata;
+use crate::state::state_api::StateReader;
+use crate::test_utils::contracts::FeatureContract;
+use crate::test_utils::initial_test_state::test_state;
+use crate::test_utils::{CairoVersion, BALANCE};
+use crate::{retdata, storage_key};
+
+#[test]
+fn test_call_does_not_write() {
+    let block_context = BlockContext::create_for_testing();
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo1);
+    let state = test_state(&block_context.chain_info, BALANCE, &[(test_contract, 1)]);
+    let contract_address = test_contract.get_instance_address(0);
+
+    let (key, value) = (felt!(0x10_u8), felt!(0x20_u8));
+    let output = call(
+        &state,
+        contract_address,
+        selector_from_name("test_storage_read_write"),
+        calldata![key, value],
+        &block_context,
+        CallLimits::default(),
+    )
+    .unwrap();
+
+    // The call reads its own write, which is then discarded.
+    assert_eq!(output.retdata, retdata![value]);
+    assert_eq!(output.call_info.execution.retdata, output.retdata);
+    assert_eq!(state.get_storage_at(contract_address, storage_key!(0x10_u8)).unwrap(), felt!(0_u8));
+}
+
+#[test]
+fn test_call_events() {
+    let block_context = BlockContext::create_for_testing();
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo1);
+    let state = test_state(&block_context.chain_info, BALANCE, &[(test_contract, 1)]);
+    let contract_address = test_contract.get_instance_address(0);
+
+    // Emit two events from an inner call.
+    let (n_events, key, data) = (felt!(2_u8), felt!(0x30_u8), felt!(0x40_u8));
+    let inner_calldata = [n_events, felt!(1_u8), key, felt!(1_u8), data];
+    let calldata = Calldata(
+        [
+            vec![
+                *contract_address.0.key(),
+                selector_from_name("test_emit_events").0,
+                inner_calldata.len().into(),
+            ],
+            inner_calldata.to_vec(),
+        ]
+        .concat()
+        .into(),
+    );
+    let output = call(
+        &state,
+        contract_address,
+        selector_from_name("test_call_contract"),
+        calldata,
+        &block_context,
+        CallLimits::default(),
+    )
+    .unwrap();
+
+    let expected_event = CallEvent {
+        from_address: contract_address,
+        content: EventContent { keys: vec![EventKey(key)], data: EventData(vec![data]) },
+    };
+    assert_eq!(output.events, vec![expected_event.clone(), expected_event]);
+    assert_eq!(output.call_info.inner_calls[0].execution.events.len(), 2);
+}
+
+#[test]
+fn test_call_step_limit() {
+    let block_context = BlockContext::create_for_testing();
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo1);
+    let state = test_state(&block_context.chain_info, BALANCE, &[(test_contract, 1)]);
+
+    let result = call(
+        &state,
+        test_contract.get_instance_address(0),
+        selector_from_name("test_storage_read_write"),
+        calldata![felt!(0x10_u8), felt!(0x20_u8)],
+        &block_context,
+        CallLimits { max_n_steps: Some(10), ..Default::default() },
+    );
+    assert!(result.is_err());
+}
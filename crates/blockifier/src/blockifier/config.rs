@@ -1,11 +1,30 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+#[cfg(feature = "native")]
+use crate::execution::native::worker::NativeWorker;
+
 #[derive(Debug, Default, Clone)]
 pub struct TransactionExecutorConfig {
     pub concurrency_config: ConcurrencyConfig,
+    pub native_compilation_config: NativeCompilationConfig,
+    pub differential_execution_config: DifferentialExecutionConfig,
+    pub native_execution_config: NativeExecutionConfig,
+    // If set, native entry points are executed in isolated worker processes.
+    #[cfg(feature = "native")]
+    pub native_worker: Option<NativeWorker>,
 }
 impl TransactionExecutorConfig {
     #[cfg(any(test, feature = "testing"))]
     pub fn create_for_testing() -> Self {
-        Self { concurrency_config: ConcurrencyConfig::create_for_testing() }
+        Self {
+            concurrency_config: ConcurrencyConfig::create_for_testing(),
+            native_compilation_config: NativeCompilationConfig::default(),
+            differential_execution_config: DifferentialExecutionConfig::default(),
+            native_execution_config: NativeExecutionConfig::default(),
+            #[cfg(feature = "native")]
+            native_worker: None,
+        }
     }
 }
 
@@ -28,3 +47,88 @@ impl ConcurrencyConfig {
         Self { enabled: true, n_workers: 4, chunk_size: 64 }
     }
 }
+
+/// Controls the compilation of Cairo 1 classes, read from storage, into native code.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct NativeCompilationConfig {
+    pub enabled: bool,
+    // LLVM optimization level, between 0 (none) and 3 (aggressive).
+    pub opt_level: u8,
+    // If set, compiled classes are persisted to (and loaded from) disk.
+    pub artifact_cache_config: Option<NativeArtifactCacheConfig>,
+    // If set, classes are compiled in the background, while their Casm representation is served.
+    pub background_compilation_config: Option<BackgroundCompilationConfig>,
+}
+
+impl Default for NativeCompilationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            opt_level: 2,
+            artifact_cache_config: None,
+            background_compilation_config: None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct NativeArtifactCacheConfig {
+    pub path: PathBuf,
+    // Maximal total size of the stored artifacts, in bytes.
+    pub max_size: u64,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BackgroundCompilationConfig {
+    pub n_workers: usize,
+    // Maximal number of classes awaiting compilation; further requests are rejected.
+    pub queue_size: usize,
+}
+
+/// Controls the differential execution mode, in which every entry point of a class compiled to
+/// native code is also executed on the Cairo VM, and the two executions are compared.
+/// The canonical (native) result is never affected; divergences are reported to a
+/// `DivergenceReporter`. Has no effect unless the `native` feature is enabled.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct DifferentialExecutionConfig {
+    pub enabled: bool,
+}
+
+/// Configures the worker processes that execute classes compiled to native code, when native
+/// execution is isolated from the sequencer process.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct NativeWorkerConfig {
+    // The path of the `native_executor_worker` binary.
+    pub executable_path: PathBuf,
+    // LLVM optimization level of the classes executed by the workers, between 0 and 3.
+    pub opt_level: u8,
+    // Classes are compiled into this cache by the sequencer process, once, and loaded from it by
+    // the workers.
+    pub artifact_cache_config: NativeArtifactCacheConfig,
+    // Maximal time to wait for a message from a worker (e.g., while the native code runs between
+    // syscalls). An unresponsive worker is killed, failing the call, and is replaced on the next
+    // execution.
+    pub response_timeout: Duration,
+    pub sandbox_config: NativeWorkerSandboxConfig,
+}
+
+/// Further restricts the worker processes. Regardless of it, workers never gain privileges, do not
+/// dump core, and run under a syscall filter that denies, e.g., spawning processes, networking,
+/// signaling other processes and modifying the file system.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct NativeWorkerSandboxConfig {
+    // If set, bounds the address space of each worker, in bytes.
+    pub max_memory: Option<u64>,
+    // If set, workers run as the given user and group IDs, rather than as the sequencer (which
+    // must be privileged enough to switch to them). The artifact cache must be readable by them.
+    pub user: Option<(u32, u32)>,
+}
+
+/// Limits applied to the execution of classes compiled to native code, on top of the gas and
+/// recursion depth limits shared with the Cairo VM.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct NativeExecutionConfig {
+    // If set, bounds the wall-clock time of each transaction execution phase (validation,
+    // execution, etc.); checked whenever a native call invokes a syscall or returns.
+    pub max_execution_time: Option<Duration>,
+}
@@ -0,0 +1,230 @@
+use std::cmp::min;
+
+use num_traits::ToPrimitive;
+use starknet_api::transaction::{Fee, Resource, ResourceBounds, ResourceBoundsMapping};
+
+use crate::context::{BlockContext, TransactionContext};
+use crate::fee::fee_utils::{balance_to_big_uint, get_fee_by_gas_vector};
+use crate::fee::gas_usage::compute_discounted_gas_from_gas_vector;
+use crate::state::cached_state::TransactionalState;
+use crate::state::state_api::{StateReader, UpdatableState};
+use crate::transaction::account_transaction::AccountTransaction;
+use crate::transaction::objects::{
+    FeeBounds, FeeType, GasVector, HasRelatedFeeType, TransactionExecutionInfo,
+    TransactionExecutionResult, TransactionInfo,
+};
+use crate::transaction::transactions::{ExecutableTransaction, ExecutionFlags};
+
+#[cfg(test)]
+#[path = "fee_estimation_test.rs"]
+mod test;
+
+/// A fee estimate of an account transaction, along with the minimal fee bounds under which it
+/// passes the fee checks.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FeeEstimation {
+    pub gas_vector: GasVector,
+    pub overall_fee: Fee,
+    pub fee_type: FeeType,
+    pub suggested_bounds: FeeBounds,
+    /// Set if the transaction reverts under the suggested bounds; i.e., if it reverts regardless
+    /// of its bounds, or under any bounds the sender can afford.
+    pub revert_error: Option<String>,
+}
+
+/// Estimates the fee of the given transaction on top of the given state, and applies the
+/// transaction to the state under the suggested bounds.
+/// The suggested bounds are the minimal ones under which the pre- and post-execution fee checks
+/// pass; of v3 transactions, the L1 gas amount is searched, priced at the block's L1 gas price.
+/// Since validation may depend on the bounds, each candidate is checked by executing the
+/// transaction under it.
+pub fn estimate_fee<S: UpdatableState>(
+    tx: &AccountTransaction,
+    state: &mut S,
+    block_context: &BlockContext,
+) -> TransactionExecutionResult<FeeEstimation> {
+    let search = FeeBoundsSearch::new(tx, block_context);
+
+    // A first candidate is the cost under the declared bounds, without fee checks.
+    let reference_execution_info = search.execute(state, None, false)?;
+    let candidate = min(
+        search.amount_of(reference_execution_info.transaction_receipt.gas).max(1),
+        search.cap(),
+    );
+    if reference_execution_info.revert_error.is_some() {
+        // The execution reverts regardless of the bounds; suggest bounds covering its cost.
+        return search.estimate(state, candidate);
+    }
+
+    let max_amount = search.affordable_amount(state)?.clamp(candidate, search.cap());
+    // Invariant: the transaction fails under `low`, and passes under `high`.
+    let (mut low, mut high) = if search.passes(state, candidate) {
+        // Usually, the candidate is minimal.
+        if candidate == 1 || !search.passes(state, candidate - 1) {
+            (candidate - 1, candidate)
+        } else {
+            (0, candidate - 1)
+        }
+    } else {
+        let mut low = candidate;
+        loop {
+            if low == max_amount {
+                // The transaction fails under any bounds the sender can afford.
+                return search.estimate(state, max_amount);
+            }
+            let next = low.saturating_mul(2).min(max_amount);
+            if search.passes(state, next) {
+                break (low, next);
+            }
+            low = next;
+        }
+    };
+    while high - low > 1 {
+        let middle = low + (high - low) / 2;
+        if search.passes(state, middle) {
+            high = middle;
+        } else {
+            low = middle;
+        }
+    }
+
+    search.estimate(state, high)
+}
+
+// Searches fee bounds of a transaction over a single amount: the max fee of deprecated
+// transactions, or the max L1 gas amount of current ones.
+struct FeeBoundsSearch<'a> {
+    tx: &'a AccountTransaction,
+    tx_context: TransactionContext,
+    l1_gas_price: u128,
+}
+
+impl<'a> FeeBoundsSearch<'a> {
+    fn new(tx: &'a AccountTransaction, block_context: &BlockContext) -> Self {
+        let tx_context = block_context.to_tx_context(tx);
+        let l1_gas_price = block_context
+            .block_info
+            .gas_prices
+            .get_gas_price_by_fee_type(&tx_context.tx_info.fee_type())
+            .into();
+        Self { tx, tx_context, l1_gas_price }
+    }
+
+    fn cap(&self) -> u128 {
+        match self.tx_context.tx_info {
+            TransactionInfo::Current(_) => u64::MAX.into(),
+            TransactionInfo::Deprecated(_) => u128::MAX,
+        }
+    }
+
+    fn amount_of(&self, gas_vector: GasVector) -> u128 {
+        match self.tx_context.tx_info {
+            TransactionInfo::Current(_) => {
+                compute_discounted_gas_from_gas_vector(&gas_vector, &self.tx_context)
+            }
+            TransactionInfo::Deprecated(_) => self.fee_of(gas_vector).0,
+        }
+    }
+
+    fn fee_of(&self, gas_vector: GasVector) -> Fee {
+        get_fee_by_gas_vector(
+            &self.tx_context.block_context.block_info,
+            gas_vector,
+            &self.tx_context.tx_info.fee_type(),
+        )
+    }
+
+    fn fee_bounds(&self, amount: u128) -> FeeBounds {
+        match self.tx_context.tx_info {
+            TransactionInfo::Current(_) => FeeBounds::ResourceBounds(
+                ResourceBoundsMapping::try_from(vec![
+                    (
+                        Resource::L1Gas,
+                        ResourceBounds {
+                            max_amount: amount.try_into().expect("Amount is capped to u64."),
+                            max_price_per_unit: self.l1_gas_price,
+                        },
+                    ),
+                    (Resource::L2Gas, ResourceBounds { max_amount: 0, max_price_per_unit: 0 }),
+                ])
+                .expect("Resource bounds of L1 and L2 gas are valid."),
+            ),
+            TransactionInfo::Deprecated(_) => FeeBounds::MaxFee(Fee(amount)),
+        }
+    }
+
+    // Returns the maximal amount the sender can pay for.
+    fn affordable_amount<S: UpdatableState>(
+        &self,
+        state: &mut S,
+    ) -> TransactionExecutionResult<u128> {
+        let (balance_low, balance_high) = state.get_fee_token_balance(
+            self.tx_context.tx_info.sender_address(),
+            self.tx_context.fee_token_address(),
+        )?;
+        let balance =
+            balance_to_big_uint(&balance_low, &balance_high).to_u128().unwrap_or(u128::MAX);
+
+        Ok(match self.tx_context.tx_info {
+            TransactionInfo::Current(_) => balance / self.l1_gas_price,
+            TransactionInfo::Deprecated(_) => balance,
+        })
+    }
+
+    // Executes the transaction under the given bounds, with fee checks; or under its declared
+    // bounds, without them.
+    fn execute<S: UpdatableState>(
+        &self,
+        state: &mut S,
+        fee_bounds: Option<FeeBounds>,
+        commit: bool,
+    ) -> TransactionExecutionResult<TransactionExecutionInfo> {
+        let mut tx = self.tx.clone();
+        let charge_fee = fee_bounds.is_some();
+        if let Some(fee_bounds) = fee_bounds {
+            tx.set_fee_bounds(fee_bounds);
+        }
+
+        let mut transactional_state = TransactionalState::create_transactional(state);
+        let execution_flags =
+            ExecutionFlags { charge_fee, validate: true, concurrency_mode: false };
+        let execution_result = tx.execute_raw(
+            &mut transactional_state,
+            &self.tx_context.block_context,
+            execution_flags,
+        );
+        if commit && execution_result.is_ok() {
+            transactional_state.commit();
+        } else {
+            transactional_state.abort();
+        }
+
+        execution_result
+    }
+
+    fn passes<S: UpdatableState>(&self, state: &mut S, amount: u128) -> bool {
+        matches!(
+            self.execute(state, Some(self.fee_bounds(amount)), false),
+            Ok(TransactionExecutionInfo { revert_error: None, .. })
+        )
+    }
+
+    // Applies the transaction under the bounds of the given amount, and returns its estimation.
+    fn estimate<S: UpdatableState>(
+        &self,
+        state: &mut S,
+        amount: u128,
+    ) -> TransactionExecutionResult<FeeEstimation> {
+        let suggested_bounds = self.fee_bounds(amount);
+        let tx_execution_info = self.execute(state, Some(suggested_bounds.clone()), true)?;
+        let gas_vector = tx_execution_info.transaction_receipt.gas;
+
+        Ok(FeeEstimation {
+            gas_vector,
+            overall_fee: self.fee_of(gas_vector),
+            fee_type: self.tx_context.tx_info.fee_type(),
+            suggested_bounds,
+            revert_error: tx_execution_info.revert_error,
+        })
+    }
+}
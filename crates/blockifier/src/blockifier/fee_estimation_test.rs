@@ -0,0 +1,124 @@
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+use starknet_api::felt;
+use starknet_api::transaction::{Fee, Resource, TransactionVersion};
+
+use crate::blockifier::config::TransactionExecutorConfig;
+use crate::blockifier::fee_estimation::FeeEstimation;
+use crate::blockifier::transaction_executor::TransactionExecutor;
+use crate::context::BlockContext;
+use crate::state::cached_state::TransactionalState;
+use crate::test_utils::{create_calldata, CairoVersion};
+use crate::transaction::account_transaction::AccountTransaction;
+use crate::transaction::objects::{FeeBounds, FeeType};
+use crate::transaction::test_utils::{
+    account_invoke_tx, block_context, create_test_init_data, l1_resource_bounds,
+};
+use crate::transaction::transactions::ExecutableTransaction;
+use crate::{invoke_tx_args, nonce};
+
+// Returns the next tighter bounds, of the same kind.
+fn tighten(fee_bounds: &FeeBounds) -> FeeBounds {
+    match fee_bounds {
+        FeeBounds::MaxFee(max_fee) => FeeBounds::MaxFee(Fee(max_fee.0 - 1)),
+        FeeBounds::ResourceBounds(resource_bounds) => {
+            let l1_bounds = resource_bounds.0[&Resource::L1Gas];
+            FeeBounds::ResourceBounds(l1_resource_bounds(
+                l1_bounds.max_amount - 1,
+                l1_bounds.max_price_per_unit,
+            ))
+        }
+    }
+}
+
+#[rstest]
+#[case(TransactionVersion::ONE, FeeType::Eth)]
+#[case(TransactionVersion::THREE, FeeType::Strk)]
+fn test_estimate_fee(
+    block_context: BlockContext,
+    #[case] version: TransactionVersion,
+    #[case] fee_type: FeeType,
+) {
+    let init_data = create_test_init_data(&block_context.chain_info, CairoVersion::Cairo1);
+    let (account_address, contract_address) =
+        (init_data.account_address, init_data.contract_address);
+    let txs: Vec<AccountTransaction> = (0..2_u8)
+        .map(|i| {
+            // Declared bounds are overridden by the estimation.
+            account_invoke_tx(invoke_tx_args! {
+                sender_address: account_address,
+                calldata: create_calldata(
+                    contract_address,
+                    "test_storage_read_write",
+                    &[felt!(0x10_u8), felt!(i + 1)],
+                ),
+                version,
+                nonce: nonce!(i),
+            })
+        })
+        .collect();
+
+    let mut tx_executor = TransactionExecutor::new(
+        init_data.state,
+        block_context.clone(),
+        TransactionExecutorConfig::default(),
+    );
+    let estimations: Vec<FeeEstimation> =
+        tx_executor.estimate_fee(&txs).into_iter().map(Result::unwrap).collect();
+
+    // Each transaction passes under its suggested bounds, on top of the previous ones, but not
+    // under tighter bounds.
+    let mut state = create_test_init_data(&block_context.chain_info, CairoVersion::Cairo1).state;
+    for (tx, estimation) in txs.iter().zip(estimations) {
+        assert_eq!(estimation.fee_type, fee_type);
+        assert_eq!(estimation.revert_error, None);
+
+        let mut tighter_tx = tx.clone();
+        tighter_tx.set_fee_bounds(tighten(&estimation.suggested_bounds));
+        let mut transactional_state = TransactionalState::create_transactional(&mut state);
+        let tighter_result =
+            tighter_tx.execute(&mut transactional_state, &block_context, true, true);
+        transactional_state.abort();
+        assert!(
+            tighter_result
+                .map_or(true, |tx_execution_info| tx_execution_info.revert_error.is_some())
+        );
+
+        let mut suggested_tx = tx.clone();
+        suggested_tx.set_fee_bounds(estimation.suggested_bounds);
+        let tx_execution_info =
+            suggested_tx.execute(&mut state, &block_context, true, true).unwrap();
+        assert_eq!(tx_execution_info.revert_error, None);
+        assert_eq!(tx_execution_info.transaction_receipt.gas, estimation.gas_vector);
+        assert_eq!(tx_execution_info.transaction_receipt.fee, estimation.overall_fee);
+    }
+}
+
+#[rstest]
+fn test_estimate_fee_of_reverted_tx(
+    block_context: BlockContext,
+    #[values(TransactionVersion::ONE, TransactionVersion::THREE)] version: TransactionVersion,
+) {
+    let init_data = create_test_init_data(&block_context.chain_info, CairoVersion::Cairo0);
+    let tx = account_invoke_tx(invoke_tx_args! {
+        sender_address: init_data.account_address,
+        calldata: create_calldata(
+            init_data.contract_address,
+            "write_and_revert",
+            &[felt!(9_u8), felt!(99_u8)],
+        ),
+        version,
+        nonce: nonce!(0_u8),
+    });
+
+    let mut tx_executor = TransactionExecutor::new(
+        init_data.state,
+        block_context,
+        TransactionExecutorConfig::default(),
+    );
+    let estimation = tx_executor.estimate_fee(&[tx]).pop().unwrap().unwrap();
+
+    // The cost of a reverted transaction is still estimated.
+    assert!(estimation.revert_error.is_some());
+    assert!(estimation.overall_fee > Fee(0));
+}
@@ -0,0 +1,121 @@
+use std::sync::{Arc, Mutex};
+
+use starknet_api::core::ContractAddress;
+use starknet_api::state::StorageKey;
+use starknet_api::transaction::EventContent;
+use starknet_types_core::felt::Felt;
+
+use crate::blockifier::transaction_executor::TransactionExecutorResult;
+use crate::execution::call_info::{CallInfo, MessageToL1};
+use crate::execution::entry_point::CallEntryPoint;
+use crate::transaction::objects::TransactionExecutionInfo;
+use crate::transaction::transaction_execution::Transaction;
+
+#[cfg(test)]
+#[path = "observer_test.rs"]
+mod test;
+
+/// Observes the executions of a transaction executor (see
+/// `TransactionExecutor::set_execution_observer`), e.g., to index them.
+/// Each transaction is reported once its result is final, in commit order; from its start to its
+/// end, with the steps of its execution in between, in execution order. These include the steps of
+/// calls that failed, and of executions that were reverted.
+/// The fee transfer is reported as a whole, rather than step by step.
+pub trait ExecutionObserver: Send {
+    fn on_tx_start(&mut self, _tx: &Transaction) {}
+
+    /// Invoked on a call to an entry point (of any `CallType`), at the given depth (starting at 1
+    /// for the calls made by the transaction itself, e.g., `__execute__`).
+    fn on_entry_point_enter(&mut self, _call: &CallEntryPoint, _depth: usize) {}
+
+    fn on_entry_point_exit(&mut self, _call: &CallEntryPoint, _depth: usize, _failed: bool) {}
+
+    fn on_storage_read(
+        &mut self,
+        _contract_address: ContractAddress,
+        _key: StorageKey,
+        _value: Felt,
+    ) {
+    }
+
+    fn on_storage_write(
+        &mut self,
+        _contract_address: ContractAddress,
+        _key: StorageKey,
+        _value: Felt,
+    ) {
+    }
+
+    fn on_event(&mut self, _from_address: ContractAddress, _event: &EventContent) {}
+
+    fn on_l2_to_l1_message(&mut self, _from_address: ContractAddress, _message: &MessageToL1) {}
+
+    fn on_fee_transfer(&mut self, _fee_transfer_call_info: &CallInfo) {}
+
+    fn on_tx_end(&mut self, _result: &TransactionExecutorResult<TransactionExecutionInfo>) {}
+}
+
+/// A step of an execution, recorded for an [ExecutionObserver].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) enum ExecutionEvent {
+    EntryPointEnter { call: CallEntryPoint, depth: usize },
+    EntryPointExit { call: CallEntryPoint, depth: usize, failed: bool },
+    StorageRead { contract_address: ContractAddress, key: StorageKey, value: Felt },
+    StorageWrite { contract_address: ContractAddress, key: StorageKey, value: Felt },
+    Event { from_address: ContractAddress, content: EventContent },
+    L2ToL1Message { from_address: ContractAddress, message: MessageToL1 },
+}
+
+/// Records the steps of the executions run with it, until taken.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct ExecutionRecorder(Arc<Mutex<Vec<ExecutionEvent>>>);
+
+impl ExecutionRecorder {
+    pub(crate) fn record(&self, event: ExecutionEvent) {
+        self.0.lock().expect("Failed to lock the execution recorder.").push(event);
+    }
+
+    pub(crate) fn take_events(&self) -> Vec<ExecutionEvent> {
+        std::mem::take(&mut *self.0.lock().expect("Failed to lock the execution recorder."))
+    }
+}
+
+/// Reports the given transaction, along with the recorded steps of its execution.
+pub(crate) fn report_tx(
+    observer: &mut dyn ExecutionObserver,
+    tx: &Transaction,
+    events: Vec<ExecutionEvent>,
+    result: &TransactionExecutorResult<TransactionExecutionInfo>,
+) {
+    observer.on_tx_start(tx);
+    for event in events {
+        match event {
+            ExecutionEvent::EntryPointEnter { call, depth } => {
+                observer.on_entry_point_enter(&call, depth)
+            }
+            ExecutionEvent::EntryPointExit { call, depth, failed } => {
+                observer.on_entry_point_exit(&call, depth, failed)
+            }
+            ExecutionEvent::StorageRead { contract_address, key, value } => {
+                observer.on_storage_read(contract_address, key, value)
+            }
+            ExecutionEvent::StorageWrite { contract_address, key, value } => {
+                observer.on_storage_write(contract_address, key, value)
+            }
+            ExecutionEvent::Event { from_address, content } => {
+                observer.on_event(from_address, &content)
+            }
+            ExecutionEvent::L2ToL1Message { from_address, message } => {
+                observer.on_l2_to_l1_message(from_address, &message)
+            }
+        }
+    }
+    if let Some(fee_transfer_call_info) = result
+        .as_ref()
+        .ok()
+        .and_then(|tx_execution_info| tx_execution_info.fee_transfer_call_info.as_ref())
+    {
+        observer.on_fee_transfer(fee_transfer_call_info);
+    }
+    observer.on_tx_end(result);
+}
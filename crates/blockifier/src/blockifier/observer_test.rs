@@ -0,0 +1,301 @@
+use std::sync::{Arc, Mutex};
+
+use assert_matches::assert_matches;
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+use starknet_api::core::{ContractAddress, EntryPointSelector, EthAddress};
+use starknet_api::felt;
+use starknet_api::state::StorageKey;
+use starknet_api::transaction::{EventContent, EventData, EventKey, Fee, L2ToL1Payload};
+use starknet_types_core::felt::Felt;
+
+use crate::abi::abi_utils::selector_from_name;
+use crate::blockifier::config::TransactionExecutorConfig;
+use crate::blockifier::observer::ExecutionObserver;
+use crate::blockifier::simulation::SimulationFlags;
+use crate::blockifier::transaction_executor::{
+    TransactionExecutor, TransactionExecutorError, TransactionExecutorResult,
+};
+use crate::context::BlockContext;
+use crate::execution::call_info::{CallInfo, MessageToL1};
+use crate::execution::entry_point::CallEntryPoint;
+use crate::test_utils::dict_state_reader::DictStateReader;
+use crate::test_utils::{create_calldata, CairoVersion};
+use crate::transaction::objects::TransactionExecutionInfo;
+use crate::transaction::test_utils::{
+    account_invoke_tx, block_context, create_test_init_data, emit_n_events_tx,
+};
+use crate::transaction::transaction_execution::Transaction;
+use crate::{invoke_tx_args, nonce};
+
+#[derive(Debug, Eq, PartialEq)]
+enum Observed {
+    TxStart,
+    Enter { selector: EntryPointSelector, depth: usize },
+    Exit { selector: EntryPointSelector, depth: usize, failed: bool },
+    StorageRead { contract_address: ContractAddress, key: StorageKey, value: Felt },
+    StorageWrite { contract_address: ContractAddress, key: StorageKey, value: Felt },
+    Event { from_address: ContractAddress, content: EventContent },
+    L2ToL1Message { from_address: ContractAddress, message: MessageToL1 },
+    FeeTransfer { actual_fee: Fee },
+    TxEnd { actual_fee: Fee },
+}
+
+struct TestObserver(Arc<Mutex<Vec<Observed>>>);
+
+impl TestObserver {
+    fn observe(&self, observed: Observed) {
+        self.0.lock().unwrap().push(observed);
+    }
+}
+
+impl ExecutionObserver for TestObserver {
+    fn on_tx_start(&mut self, _tx: &Transaction) {
+        self.observe(Observed::TxStart);
+    }
+
+    fn on_entry_point_enter(&mut self, call: &CallEntryPoint, depth: usize) {
+        self.observe(Observed::Enter { selector: call.entry_point_selector, depth });
+    }
+
+    fn on_entry_point_exit(&mut self, call: &CallEntryPoint, depth: usize, failed: bool) {
+        self.observe(Observed::Exit { selector: call.entry_point_selector, depth, failed });
+    }
+
+    fn on_storage_read(&mut self, contract_address: ContractAddress, key: StorageKey, value: Felt) {
+        self.observe(Observed::StorageRead { contract_address, key, value });
+    }
+
+    fn on_storage_write(
+        &mut self,
+        contract_address: ContractAddress,
+        key: StorageKey,
+        value: Felt,
+    ) {
+        self.observe(Observed::StorageWrite { contract_address, key, value });
+    }
+
+    fn on_event(&mut self, from_address: ContractAddress, event: &EventContent) {
+        self.observe(Observed::Event { from_address, content: event.clone() });
+    }
+
+    fn on_l2_to_l1_message(&mut self, from_address: ContractAddress, message: &MessageToL1) {
+        self.observe(Observed::L2ToL1Message { from_address, message: message.clone() });
+    }
+
+    fn on_fee_transfer(&mut self, fee_transfer_call_info: &CallInfo) {
+        // The fee transfer calldata is [recipient, amount_low, amount_high].
+        let amount = fee_transfer_call_info.call.calldata.0[1];
+        self.observe(Observed::FeeTransfer { actual_fee: Fee(amount.try_into().unwrap()) });
+    }
+
+    fn on_tx_end(&mut self, result: &TransactionExecutorResult<TransactionExecutionInfo>) {
+        self.observe(Observed::TxEnd {
+            actual_fee: result.as_ref().unwrap().transaction_receipt.fee,
+        });
+    }
+}
+
+#[rstest]
+fn test_execution_observer(
+    block_context: BlockContext,
+    #[values(false, true)] concurrency_enabled: bool,
+) {
+    let mut config = TransactionExecutorConfig::create_for_testing();
+    config.concurrency_config.enabled &= concurrency_enabled;
+    let (mut tx_executor, account_address, contract_address, observed) =
+        observed_tx_executor(block_context, config);
+
+    let key = felt!(0x10_u8);
+    let invoke_tx = |value: u8| {
+        Transaction::AccountTransaction(account_invoke_tx(invoke_tx_args! {
+            sender_address: account_address,
+            calldata: create_calldata(
+                contract_address,
+                "test_storage_read_write",
+                &[key, felt!(value)],
+            ),
+            nonce: nonce!(value - 1),
+        }))
+    };
+
+    // Simulations are not reported.
+    tx_executor.simulate(vec![invoke_tx(1)], SimulationFlags::default());
+    assert!(observed.lock().unwrap().is_empty());
+
+    let results = tx_executor.execute_txs(&[invoke_tx(1), invoke_tx(2)]);
+
+    let key = StorageKey::try_from(key).unwrap();
+    let (validate, execute, inner) = (
+        selector_from_name("__validate__"),
+        selector_from_name("__execute__"),
+        selector_from_name("test_storage_read_write"),
+    );
+    let expected_observed = results
+        .iter()
+        .zip(1_u8..)
+        .flat_map(|(result, value)| {
+            let actual_fee = result.as_ref().unwrap().transaction_receipt.fee;
+            let value = felt!(value);
+            [
+                Observed::TxStart,
+                Observed::Enter { selector: validate, depth: 1 },
+                Observed::Exit { selector: validate, depth: 1, failed: false },
+                Observed::Enter { selector: execute, depth: 1 },
+                Observed::Enter { selector: inner, depth: 2 },
+                Observed::StorageWrite { contract_address, key, value },
+                Observed::StorageRead { contract_address, key, value },
+                Observed::Exit { selector: inner, depth: 2, failed: false },
+                Observed::Exit { selector: execute, depth: 1, failed: false },
+                Observed::FeeTransfer { actual_fee },
+                Observed::TxEnd { actual_fee },
+            ]
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(results.len(), 2);
+    assert_eq!(*observed.lock().unwrap(), expected_observed);
+}
+
+// Returns an executor reporting to a test observer, along with the account and test contract
+// addresses, and the observed steps.
+fn observed_tx_executor(
+    block_context: BlockContext,
+    config: TransactionExecutorConfig,
+) -> (
+    TransactionExecutor<DictStateReader>,
+    ContractAddress,
+    ContractAddress,
+    Arc<Mutex<Vec<Observed>>>,
+) {
+    let init_data = create_test_init_data(&block_context.chain_info, CairoVersion::Cairo1);
+    let mut tx_executor = TransactionExecutor::new(init_data.state, block_context, config);
+    let observed = Arc::new(Mutex::new(Vec::new()));
+    tx_executor.set_execution_observer(Box::new(TestObserver(observed.clone())));
+    (tx_executor, init_data.account_address, init_data.contract_address, observed)
+}
+
+#[rstest]
+fn test_reverted_tx_is_observed(block_context: BlockContext) {
+    let (mut tx_executor, account_address, contract_address, observed) =
+        observed_tx_executor(block_context, TransactionExecutorConfig::default());
+
+    let tx_execution_info = tx_executor
+        .execute(&Transaction::AccountTransaction(account_invoke_tx(invoke_tx_args! {
+            sender_address: account_address,
+            calldata: create_calldata(contract_address, "fail", &[]),
+            nonce: nonce!(0_u8),
+        })))
+        .unwrap();
+    assert!(tx_execution_info.is_reverted());
+
+    // The failed call is reported, up to the failure, followed by the fee transfer.
+    let (validate, execute, fail) = (
+        selector_from_name("__validate__"),
+        selector_from_name("__execute__"),
+        selector_from_name("fail"),
+    );
+    let actual_fee = tx_execution_info.transaction_receipt.fee;
+    assert_eq!(
+        *observed.lock().unwrap(),
+        vec![
+            Observed::TxStart,
+            Observed::Enter { selector: validate, depth: 1 },
+            Observed::Exit { selector: validate, depth: 1, failed: false },
+            Observed::Enter { selector: execute, depth: 1 },
+            Observed::Enter { selector: fail, depth: 2 },
+            Observed::Exit { selector: fail, depth: 2, failed: true },
+            Observed::Exit { selector: execute, depth: 1, failed: true },
+            Observed::FeeTransfer { actual_fee },
+            Observed::TxEnd { actual_fee },
+        ]
+    );
+}
+
+#[rstest]
+fn test_events_and_messages_are_observed(
+    block_context: BlockContext,
+    #[values(false, true)] concurrency_enabled: bool,
+) {
+    let mut config = TransactionExecutorConfig::create_for_testing();
+    config.concurrency_config.enabled &= concurrency_enabled;
+    let (mut tx_executor, account_address, contract_address, observed) =
+        observed_tx_executor(block_context, config);
+
+    let (key, data, to_address) = (felt!(0x11_u8), felt!(0x12_u8), felt!(0x13_u8));
+    let invoke_tx = |function_name: &str, args: &[Felt], nonce: u8| {
+        Transaction::AccountTransaction(account_invoke_tx(invoke_tx_args! {
+            sender_address: account_address,
+            calldata: create_calldata(contract_address, function_name, args),
+            nonce: nonce!(nonce),
+        }))
+    };
+    let results = tx_executor.execute_txs(&[
+        // Emits one event, with one key and one data element.
+        invoke_tx("test_emit_events", &[felt!(1_u8), felt!(1_u8), key, felt!(1_u8), data], 0),
+        invoke_tx("send_message", &[to_address], 1),
+    ]);
+    assert!(results.iter().all(|result| !result.as_ref().unwrap().is_reverted()));
+
+    let observed_outputs: Vec<Observed> = std::mem::take(&mut *observed.lock().unwrap())
+        .into_iter()
+        .filter(|observed| {
+            matches!(observed, Observed::Event { .. } | Observed::L2ToL1Message { .. })
+        })
+        .collect();
+    assert_eq!(
+        observed_outputs,
+        vec![
+            Observed::Event {
+                from_address: contract_address,
+                content: EventContent { keys: vec![EventKey(key)], data: EventData(vec![data]) },
+            },
+            Observed::L2ToL1Message {
+                from_address: contract_address,
+                message: MessageToL1 {
+                    to_address: EthAddress::try_from(to_address).unwrap(),
+                    payload: L2ToL1Payload(vec![felt!(12_u8), felt!(34_u8)]),
+                },
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_block_full_tx_is_not_observed() {
+    let max_n_events_in_block = 10;
+    let (mut tx_executor, account_address, contract_address, observed) = observed_tx_executor(
+        BlockContext::create_for_bouncer_testing(max_n_events_in_block),
+        TransactionExecutorConfig::default(),
+    );
+    let emit_n_events = |n_events: usize, nonce: u8| {
+        Transaction::AccountTransaction(emit_n_events_tx(
+            n_events,
+            account_address,
+            contract_address,
+            nonce!(nonce),
+        ))
+    };
+    let n_observed_events = || {
+        let n_events = observed
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|observed| matches!(observed, Observed::Event { .. }))
+            .count();
+        observed.lock().unwrap().clear();
+        n_events
+    };
+
+    tx_executor.execute(&emit_n_events(8, 0)).unwrap();
+    assert_eq!(n_observed_events(), 8);
+
+    // No room for this transaction in the block; it is not reported, and its steps are discarded.
+    assert_matches!(
+        tx_executor.execute(&emit_n_events(4, 1)),
+        Err(TransactionExecutorError::BlockFull)
+    );
+    assert!(observed.lock().unwrap().is_empty());
+
+    tx_executor.execute(&emit_n_events(1, 1)).unwrap();
+    assert_eq!(n_observed_events(), 1);
+}
@@ -0,0 +1,448 @@
+use std::collections::BTreeMap;
+
+use cairo_vm::types::builtin_name::BuiltinName;
+use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
+use serde::{Serialize, Serializer};
+use starknet_api::core::{
+    ClassHash, CompiledClassHash, ContractAddress, EntryPointSelector, EthAddress, Nonce,
+};
+use starknet_api::deprecated_contract_class::EntryPointType;
+use starknet_api::state::StorageKey;
+use starknet_api::transaction::{Calldata, Fee};
+use starknet_types_core::felt::Felt;
+
+use crate::context::BlockContext;
+use crate::execution::call_info::CallInfo;
+use crate::execution::entry_point::CallType;
+use crate::fee::fee_utils::get_fee_by_gas_vector;
+use crate::state::cached_state::StateMaps;
+use crate::state::state_api::{StateReader, StateResult};
+use crate::transaction::objects::{
+    FeeType, GasVector, HasRelatedFeeType, TransactionExecutionInfo, TransactionInfoCreator,
+};
+use crate::transaction::transaction_execution::Transaction;
+use crate::transaction::transaction_types::TransactionType;
+
+#[cfg(test)]
+#[path = "simulation_test.rs"]
+mod test;
+
+/// Flags of a simulation; see `TransactionExecutor::simulate`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SimulationFlags {
+    pub skip_validate: bool,
+    pub skip_fee_charge: bool,
+    /// Executes the transactions as if their versions had the query bit set.
+    pub query_version: bool,
+}
+
+/// The result of simulating a transaction, shaped like the RPC `SIMULATED_TRANSACTION`.
+#[derive(Debug, Eq, PartialEq, Serialize)]
+pub struct SimulatedTransaction {
+    pub transaction_trace: TransactionTrace,
+    pub fee_estimation: FeeEstimate,
+}
+
+impl SimulatedTransaction {
+    pub fn new(
+        tx: &Transaction,
+        tx_execution_info: TransactionExecutionInfo,
+        state_diff: StateDiff,
+        block_context: &BlockContext,
+    ) -> Self {
+        let fee_estimation = FeeEstimate::new(
+            block_context,
+            &tx.create_tx_info().fee_type(),
+            tx_execution_info.transaction_receipt.gas,
+        );
+        let execution_resources = TraceExecutionResources {
+            computation_resources: (&tx_execution_info.transaction_receipt.resources.vm_resources)
+                .into(),
+            data_availability: tx_execution_info.transaction_receipt.da_gas,
+        };
+        let validate_invocation =
+            tx_execution_info.validate_call_info.map(FunctionInvocation::from);
+        let execute_invocation = tx_execution_info.execute_call_info.map(FunctionInvocation::from);
+        let fee_transfer_invocation =
+            tx_execution_info.fee_transfer_call_info.map(FunctionInvocation::from);
+
+        let tx_type = match tx {
+            Transaction::AccountTransaction(account_tx) => account_tx.tx_type(),
+            Transaction::L1HandlerTransaction(_) => TransactionType::L1Handler,
+        };
+        let transaction_trace = match tx_type {
+            TransactionType::Declare => TransactionTrace::Declare {
+                validate_invocation,
+                fee_transfer_invocation,
+                state_diff,
+                execution_resources,
+            },
+            TransactionType::DeployAccount => TransactionTrace::DeployAccount {
+                validate_invocation,
+                constructor_invocation: execute_invocation,
+                fee_transfer_invocation,
+                state_diff,
+                execution_resources,
+            },
+            TransactionType::InvokeFunction => TransactionTrace::Invoke {
+                validate_invocation,
+                execute_invocation: match tx_execution_info.revert_error {
+                    Some(revert_reason) => ExecuteInvocation::Reverted { revert_reason },
+                    None => ExecuteInvocation::Success(execute_invocation),
+                },
+                fee_transfer_invocation,
+                state_diff,
+                execution_resources,
+            },
+            TransactionType::L1Handler => TransactionTrace::L1Handler {
+                function_invocation: execute_invocation,
+                state_diff,
+                execution_resources,
+            },
+        };
+
+        Self { transaction_trace, fee_estimation }
+    }
+}
+
+/// A transaction trace, shaped like the RPC `TRANSACTION_TRACE`.
+#[derive(Debug, Eq, PartialEq, Serialize)]
+#[serde(tag = "type")]
+pub enum TransactionTrace {
+    #[serde(rename = "DECLARE")]
+    Declare {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        validate_invocation: Option<FunctionInvocation>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        fee_transfer_invocation: Option<FunctionInvocation>,
+        state_diff: StateDiff,
+        execution_resources: TraceExecutionResources,
+    },
+    #[serde(rename = "DEPLOY_ACCOUNT")]
+    DeployAccount {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        validate_invocation: Option<FunctionInvocation>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        constructor_invocation: Option<FunctionInvocation>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        fee_transfer_invocation: Option<FunctionInvocation>,
+        state_diff: StateDiff,
+        execution_resources: TraceExecutionResources,
+    },
+    #[serde(rename = "INVOKE")]
+    Invoke {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        validate_invocation: Option<FunctionInvocation>,
+        execute_invocation: ExecuteInvocation,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        fee_transfer_invocation: Option<FunctionInvocation>,
+        state_diff: StateDiff,
+        execution_resources: TraceExecutionResources,
+    },
+    #[serde(rename = "L1_HANDLER")]
+    L1Handler {
+        function_invocation: Option<FunctionInvocation>,
+        state_diff: StateDiff,
+        execution_resources: TraceExecutionResources,
+    },
+}
+
+/// The execution part of an invoke transaction trace; a reverted execution has no invocation.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum ExecuteInvocation {
+    Success(Option<FunctionInvocation>),
+    Reverted { revert_reason: String },
+}
+
+/// An entry point invocation, including its inner calls, shaped like the RPC
+/// `FUNCTION_INVOCATION`.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct FunctionInvocation {
+    pub contract_address: ContractAddress,
+    pub entry_point_selector: EntryPointSelector,
+    pub calldata: Calldata,
+    pub caller_address: ContractAddress,
+    pub class_hash: Option<ClassHash>,
+    pub entry_point_type: EntryPointType,
+    #[serde(serialize_with = "serialize_call_type")]
+    pub call_type: CallType,
+    pub result: Vec<Felt>,
+    pub calls: Vec<FunctionInvocation>,
+    pub events: Vec<TraceEvent>,
+    pub messages: Vec<TraceMessage>,
+    pub execution_resources: ComputationResources,
+    pub is_reverted: bool,
+}
+
+impl From<CallInfo> for FunctionInvocation {
+    fn from(call_info: CallInfo) -> Self {
+        let contract_address = call_info.call.storage_address;
+        Self {
+            contract_address,
+            entry_point_selector: call_info.call.entry_point_selector,
+            calldata: call_info.call.calldata,
+            caller_address: call_info.call.caller_address,
+            class_hash: call_info.call.class_hash,
+            entry_point_type: call_info.call.entry_point_type,
+            call_type: call_info.call.call_type,
+            result: call_info.execution.retdata.0,
+            calls: call_info.inner_calls.into_iter().map(Self::from).collect(),
+            events: call_info
+                .execution
+                .events
+                .into_iter()
+                .map(|ordered_event| TraceEvent {
+                    order: ordered_event.order,
+                    keys: ordered_event.event.keys.into_iter().map(|key| key.0).collect(),
+                    data: ordered_event.event.data.0,
+                })
+                .collect(),
+            messages: call_info
+                .execution
+                .l2_to_l1_messages
+                .into_iter()
+                .map(|ordered_message| TraceMessage {
+                    order: ordered_message.order,
+                    from_address: contract_address,
+                    to_address: ordered_message.message.to_address,
+                    payload: ordered_message.message.payload.0,
+                })
+                .collect(),
+            execution_resources: (&call_info.resources).into(),
+            is_reverted: call_info.execution.failed,
+        }
+    }
+}
+
+// Serializes a call type as the RPC `CALL_TYPE`; delegate calls are library calls.
+fn serialize_call_type<S: Serializer>(
+    call_type: &CallType,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(match call_type {
+        CallType::Call => "CALL",
+        CallType::Delegate => "LIBRARY_CALL",
+    })
+}
+
+/// An event emitted by an invocation, shaped like the RPC `ORDERED_EVENT`.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct TraceEvent {
+    pub order: usize,
+    pub keys: Vec<Felt>,
+    pub data: Vec<Felt>,
+}
+
+/// A message sent to L1 by an invocation, shaped like the RPC `ORDERED_MESSAGE`.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct TraceMessage {
+    pub order: usize,
+    pub from_address: ContractAddress,
+    pub to_address: EthAddress,
+    pub payload: Vec<Felt>,
+}
+
+/// The VM resources consumed by an execution, shaped like the RPC `COMPUTATION_RESOURCES`.
+/// Unused resources are omitted.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
+pub struct ComputationResources {
+    pub steps: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory_holes: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub range_check_builtin_applications: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pedersen_builtin_applications: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub poseidon_builtin_applications: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ec_op_builtin_applications: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ecdsa_builtin_applications: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bitwise_builtin_applications: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub keccak_builtin_applications: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub segment_arena_builtin: Option<usize>,
+}
+
+impl From<&ExecutionResources> for ComputationResources {
+    fn from(resources: &ExecutionResources) -> Self {
+        let used = |n: usize| (n > 0).then_some(n);
+        let builtin = |name: BuiltinName| {
+            resources.builtin_instance_counter.get(&name).copied().and_then(used)
+        };
+        Self {
+            steps: resources.n_steps,
+            memory_holes: used(resources.n_memory_holes),
+            range_check_builtin_applications: builtin(BuiltinName::range_check),
+            pedersen_builtin_applications: builtin(BuiltinName::pedersen),
+            poseidon_builtin_applications: builtin(BuiltinName::poseidon),
+            ec_op_builtin_applications: builtin(BuiltinName::ec_op),
+            ecdsa_builtin_applications: builtin(BuiltinName::ecdsa),
+            bitwise_builtin_applications: builtin(BuiltinName::bitwise),
+            keccak_builtin_applications: builtin(BuiltinName::keccak),
+            segment_arena_builtin: builtin(BuiltinName::segment_arena),
+        }
+    }
+}
+
+/// The resources consumed by a transaction, shaped like the RPC `EXECUTION_RESOURCES`.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct TraceExecutionResources {
+    #[serde(flatten)]
+    pub computation_resources: ComputationResources,
+    pub data_availability: GasVector,
+}
+
+/// The state diff of a transaction, shaped like the RPC `STATE_DIFF`. Entries are sorted.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
+pub struct StateDiff {
+    pub storage_diffs: Vec<ContractStorageDiff>,
+    pub deprecated_declared_classes: Vec<ClassHash>,
+    pub declared_classes: Vec<DeclaredClass>,
+    pub deployed_contracts: Vec<DeployedContract>,
+    pub replaced_classes: Vec<ReplacedClass>,
+    pub nonces: Vec<ContractNonce>,
+}
+
+impl StateDiff {
+    /// Builds the state diff of the given writes, made on top of the given state; a class hash
+    /// written to an address with no class is a deployment, and otherwise a class replacement.
+    pub fn new(state_maps: StateMaps, state: &impl StateReader) -> StateResult<Self> {
+        let mut storage_diffs: BTreeMap<ContractAddress, Vec<StorageDiffEntry>> = BTreeMap::new();
+        for ((address, key), value) in state_maps.storage {
+            storage_diffs.entry(address).or_default().push(StorageDiffEntry { key, value });
+        }
+
+        let mut deployed_contracts = Vec::new();
+        let mut replaced_classes = Vec::new();
+        for (address, class_hash) in sorted(state_maps.class_hashes) {
+            if state.get_class_hash_at(address)? == ClassHash::default() {
+                deployed_contracts.push(DeployedContract { address, class_hash });
+            } else {
+                replaced_classes.push(ReplacedClass { contract_address: address, class_hash });
+            }
+        }
+
+        // Classes declared without a compiled class hash are Cairo 0 classes.
+        let mut deprecated_declared_classes: Vec<ClassHash> = state_maps
+            .declared_contracts
+            .into_iter()
+            .filter_map(|(class_hash, declared)| {
+                (declared && !state_maps.compiled_class_hashes.contains_key(&class_hash))
+                    .then_some(class_hash)
+            })
+            .collect();
+        deprecated_declared_classes.sort();
+
+        Ok(Self {
+            storage_diffs: storage_diffs
+                .into_iter()
+                .map(|(address, mut storage_entries)| {
+                    storage_entries.sort_by_key(|storage_entry| storage_entry.key);
+                    ContractStorageDiff { address, storage_entries }
+                })
+                .collect(),
+            deprecated_declared_classes,
+            declared_classes: sorted(state_maps.compiled_class_hashes)
+                .into_iter()
+                .map(|(class_hash, compiled_class_hash)| DeclaredClass {
+                    class_hash,
+                    compiled_class_hash,
+                })
+                .collect(),
+            deployed_contracts,
+            replaced_classes,
+            nonces: sorted(state_maps.nonces)
+                .into_iter()
+                .map(|(contract_address, nonce)| ContractNonce { contract_address, nonce })
+                .collect(),
+        })
+    }
+}
+
+fn sorted<K: Ord, V>(entries: impl IntoIterator<Item = (K, V)>) -> BTreeMap<K, V> {
+    entries.into_iter().collect()
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct ContractStorageDiff {
+    pub address: ContractAddress,
+    pub storage_entries: Vec<StorageDiffEntry>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct StorageDiffEntry {
+    pub key: StorageKey,
+    pub value: Felt,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct DeclaredClass {
+    pub class_hash: ClassHash,
+    pub compiled_class_hash: CompiledClassHash,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct DeployedContract {
+    pub address: ContractAddress,
+    pub class_hash: ClassHash,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct ReplacedClass {
+    pub contract_address: ContractAddress,
+    pub class_hash: ClassHash,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct ContractNonce {
+    pub contract_address: ContractAddress,
+    pub nonce: Nonce,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+pub enum PriceUnit {
+    #[serde(rename = "WEI")]
+    Wei,
+    #[serde(rename = "FRI")]
+    Fri,
+}
+
+impl From<FeeType> for PriceUnit {
+    fn from(fee_type: FeeType) -> Self {
+        match fee_type {
+            FeeType::Eth => Self::Wei,
+            FeeType::Strk => Self::Fri,
+        }
+    }
+}
+
+/// A fee estimate, shaped like the RPC `FEE_ESTIMATE`. The overall fee is computed from the
+/// consumed gas, regardless of the fee the transaction was (or was not) charged.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+pub struct FeeEstimate {
+    pub gas_consumed: u128,
+    pub gas_price: u128,
+    pub data_gas_consumed: u128,
+    pub data_gas_price: u128,
+    pub overall_fee: Fee,
+    pub unit: PriceUnit,
+}
+
+impl FeeEstimate {
+    pub fn new(block_context: &BlockContext, fee_type: &FeeType, gas_vector: GasVector) -> Self {
+        let gas_prices = &block_context.block_info.gas_prices;
+        Self {
+            gas_consumed: gas_vector.l1_gas,
+            gas_price: gas_prices.get_gas_price_by_fee_type(fee_type).into(),
+            data_gas_consumed: gas_vector.l1_data_gas,
+            data_gas_price: gas_prices.get_data_gas_price_by_fee_type(fee_type).into(),
+            overall_fee: get_fee_by_gas_vector(&block_context.block_info, gas_vector, fee_type),
+            unit: (*fee_type).into(),
+        }
+    }
+}
@@ -0,0 +1,238 @@
+use std::collections::HashMap;
+
+use assert_matches::assert_matches;
+use cairo_vm::types::builtin_name::BuiltinName;
+use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+use serde_json::json;
+use starknet_api::core::{EntryPointSelector, EthAddress};
+use starknet_api::transaction::{
+    EventContent, EventData, EventKey, Fee, L2ToL1Payload, TransactionVersion,
+};
+use starknet_api::{class_hash, contract_address, felt, patricia_key};
+
+use crate::blockifier::config::TransactionExecutorConfig;
+use crate::blockifier::simulation::{
+    ContractNonce, ExecuteInvocation, FunctionInvocation, PriceUnit, SimulatedTransaction,
+    SimulationFlags, StateDiff, TraceExecutionResources, TransactionTrace,
+};
+use crate::blockifier::transaction_executor::{TransactionExecutor, BLOCK_STATE_ACCESS_ERR};
+use crate::context::BlockContext;
+use crate::execution::call_info::{
+    CallExecution, CallInfo, MessageToL1, OrderedEvent, OrderedL2ToL1Message, Retdata,
+};
+use crate::execution::entry_point::CallEntryPoint;
+use crate::state::cached_state::StateMaps;
+use crate::state::state_api::StateReader;
+use crate::test_utils::dict_state_reader::DictStateReader;
+use crate::test_utils::{create_calldata, CairoVersion};
+use crate::transaction::objects::GasVector;
+use crate::transaction::test_utils::{account_invoke_tx, block_context, create_test_init_data};
+use crate::transaction::transaction_execution::Transaction;
+use crate::{compiled_class_hash, invoke_tx_args, nonce, storage_key};
+
+#[rstest]
+fn test_simulate(
+    block_context: BlockContext,
+    #[values(TransactionVersion::ONE, TransactionVersion::THREE)] version: TransactionVersion,
+    #[values(false, true)] skip_validate: bool,
+    #[values(false, true)] skip_fee_charge: bool,
+    #[values(false, true)] query_version: bool,
+) {
+    let init_data = create_test_init_data(&block_context.chain_info, CairoVersion::Cairo1);
+    let (account_address, contract_address) =
+        (init_data.account_address, init_data.contract_address);
+    let mut tx_executor = TransactionExecutor::new(
+        init_data.state,
+        block_context,
+        TransactionExecutorConfig::default(),
+    );
+    let txs = (0..2_u8)
+        .map(|i| {
+            Transaction::AccountTransaction(account_invoke_tx(invoke_tx_args! {
+                sender_address: account_address,
+                calldata: create_calldata(
+                    contract_address,
+                    "test_storage_read_write",
+                    &[felt!(0x10_u8), felt!(i + 1)],
+                ),
+                version,
+                nonce: nonce!(i),
+            }))
+        })
+        .collect();
+
+    let flags = SimulationFlags { skip_validate, skip_fee_charge, query_version };
+    let results: Vec<SimulatedTransaction> =
+        tx_executor.simulate(txs, flags).into_iter().map(Result::unwrap).collect();
+
+    for (i, SimulatedTransaction { transaction_trace, fee_estimation }) in
+        results.iter().enumerate()
+    {
+        let TransactionTrace::Invoke {
+            validate_invocation,
+            execute_invocation,
+            fee_transfer_invocation,
+            state_diff,
+            ..
+        } = transaction_trace
+        else {
+            panic!("Expected an invoke trace, got {transaction_trace:?}.");
+        };
+        assert_eq!(validate_invocation.is_none(), skip_validate);
+        assert_eq!(fee_transfer_invocation.is_none(), skip_fee_charge);
+        assert_matches!(
+            execute_invocation,
+            ExecuteInvocation::Success(Some(invocation))
+            if invocation.calls[0].contract_address == contract_address
+                && invocation.calls[0].result == vec![felt!(u8::try_from(i).unwrap() + 1)]
+        );
+        // Each transaction is simulated on top of the previous ones.
+        assert!(
+            state_diff.nonces.contains(&ContractNonce {
+                contract_address: account_address,
+                nonce: nonce!(i + 1)
+            })
+        );
+
+        // The fee is estimated whether or not it is charged.
+        assert!(fee_estimation.overall_fee > Fee(0));
+        let expected_unit =
+            if version == TransactionVersion::THREE { PriceUnit::Fri } else { PriceUnit::Wei };
+        assert_eq!(fee_estimation.unit, expected_unit);
+    }
+
+    // Nothing is committed to the block state.
+    let block_state = tx_executor.block_state.as_ref().expect(BLOCK_STATE_ACCESS_ERR);
+    assert_eq!(block_state.get_nonce_at(account_address).unwrap(), nonce!(0_u8));
+}
+
+#[test]
+fn test_trace_serialization() {
+    let (contract_address, deployed_address) =
+        (contract_address!("0x100"), contract_address!("0x200"));
+    let (class_hash, replacing_class_hash, declared_class_hash) =
+        (class_hash!("0x10"), class_hash!("0x11"), class_hash!("0x12"));
+    let deprecated_declared_class_hash = class_hash!("0x13");
+    let call_info = CallInfo {
+        call: CallEntryPoint {
+            class_hash: Some(class_hash),
+            entry_point_selector: EntryPointSelector(felt!("0x20")),
+            storage_address: contract_address,
+            ..Default::default()
+        },
+        execution: CallExecution {
+            retdata: Retdata(vec![felt!("0x1")]),
+            events: vec![OrderedEvent {
+                order: 0,
+                event: EventContent {
+                    keys: vec![EventKey(felt!("0x2"))],
+                    data: EventData(vec![felt!("0x3")]),
+                },
+            }],
+            l2_to_l1_messages: vec![OrderedL2ToL1Message {
+                order: 1,
+                message: MessageToL1 {
+                    to_address: EthAddress::try_from(felt!("0x4")).unwrap(),
+                    payload: L2ToL1Payload(vec![felt!("0x5")]),
+                },
+            }],
+            ..Default::default()
+        },
+        resources: ExecutionResources {
+            n_steps: 10,
+            n_memory_holes: 0,
+            builtin_instance_counter: HashMap::from([(BuiltinName::range_check, 2)]),
+        },
+        ..Default::default()
+    };
+
+    // The contract at `contract_address` is deployed before the transaction.
+    let state = DictStateReader {
+        address_to_class_hash: HashMap::from([(contract_address, class_hash)]),
+        ..Default::default()
+    };
+    let state_maps = StateMaps {
+        storage: HashMap::from([
+            ((contract_address, storage_key!("0x31")), felt!("0x7")),
+            ((contract_address, storage_key!("0x30")), felt!("0x6")),
+        ]),
+        nonces: HashMap::from([(contract_address, nonce!(1_u8))]),
+        class_hashes: HashMap::from([
+            (contract_address, replacing_class_hash),
+            (deployed_address, class_hash),
+        ]),
+        compiled_class_hashes: HashMap::from([(
+            declared_class_hash,
+            compiled_class_hash!(0x40_u8),
+        )]),
+        declared_contracts: HashMap::from([
+            (declared_class_hash, true),
+            (deprecated_declared_class_hash, true),
+        ]),
+    };
+
+    let transaction_trace = TransactionTrace::Invoke {
+        validate_invocation: None,
+        execute_invocation: ExecuteInvocation::Success(Some(FunctionInvocation::from(call_info))),
+        fee_transfer_invocation: None,
+        state_diff: StateDiff::new(state_maps, &state).unwrap(),
+        execution_resources: TraceExecutionResources {
+            computation_resources: (&ExecutionResources {
+                n_steps: 100,
+                n_memory_holes: 3,
+                builtin_instance_counter: HashMap::from([(BuiltinName::pedersen, 4)]),
+            })
+                .into(),
+            data_availability: GasVector { l1_gas: 0, l1_data_gas: 128 },
+        },
+    };
+
+    assert_eq!(
+        serde_json::to_value(transaction_trace).unwrap(),
+        json!({
+            "type": "INVOKE",
+            "execute_invocation": {
+                "contract_address": "0x100",
+                "entry_point_selector": "0x20",
+                "calldata": [],
+                "caller_address": "0x0",
+                "class_hash": "0x10",
+                "entry_point_type": "EXTERNAL",
+                "call_type": "CALL",
+                "result": ["0x1"],
+                "calls": [],
+                "events": [{ "order": 0, "keys": ["0x2"], "data": ["0x3"] }],
+                "messages": [{
+                    "order": 1,
+                    "from_address": "0x100",
+                    "to_address": "0x0000000000000000000000000000000000000004",
+                    "payload": ["0x5"]
+                }],
+                "execution_resources": { "steps": 10, "range_check_builtin_applications": 2 },
+                "is_reverted": false
+            },
+            "state_diff": {
+                "storage_diffs": [{
+                    "address": "0x100",
+                    "storage_entries": [
+                        { "key": "0x30", "value": "0x6" },
+                        { "key": "0x31", "value": "0x7" }
+                    ]
+                }],
+                "deprecated_declared_classes": ["0x13"],
+                "declared_classes": [{ "class_hash": "0x12", "compiled_class_hash": "0x40" }],
+                "deployed_contracts": [{ "address": "0x200", "class_hash": "0x10" }],
+                "replaced_classes": [{ "contract_address": "0x100", "class_hash": "0x11" }],
+                "nonces": [{ "contract_address": "0x100", "nonce": "0x1" }]
+            },
+            "execution_resources": {
+                "steps": 100,
+                "memory_holes": 3,
+                "pedersen_builtin_applications": 4,
+                "data_availability": { "l1_gas": 0, "l1_data_gas": 128 }
+            }
+        })
+    );
+}
@@ -15,6 +15,7 @@ use crate::fee::fee_checks::PostValidationReport;
 use crate::state::cached_state::CachedState;
 use crate::state::errors::StateError;
 use crate::state::state_api::StateReader;
+use crate::state::state_override::{StateOverrideReader, StateOverrides};
 use crate::transaction::account_transaction::AccountTransaction;
 use crate::transaction::errors::{TransactionExecutionError, TransactionPreValidationError};
 use crate::transaction::transaction_execution::Transaction;
@@ -150,3 +151,18 @@ impl<S: StateReader> StatefulValidator<S> {
             .get_nonce_at(account_address)?)
     }
 }
+
+impl<S: StateReader> StatefulValidator<StateOverrideReader<S>> {
+    /// Creates a validator whose state reads the given overrides instead of the corresponding
+    /// values in the given state reader, which is left untouched.
+    pub fn create_with_state_overrides(
+        state_reader: S,
+        overrides: StateOverrides,
+        block_context: BlockContext,
+    ) -> Self {
+        Self::create(
+            CachedState::new(StateOverrideReader::new(state_reader, overrides)),
+            block_context,
+        )
+    }
+}
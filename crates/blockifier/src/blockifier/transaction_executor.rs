@@ -13,13 +13,20 @@ use starknet_api::core::ClassHash;
 use thiserror::Error;
 
 use crate::blockifier::config::TransactionExecutorConfig;
+use crate::blockifier::fee_estimation::{estimate_fee, FeeEstimation};
+use crate::blockifier::observer::{report_tx, ExecutionObserver, ExecutionRecorder};
+use crate::blockifier::simulation::{SimulatedTransaction, SimulationFlags, StateDiff};
 use crate::bouncer::{Bouncer, BouncerWeights};
 #[cfg(feature = "concurrency")]
 use crate::concurrency::worker_logic::WorkerExecutor;
 use crate::context::BlockContext;
+#[cfg(feature = "native")]
+use crate::execution::native::differential_execution::{DivergenceReport, DivergenceReporter};
 use crate::state::cached_state::{CachedState, CommitmentStateDiff, TransactionalState};
 use crate::state::errors::StateError;
 use crate::state::state_api::StateReader;
+use crate::state::state_override::{StateOverrideReader, StateOverrides};
+use crate::transaction::account_transaction::AccountTransaction;
 use crate::transaction::errors::TransactionExecutionError;
 use crate::transaction::objects::TransactionExecutionInfo;
 use crate::transaction::transaction_execution::Transaction;
@@ -57,15 +64,26 @@ pub struct TransactionExecutor<S: StateReader> {
     // committing the chunk. The block state is wrapped with an Option<_> to allow setting it to
     // `None` while it is moved to the worker executor.
     pub block_state: Option<CachedState<S>>,
+
+    // Set by `set_execution_observer`.
+    execution_observer: Option<Box<dyn ExecutionObserver>>,
 }
 
 impl<S: StateReader> TransactionExecutor<S> {
     pub fn new(
         block_state: CachedState<S>,
-        block_context: BlockContext,
+        mut block_context: BlockContext,
         config: TransactionExecutorConfig,
     ) -> Self {
         log::debug!("Initializing Transaction Executor...");
+        #[cfg(feature = "native")]
+        {
+            if config.differential_execution_config.enabled {
+                block_context.divergence_reporter = Some(DivergenceReporter::default());
+            }
+            block_context.native_execution_config = config.native_execution_config.clone();
+            block_context.native_worker = config.native_worker.clone();
+        }
         let bouncer_config = block_context.bouncer_config.clone();
         // Note: the state might not be empty even at this point; it is the creator's
         // responsibility to tune the bouncer according to pre and post block process.
@@ -74,18 +92,44 @@ impl<S: StateReader> TransactionExecutor<S> {
             bouncer: Bouncer::new(bouncer_config),
             config,
             block_state: Some(block_state),
+            execution_observer: None,
         };
         log::debug!("Initialized Transaction Executor.");
 
         tx_executor
     }
 
+    /// Reports the transactions executed from now on (sequentially or concurrently) to the given
+    /// observer. Simulated transactions and fee estimations are not reported.
+    pub fn set_execution_observer(&mut self, observer: Box<dyn ExecutionObserver>) {
+        self.execution_observer = Some(observer);
+        self.block_context.execution_recorder = Some(ExecutionRecorder::default());
+    }
+
     /// Executes the given transaction on the state maintained by the executor.
     /// Returns the execution result (info or error) if there is room for the transaction;
     /// Otherwise, returns BlockFull error.
     pub fn execute(
         &mut self,
         tx: &Transaction,
+    ) -> TransactionExecutorResult<TransactionExecutionInfo> {
+        let result = self.execute_unobserved(tx);
+        if let (Some(observer), Some(recorder)) =
+            (self.execution_observer.as_mut(), &self.block_context.execution_recorder)
+        {
+            // The steps of a transaction that does not fit in the block are discarded with it.
+            let events = recorder.take_events();
+            if !matches!(result, Err(TransactionExecutorError::BlockFull)) {
+                report_tx(observer.as_mut(), tx, events, &result);
+            }
+        }
+
+        result
+    }
+
+    fn execute_unobserved(
+        &mut self,
+        tx: &Transaction,
     ) -> TransactionExecutorResult<TransactionExecutionInfo> {
         let mut transactional_state = TransactionalState::create_transactional(
             self.block_state.as_mut().expect(BLOCK_STATE_ACCESS_ERR),
@@ -115,6 +159,88 @@ impl<S: StateReader> TransactionExecutor<S> {
         }
     }
 
+    /// Simulates the given transactions one after the other, each on top of the state resulting
+    /// from the previous ones, and returns their traces. Nothing is committed to the block state,
+    /// and the bouncer is not updated.
+    pub fn simulate(
+        &mut self,
+        txs: Vec<Transaction>,
+        flags: SimulationFlags,
+    ) -> Vec<TransactionExecutorResult<SimulatedTransaction>> {
+        let mut simulation_state = TransactionalState::create_transactional(
+            self.block_state.as_mut().expect(BLOCK_STATE_ACCESS_ERR),
+        );
+        let execution_flags = ExecutionFlags {
+            charge_fee: !flags.skip_fee_charge,
+            validate: !flags.skip_validate,
+            concurrency_mode: false,
+        };
+        let results = txs
+            .into_iter()
+            .map(|mut tx| -> TransactionExecutorResult<SimulatedTransaction> {
+                if flags.query_version {
+                    tx.set_only_query();
+                }
+                let mut transactional_state =
+                    TransactionalState::create_transactional(&mut simulation_state);
+                let tx_execution_info =
+                    tx.execute_raw(&mut transactional_state, &self.block_context, execution_flags)?;
+                let state_diff = StateDiff::new(
+                    transactional_state.to_state_diff()?,
+                    &transactional_state.state,
+                )?;
+                transactional_state.commit();
+                Ok(SimulatedTransaction::new(
+                    &tx,
+                    tx_execution_info,
+                    state_diff,
+                    &self.block_context,
+                ))
+            })
+            .collect();
+        simulation_state.abort();
+        self.discard_recorded_events();
+
+        results
+    }
+
+    /// Estimates the fees of the given transactions (see [estimate_fee]), each on top of the
+    /// previous ones, applied under their suggested bounds. The block state is left unchanged.
+    pub fn estimate_fee(
+        &mut self,
+        txs: &[AccountTransaction],
+    ) -> Vec<TransactionExecutorResult<FeeEstimation>> {
+        let mut estimation_state = TransactionalState::create_transactional(
+            self.block_state.as_mut().expect(BLOCK_STATE_ACCESS_ERR),
+        );
+        let results = txs
+            .iter()
+            .map(|tx| Ok(estimate_fee(tx, &mut estimation_state, &self.block_context)?))
+            .collect();
+        estimation_state.abort();
+        self.discard_recorded_events();
+
+        results
+    }
+
+    // Discards the steps recorded by unreported executions, e.g., simulations.
+    fn discard_recorded_events(&self) {
+        if let Some(recorder) = &self.block_context.execution_recorder {
+            recorder.take_events();
+        }
+    }
+
+    /// Returns the divergences between VM and native executions found since the last call.
+    /// Always empty, unless the executor runs in differential execution mode.
+    #[cfg(feature = "native")]
+    pub fn take_divergence_reports(&self) -> Vec<DivergenceReport> {
+        self.block_context
+            .divergence_reporter
+            .as_ref()
+            .map(DivergenceReporter::take_reports)
+            .unwrap_or_default()
+    }
+
     pub fn execute_txs_sequentially(
         &mut self,
         txs: &[Transaction],
@@ -175,6 +301,23 @@ impl<S: StateReader> TransactionExecutor<S> {
     }
 }
 
+impl<S: StateReader> TransactionExecutor<StateOverrideReader<S>> {
+    /// Creates an executor whose state reads the given overrides instead of the corresponding
+    /// values in the given state reader, which is left untouched.
+    pub fn new_with_state_overrides(
+        state_reader: S,
+        overrides: StateOverrides,
+        block_context: BlockContext,
+        config: TransactionExecutorConfig,
+    ) -> Self {
+        Self::new(
+            CachedState::new(StateOverrideReader::new(state_reader, overrides)),
+            block_context,
+            config,
+        )
+    }
+}
+
 impl<S: StateReader + Send + Sync> TransactionExecutor<S> {
     /// Executes the given transactions on the state maintained by the executor.
     /// Stops if and when there is no more room in the block, and returns the executed transactions'
@@ -268,7 +411,7 @@ impl<S: StateReader + Send + Sync> TransactionExecutor<S> {
         let n_committed_txs = worker_executor.scheduler.get_n_committed_txs();
         let mut tx_execution_results = Vec::new();
         let mut visited_pcs: HashMap<ClassHash, HashSet<usize>> = HashMap::new();
-        for execution_output in worker_executor.execution_outputs.iter() {
+        for (tx, execution_output) in chunk.iter().zip(worker_executor.execution_outputs.iter()) {
             if tx_execution_results.len() >= n_committed_txs {
                 break;
             }
@@ -277,8 +420,17 @@ impl<S: StateReader + Send + Sync> TransactionExecutor<S> {
                 .expect("Failed to lock execution output.")
                 .take()
                 .expect("Output must be ready.");
-            tx_execution_results
-                .push(locked_execution_output.result.map_err(TransactionExecutorError::from));
+            let tx_execution_result =
+                locked_execution_output.result.map_err(TransactionExecutorError::from);
+            if let Some(observer) = self.execution_observer.as_mut() {
+                report_tx(
+                    observer.as_mut(),
+                    tx,
+                    locked_execution_output.execution_events,
+                    &tx_execution_result,
+                );
+            }
+            tx_execution_results.push(tx_execution_result);
             for (class_hash, class_visited_pcs) in locked_execution_output.visited_pcs {
                 visited_pcs.entry(class_hash).or_default().extend(class_visited_pcs);
             }
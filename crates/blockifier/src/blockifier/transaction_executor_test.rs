@@ -1,3 +1,6 @@
+#[cfg(feature = "native")]
+use std::collections::HashSet;
+
 use assert_matches::assert_matches;
 use pretty_assertions::assert_eq;
 use rstest::rstest;
@@ -9,6 +12,8 @@ use crate::blockifier::config::TransactionExecutorConfig;
 use crate::blockifier::transaction_executor::{
     TransactionExecutor, TransactionExecutorError, BLOCK_STATE_ACCESS_ERR,
 };
+#[cfg(feature = "native")]
+use crate::bouncer::get_casm_hash_calculation_resources;
 use crate::bouncer::{Bouncer, BouncerWeights};
 use crate::context::BlockContext;
 use crate::state::cached_state::CachedState;
@@ -206,6 +211,44 @@ fn test_invoke(
     tx_executor_test_body(state, block_context, tx, expected_bouncer_weights);
 }
 
+#[cfg(feature = "native")]
+#[rstest]
+fn test_invoke_native(block_context: BlockContext) {
+    let test_contract = FeatureContract::SierraTestContract;
+    let account_contract = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo1);
+    let state = test_state(
+        &block_context.chain_info,
+        BALANCE,
+        &[(test_contract, 1), (account_contract, 1)],
+    );
+
+    let calldata = create_calldata(
+        test_contract.get_instance_address(0),
+        "test_storage_read_write",
+        &[felt!(0x1_u8), felt!(0x2_u8)],
+    );
+    let tx = Transaction::AccountTransaction(account_invoke_tx(invoke_tx_args! {
+        sender_address: account_contract.get_instance_address(0),
+        calldata,
+        version: TransactionVersion::THREE,
+    }));
+    let mut tx_executor =
+        TransactionExecutor::new(state, block_context, TransactionExecutorConfig::default());
+    tx_executor.execute(&tx).unwrap();
+
+    // The hash computation of native classes is estimated as that of their Casm equivalents.
+    let casm_hash_computation_resources = get_casm_hash_calculation_resources(
+        tx_executor.block_state.as_ref().expect(BLOCK_STATE_ACCESS_ERR),
+        &HashSet::from([test_contract.get_class_hash()]),
+    )
+    .unwrap();
+    assert_eq!(
+        casm_hash_computation_resources,
+        test_contract.get_casm_equivalent_class().estimate_casm_hash_computation_resources()
+    );
+    tx_executor.finalize().unwrap();
+}
+
 #[rstest]
 fn test_l1_handler(block_context: BlockContext) {
     let test_contract = FeatureContract::TestContract(CairoVersion::Cairo1);
@@ -1,6 +1,9 @@
 use std::collections::{HashMap, HashSet};
+use std::fmt::Debug;
+use std::hash::Hash;
 use std::sync::{Arc, Mutex, MutexGuard};
 
+use itertools::Itertools;
 use starknet_api::core::{ClassHash, CompiledClassHash, ContractAddress, Nonce};
 use starknet_api::state::StorageKey;
 use starknet_types_core::felt::Felt;
@@ -8,9 +11,9 @@ use starknet_types_core::felt::Felt;
 use crate::concurrency::versioned_storage::VersionedStorage;
 use crate::concurrency::TxIndex;
 use crate::execution::contract_class::ContractClass;
-use crate::state::cached_state::{ContractClassMapping, StateMaps};
+use crate::state::cached_state::{ContractClassMapping, StateMaps, StorageEntry};
 use crate::state::errors::StateError;
-use crate::state::state_api::{StateReader, StateResult, UpdatableState};
+use crate::state::state_api::{check_batch_read, StateReader, StateResult, UpdatableState};
 
 #[cfg(test)]
 #[path = "versioned_state_test.rs"]
@@ -283,6 +286,35 @@ impl<S: StateReader> UpdatableState for VersionedStateProxy<S> {
     }
 }
 
+/// Reads the given keys at the given version. Keys with no value in the storage are read from the
+/// initial state, in one batch, and cached as initial values.
+fn read_batch<K, V>(
+    storage: &mut VersionedStorage<K, V>,
+    tx_index: TxIndex,
+    keys: &[K],
+    read_initial_values: impl FnOnce(&[K]) -> StateResult<Vec<V>>,
+) -> StateResult<Vec<V>>
+where
+    K: Clone + Copy + Eq + Hash + Debug,
+    V: Clone + Debug,
+{
+    let missing_keys: Vec<K> = keys
+        .iter()
+        .copied()
+        .filter(|key| storage.read(tx_index, *key).is_none())
+        .unique()
+        .collect();
+    if !missing_keys.is_empty() {
+        let initial_values =
+            check_batch_read(missing_keys.len(), read_initial_values(&missing_keys)?)?;
+        for (key, initial_value) in missing_keys.into_iter().zip(initial_values) {
+            storage.set_initial_value(key, initial_value);
+        }
+    }
+
+    Ok(keys.iter().map(|key| storage.read(tx_index, *key).expect(READ_ERR)).collect())
+}
+
 impl<S: StateReader> StateReader for VersionedStateProxy<S> {
     fn get_storage_at(
         &self,
@@ -312,6 +344,22 @@ impl<S: StateReader> StateReader for VersionedStateProxy<S> {
         }
     }
 
+    fn get_storage_values(&self, storage_entries: &[StorageEntry]) -> StateResult<Vec<Felt>> {
+        let mut state = self.state();
+        let state = &mut *state;
+        read_batch(&mut state.storage, self.tx_index, storage_entries, |missing_entries| {
+            state.initial_state.get_storage_values(missing_entries)
+        })
+    }
+
+    fn get_nonces(&self, contract_addresses: &[ContractAddress]) -> StateResult<Vec<Nonce>> {
+        let mut state = self.state();
+        let state = &mut *state;
+        read_batch(&mut state.nonces, self.tx_index, contract_addresses, |missing_addresses| {
+            state.initial_state.get_nonces(missing_addresses)
+        })
+    }
+
     fn get_class_hash_at(&self, contract_address: ContractAddress) -> StateResult<ClassHash> {
         let mut state = self.state();
         match state.class_hashes.read(self.tx_index, contract_address) {
@@ -642,3 +642,59 @@ fn test_versioned_proxy_state_flow(
         modified_block_state.get_compiled_contract_class(class_hash).unwrap() == contract_class_2
     );
 }
+
+#[test]
+fn test_versioned_state_proxy_batch_reads() {
+    let contract_address = contract_address!("0x1");
+    let another_contract_address = contract_address!("0x2");
+    let key = storage_key!(0x10_u8);
+    let another_key = storage_key!(0x11_u8);
+    let (felt, another_felt, written_felt) = (felt!(13_u8), felt!(14_u8), felt!(15_u8));
+    let (nonce, another_nonce, written_nonce) = (nonce!(2_u8), nonce!(3_u8), nonce!(4_u8));
+
+    let cached_state = CachedState::from(DictStateReader {
+        storage_view: HashMap::from([
+            ((contract_address, key), felt),
+            ((contract_address, another_key), another_felt),
+        ]),
+        address_to_nonce: HashMap::from([
+            (contract_address, nonce),
+            (another_contract_address, another_nonce),
+        ]),
+        ..Default::default()
+    });
+    let safe_versioned_state = safe_versioned_state_for_testing(cached_state);
+    safe_versioned_state.pin_version(3).state().apply_writes(
+        3,
+        &StateMaps {
+            storage: HashMap::from([((contract_address, key), written_felt)]),
+            nonces: HashMap::from([(contract_address, written_nonce)]),
+            ..Default::default()
+        },
+        &HashMap::default(),
+    );
+
+    // Repeated keys are read once from the initial state, and served by version.
+    let storage_entries =
+        [(contract_address, key), (contract_address, another_key), (contract_address, key)];
+    let contract_addresses = [contract_address, another_contract_address, contract_address];
+    let earlier_proxy = safe_versioned_state.pin_version(2);
+    assert_eq!(
+        earlier_proxy.get_storage_values(&storage_entries).unwrap(),
+        vec![felt, another_felt, felt]
+    );
+    assert_eq!(
+        earlier_proxy.get_nonces(&contract_addresses).unwrap(),
+        vec![nonce, another_nonce, nonce]
+    );
+
+    let later_proxy = safe_versioned_state.pin_version(5);
+    assert_eq!(
+        later_proxy.get_storage_values(&storage_entries).unwrap(),
+        vec![written_felt, another_felt, written_felt]
+    );
+    assert_eq!(
+        later_proxy.get_nonces(&contract_addresses).unwrap(),
+        vec![written_nonce, another_nonce, written_nonce]
+    );
+}
@@ -1,3 +1,4 @@
+use std::borrow::Cow;
 use std::collections::{HashMap, HashSet};
 use std::fmt::Debug;
 use std::sync::Mutex;
@@ -7,6 +8,7 @@ use std::time::Duration;
 use starknet_api::core::ClassHash;
 
 use super::versioned_state::VersionedState;
+use crate::blockifier::observer::{ExecutionEvent, ExecutionRecorder};
 use crate::blockifier::transaction_executor::TransactionExecutorError;
 use crate::bouncer::Bouncer;
 use crate::concurrency::fee_utils::complete_fee_transfer_flow;
@@ -36,6 +38,8 @@ pub struct ExecutionTaskOutput {
     pub contract_classes: ContractClassMapping,
     pub visited_pcs: HashMap<ClassHash, HashSet<usize>>,
     pub result: TransactionExecutionResult<TransactionExecutionInfo>,
+    /// The steps of the execution, if observed; reported once the transaction is committed.
+    pub(crate) execution_events: Vec<ExecutionEvent>,
 }
 
 pub struct WorkerExecutor<'a, S: StateReader> {
@@ -128,8 +132,21 @@ impl<'a, S: StateReader> WorkerExecutor<'a, S> {
             TransactionalState::create_transactional(&mut tx_versioned_state);
         let execution_flags =
             ExecutionFlags { charge_fee: true, validate: true, concurrency_mode: true };
+        // Each execution is recorded separately, as they may run concurrently and be re-executed.
+        let block_context = match self.block_context.execution_recorder {
+            Some(_) => Cow::Owned(BlockContext {
+                execution_recorder: Some(ExecutionRecorder::default()),
+                ..self.block_context.clone()
+            }),
+            None => Cow::Borrowed(self.block_context),
+        };
         let execution_result =
-            tx.execute_raw(&mut transactional_state, self.block_context, execution_flags);
+            tx.execute_raw(&mut transactional_state, &block_context, execution_flags);
+        let execution_events = block_context
+            .execution_recorder
+            .as_ref()
+            .map(ExecutionRecorder::take_events)
+            .unwrap_or_default();
 
         if execution_result.is_ok() {
             // TODO(Noa, 15/05/2024): use `tx_versioned_state` when we add support to transactional
@@ -156,6 +173,7 @@ impl<'a, S: StateReader> WorkerExecutor<'a, S> {
             contract_classes,
             visited_pcs,
             result: execution_result,
+            execution_events,
         });
     }
 
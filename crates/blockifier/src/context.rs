@@ -1,7 +1,17 @@
+use std::sync::Arc;
+
 use starknet_api::core::{ChainId, ContractAddress};
 
 use crate::blockifier::block::BlockInfo;
+#[cfg(feature = "native")]
+use crate::blockifier::config::NativeExecutionConfig;
+use crate::blockifier::observer::ExecutionRecorder;
 use crate::bouncer::BouncerConfig;
+#[cfg(feature = "native")]
+use crate::execution::native::differential_execution::DivergenceReporter;
+#[cfg(feature = "native")]
+use crate::execution::native::worker::NativeWorker;
+use crate::execution::syscalls::interceptor::SyscallInterceptor;
 use crate::transaction::objects::{
     FeeType, HasRelatedFeeType, TransactionInfo, TransactionInfoCreator,
 };
@@ -30,6 +40,19 @@ pub struct BlockContext {
     pub(crate) chain_info: ChainInfo,
     pub(crate) versioned_constants: VersionedConstants,
     pub(crate) bouncer_config: BouncerConfig,
+    // Registered on the execution contexts of the block's transactions.
+    pub(crate) syscall_interceptor: Option<Arc<dyn SyscallInterceptor>>,
+    // Set by the transaction executor when its executions are observed.
+    pub(crate) execution_recorder: Option<ExecutionRecorder>,
+    // Set by the transaction executor when running in differential execution mode.
+    #[cfg(feature = "native")]
+    pub(crate) divergence_reporter: Option<DivergenceReporter>,
+    // Set by the transaction executor.
+    #[cfg(feature = "native")]
+    pub(crate) native_execution_config: NativeExecutionConfig,
+    // Set by the transaction executor when native execution is isolated in worker processes.
+    #[cfg(feature = "native")]
+    pub(crate) native_worker: Option<NativeWorker>,
 }
 
 impl BlockContext {
@@ -39,7 +62,20 @@ impl BlockContext {
         versioned_constants: VersionedConstants,
         bouncer_config: BouncerConfig,
     ) -> Self {
-        BlockContext { block_info, chain_info, versioned_constants, bouncer_config }
+        BlockContext {
+            block_info,
+            chain_info,
+            versioned_constants,
+            bouncer_config,
+            syscall_interceptor: None,
+            execution_recorder: None,
+            #[cfg(feature = "native")]
+            divergence_reporter: None,
+            #[cfg(feature = "native")]
+            native_execution_config: NativeExecutionConfig::default(),
+            #[cfg(feature = "native")]
+            native_worker: None,
+        }
     }
 
     pub fn block_info(&self) -> &BlockInfo {
@@ -54,6 +90,11 @@ impl BlockContext {
         &self.versioned_constants
     }
 
+    /// Intercepts the syscalls executed in this block; see [SyscallInterceptor].
+    pub fn set_syscall_interceptor(&mut self, syscall_interceptor: Arc<dyn SyscallInterceptor>) {
+        self.syscall_interceptor = Some(syscall_interceptor);
+    }
+
     pub fn to_tx_context(
         &self,
         tx_info_creator: &impl TransactionInfoCreator,
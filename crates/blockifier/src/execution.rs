@@ -9,6 +9,7 @@ pub mod entry_point_execution;
 pub mod errors;
 pub mod execution_utils;
 pub mod hint_code;
+#[cfg(feature = "native")]
 pub mod native;
 pub mod stack_trace;
 pub mod syscalls;
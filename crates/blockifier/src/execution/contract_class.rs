@@ -1,20 +1,12 @@
 use std::collections::{HashMap, HashSet};
-use std::ops::{Deref, Index};
+use std::mem;
+use std::ops::Deref;
 use std::sync::Arc;
 
 use cairo_lang_casm;
 use cairo_lang_casm::hints::Hint;
-use cairo_lang_sierra::ids::FunctionId;
-use cairo_lang_starknet_classes::casm_contract_class::{
-    CasmContractClass, CasmContractEntryPoint, StarknetSierraCompilationError,
-};
-use cairo_lang_starknet_classes::contract_class::{
-    ContractClass as SierraContractClass, ContractEntryPoint,
-    ContractEntryPoints as SierraContractEntryPoints,
-};
+use cairo_lang_starknet_classes::casm_contract_class::{CasmContractClass, CasmContractEntryPoint};
 use cairo_lang_starknet_classes::NestedIntList;
-use cairo_lang_utils::bigint::BigUintAsHex;
-use cairo_native::executor::AotNativeExecutor;
 use cairo_vm::serde::deserialize_program::{
     ApTracking, FlowTrackingData, HintParams, ReferenceManager,
 };
@@ -25,7 +17,7 @@ use cairo_vm::types::relocatable::MaybeRelocatable;
 use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
 use itertools::Itertools;
 use serde::de::Error as DeserializationError;
-use serde::{Deserialize, Deserializer};
+use serde::{Deserialize, Deserializer, Serialize};
 use starknet_api::core::EntryPointSelector;
 use starknet_api::deprecated_contract_class::{
     ContractClass as DeprecatedContractClass, EntryPoint, EntryPointOffset, EntryPointType,
@@ -33,15 +25,16 @@ use starknet_api::deprecated_contract_class::{
 };
 use starknet_types_core::felt::Felt;
 
-use super::entry_point::EntryPointExecutionResult;
-use super::errors::EntryPointExecutionError;
 use super::execution_utils::poseidon_hash_many_cost;
-use super::native::utils::contract_entrypoint_to_entrypoint_selector;
 use crate::abi::abi_utils::selector_from_name;
 use crate::abi::constants::{self, CONSTRUCTOR_ENTRY_POINT_NAME};
 use crate::execution::entry_point::CallEntryPoint;
 use crate::execution::errors::{ContractClassError, PreExecutionError};
 use crate::execution::execution_utils::sn_api_to_cairo_vm_program;
+#[cfg(feature = "native")]
+pub use crate::execution::native::contract_class::{
+    NativeContractClassV1, NativeContractClassV1Inner, NativeEntryPointError,
+};
 use crate::fee::eth_gas_constants;
 use crate::transaction::errors::TransactionExecutionError;
 
@@ -60,6 +53,7 @@ pub type ContractClassResult<T> = Result<T, ContractClassError>;
 pub enum ContractClass {
     V0(ContractClassV0),
     V1(ContractClassV1),
+    #[cfg(feature = "native")]
     V1Native(NativeContractClassV1),
 }
 
@@ -68,6 +62,7 @@ impl ContractClass {
         match self {
             ContractClass::V0(class) => class.constructor_selector(),
             ContractClass::V1(class) => class.constructor_selector(),
+            #[cfg(feature = "native")]
             ContractClass::V1Native(class) => class.constructor_selector(),
         }
     }
@@ -76,7 +71,10 @@ impl ContractClass {
         match self {
             ContractClass::V0(class) => class.estimate_casm_hash_computation_resources(),
             ContractClass::V1(class) => class.estimate_casm_hash_computation_resources(),
-            ContractClass::V1Native(_) => todo!("sierra estimate casm hash computation resources"),
+            #[cfg(feature = "native")]
+            ContractClass::V1Native(class) => {
+                class.casm_equivalent_class().estimate_casm_hash_computation_resources()
+            }
         }
     }
 
@@ -89,7 +87,10 @@ impl ContractClass {
                 panic!("get_visited_segments is not supported for v0 contracts.")
             }
             ContractClass::V1(class) => class.get_visited_segments(visited_pcs),
-            ContractClass::V1Native(_) => todo!("sierra visited segments"),
+            #[cfg(feature = "native")]
+            ContractClass::V1Native(class) => {
+                class.casm_equivalent_class().get_visited_segments(visited_pcs)
+            }
         }
     }
 
@@ -97,7 +98,18 @@ impl ContractClass {
         match self {
             ContractClass::V0(class) => class.bytecode_length(),
             ContractClass::V1(class) => class.bytecode_length(),
-            ContractClass::V1Native(_) => todo!("sierra estimate casm hash computation resources"),
+            #[cfg(feature = "native")]
+            ContractClass::V1Native(class) => class.casm_equivalent_class().bytecode_length(),
+        }
+    }
+
+    /// Returns a rough estimation of the memory held by the class; used to bound caches.
+    pub fn estimated_size_in_bytes(&self) -> usize {
+        match self {
+            ContractClass::V0(class) => class.estimated_size_in_bytes(),
+            ContractClass::V1(class) => class.estimated_size_in_bytes(),
+            #[cfg(feature = "native")]
+            ContractClass::V1Native(class) => class.estimated_size_in_bytes(),
         }
     }
 }
@@ -130,6 +142,16 @@ impl ContractClassV0 {
         self.program.data_len()
     }
 
+    fn estimated_size_in_bytes(&self) -> usize {
+        // Hints, identifiers and references, which dominate the size of most programs, are only
+        // accessible through the serialized program (which also includes the bytecode).
+        let serialized_program_size =
+            self.program.serialize().map_or(0, |serialized_program| serialized_program.len());
+        self.bytecode_length() * mem::size_of::<MaybeRelocatable>()
+            + self.n_entry_points() * mem::size_of::<EntryPoint>()
+            + serialized_program_size
+    }
+
     fn estimate_casm_hash_computation_resources(&self) -> ExecutionResources {
         let hashed_data_size = (constants::CAIRO0_ENTRY_POINT_STRUCT_SIZE * self.n_entry_points())
             + self.n_builtins()
@@ -193,6 +215,14 @@ impl ContractClassV1 {
         &self.bytecode_segment_lengths
     }
 
+    pub(crate) fn estimated_size_in_bytes(&self) -> usize {
+        let entry_points_size = self.entry_points_by_type.values().map(Vec::len).sum::<usize>()
+            * mem::size_of::<EntryPointV1>();
+        let hints_size: usize =
+            self.hints.keys().map(|hint_code| hint_code.len() + mem::size_of::<Hint>()).sum();
+        self.bytecode_length() * mem::size_of::<MaybeRelocatable>() + entry_points_size + hints_size
+    }
+
     pub fn get_entry_point(
         &self,
         call: &CallEntryPoint,
@@ -351,10 +381,10 @@ pub struct ContractClassV1Inner {
     pub program: Program,
     pub entry_points_by_type: HashMap<EntryPointType, Vec<EntryPointV1>>,
     pub hints: HashMap<String, Hint>,
-    bytecode_segment_lengths: NestedIntList,
+    pub(crate) bytecode_segment_lengths: NestedIntList,
 }
 
-#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+#[derive(Clone, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize)]
 pub struct EntryPointV1 {
     pub selector: EntryPointSelector,
     pub offset: EntryPointOffset,
@@ -518,6 +548,7 @@ impl ClassInfo {
         let (contract_class_version, condition) = match contract_class {
             ContractClass::V0(_) => (0, sierra_program_length == 0),
             ContractClass::V1(_) => (1, sierra_program_length > 0),
+            #[cfg(feature = "native")]
             ContractClass::V1Native(_) => (1, sierra_program_length > 0),
         };
 
@@ -531,195 +562,3 @@ impl ClassInfo {
         }
     }
 }
-
-#[derive(Clone, Debug, PartialEq)]
-pub struct NativeContractClassV1(pub Arc<NativeContractClassV1Inner>);
-impl Deref for NativeContractClassV1 {
-    type Target = NativeContractClassV1Inner;
-
-    fn deref(&self) -> &Self::Target {
-        &self.0
-    }
-}
-
-#[derive(Debug, thiserror::Error)]
-pub enum NativeEntryPointError {
-    #[error("FunctionId {0} not found")]
-    FunctionIdNotFound(usize),
-}
-
-impl NativeContractClassV1 {
-    fn constructor_selector(&self) -> Option<EntryPointSelector> {
-        self.entry_points_by_type.constructor.first().map(|ep| ep.selector)
-    }
-
-    /// Initialize a compiled contract class for native.
-    ///
-    /// executor must be derived from sierra_program which in turn must be derived from
-    /// sierra_contract_class.
-    pub fn new(
-        executor: AotNativeExecutor,
-        sierra_contract_class: SierraContractClass,
-    ) -> Result<NativeContractClassV1, NativeEntryPointError> {
-        let contract = NativeContractClassV1Inner::new(executor, sierra_contract_class)?;
-
-        Ok(Self(Arc::new(contract)))
-    }
-
-    pub fn to_casm_contract_class(
-        self,
-    ) -> Result<CasmContractClass, StarknetSierraCompilationError> {
-        let sierra_contract_class = SierraContractClass {
-            // Cloning because these are behind an Arc.
-            sierra_program: self.sierra_program_raw.clone(),
-            entry_points_by_type: self.fallback_entry_points_by_type.clone(),
-            abi: None,
-            sierra_program_debug_info: None,
-            contract_class_version: String::default(),
-        };
-
-        CasmContractClass::from_contract_class(sierra_contract_class, false, usize::MAX)
-    }
-
-    /// Returns an entry point into the natively compiled contract.
-    pub fn get_entrypoint(
-        &self,
-        entry_point_type: EntryPointType,
-        entrypoint_selector: EntryPointSelector,
-    ) -> EntryPointExecutionResult<&FunctionId> {
-        let entrypoints = &self.entry_points_by_type[entry_point_type];
-
-        entrypoints
-            .iter()
-            .find(|entrypoint| entrypoint.selector == entrypoint_selector)
-            .map(|op| &op.function_id)
-            .ok_or_else(|| EntryPointExecutionError::NativeExecutionError {
-                info: format!("Entrypoint selector {} not found", entrypoint_selector.0),
-            })
-    }
-}
-
-#[derive(Debug)]
-pub struct NativeContractClassV1Inner {
-    pub executor: AotNativeExecutor,
-    entry_points_by_type: NativeContractEntryPoints,
-    // Storing the raw sierra program and entry points to be able to fallback to the vm
-    sierra_program_raw: Vec<BigUintAsHex>,
-    fallback_entry_points_by_type: SierraContractEntryPoints,
-}
-
-impl NativeContractClassV1Inner {
-    /// See [NativeContractClassV1::new]
-    fn new(
-        executor: AotNativeExecutor,
-        sierra_contract_class: SierraContractClass,
-    ) -> Result<Self, NativeEntryPointError> {
-        // This exception should never occur as it was also used to create the AotNativeExecutor
-        let sierra_program =
-            sierra_contract_class.extract_sierra_program().expect("can't extract sierra program");
-        // Note [Cairo Native ABI]
-        // The supplied (compiled) sierra program might have been populated with debug info and this
-        // affects the ABI, because the debug info is appended to the function name and the
-        // function name is what is used by Cairo Native to lookup the function.
-        // Therefore it's not enough to know the function index and we need enrich the contract
-        // entry point with FunctionIds from SierraProgram.
-        let lookup_fid: HashMap<usize, &FunctionId> =
-            HashMap::from_iter(sierra_program.funcs.iter().map(|fid| {
-                // This exception should never occur as the id is also in [SierraContractClass]
-                let id: usize = fid.id.id.try_into().expect("function id exceeds usize");
-                (id, &fid.id)
-            }));
-
-        Ok(NativeContractClassV1Inner {
-            executor,
-            entry_points_by_type: NativeContractEntryPoints::try_from(
-                &lookup_fid,
-                &sierra_contract_class.entry_points_by_type,
-            )?,
-            sierra_program_raw: sierra_contract_class.sierra_program,
-            fallback_entry_points_by_type: sierra_contract_class.entry_points_by_type,
-        })
-    }
-}
-
-// The location where the compiled contract is loaded into memory will not
-// be the same therefore we exclude it from the comparison.
-impl PartialEq for NativeContractClassV1Inner {
-    fn eq(&self, other: &Self) -> bool {
-        self.entry_points_by_type == other.entry_points_by_type
-            && self.sierra_program_raw == other.sierra_program_raw
-    }
-}
-
-#[derive(Debug, PartialEq)]
-/// Modelled after [SierraContractEntryPoints]
-/// and enriched with information for the Cairo Native ABI.
-/// See Note [Cairo Native ABI]
-struct NativeContractEntryPoints {
-    constructor: Vec<NativeEntryPoint>,
-    external: Vec<NativeEntryPoint>,
-    l1_handler: Vec<NativeEntryPoint>,
-}
-
-impl NativeContractEntryPoints {
-    /// Convert [SierraContractEntryPoints] to [NativeContractEntryPoints] via a
-    /// [FunctionId] lookup table.
-    ///
-    /// On failure returns the first FunctionId that it couldn't find.
-    fn try_from(
-        lookup: &HashMap<usize, &FunctionId>,
-        sep: &SierraContractEntryPoints,
-    ) -> Result<NativeContractEntryPoints, NativeEntryPointError> {
-        let constructor = sep
-            .constructor
-            .iter()
-            .map(|c| NativeEntryPoint::try_from(lookup, c))
-            .collect::<Result<_, _>>()?;
-        let external = sep
-            .external
-            .iter()
-            .map(|c| NativeEntryPoint::try_from(lookup, c))
-            .collect::<Result<_, _>>()?;
-        let l1_handler = sep
-            .l1_handler
-            .iter()
-            .map(|c| NativeEntryPoint::try_from(lookup, c))
-            .collect::<Result<_, _>>()?;
-
-        Ok(NativeContractEntryPoints { constructor, external, l1_handler })
-    }
-}
-
-impl Index<EntryPointType> for NativeContractEntryPoints {
-    type Output = Vec<NativeEntryPoint>;
-
-    fn index(&self, index: EntryPointType) -> &Self::Output {
-        match index {
-            EntryPointType::Constructor => &self.constructor,
-            EntryPointType::External => &self.external,
-            EntryPointType::L1Handler => &self.l1_handler,
-        }
-    }
-}
-
-#[derive(Debug, PartialEq)]
-/// Provides a relation between a function in a contract and a compiled contract
-struct NativeEntryPoint {
-    /// The selector is the key to find the function in the contract
-    selector: EntryPointSelector,
-    /// and the function_id is the key to find the function in the compiled contract
-    function_id: FunctionId,
-}
-
-impl NativeEntryPoint {
-    fn try_from(
-        lookup: &HashMap<usize, &FunctionId>,
-        cep: &ContractEntryPoint,
-    ) -> Result<NativeEntryPoint, NativeEntryPointError> {
-        let &function_id = lookup
-            .get(&cep.function_idx)
-            .ok_or(NativeEntryPointError::FunctionIdNotFound(cep.function_idx))?;
-        let selector = contract_entrypoint_to_entrypoint_selector(cep);
-        Ok(NativeEntryPoint { selector, function_id: function_id.clone() })
-    }
-}
@@ -25,6 +25,7 @@ use starknet_types_core::felt::{Felt, FromStrError};
 use thiserror::Error;
 
 use crate::blockifier::block::BlockInfo;
+use crate::blockifier::observer::ExecutionEvent;
 use crate::context::TransactionContext;
 use crate::execution::call_info::{CallInfo, OrderedEvent, OrderedL2ToL1Message};
 use crate::execution::common_hints::{
@@ -46,6 +47,9 @@ use crate::execution::execution_utils::{
 };
 use crate::execution::hint_code;
 use crate::execution::syscalls::hint_processor::EmitEventError;
+use crate::execution::syscalls::interceptor::{
+    InterceptedSyscall, SyscallInterception, SyscallInterceptor,
+};
 use crate::state::errors::StateError;
 use crate::state::state_api::State;
 
@@ -81,8 +85,12 @@ pub enum DeprecatedSyscallExecutionError {
     },
     #[error("Invalid syscall input: {input:?}; {info}")]
     InvalidSyscallInput { input: Felt, info: String },
+    #[error("Invalid substitute syscall response: {response:?}.")]
+    InvalidSubstituteResponse { response: Vec<Felt> },
     #[error("Invalid syscall selector: {0:?}.")]
     InvalidDeprecatedSyscallSelector(Felt),
+    #[error("Intercepted syscall failed; error data: {error_data:?}.")]
+    InterceptedSyscallFailure { error_data: Vec<Felt> },
     #[error(transparent)]
     MathError(#[from] cairo_vm::types::errors::math_errors::MathError),
     #[error(transparent)]
@@ -243,38 +251,54 @@ impl<'a> DeprecatedSyscallHintProcessor<'a> {
         self.increment_syscall_count(&selector);
 
         match selector {
-            DeprecatedSyscallSelector::CallContract => self.execute_syscall(vm, call_contract),
-            DeprecatedSyscallSelector::DelegateCall => self.execute_syscall(vm, delegate_call),
+            DeprecatedSyscallSelector::CallContract => {
+                self.execute_syscall(vm, selector, call_contract)
+            }
+            DeprecatedSyscallSelector::DelegateCall => {
+                self.execute_syscall(vm, selector, delegate_call)
+            }
             DeprecatedSyscallSelector::DelegateL1Handler => {
-                self.execute_syscall(vm, delegate_l1_handler)
+                self.execute_syscall(vm, selector, delegate_l1_handler)
+            }
+            DeprecatedSyscallSelector::Deploy => self.execute_syscall(vm, selector, deploy),
+            DeprecatedSyscallSelector::EmitEvent => self.execute_syscall(vm, selector, emit_event),
+            DeprecatedSyscallSelector::GetBlockNumber => {
+                self.execute_syscall(vm, selector, get_block_number)
             }
-            DeprecatedSyscallSelector::Deploy => self.execute_syscall(vm, deploy),
-            DeprecatedSyscallSelector::EmitEvent => self.execute_syscall(vm, emit_event),
-            DeprecatedSyscallSelector::GetBlockNumber => self.execute_syscall(vm, get_block_number),
             DeprecatedSyscallSelector::GetBlockTimestamp => {
-                self.execute_syscall(vm, get_block_timestamp)
+                self.execute_syscall(vm, selector, get_block_timestamp)
             }
             DeprecatedSyscallSelector::GetCallerAddress => {
-                self.execute_syscall(vm, get_caller_address)
+                self.execute_syscall(vm, selector, get_caller_address)
             }
             DeprecatedSyscallSelector::GetContractAddress => {
-                self.execute_syscall(vm, get_contract_address)
+                self.execute_syscall(vm, selector, get_contract_address)
             }
             DeprecatedSyscallSelector::GetSequencerAddress => {
-                self.execute_syscall(vm, get_sequencer_address)
+                self.execute_syscall(vm, selector, get_sequencer_address)
+            }
+            DeprecatedSyscallSelector::GetTxInfo => self.execute_syscall(vm, selector, get_tx_info),
+            DeprecatedSyscallSelector::GetTxSignature => {
+                self.execute_syscall(vm, selector, get_tx_signature)
+            }
+            DeprecatedSyscallSelector::LibraryCall => {
+                self.execute_syscall(vm, selector, library_call)
             }
-            DeprecatedSyscallSelector::GetTxInfo => self.execute_syscall(vm, get_tx_info),
-            DeprecatedSyscallSelector::GetTxSignature => self.execute_syscall(vm, get_tx_signature),
-            DeprecatedSyscallSelector::LibraryCall => self.execute_syscall(vm, library_call),
             DeprecatedSyscallSelector::LibraryCallL1Handler => {
-                self.execute_syscall(vm, library_call_l1_handler)
+                self.execute_syscall(vm, selector, library_call_l1_handler)
+            }
+            DeprecatedSyscallSelector::ReplaceClass => {
+                self.execute_syscall(vm, selector, replace_class)
             }
-            DeprecatedSyscallSelector::ReplaceClass => self.execute_syscall(vm, replace_class),
             DeprecatedSyscallSelector::SendMessageToL1 => {
-                self.execute_syscall(vm, send_message_to_l1)
+                self.execute_syscall(vm, selector, send_message_to_l1)
+            }
+            DeprecatedSyscallSelector::StorageRead => {
+                self.execute_syscall(vm, selector, storage_read)
+            }
+            DeprecatedSyscallSelector::StorageWrite => {
+                self.execute_syscall(vm, selector, storage_write)
             }
-            DeprecatedSyscallSelector::StorageRead => self.execute_syscall(vm, storage_read),
-            DeprecatedSyscallSelector::StorageWrite => self.execute_syscall(vm, storage_write),
             _ => Err(HintError::UnknownHint(
                 format!("Unsupported syscall selector {selector:?}.").into(),
             )),
@@ -312,6 +336,7 @@ impl<'a> DeprecatedSyscallHintProcessor<'a> {
     fn execute_syscall<Request, Response, ExecuteCallback>(
         &mut self,
         vm: &mut VirtualMachine,
+        selector: DeprecatedSyscallSelector,
         execute_callback: ExecuteCallback,
     ) -> HintExecutionResult
     where
@@ -325,12 +350,75 @@ impl<'a> DeprecatedSyscallHintProcessor<'a> {
     {
         let request = Request::read(vm, &mut self.syscall_ptr)?;
 
-        let response = execute_callback(request, vm, self)?;
+        let response = match self.context.syscall_interceptor() {
+            Some(interceptor) => self.execute_intercepted_syscall(
+                vm,
+                interceptor.as_ref(),
+                selector,
+                request,
+                execute_callback,
+            )?,
+            None => execute_callback(request, vm, self)?,
+        };
         response.write(vm, &mut self.syscall_ptr)?;
 
         Ok(())
     }
 
+    // Executes the syscall through the given interceptor, which may veto or substitute it, and may
+    // override its outcome. Cairo 0 syscalls cannot fail; hence, a failed outcome aborts the call.
+    fn execute_intercepted_syscall<Request, Response, ExecuteCallback>(
+        &mut self,
+        vm: &mut VirtualMachine,
+        interceptor: &dyn SyscallInterceptor,
+        selector: DeprecatedSyscallSelector,
+        request: Request,
+        execute_callback: ExecuteCallback,
+    ) -> DeprecatedSyscallResult<Response>
+    where
+        Request: SyscallRequest,
+        Response: SyscallResponse,
+        ExecuteCallback: FnOnce(
+            Request,
+            &mut VirtualMachine,
+            &mut DeprecatedSyscallHintProcessor<'_>,
+        ) -> DeprecatedSyscallResult<Response>,
+    {
+        let syscall = InterceptedSyscall {
+            selector,
+            storage_address: self.storage_address,
+            caller_address: self.caller_address,
+            execution_mode: self.execution_mode(),
+            request: request.intercepted(),
+        };
+
+        // The executed response, kept as is unless overridden.
+        let mut executed_response = None;
+        let mut outcome = match interceptor.pre_syscall(&syscall) {
+            SyscallInterception::Proceed => {
+                let response = execute_callback(request, vm, self)?;
+                let flat_response = response.to_felts(vm)?;
+                executed_response = Some((response, flat_response.clone()));
+                Ok(flat_response)
+            }
+            SyscallInterception::Veto { error_data } => Err(error_data),
+            SyscallInterception::Respond(response) => Ok(response),
+        };
+        interceptor.post_syscall(&syscall, &mut outcome);
+
+        match (outcome, executed_response) {
+            (Ok(flat_response), Some((response, executed_flat_response)))
+                if flat_response == executed_flat_response =>
+            {
+                Ok(response)
+            }
+            (Ok(flat_response), _) => Response::from_felts(flat_response, vm, self),
+            (Err(error_data), _) => {
+                Err(DeprecatedSyscallExecutionError::InterceptedSyscallFailure { error_data })
+            }
+        }
+    }
+
     fn read_next_syscall_selector(
         &mut self,
         vm: &mut VirtualMachine,
@@ -385,6 +473,8 @@ impl<'a> DeprecatedSyscallHintProcessor<'a> {
         self.accessed_keys.insert(key);
         let value = self.state.get_storage_at(self.storage_address, key)?;
         self.read_values.push(value);
+        let contract_address = self.storage_address;
+        self.context.record(|| ExecutionEvent::StorageRead { contract_address, key, value });
 
         Ok(StorageReadResponse { value })
     }
@@ -396,6 +486,8 @@ impl<'a> DeprecatedSyscallHintProcessor<'a> {
     ) -> DeprecatedSyscallResult<StorageWriteResponse> {
         self.accessed_keys.insert(key);
         self.state.set_storage_at(self.storage_address, key, value)?;
+        let contract_address = self.storage_address;
+        self.context.record(|| ExecutionEvent::StorageWrite { contract_address, key, value });
 
         Ok(StorageWriteResponse {})
     }
@@ -479,12 +571,22 @@ pub fn execute_inner_call(
 ) -> DeprecatedSyscallResult<ReadOnlySegment> {
     let call_info =
         call.execute(syscall_handler.state, syscall_handler.resources, syscall_handler.context)?;
-    let retdata = &call_info.execution.retdata.0;
+    let retdata_segment =
+        create_retdata_segment(vm, syscall_handler, &call_info.execution.retdata.0)?;
+
+    syscall_handler.inner_calls.push(call_info);
+    Ok(retdata_segment)
+}
+
+pub fn create_retdata_segment(
+    vm: &mut VirtualMachine,
+    syscall_handler: &mut DeprecatedSyscallHintProcessor<'_>,
+    raw_retdata: &[Felt],
+) -> DeprecatedSyscallResult<ReadOnlySegment> {
     let retdata: Vec<MaybeRelocatable> =
-        retdata.iter().map(|&x| MaybeRelocatable::from(x)).collect();
+        raw_retdata.iter().map(|&x| MaybeRelocatable::from(x)).collect();
     let retdata_segment_start_ptr = syscall_handler.read_only_segments.allocate(vm, &retdata)?;
 
-    syscall_handler.inner_calls.push(call_info);
     Ok(ReadOnlySegment { start_ptr: retdata_segment_start_ptr, length: retdata.len() })
 }
 
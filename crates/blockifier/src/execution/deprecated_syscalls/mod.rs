@@ -14,15 +14,19 @@ use starknet_types_core::felt::Felt;
 use strum_macros::EnumIter;
 
 use self::hint_processor::{
-    execute_inner_call, execute_library_call, felt_to_bool, read_call_params, read_calldata,
-    read_felt_array, DeprecatedSyscallExecutionError, DeprecatedSyscallHintProcessor,
+    create_retdata_segment, execute_inner_call, execute_library_call, felt_to_bool,
+    read_call_params, read_calldata, read_felt_array, DeprecatedSyscallExecutionError,
+    DeprecatedSyscallHintProcessor,
 };
 use super::syscalls::exceeds_event_size_limit;
+use super::syscalls::interceptor::InterceptedRequest;
+use crate::blockifier::observer::ExecutionEvent;
 use crate::execution::call_info::{MessageToL1, OrderedEvent, OrderedL2ToL1Message};
 use crate::execution::common_hints::ExecutionMode;
 use crate::execution::entry_point::{CallEntryPoint, CallType, ConstructorContext};
 use crate::execution::execution_utils::{
-    execute_deployment, felt_from_ptr, write_felt, write_maybe_relocatable, ReadOnlySegment,
+    execute_deployment, felt_from_ptr, felt_range_from_ptr, write_felt, write_maybe_relocatable,
+    ReadOnlySegment,
 };
 
 #[cfg(test)]
@@ -118,10 +122,34 @@ impl TryFrom<Felt> for DeprecatedSyscallSelector {
 
 pub trait SyscallRequest: Sized {
     fn read(_vm: &VirtualMachine, _ptr: &mut Relocatable) -> DeprecatedSyscallResult<Self>;
+
+    /// The request, as exposed to a syscall interceptor.
+    fn intercepted(&self) -> InterceptedRequest {
+        InterceptedRequest::Opaque
+    }
 }
 
 pub trait SyscallResponse {
     fn write(self, _vm: &mut VirtualMachine, _ptr: &mut Relocatable) -> WriteResponseResult;
+
+    /// The response, as exposed to a syscall interceptor; see
+    /// [crate::execution::syscalls::interceptor::SyscallOutcome].
+    fn to_felts(&self, _vm: &VirtualMachine) -> DeprecatedSyscallResult<Vec<Felt>> {
+        Ok(vec![])
+    }
+
+    /// Builds a response substituted by a syscall interceptor; see
+    /// [crate::execution::syscalls::interceptor::SyscallOutcome].
+    fn from_felts(
+        response: Vec<Felt>,
+        _vm: &mut VirtualMachine,
+        _syscall_handler: &mut DeprecatedSyscallHintProcessor<'_>,
+    ) -> DeprecatedSyscallResult<Self>
+    where
+        Self: Sized,
+    {
+        Err(DeprecatedSyscallExecutionError::InvalidSubstituteResponse { response })
+    }
 }
 
 // Common structs.
@@ -142,6 +170,17 @@ impl SyscallResponse for EmptyResponse {
     fn write(self, _vm: &mut VirtualMachine, _ptr: &mut Relocatable) -> WriteResponseResult {
         Ok(())
     }
+
+    fn from_felts(
+        response: Vec<Felt>,
+        _vm: &mut VirtualMachine,
+        _syscall_handler: &mut DeprecatedSyscallHintProcessor<'_>,
+    ) -> DeprecatedSyscallResult<Self> {
+        match response.is_empty() {
+            true => Ok(EmptyResponse),
+            false => Err(DeprecatedSyscallExecutionError::InvalidSubstituteResponse { response }),
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -155,6 +194,20 @@ impl SyscallResponse for SingleSegmentResponse {
         write_maybe_relocatable(vm, ptr, self.segment.start_ptr)?;
         Ok(())
     }
+
+    fn to_felts(&self, vm: &VirtualMachine) -> DeprecatedSyscallResult<Vec<Felt>> {
+        Ok(felt_range_from_ptr(vm, self.segment.start_ptr, self.segment.length)?)
+    }
+
+    fn from_felts(
+        response: Vec<Felt>,
+        vm: &mut VirtualMachine,
+        syscall_handler: &mut DeprecatedSyscallHintProcessor<'_>,
+    ) -> DeprecatedSyscallResult<Self> {
+        Ok(SingleSegmentResponse {
+            segment: create_retdata_segment(vm, syscall_handler, &response)?,
+        })
+    }
 }
 
 // CallContract syscall.
@@ -176,6 +229,14 @@ impl SyscallRequest for CallContractRequest {
 
         Ok(CallContractRequest { contract_address, function_selector, calldata })
     }
+
+    fn intercepted(&self) -> InterceptedRequest {
+        InterceptedRequest::CallContract {
+            contract_address: self.contract_address,
+            entry_point_selector: self.function_selector,
+            calldata: self.calldata.clone(),
+        }
+    }
 }
 
 pub type CallContractResponse = SingleSegmentResponse;
@@ -287,6 +348,15 @@ impl SyscallRequest for DeployRequest {
             deploy_from_zero: felt_to_bool(deploy_from_zero)?,
         })
     }
+
+    fn intercepted(&self) -> InterceptedRequest {
+        InterceptedRequest::Deploy {
+            class_hash: self.class_hash,
+            contract_address_salt: self.contract_address_salt,
+            constructor_calldata: self.constructor_calldata.clone(),
+            deploy_from_zero: self.deploy_from_zero,
+        }
+    }
 }
 
 #[derive(Debug, Eq, PartialEq)]
@@ -304,6 +374,23 @@ impl SyscallResponse for DeployResponse {
         write_maybe_relocatable(vm, ptr, 0)?;
         Ok(())
     }
+
+    fn to_felts(&self, _vm: &VirtualMachine) -> DeprecatedSyscallResult<Vec<Felt>> {
+        Ok(vec![*self.contract_address.0.key()])
+    }
+
+    fn from_felts(
+        response: Vec<Felt>,
+        _vm: &mut VirtualMachine,
+        _syscall_handler: &mut DeprecatedSyscallHintProcessor<'_>,
+    ) -> DeprecatedSyscallResult<Self> {
+        match response[..] {
+            [contract_address] => Ok(DeployResponse {
+                contract_address: ContractAddress::try_from(contract_address)?,
+            }),
+            _ => Err(DeprecatedSyscallExecutionError::InvalidSubstituteResponse { response }),
+        }
+    }
 }
 
 pub fn deploy(
@@ -363,6 +450,10 @@ impl SyscallRequest for EmitEventRequest {
 
         Ok(EmitEventRequest { content: EventContent { keys, data } })
     }
+
+    fn intercepted(&self) -> InterceptedRequest {
+        InterceptedRequest::EmitEvent { content: self.content.clone() }
+    }
 }
 
 type EmitEventResponse = EmptyResponse;
@@ -372,12 +463,15 @@ pub fn emit_event(
     _vm: &mut VirtualMachine,
     syscall_handler: &mut DeprecatedSyscallHintProcessor<'_>,
 ) -> DeprecatedSyscallResult<EmitEventResponse> {
+    let from_address = syscall_handler.storage_address;
     let execution_context = &mut syscall_handler.context;
     exceeds_event_size_limit(
         execution_context.versioned_constants(),
         execution_context.n_emitted_events + 1,
         &request.content,
     )?;
+    execution_context
+        .record(|| ExecutionEvent::Event { from_address, content: request.content.clone() });
     let ordered_event =
         OrderedEvent { order: execution_context.n_emitted_events, event: request.content };
     syscall_handler.events.push(ordered_event);
@@ -568,6 +662,14 @@ impl SyscallRequest for LibraryCallRequest {
 
         Ok(LibraryCallRequest { class_hash, function_selector, calldata })
     }
+
+    fn intercepted(&self) -> InterceptedRequest {
+        InterceptedRequest::LibraryCall {
+            class_hash: self.class_hash,
+            entry_point_selector: self.function_selector,
+            calldata: self.calldata.clone(),
+        }
+    }
 }
 
 type LibraryCallResponse = CallContractResponse;
@@ -628,6 +730,10 @@ impl SyscallRequest for ReplaceClassRequest {
 
         Ok(ReplaceClassRequest { class_hash })
     }
+
+    fn intercepted(&self) -> InterceptedRequest {
+        InterceptedRequest::ReplaceClass { class_hash: self.class_hash }
+    }
 }
 
 pub type ReplaceClassResponse = EmptyResponse;
@@ -662,6 +768,10 @@ impl SyscallRequest for SendMessageToL1Request {
 
         Ok(SendMessageToL1Request { message: MessageToL1 { to_address, payload } })
     }
+
+    fn intercepted(&self) -> InterceptedRequest {
+        InterceptedRequest::SendMessageToL1 { message: self.message.clone() }
+    }
 }
 
 type SendMessageToL1Response = EmptyResponse;
@@ -671,6 +781,11 @@ pub fn send_message_to_l1(
     _vm: &mut VirtualMachine,
     syscall_handler: &mut DeprecatedSyscallHintProcessor<'_>,
 ) -> DeprecatedSyscallResult<SendMessageToL1Response> {
+    let from_address = syscall_handler.storage_address;
+    syscall_handler.context.record(|| ExecutionEvent::L2ToL1Message {
+        from_address,
+        message: request.message.clone(),
+    });
     let execution_context = &mut syscall_handler.context;
     let ordered_message_to_l1 = OrderedL2ToL1Message {
         order: execution_context.n_sent_messages_to_l1,
@@ -697,6 +812,10 @@ impl SyscallRequest for StorageReadRequest {
         let address = StorageKey::try_from(felt_from_ptr(vm, ptr)?)?;
         Ok(StorageReadRequest { address })
     }
+
+    fn intercepted(&self) -> InterceptedRequest {
+        InterceptedRequest::StorageRead { key: self.address }
+    }
 }
 
 #[derive(Debug, Eq, PartialEq)]
@@ -709,6 +828,21 @@ impl SyscallResponse for StorageReadResponse {
         write_felt(vm, ptr, self.value)?;
         Ok(())
     }
+
+    fn to_felts(&self, _vm: &VirtualMachine) -> DeprecatedSyscallResult<Vec<Felt>> {
+        Ok(vec![self.value])
+    }
+
+    fn from_felts(
+        response: Vec<Felt>,
+        _vm: &mut VirtualMachine,
+        _syscall_handler: &mut DeprecatedSyscallHintProcessor<'_>,
+    ) -> DeprecatedSyscallResult<Self> {
+        match response[..] {
+            [value] => Ok(StorageReadResponse { value }),
+            _ => Err(DeprecatedSyscallExecutionError::InvalidSubstituteResponse { response }),
+        }
+    }
 }
 
 pub fn storage_read(
@@ -736,6 +870,10 @@ impl SyscallRequest for StorageWriteRequest {
         let value = felt_from_ptr(vm, ptr)?;
         Ok(StorageWriteRequest { address, value })
     }
+
+    fn intercepted(&self) -> InterceptedRequest {
+        InterceptedRequest::StorageWrite { key: self.address, value: self.value }
+    }
 }
 
 pub type StorageWriteResponse = EmptyResponse;
@@ -1,6 +1,8 @@
 use std::cell::RefCell;
 use std::cmp::min;
 use std::sync::Arc;
+#[cfg(feature = "native")]
+use std::time::Instant;
 
 use cairo_vm::vm::runners::cairo_runner::{ExecutionResources, ResourceTracker, RunResources};
 use num_traits::{Inv, Zero};
@@ -12,6 +14,7 @@ use starknet_types_core::felt::Felt;
 
 use crate::abi::abi_utils::selector_from_name;
 use crate::abi::constants;
+use crate::blockifier::observer::{ExecutionEvent, ExecutionRecorder};
 use crate::context::{BlockContext, TransactionContext};
 use crate::execution::call_info::CallInfo;
 use crate::execution::common_hints::ExecutionMode;
@@ -19,6 +22,9 @@ use crate::execution::errors::{
     ConstructorEntryPointExecutionError, EntryPointExecutionError, PreExecutionError,
 };
 use crate::execution::execution_utils::execute_entry_point_call;
+#[cfg(feature = "native")]
+use crate::execution::native::worker::WorkerProcess;
+use crate::execution::syscalls::interceptor::SyscallInterceptor;
 use crate::state::state_api::State;
 use crate::transaction::objects::{HasRelatedFeeType, TransactionExecutionResult, TransactionInfo};
 use crate::transaction::transaction_types::TransactionType;
@@ -63,6 +69,25 @@ pub struct CallEntryPoint {
 
 impl CallEntryPoint {
     pub fn execute(
+        self,
+        state: &mut dyn State,
+        resources: &mut ExecutionResources,
+        context: &mut EntryPointExecutionContext,
+    ) -> EntryPointExecutionResult<CallInfo> {
+        let Some(recorder) = context.execution_recorder().cloned() else {
+            return self.execute_unrecorded(state, resources, context);
+        };
+
+        let depth = *context.current_recursion_depth.borrow() + 1;
+        recorder.record(ExecutionEvent::EntryPointEnter { call: self.clone(), depth });
+        let result = self.clone().execute_unrecorded(state, resources, context);
+        let failed = result.as_ref().map_or(true, |call_info| call_info.execution.failed);
+        recorder.record(ExecutionEvent::EntryPointExit { call: self, depth, failed });
+
+        result
+    }
+
+    fn execute_unrecorded(
         mut self,
         state: &mut dyn State,
         resources: &mut ExecutionResources,
@@ -126,6 +151,22 @@ pub struct EntryPointExecutionContext {
 
     // The execution mode affects the behavior of the hint processor.
     pub execution_mode: ExecutionMode,
+    /// Observes and possibly overrides the syscalls of the execution; defaults to that of the
+    /// block context.
+    pub syscall_interceptor: Option<Arc<dyn SyscallInterceptor>>,
+    // Records the steps of the execution, if it is observed; see `execution_recorder`.
+    pub(crate) execution_recorder: Option<ExecutionRecorder>,
+    // Set while running the VM side of a differential execution; nested calls of such a run are
+    // executed on the VM as well, and are not compared.
+    #[cfg(feature = "native")]
+    pub(crate) in_shadow_execution: bool,
+    // Native execution fails once this point in time has passed.
+    #[cfg(feature = "native")]
+    pub(crate) native_execution_deadline: Option<Instant>,
+    // The worker process serving the native calls of the current execution, if native execution
+    // is isolated; checked out by the outermost native call.
+    #[cfg(feature = "native")]
+    pub(crate) native_worker_process: Option<WorkerProcess>,
 }
 
 impl EntryPointExecutionContext {
@@ -142,9 +183,48 @@ impl EntryPointExecutionContext {
             tx_context: tx_context.clone(),
             current_recursion_depth: Default::default(),
             execution_mode: mode,
+            syscall_interceptor: tx_context.block_context.syscall_interceptor.clone(),
+            execution_recorder: tx_context.block_context.execution_recorder.clone(),
+            #[cfg(feature = "native")]
+            in_shadow_execution: false,
+            #[cfg(feature = "native")]
+            native_execution_deadline: tx_context
+                .block_context
+                .native_execution_config
+                .max_execution_time
+                .map(|max_execution_time| Instant::now() + max_execution_time),
+            #[cfg(feature = "native")]
+            native_worker_process: None,
         })
     }
 
+    /// Returns the recorder of the execution steps, unless they are not observed; the VM side of a
+    /// differential execution is not.
+    pub(crate) fn execution_recorder(&self) -> Option<&ExecutionRecorder> {
+        #[cfg(feature = "native")]
+        if self.in_shadow_execution {
+            return None;
+        }
+        self.execution_recorder.as_ref()
+    }
+
+    /// Returns the interceptor of the syscalls, unless they are not observed; those of the VM side
+    /// of a differential execution are not.
+    pub(crate) fn syscall_interceptor(&self) -> Option<Arc<dyn SyscallInterceptor>> {
+        #[cfg(feature = "native")]
+        if self.in_shadow_execution {
+            return None;
+        }
+        self.syscall_interceptor.clone()
+    }
+
+    /// Records a step of the execution, if it is observed.
+    pub(crate) fn record(&self, event: impl FnOnce() -> ExecutionEvent) {
+        if let Some(recorder) = self.execution_recorder() {
+            recorder.record(event());
+        }
+    }
+
     pub fn new_validate(
         tx_context: Arc<TransactionContext>,
         limit_steps_by_resources: bool,
@@ -1,3 +1,4 @@
+#[cfg(feature = "native")]
 use cairo_native::error::Error as NativeRunnerError;
 use cairo_vm::types::errors::math_errors::MathError;
 use cairo_vm::vm::errors::cairo_run_errors::CairoRunError;
@@ -11,8 +12,12 @@ use starknet_api::deprecated_contract_class::EntryPointType;
 use starknet_types_core::felt::Felt;
 use thiserror::Error;
 
+#[cfg(feature = "native")]
+use crate::execution::contract_class::NativeEntryPointError;
 use crate::execution::entry_point::ConstructorContext;
 use crate::execution::execution_utils::format_panic_data;
+#[cfg(feature = "native")]
+use crate::execution::syscalls::hint_processor::SyscallExecutionError;
 use crate::state::errors::StateError;
 
 // TODO(AlonH, 21/12/2022): Implement Display for all types that appear in errors.
@@ -85,10 +90,22 @@ pub enum EntryPointExecutionError {
     InternalError(String),
     #[error("Invalid input: {input_descriptor}; {info}")]
     InvalidExecutionInput { input_descriptor: String, info: String },
+    #[cfg(feature = "native")]
     #[error("Native execution error: {info}")]
     NativeExecutionError { info: String },
+    #[cfg(feature = "native")]
     #[error("Native Fallback Error: {info}")]
     NativeFallbackError { info: Box<EntryPointExecutionError> },
+    #[cfg(feature = "native")]
+    #[error("Native execution exceeded its maximal execution time.")]
+    NativeExecutionTimeout,
+    #[cfg(feature = "native")]
+    #[error(transparent)]
+    NativeUnrecoverableError(Box<SyscallExecutionError>),
+    #[cfg(feature = "native")]
+    #[error(transparent)]
+    NativeWorkerError(#[from] NativeWorkerError),
+    #[cfg(feature = "native")]
     #[error("Native unexpected error: {source}")]
     NativeUnexpectedError {
         #[source]
@@ -147,3 +164,43 @@ pub enum ContractClassError {
         sierra_program_length: usize,
     },
 }
+
+#[cfg(feature = "native")]
+#[derive(Debug, Error)]
+pub enum NativeCompilationError {
+    #[error("Failed to load a native artifact: {0}")]
+    ArtifactLoadError(String),
+    #[error(transparent)]
+    EntryPointError(#[from] NativeEntryPointError),
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+    #[error("Failed to compile Sierra to native code: {0}")]
+    NativeCompilerError(#[from] NativeRunnerError),
+    #[error("Native compiler panicked.")]
+    NativeCompilerPanic,
+    #[error("Failed to compile native module to an object: {0}")]
+    ObjectCompilationError(String),
+    #[error("Failed to extract the Sierra program: {0}")]
+    SierraProgramExtractionError(String),
+}
+
+#[cfg(feature = "native")]
+#[derive(Debug, Error)]
+pub enum NativeWorkerError {
+    #[error("Failed to load class {class_hash} in the native worker: {info}")]
+    ClassLoadingError { class_hash: ClassHash, info: String },
+    #[error("Native worker crashed ({exit_status}).")]
+    Crashed { exit_status: String },
+    #[error("Native worker failed to execute: {0}")]
+    ExecutionError(String),
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+    #[error("Unexpected message from the native worker: {0}")]
+    ProtocolError(String),
+    #[error(transparent)]
+    SerdeError(#[from] serde_json::Error),
+    #[error("Failed to spawn a native worker: {0}")]
+    SpawnError(std::io::Error),
+    #[error("Native worker did not respond within {timeout:?}.")]
+    Unresponsive { timeout: std::time::Duration },
+}
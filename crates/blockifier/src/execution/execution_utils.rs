@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+#[cfg(feature = "native")]
 use std::env;
 
 use cairo_lang_runner::casm_run::format_next_item;
@@ -20,6 +21,8 @@ use starknet_api::transaction::Calldata;
 use starknet_types_core::felt::Felt;
 
 use super::contract_class::ContractClassV1;
+#[cfg(feature = "native")]
+use super::contract_class::NativeContractClassV1;
 use super::entry_point::ConstructorEntryPointExecutionResult;
 use super::errors::{ConstructorEntryPointExecutionError, EntryPointExecutionError};
 use crate::execution::call_info::{CallInfo, Retdata};
@@ -29,10 +32,14 @@ use crate::execution::entry_point::{
     EntryPointExecutionContext, EntryPointExecutionResult,
 };
 use crate::execution::errors::PostExecutionError;
-use crate::execution::native::entry_point_execution as native_entry_point_execution;
+#[cfg(feature = "native")]
+use crate::execution::native::{
+    differential_execution, entry_point_execution as native_entry_point_execution,
+};
 use crate::execution::{deprecated_entry_point_execution, entry_point_execution};
 use crate::state::errors::StateError;
 use crate::state::state_api::State;
+#[cfg(feature = "native")]
 use crate::state::state_wrapper::DynStateWrapper;
 use crate::transaction::objects::TransactionInfo;
 
@@ -65,50 +72,82 @@ pub fn execute_entry_point_call(
             resources,
             context,
         ),
+        #[cfg(feature = "native")]
         ContractClass::V1Native(contract_class) => {
-            // Wrap the state into a DynStateWrapper to be transactional
-            let mut state_wrapped = DynStateWrapper::new(state);
-            let fallback = env::var("FALLBACK_ENABLED").unwrap_or(String::from("0")) == "1";
-
-            match native_entry_point_execution::execute_entry_point_call(
-                call.clone(),
-                contract_class.clone(),
-                &mut state_wrapped,
-                resources,
-                context,
-            ) {
-                Ok(res) => {
-                    // If everything went well, commit the changes to the state
-                    state_wrapped.commit().unwrap();
-
-                    Ok(res)
-                }
-                Err(EntryPointExecutionError::NativeUnexpectedError { .. }) if fallback => {
-                    // Fallback to VM execution in case of an Error
-                    let casm_contract_class =
-                        contract_class.to_casm_contract_class().map_err(|e| {
-                            EntryPointExecutionError::FailedToConvertSierraToCasm(e.to_string())
-                        })?;
-                    let contract_class_v1: ContractClassV1 =
-                        casm_contract_class.try_into().unwrap();
-                    // Use old state if native execution failed
-                    entry_point_execution::execute_entry_point_call(
-                        call,
-                        contract_class_v1,
-                        state,
-                        resources,
-                        context,
-                    )
-                    .map_err(|e| {
-                        EntryPointExecutionError::NativeFallbackError { info: Box::new(e) }
-                    })
+            // The VM side of a differential execution runs its whole call tree on the VM.
+            if context.in_shadow_execution {
+                return differential_execution::execute_casm_equivalent_entry_point_call(
+                    call,
+                    &contract_class,
+                    state,
+                    resources,
+                    context,
+                );
+            }
+            match context.tx_context.block_context.divergence_reporter.clone() {
+                Some(divergence_reporter) => differential_execution::execute_entry_point_call(
+                    call,
+                    contract_class,
+                    state,
+                    resources,
+                    context,
+                    &divergence_reporter,
+                ),
+                None => {
+                    execute_native_entry_point_call(call, contract_class, state, resources, context)
                 }
-                Err(e) => Err(e),
             }
         }
     }
 }
 
+/// Executes an entry point of a class compiled to native code, falling back to the Cairo VM on
+/// unexpected native errors if `FALLBACK_ENABLED` is set.
+#[cfg(feature = "native")]
+pub fn execute_native_entry_point_call(
+    call: CallEntryPoint,
+    contract_class: NativeContractClassV1,
+    state: &mut dyn State,
+    resources: &mut ExecutionResources,
+    context: &mut EntryPointExecutionContext,
+) -> EntryPointExecutionResult<CallInfo> {
+    // Wrap the state into a DynStateWrapper to be transactional
+    let mut state_wrapped = DynStateWrapper::new(state);
+    let fallback = env::var("FALLBACK_ENABLED").unwrap_or(String::from("0")) == "1";
+
+    match native_entry_point_execution::execute_entry_point_call(
+        call.clone(),
+        contract_class.clone(),
+        &mut state_wrapped,
+        resources,
+        context,
+    ) {
+        Ok(res) => {
+            // If everything went well, commit the changes to the state
+            state_wrapped.commit().unwrap();
+
+            Ok(res)
+        }
+        Err(EntryPointExecutionError::NativeUnexpectedError { .. }) if fallback => {
+            // Fallback to VM execution in case of an Error
+            let casm_contract_class = contract_class.to_casm_contract_class().map_err(|e| {
+                EntryPointExecutionError::FailedToConvertSierraToCasm(e.to_string())
+            })?;
+            let contract_class_v1: ContractClassV1 = casm_contract_class.try_into().unwrap();
+            // Use old state if native execution failed
+            entry_point_execution::execute_entry_point_call(
+                call,
+                contract_class_v1,
+                state,
+                resources,
+                context,
+            )
+            .map_err(|e| EntryPointExecutionError::NativeFallbackError { info: Box::new(e) })
+        }
+        Err(e) => Err(e),
+    }
+}
+
 pub fn read_execution_retdata(
     runner: &CairoRunner,
     retdata_size: MaybeRelocatable,
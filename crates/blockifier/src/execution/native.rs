@@ -1,3 +1,11 @@
+pub mod artifact_cache;
+pub mod compilation_pool;
+pub mod compiler;
+pub mod contract_class;
+pub mod differential_execution;
 pub mod entry_point_execution;
 pub mod syscall_handler;
 pub mod utils;
+pub mod worker;
+pub mod worker_protocol;
+pub mod worker_sandbox;
@@ -0,0 +1,256 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use cairo_lang_sierra::program::Program;
+use cairo_lang_starknet_classes::contract_class::ContractClass as SierraContractClass;
+use cairo_native::object_to_shared_lib;
+use sha2::{Digest, Sha256};
+use starknet_api::core::ClassHash;
+
+use crate::blockifier::config::NativeArtifactCacheConfig;
+use crate::execution::contract_class::NativeContractClassV1;
+use crate::execution::native::compiler::{
+    compile_sierra_to_object, extract_sierra_program, load_native_executor, native_opt_level,
+    NativeCompilationResult,
+};
+
+#[cfg(test)]
+#[path = "artifact_cache_test.rs"]
+pub mod test;
+
+/// Identifies the native compiler that produced an artifact; artifacts of other compiler versions
+/// are never loaded.
+// Derived from the locked `cairo-native` version and commit; see `build.rs`.
+pub const NATIVE_COMPILER_VERSION: &str = env!("NATIVE_COMPILER_VERSION");
+
+const ARTIFACT_EXTENSION: &str = "so";
+const CHECKSUM_EXTENSION: &str = "sha256";
+
+/// A persistent, size-bounded cache of natively compiled contract classes, stored as shared
+/// libraries. Artifacts are keyed by class hash, native compiler version and optimization level,
+/// and are verified against a stored checksum before being loaded.
+/// When the total size of the artifacts exceeds the configured maximum, the least recently used
+/// ones are evicted.
+#[derive(Clone, Debug)]
+pub struct NativeArtifactCache {
+    config: NativeArtifactCacheConfig,
+    compiler_version: String,
+}
+
+impl NativeArtifactCache {
+    pub fn new(config: NativeArtifactCacheConfig) -> Self {
+        Self { config, compiler_version: NATIVE_COMPILER_VERSION.to_string() }
+    }
+
+    /// Like [Self::new], as if artifacts were compiled by the given native compiler version.
+    #[cfg(test)]
+    pub(crate) fn new_with_compiler_version(
+        config: NativeArtifactCacheConfig,
+        compiler_version: &str,
+    ) -> Self {
+        Self { config, compiler_version: compiler_version.to_string() }
+    }
+
+    /// Returns the native contract class of the given Sierra class; loads it from disk if a valid
+    /// artifact exists, and compiles (and stores) it otherwise.
+    pub fn get_or_compile(
+        &self,
+        class_hash: ClassHash,
+        sierra_contract_class: SierraContractClass,
+        opt_level: u8,
+    ) -> NativeCompilationResult<NativeContractClassV1> {
+        let sierra_program = extract_sierra_program(&sierra_contract_class)?;
+        let artifact_path = self.artifact_path(class_hash, opt_level);
+
+        if self.contains_valid_artifact(&artifact_path)? {
+            match load_native_executor(&artifact_path, &sierra_program) {
+                Ok(executor) => {
+                    mark_used(&artifact_path)?;
+                    return Ok(NativeContractClassV1::new_with_native_code_size(
+                        executor,
+                        sierra_contract_class,
+                        native_code_size(&artifact_path)?,
+                    )?);
+                }
+                Err(error) => {
+                    log::warn!(
+                        "Failed to load native artifact {}; recompiling. Error: {error}",
+                        artifact_path.display()
+                    );
+                    remove_artifact(&artifact_path)?;
+                }
+            }
+        }
+
+        self.compile(&artifact_path, &sierra_program, opt_level)?;
+        let executor = load_native_executor(&artifact_path, &sierra_program)?;
+
+        Ok(NativeContractClassV1::new_with_native_code_size(
+            executor,
+            sierra_contract_class,
+            native_code_size(&artifact_path)?,
+        )?)
+    }
+
+    /// Returns the path of a valid artifact of the given Sierra class, compiling (and storing) it
+    /// if there is none. The artifact is left for other processes to load (see `load_artifact`).
+    pub fn get_or_compile_artifact(
+        &self,
+        class_hash: ClassHash,
+        sierra_contract_class: &SierraContractClass,
+        opt_level: u8,
+    ) -> NativeCompilationResult<PathBuf> {
+        let artifact_path = self.artifact_path(class_hash, opt_level);
+
+        if self.contains_valid_artifact(&artifact_path)? {
+            mark_used(&artifact_path)?;
+        } else {
+            let sierra_program = extract_sierra_program(sierra_contract_class)?;
+            self.compile(&artifact_path, &sierra_program, opt_level)?;
+        }
+
+        Ok(artifact_path)
+    }
+
+    pub fn artifact_path(&self, class_hash: ClassHash, opt_level: u8) -> PathBuf {
+        // Levels that compile to the same artifact share its path.
+        let opt_level = native_opt_level(opt_level) as u8;
+        self.config.path.join(format!(
+            "{:#066x}-{}-O{opt_level}.{ARTIFACT_EXTENSION}",
+            class_hash.0, self.compiler_version
+        ))
+    }
+
+    /// Returns whether an artifact exists at the given path and matches its checksum.
+    /// Corrupted artifacts are removed.
+    fn contains_valid_artifact(&self, artifact_path: &Path) -> NativeCompilationResult<bool> {
+        if !artifact_path.exists() {
+            return Ok(false);
+        }
+
+        let expected_checksum = fs::read_to_string(checksum_path(artifact_path)).ok();
+        if expected_checksum == Some(checksum(&fs::read(artifact_path)?)) {
+            return Ok(true);
+        }
+
+        log::warn!("Native artifact {} is corrupted; removing it.", artifact_path.display());
+        remove_artifact(artifact_path)?;
+        Ok(false)
+    }
+
+    /// Compiles the given Sierra program and stores it at the given path, evicting other artifacts
+    /// as needed.
+    fn compile(
+        &self,
+        artifact_path: &Path,
+        sierra_program: &Program,
+        opt_level: u8,
+    ) -> NativeCompilationResult<()> {
+        let object = compile_sierra_to_object(sierra_program, native_opt_level(opt_level))?;
+        self.store(artifact_path, &object)?;
+        self.evict(artifact_path)
+    }
+
+    /// Links the given object into a shared library and stores it, along with its checksum.
+    // Files are written to temporary paths and then renamed, so that concurrent readers never
+    // observe a partially written artifact. The checksum is renamed first, so that an existing
+    // artifact always has a checksum.
+    fn store(&self, artifact_path: &Path, object: &[u8]) -> NativeCompilationResult<()> {
+        fs::create_dir_all(&self.config.path)?;
+
+        let temp_artifact_path = tempfile::Builder::new()
+            .suffix(&format!(".{ARTIFACT_EXTENSION}"))
+            .tempfile_in(&self.config.path)?
+            .into_temp_path();
+        object_to_shared_lib(object, &temp_artifact_path)?;
+
+        let mut temp_checksum_file = tempfile::NamedTempFile::new_in(&self.config.path)?;
+        std::io::Write::write_all(
+            &mut temp_checksum_file,
+            checksum(&fs::read(&temp_artifact_path)?).as_bytes(),
+        )?;
+
+        temp_checksum_file.persist(checksum_path(artifact_path)).map_err(|error| error.error)?;
+        temp_artifact_path.persist(artifact_path).map_err(|error| error.error)?;
+
+        Ok(())
+    }
+
+    /// Evicts the least recently used artifacts until the cache fits in its maximum size.
+    /// The artifact at `keep_path` (typically, the one just stored) is never evicted.
+    fn evict(&self, keep_path: &Path) -> NativeCompilationResult<()> {
+        let mut artifacts = Vec::new();
+        for entry in fs::read_dir(&self.config.path)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|extension| extension == ARTIFACT_EXTENSION) {
+                let metadata = fs::metadata(&path)?;
+                artifacts.push((metadata.modified()?, metadata.len(), path));
+            }
+        }
+
+        let mut total_size: u64 = artifacts.iter().map(|(_, size, _)| size).sum();
+        artifacts.sort();
+        for (_, size, path) in artifacts {
+            if total_size <= self.config.max_size {
+                break;
+            }
+            if path == keep_path {
+                continue;
+            }
+
+            log::debug!("Evicting native artifact {}.", path.display());
+            remove_artifact(&path)?;
+            total_size -= size;
+        }
+
+        Ok(())
+    }
+}
+
+/// Loads the native contract class of the given Sierra class from an artifact stored by a
+/// `NativeArtifactCache` (typically, of another process).
+pub fn load_artifact(
+    artifact_path: &Path,
+    sierra_contract_class: SierraContractClass,
+) -> NativeCompilationResult<NativeContractClassV1> {
+    let sierra_program = extract_sierra_program(&sierra_contract_class)?;
+    let executor = load_native_executor(artifact_path, &sierra_program)?;
+
+    Ok(NativeContractClassV1::new_with_native_code_size(
+        executor,
+        sierra_contract_class,
+        native_code_size(artifact_path)?,
+    )?)
+}
+
+/// Returns the size of the code loaded from the given artifact.
+fn native_code_size(artifact_path: &Path) -> NativeCompilationResult<usize> {
+    let size = fs::metadata(artifact_path)?.len();
+    Ok(usize::try_from(size).expect("Artifact size fits in usize."))
+}
+
+/// Marks the artifact as recently used.
+fn mark_used(artifact_path: &Path) -> NativeCompilationResult<()> {
+    fs::File::options().write(true).open(artifact_path)?.set_modified(SystemTime::now())?;
+    Ok(())
+}
+
+fn checksum_path(artifact_path: &Path) -> PathBuf {
+    artifact_path.with_extension(CHECKSUM_EXTENSION)
+}
+
+fn checksum(data: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(data))
+}
+
+fn remove_artifact(artifact_path: &Path) -> NativeCompilationResult<()> {
+    for path in [artifact_path.to_path_buf(), checksum_path(artifact_path)] {
+        match fs::remove_file(path) {
+            Err(error) if error.kind() != std::io::ErrorKind::NotFound => return Err(error.into()),
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
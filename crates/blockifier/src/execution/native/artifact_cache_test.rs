@@ -0,0 +1,132 @@
+use std::fs;
+
+use cairo_lang_starknet_classes::contract_class::ContractClass as SierraContractClass;
+use pretty_assertions::assert_eq;
+
+use super::{checksum, checksum_path, load_artifact, NativeArtifactCache, NATIVE_COMPILER_VERSION};
+use crate::blockifier::config::NativeArtifactCacheConfig;
+use crate::test_utils::contracts::FeatureContract;
+
+fn sierra_contract_class(feature_contract: FeatureContract) -> SierraContractClass {
+    serde_json::from_str(&feature_contract.get_raw_class()).unwrap()
+}
+
+#[test]
+fn test_get_or_compile() {
+    let cache_dir = tempfile::tempdir().unwrap();
+    let cache = NativeArtifactCache::new(NativeArtifactCacheConfig {
+        path: cache_dir.path().to_path_buf(),
+        max_size: u64::MAX,
+    });
+    let feature_contract = FeatureContract::SierraTestContract;
+    let class_hash = feature_contract.get_class_hash();
+    let artifact_path = cache.artifact_path(class_hash, 2);
+
+    // Cache miss: compile and store.
+    let compiled_class =
+        cache.get_or_compile(class_hash, sierra_contract_class(feature_contract), 2).unwrap();
+    assert!(artifact_path.exists() && checksum_path(&artifact_path).exists());
+    let stored_artifact = fs::read(&artifact_path).unwrap();
+
+    // Cache hit: load the stored artifact.
+    let loaded_class =
+        cache.get_or_compile(class_hash, sierra_contract_class(feature_contract), 2).unwrap();
+    assert_eq!(loaded_class, compiled_class);
+    assert_eq!(fs::read(&artifact_path).unwrap(), stored_artifact);
+
+    // A different optimization level is a different artifact; levels above 3 are treated as 3.
+    assert!(!cache.artifact_path(class_hash, 0).exists());
+    assert_eq!(cache.artifact_path(class_hash, 7), cache.artifact_path(class_hash, 3));
+
+    // Checksum mismatch: the corrupted artifact is removed, then recompiled and stored anew, with
+    // a valid checksum.
+    fs::write(checksum_path(&artifact_path), "corrupted").unwrap();
+    let recompiled_class =
+        cache.get_or_compile(class_hash, sierra_contract_class(feature_contract), 2).unwrap();
+    assert_eq!(recompiled_class, compiled_class);
+    let restored_artifact = fs::read(&artifact_path).unwrap();
+    assert_eq!(
+        fs::read_to_string(checksum_path(&artifact_path)).unwrap(),
+        checksum(&restored_artifact)
+    );
+}
+
+#[test]
+fn test_get_or_compile_artifact() {
+    let cache_dir = tempfile::tempdir().unwrap();
+    let cache = NativeArtifactCache::new(NativeArtifactCacheConfig {
+        path: cache_dir.path().to_path_buf(),
+        max_size: u64::MAX,
+    });
+    let feature_contract = FeatureContract::SierraTestContract;
+    let class_hash = feature_contract.get_class_hash();
+    let sierra_contract_class = sierra_contract_class(feature_contract);
+
+    // Cache miss: compile and store.
+    let artifact_path =
+        cache.get_or_compile_artifact(class_hash, &sierra_contract_class, 2).unwrap();
+    assert_eq!(artifact_path, cache.artifact_path(class_hash, 2));
+    let stored_artifact = fs::read(&artifact_path).unwrap();
+
+    // Cache hit: the stored artifact is kept, and loads to the same class as a direct lookup.
+    assert_eq!(
+        cache.get_or_compile_artifact(class_hash, &sierra_contract_class, 2).unwrap(),
+        artifact_path
+    );
+    assert_eq!(fs::read(&artifact_path).unwrap(), stored_artifact);
+    assert_eq!(
+        load_artifact(&artifact_path, sierra_contract_class.clone()).unwrap(),
+        cache.get_or_compile(class_hash, sierra_contract_class, 2).unwrap()
+    );
+}
+
+#[test]
+fn test_compiler_version() {
+    let cache_dir = tempfile::tempdir().unwrap();
+    let config =
+        NativeArtifactCacheConfig { path: cache_dir.path().to_path_buf(), max_size: u64::MAX };
+    let cache = NativeArtifactCache::new(config.clone());
+    let other_compiler_cache =
+        NativeArtifactCache::new_with_compiler_version(config, "cairo-native-0.0.0-other");
+    let feature_contract = FeatureContract::SierraTestContract;
+    let class_hash = feature_contract.get_class_hash();
+    cache.get_or_compile(class_hash, sierra_contract_class(feature_contract), 2).unwrap();
+
+    // Artifacts of another native compiler miss the cache, and are compiled anew.
+    let other_artifact_path = other_compiler_cache.artifact_path(class_hash, 2);
+    assert_ne!(other_artifact_path, cache.artifact_path(class_hash, 2));
+    assert!(!other_artifact_path.exists());
+    other_compiler_cache
+        .get_or_compile(class_hash, sierra_contract_class(feature_contract), 2)
+        .unwrap();
+    assert!(other_artifact_path.exists());
+
+    // The default version identifies the locked native compiler.
+    assert!(NATIVE_COMPILER_VERSION.starts_with("cairo-native-"));
+}
+
+#[test]
+fn test_eviction() {
+    let cache_dir = tempfile::tempdir().unwrap();
+    // Fits a single artifact.
+    let cache = NativeArtifactCache::new(NativeArtifactCacheConfig {
+        path: cache_dir.path().to_path_buf(),
+        max_size: 1,
+    });
+
+    let first_contract = FeatureContract::SierraTestContract;
+    let second_contract = FeatureContract::SierraExecutionInfoV1Contract;
+    for feature_contract in [first_contract, second_contract] {
+        cache
+            .get_or_compile(
+                feature_contract.get_class_hash(),
+                sierra_contract_class(feature_contract),
+                2,
+            )
+            .unwrap();
+    }
+
+    let first_artifact_path = cache.artifact_path(first_contract.get_class_hash(), 2);
+    assert!(!first_artifact_path.exists() && !checksum_path(&first_artifact_path).exists());
+    assert!(cache.artifact_path(second_contract.get_class_hash(), 2).exists());
+}
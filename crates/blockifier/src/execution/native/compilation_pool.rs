@@ -0,0 +1,193 @@
+use std::collections::HashSet;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc::{self, Receiver, SyncSender, TrySendError};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread;
+
+use cairo_lang_starknet_classes::contract_class::ContractClass as SierraContractClass;
+use starknet_api::core::ClassHash;
+
+use crate::blockifier::config::BackgroundCompilationConfig;
+use crate::execution::contract_class::{ContractClass, NativeContractClassV1};
+use crate::execution::errors::NativeCompilationError;
+use crate::execution::native::artifact_cache::NativeArtifactCache;
+use crate::execution::native::compiler::{
+    compile_sierra_to_native, native_opt_level, NativeCompilationResult,
+};
+use crate::state::global_cache::GlobalContractCache;
+
+#[cfg(test)]
+#[path = "compilation_pool_test.rs"]
+pub mod test;
+
+struct CompilationRequest {
+    class_hash: ClassHash,
+    sierra_contract_class: SierraContractClass,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum CompilationRequestStatus {
+    Enqueued,
+    // The class is already awaiting (or undergoing) compilation.
+    AlreadyRequested,
+    // A previous compilation of the class has failed.
+    Blacklisted,
+    QueueFull,
+    // The queue is disconnected from the workers; not expected while the pool is alive.
+    Disconnected,
+}
+
+#[derive(Debug, Default)]
+struct CompilationPoolState {
+    // Classes that are awaiting (or undergoing) compilation.
+    requested: HashSet<ClassHash>,
+    // Classes that failed to compile; these are never compiled again.
+    blacklist: HashSet<ClassHash>,
+}
+
+/// A pool of worker threads that compiles contract classes to native code in the background.
+/// Callers keep serving the Casm representation of a class while it is being compiled; once
+/// compilation is done, the cached Casm class is atomically replaced with its `V1Native` variant
+/// in the global contract cache.
+/// Workers terminate once all clones of the pool are dropped.
+#[derive(Clone, Debug)]
+pub struct NativeCompilationPool {
+    sender: SyncSender<CompilationRequest>,
+    // Also held by the pool, so that the queue stays connected even without workers.
+    _receiver: Arc<Mutex<Receiver<CompilationRequest>>>,
+    state: Arc<Mutex<CompilationPoolState>>,
+}
+
+impl NativeCompilationPool {
+    pub fn new(
+        config: BackgroundCompilationConfig,
+        opt_level: u8,
+        native_artifact_cache: Option<NativeArtifactCache>,
+        global_contract_cache: GlobalContractCache,
+    ) -> Self {
+        let (sender, receiver) = mpsc::sync_channel(config.queue_size);
+        let receiver = Arc::new(Mutex::new(receiver));
+        let state = Arc::new(Mutex::new(CompilationPoolState::default()));
+
+        for worker_id in 0..config.n_workers {
+            let worker = CompilationWorker {
+                receiver: Arc::clone(&receiver),
+                state: Arc::clone(&state),
+                opt_level,
+                native_artifact_cache: native_artifact_cache.clone(),
+                global_contract_cache: global_contract_cache.clone(),
+            };
+            thread::Builder::new()
+                .name(format!("native-compilation-worker-{worker_id}"))
+                .spawn(move || worker.run())
+                .expect("Failed to spawn a native compilation worker.");
+        }
+
+        Self { sender, _receiver: receiver, state }
+    }
+
+    /// Requests the compilation of the given class to native code. The class is expected to
+    /// already reside in the global contract cache, in its Casm representation.
+    pub fn request_compilation(
+        &self,
+        class_hash: ClassHash,
+        sierra_contract_class: SierraContractClass,
+    ) -> CompilationRequestStatus {
+        let mut state = self.lock_state();
+        if state.blacklist.contains(&class_hash) {
+            return CompilationRequestStatus::Blacklisted;
+        }
+        if state.requested.contains(&class_hash) {
+            return CompilationRequestStatus::AlreadyRequested;
+        }
+
+        match self.sender.try_send(CompilationRequest { class_hash, sierra_contract_class }) {
+            Ok(()) => {
+                state.requested.insert(class_hash);
+                CompilationRequestStatus::Enqueued
+            }
+            Err(TrySendError::Full(_)) => CompilationRequestStatus::QueueFull,
+            Err(TrySendError::Disconnected(_)) => {
+                log::warn!(
+                    "Native compilation queue is disconnected; class {class_hash} was not \
+                     enqueued."
+                );
+                CompilationRequestStatus::Disconnected
+            }
+        }
+    }
+
+    pub fn is_blacklisted(&self, class_hash: &ClassHash) -> bool {
+        self.lock_state().blacklist.contains(class_hash)
+    }
+
+    fn lock_state(&self) -> MutexGuard<'_, CompilationPoolState> {
+        self.state.lock().expect("Native compilation pool state is poisoned.")
+    }
+}
+
+struct CompilationWorker {
+    receiver: Arc<Mutex<Receiver<CompilationRequest>>>,
+    state: Arc<Mutex<CompilationPoolState>>,
+    opt_level: u8,
+    native_artifact_cache: Option<NativeArtifactCache>,
+    global_contract_cache: GlobalContractCache,
+}
+
+impl CompilationWorker {
+    fn run(self) {
+        loop {
+            let request = self.receiver.lock().expect("Compilation queue is poisoned.").recv();
+            // All senders were dropped; the pool is gone.
+            let Ok(CompilationRequest { class_hash, sierra_contract_class }) = request else {
+                return;
+            };
+
+            // Compilation failures (including panics) must not bring down the worker.
+            let compilation_result = panic::catch_unwind(AssertUnwindSafe(|| {
+                self.compile(class_hash, sierra_contract_class)
+            }))
+            .unwrap_or(Err(NativeCompilationError::NativeCompilerPanic));
+
+            let mut state = self.state.lock().expect("Native compilation pool state is poisoned.");
+            match compilation_result {
+                Ok(native_contract_class) => {
+                    let replaced = self
+                        .global_contract_cache
+                        .replace(class_hash, ContractClass::V1Native(native_contract_class));
+                    if !replaced {
+                        log::debug!(
+                            "Class {class_hash} was evicted from the global contract cache during \
+                             its native compilation."
+                        );
+                    }
+                }
+                Err(error) => {
+                    log::warn!(
+                        "Failed to compile class {class_hash} to native code; blacklisting it. \
+                         Error: {error}"
+                    );
+                    state.blacklist.insert(class_hash);
+                }
+            }
+            state.requested.remove(&class_hash);
+        }
+    }
+
+    fn compile(
+        &self,
+        class_hash: ClassHash,
+        sierra_contract_class: SierraContractClass,
+    ) -> NativeCompilationResult<NativeContractClassV1> {
+        match &self.native_artifact_cache {
+            Some(native_artifact_cache) => native_artifact_cache.get_or_compile(
+                class_hash,
+                sierra_contract_class,
+                self.opt_level,
+            ),
+            None => {
+                compile_sierra_to_native(sierra_contract_class, native_opt_level(self.opt_level))
+            }
+        }
+    }
+}
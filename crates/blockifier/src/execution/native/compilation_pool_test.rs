@@ -0,0 +1,111 @@
+use std::thread;
+use std::time::{Duration, Instant};
+
+use cairo_lang_starknet_classes::contract_class::ContractClass as SierraContractClass;
+use pretty_assertions::assert_eq;
+
+use super::{CompilationRequestStatus, NativeCompilationPool};
+use crate::blockifier::config::BackgroundCompilationConfig;
+use crate::execution::contract_class::{ContractClass, ContractClassV1};
+use crate::state::global_cache::{GlobalContractCache, GLOBAL_CONTRACT_CACHE_SIZE_FOR_TEST};
+use crate::test_utils::contracts::FeatureContract;
+
+const COMPILATION_TIMEOUT: Duration = Duration::from_secs(60);
+
+fn sierra_contract_class(feature_contract: FeatureContract) -> SierraContractClass {
+    serde_json::from_str(&feature_contract.get_raw_class()).unwrap()
+}
+
+fn wait_for(condition: impl Fn() -> bool) {
+    let start = Instant::now();
+    while !condition() {
+        assert!(start.elapsed() < COMPILATION_TIMEOUT, "Timed out waiting for compilation.");
+        thread::sleep(Duration::from_millis(10));
+    }
+}
+
+#[test]
+fn test_background_compilation() {
+    let global_contract_cache = GlobalContractCache::new(GLOBAL_CONTRACT_CACHE_SIZE_FOR_TEST);
+    let pool = NativeCompilationPool::new(
+        BackgroundCompilationConfig { n_workers: 2, queue_size: 4 },
+        2,
+        None,
+        global_contract_cache.clone(),
+    );
+    let feature_contract = FeatureContract::SierraTestContract;
+    let class_hash = feature_contract.get_class_hash();
+    global_contract_cache.set(class_hash, ContractClass::V1(ContractClassV1::empty_for_testing()));
+
+    assert_eq!(
+        pool.request_compilation(class_hash, sierra_contract_class(feature_contract)),
+        CompilationRequestStatus::Enqueued
+    );
+    // The Casm class is served until compilation is done.
+    wait_for(|| matches!(global_contract_cache.get(&class_hash), Some(ContractClass::V1Native(_))));
+    assert!(!pool.is_blacklisted(&class_hash));
+}
+
+#[test]
+fn test_compilation_failure_blacklist() {
+    let global_contract_cache = GlobalContractCache::new(GLOBAL_CONTRACT_CACHE_SIZE_FOR_TEST);
+    let pool = NativeCompilationPool::new(
+        BackgroundCompilationConfig { n_workers: 1, queue_size: 1 },
+        2,
+        None,
+        global_contract_cache.clone(),
+    );
+    let feature_contract = FeatureContract::SierraTestContract;
+    let class_hash = feature_contract.get_class_hash();
+    let casm_contract_class = ContractClass::V1(ContractClassV1::empty_for_testing());
+    global_contract_cache.set(class_hash, casm_contract_class.clone());
+
+    let mut invalid_sierra_contract_class = sierra_contract_class(feature_contract);
+    invalid_sierra_contract_class.sierra_program.clear();
+    assert_eq!(
+        pool.request_compilation(class_hash, invalid_sierra_contract_class),
+        CompilationRequestStatus::Enqueued
+    );
+    wait_for(|| pool.is_blacklisted(&class_hash));
+
+    assert_eq!(global_contract_cache.get(&class_hash), Some(casm_contract_class));
+    assert_eq!(
+        pool.request_compilation(class_hash, sierra_contract_class(feature_contract)),
+        CompilationRequestStatus::Blacklisted
+    );
+}
+
+#[test]
+fn test_bounded_queue() {
+    // No workers; requests are never consumed.
+    let pool = NativeCompilationPool::new(
+        BackgroundCompilationConfig { n_workers: 0, queue_size: 1 },
+        2,
+        None,
+        GlobalContractCache::new(GLOBAL_CONTRACT_CACHE_SIZE_FOR_TEST),
+    );
+    let first_contract = FeatureContract::SierraTestContract;
+    let second_contract = FeatureContract::SierraExecutionInfoV1Contract;
+
+    assert_eq!(
+        pool.request_compilation(
+            first_contract.get_class_hash(),
+            sierra_contract_class(first_contract)
+        ),
+        CompilationRequestStatus::Enqueued
+    );
+    assert_eq!(
+        pool.request_compilation(
+            first_contract.get_class_hash(),
+            sierra_contract_class(first_contract)
+        ),
+        CompilationRequestStatus::AlreadyRequested
+    );
+    assert_eq!(
+        pool.request_compilation(
+            second_contract.get_class_hash(),
+            sierra_contract_class(second_contract)
+        ),
+        CompilationRequestStatus::QueueFull
+    );
+}
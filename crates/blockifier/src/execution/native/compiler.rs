@@ -0,0 +1,126 @@
+use std::path::Path;
+
+use cairo_lang_sierra::program::Program;
+use cairo_lang_sierra::program_registry::ProgramRegistry;
+use cairo_lang_starknet_classes::contract_class::{
+    ContractClass as SierraContractClass, ContractEntryPoint, ContractEntryPoints,
+};
+use cairo_lang_utils::bigint::BigUintAsHex;
+use cairo_native::context::NativeContext;
+use cairo_native::executor::AotNativeExecutor;
+use cairo_native::metadata::gas::{GasMetadata, MetadataComputationConfig};
+use cairo_native::{module_to_object, OptLevel};
+use libloading::Library;
+use starknet_api::deprecated_contract_class::EntryPointType;
+use starknet_api::state::ContractClass as SnApiContractClass;
+
+use crate::execution::contract_class::NativeContractClassV1;
+use crate::execution::errors::NativeCompilationError;
+
+#[cfg(test)]
+#[path = "compiler_test.rs"]
+pub mod test;
+
+pub type NativeCompilationResult<T> = Result<T, NativeCompilationError>;
+
+/// Compiles the given Sierra contract class to native code and loads it into the process' memory
+/// space.
+pub fn compile_sierra_to_native(
+    sierra_contract_class: SierraContractClass,
+    opt_level: OptLevel,
+) -> NativeCompilationResult<NativeContractClassV1> {
+    let sierra_program = extract_sierra_program(&sierra_contract_class)?;
+
+    let native_context = NativeContext::new();
+    let native_program = native_context.compile(&sierra_program)?;
+    let executor = AotNativeExecutor::from_native_module(native_program, opt_level);
+
+    Ok(NativeContractClassV1::new(executor, sierra_contract_class)?)
+}
+
+/// Compiles the given Sierra program into a native object, to be linked into a shared library.
+pub fn compile_sierra_to_object(
+    sierra_program: &Program,
+    opt_level: OptLevel,
+) -> NativeCompilationResult<Vec<u8>> {
+    let native_context = NativeContext::new();
+    let native_program = native_context.compile(sierra_program)?;
+
+    module_to_object(native_program.module(), opt_level)
+        .map_err(|error| NativeCompilationError::ObjectCompilationError(error.to_string()))
+}
+
+/// Loads a native executor from a shared library that was built from the given Sierra program.
+pub fn load_native_executor(
+    shared_library_path: &Path,
+    sierra_program: &Program,
+) -> NativeCompilationResult<AotNativeExecutor> {
+    let registry = ProgramRegistry::new(sierra_program)
+        .map_err(|error| NativeCompilationError::ArtifactLoadError(error.to_string()))?;
+    let gas_metadata = GasMetadata::new(sierra_program, Some(MetadataComputationConfig::default()))
+        .map_err(|error| NativeCompilationError::ArtifactLoadError(error.to_string()))?;
+    // Safety: the library is expected to be built from `sierra_program`; its integrity is the
+    // responsibility of the caller.
+    let library = unsafe { Library::new(shared_library_path) }
+        .map_err(|error| NativeCompilationError::ArtifactLoadError(error.to_string()))?;
+
+    Ok(AotNativeExecutor::new(library, registry, gas_metadata))
+}
+
+// TODO(rodro): we are having two instances of a sierra program, one it's object form
+// and another in its felt encoded form. This can be avoided by either:
+//   1. Having access to the encoding/decoding functions
+//   2. Refactoring the code on the Cairo mono-repo
+pub fn extract_sierra_program(
+    sierra_contract_class: &SierraContractClass,
+) -> NativeCompilationResult<Program> {
+    sierra_contract_class
+        .extract_sierra_program()
+        .map_err(|error| NativeCompilationError::SierraProgramExtractionError(error.to_string()))
+}
+
+/// Maps a numeric optimization level (as given in configuration) to its native compiler
+/// counterpart. Levels above 3 are treated as 3.
+pub fn native_opt_level(opt_level: u8) -> OptLevel {
+    match opt_level {
+        0 => OptLevel::None,
+        1 => OptLevel::Less,
+        2 => OptLevel::Default,
+        _ => OptLevel::Aggressive,
+    }
+}
+
+/// Converts a Sierra contract class from its SN API representation (the way it is stored) to the
+/// representation expected by the native compiler.
+// Note: the ABI and the debug info are not required for execution and are not restored.
+pub fn sn_api_to_sierra_contract_class(contract_class: SnApiContractClass) -> SierraContractClass {
+    let SnApiContractClass { sierra_program, mut entry_points_by_type, .. } = contract_class;
+
+    let mut take_entry_points = |entry_point_type: EntryPointType| -> Vec<ContractEntryPoint> {
+        entry_points_by_type
+            .remove(&entry_point_type)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|entry_point| ContractEntryPoint {
+                selector: entry_point.selector.0.to_biguint(),
+                function_idx: entry_point.function_idx.0,
+            })
+            .collect()
+    };
+    let entry_points_by_type = ContractEntryPoints {
+        external: take_entry_points(EntryPointType::External),
+        l1_handler: take_entry_points(EntryPointType::L1Handler),
+        constructor: take_entry_points(EntryPointType::Constructor),
+    };
+
+    SierraContractClass {
+        sierra_program: sierra_program
+            .into_iter()
+            .map(|felt| BigUintAsHex { value: felt.to_biguint() })
+            .collect(),
+        sierra_program_debug_info: None,
+        contract_class_version: String::default(),
+        entry_points_by_type,
+        abi: None,
+    }
+}
@@ -0,0 +1,29 @@
+use cairo_lang_starknet_classes::contract_class::ContractClass as SierraContractClass;
+use cairo_native::OptLevel;
+use pretty_assertions::assert_eq;
+
+use super::{compile_sierra_to_native, sn_api_to_sierra_contract_class};
+use crate::execution::contract_class::NativeContractClassV1;
+use crate::test_utils::contracts::FeatureContract;
+
+#[test]
+fn test_compile_sn_api_sierra_contract_class() {
+    let feature_contract = FeatureContract::SierraTestContract;
+    let sierra_contract_class: SierraContractClass =
+        serde_json::from_str(&feature_contract.get_raw_class()).unwrap();
+
+    let converted_contract_class =
+        sn_api_to_sierra_contract_class(feature_contract.get_sn_api_sierra_contract_class());
+    assert_eq!(converted_contract_class.sierra_program, sierra_contract_class.sierra_program);
+    assert_eq!(
+        converted_contract_class.entry_points_by_type,
+        sierra_contract_class.entry_points_by_type
+    );
+
+    let native_contract_class =
+        compile_sierra_to_native(converted_contract_class, OptLevel::Default).unwrap();
+    assert_eq!(
+        native_contract_class,
+        NativeContractClassV1::from_file(&feature_contract.get_compiled_path())
+    );
+}
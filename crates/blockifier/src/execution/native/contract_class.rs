@@ -0,0 +1,267 @@
+use std::collections::HashMap;
+use std::mem;
+use std::ops::{Deref, Index};
+use std::sync::Arc;
+
+use cairo_lang_sierra::ids::FunctionId;
+use cairo_lang_starknet_classes::casm_contract_class::{
+    CasmContractClass, StarknetSierraCompilationError,
+};
+use cairo_lang_starknet_classes::contract_class::{
+    ContractClass as SierraContractClass, ContractEntryPoint,
+    ContractEntryPoints as SierraContractEntryPoints,
+};
+use cairo_lang_utils::bigint::BigUintAsHex;
+use cairo_native::executor::AotNativeExecutor;
+use starknet_api::core::EntryPointSelector;
+use starknet_api::deprecated_contract_class::EntryPointType;
+
+use crate::execution::contract_class::ContractClassV1;
+use crate::execution::entry_point::EntryPointExecutionResult;
+use crate::execution::errors::EntryPointExecutionError;
+use crate::execution::native::utils::contract_entrypoint_to_entrypoint_selector;
+
+/// A rough estimate of the size of the native code compiled from a Sierra felt.
+const ESTIMATED_NATIVE_CODE_BYTES_PER_SIERRA_FELT: usize = 256;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct NativeContractClassV1(pub Arc<NativeContractClassV1Inner>);
+impl Deref for NativeContractClassV1 {
+    type Target = NativeContractClassV1Inner;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum NativeEntryPointError {
+    #[error("Failed to convert Sierra to Casm: {0}")]
+    FailedToConvertSierraToCasm(String),
+    #[error("FunctionId {0} not found")]
+    FunctionIdNotFound(usize),
+}
+
+impl NativeContractClassV1 {
+    pub(crate) fn constructor_selector(&self) -> Option<EntryPointSelector> {
+        self.entry_points_by_type.constructor.first().map(|ep| ep.selector)
+    }
+
+    /// Estimates the size of the compiled code, and of the Sierra program and Casm class kept for
+    /// VM fallback.
+    pub(crate) fn estimated_size_in_bytes(&self) -> usize {
+        self.sierra_program_raw.len() * mem::size_of::<BigUintAsHex>()
+            + self.native_code_size
+            + self.casm_equivalent_class.estimated_size_in_bytes()
+    }
+
+    /// Returns the Casm class compiled from the same Sierra program. Native execution is charged
+    /// as the VM execution of this class, and its hash computation is estimated by it.
+    pub fn casm_equivalent_class(&self) -> &ContractClassV1 {
+        &self.casm_equivalent_class
+    }
+
+    /// Initialize a compiled contract class for native.
+    ///
+    /// executor must be derived from sierra_program which in turn must be derived from
+    /// sierra_contract_class.
+    /// The size of the compiled code, which is unknown when it is compiled in memory, is estimated
+    /// by that of the Sierra program.
+    /// The Sierra program is also compiled to Casm (see [Self::casm_equivalent_class]).
+    pub fn new(
+        executor: AotNativeExecutor,
+        sierra_contract_class: SierraContractClass,
+    ) -> Result<NativeContractClassV1, NativeEntryPointError> {
+        let native_code_size = sierra_contract_class.sierra_program.len()
+            * ESTIMATED_NATIVE_CODE_BYTES_PER_SIERRA_FELT;
+        Self::new_with_native_code_size(executor, sierra_contract_class, native_code_size)
+    }
+
+    /// Like [Self::new], for compiled code of a known size (e.g., loaded from a shared library).
+    pub fn new_with_native_code_size(
+        executor: AotNativeExecutor,
+        sierra_contract_class: SierraContractClass,
+        native_code_size: usize,
+    ) -> Result<NativeContractClassV1, NativeEntryPointError> {
+        let contract =
+            NativeContractClassV1Inner::new(executor, sierra_contract_class, native_code_size)?;
+
+        Ok(Self(Arc::new(contract)))
+    }
+
+    pub fn to_casm_contract_class(
+        self,
+    ) -> Result<CasmContractClass, StarknetSierraCompilationError> {
+        CasmContractClass::from_contract_class(self.to_sierra_contract_class(), false, usize::MAX)
+    }
+
+    /// Returns the Sierra contract class this class was compiled from, without its ABI and debug
+    /// info.
+    pub fn to_sierra_contract_class(&self) -> SierraContractClass {
+        SierraContractClass {
+            // Cloning because these are behind an Arc.
+            sierra_program: self.sierra_program_raw.clone(),
+            entry_points_by_type: self.fallback_entry_points_by_type.clone(),
+            abi: None,
+            sierra_program_debug_info: None,
+            contract_class_version: String::default(),
+        }
+    }
+
+    /// Returns an entry point into the natively compiled contract.
+    pub fn get_entrypoint(
+        &self,
+        entry_point_type: EntryPointType,
+        entrypoint_selector: EntryPointSelector,
+    ) -> EntryPointExecutionResult<&FunctionId> {
+        let entrypoints = &self.entry_points_by_type[entry_point_type];
+
+        entrypoints
+            .iter()
+            .find(|entrypoint| entrypoint.selector == entrypoint_selector)
+            .map(|op| &op.function_id)
+            .ok_or_else(|| EntryPointExecutionError::NativeExecutionError {
+                info: format!("Entrypoint selector {} not found", entrypoint_selector.0),
+            })
+    }
+}
+
+#[derive(Debug)]
+pub struct NativeContractClassV1Inner {
+    pub executor: AotNativeExecutor,
+    entry_points_by_type: NativeContractEntryPoints,
+    // Storing the raw sierra program and entry points to be able to fallback to the vm
+    sierra_program_raw: Vec<BigUintAsHex>,
+    fallback_entry_points_by_type: SierraContractEntryPoints,
+    casm_equivalent_class: ContractClassV1,
+    native_code_size: usize,
+}
+
+impl NativeContractClassV1Inner {
+    /// See [NativeContractClassV1::new]
+    fn new(
+        executor: AotNativeExecutor,
+        sierra_contract_class: SierraContractClass,
+        native_code_size: usize,
+    ) -> Result<Self, NativeEntryPointError> {
+        // This exception should never occur as it was also used to create the AotNativeExecutor
+        let sierra_program =
+            sierra_contract_class.extract_sierra_program().expect("can't extract sierra program");
+        // Note [Cairo Native ABI]
+        // The supplied (compiled) sierra program might have been populated with debug info and this
+        // affects the ABI, because the debug info is appended to the function name and the
+        // function name is what is used by Cairo Native to lookup the function.
+        // Therefore it's not enough to know the function index and we need enrich the contract
+        // entry point with FunctionIds from SierraProgram.
+        let lookup_fid: HashMap<usize, &FunctionId> =
+            HashMap::from_iter(sierra_program.funcs.iter().map(|fid| {
+                // This exception should never occur as the id is also in [SierraContractClass]
+                let id: usize = fid.id.id.try_into().expect("function id exceeds usize");
+                (id, &fid.id)
+            }));
+        let casm_equivalent_class = CasmContractClass::from_contract_class(
+            sierra_contract_class.clone(),
+            false,
+            usize::MAX,
+        )
+        .map_err(|e| NativeEntryPointError::FailedToConvertSierraToCasm(e.to_string()))
+        .and_then(|casm_contract_class| {
+            ContractClassV1::try_from(casm_contract_class)
+                .map_err(|e| NativeEntryPointError::FailedToConvertSierraToCasm(e.to_string()))
+        })?;
+
+        Ok(NativeContractClassV1Inner {
+            executor,
+            entry_points_by_type: NativeContractEntryPoints::try_from(
+                &lookup_fid,
+                &sierra_contract_class.entry_points_by_type,
+            )?,
+            sierra_program_raw: sierra_contract_class.sierra_program,
+            fallback_entry_points_by_type: sierra_contract_class.entry_points_by_type,
+            casm_equivalent_class,
+            native_code_size,
+        })
+    }
+}
+
+// The location where the compiled contract is loaded into memory will not
+// be the same therefore we exclude it from the comparison.
+impl PartialEq for NativeContractClassV1Inner {
+    fn eq(&self, other: &Self) -> bool {
+        self.entry_points_by_type == other.entry_points_by_type
+            && self.sierra_program_raw == other.sierra_program_raw
+    }
+}
+
+#[derive(Debug, PartialEq)]
+/// Modelled after [SierraContractEntryPoints]
+/// and enriched with information for the Cairo Native ABI.
+/// See Note [Cairo Native ABI]
+struct NativeContractEntryPoints {
+    constructor: Vec<NativeEntryPoint>,
+    external: Vec<NativeEntryPoint>,
+    l1_handler: Vec<NativeEntryPoint>,
+}
+
+impl NativeContractEntryPoints {
+    /// Convert [SierraContractEntryPoints] to [NativeContractEntryPoints] via a
+    /// [FunctionId] lookup table.
+    ///
+    /// On failure returns the first FunctionId that it couldn't find.
+    fn try_from(
+        lookup: &HashMap<usize, &FunctionId>,
+        sep: &SierraContractEntryPoints,
+    ) -> Result<NativeContractEntryPoints, NativeEntryPointError> {
+        let constructor = sep
+            .constructor
+            .iter()
+            .map(|c| NativeEntryPoint::try_from(lookup, c))
+            .collect::<Result<_, _>>()?;
+        let external = sep
+            .external
+            .iter()
+            .map(|c| NativeEntryPoint::try_from(lookup, c))
+            .collect::<Result<_, _>>()?;
+        let l1_handler = sep
+            .l1_handler
+            .iter()
+            .map(|c| NativeEntryPoint::try_from(lookup, c))
+            .collect::<Result<_, _>>()?;
+
+        Ok(NativeContractEntryPoints { constructor, external, l1_handler })
+    }
+}
+
+impl Index<EntryPointType> for NativeContractEntryPoints {
+    type Output = Vec<NativeEntryPoint>;
+
+    fn index(&self, index: EntryPointType) -> &Self::Output {
+        match index {
+            EntryPointType::Constructor => &self.constructor,
+            EntryPointType::External => &self.external,
+            EntryPointType::L1Handler => &self.l1_handler,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+/// Provides a relation between a function in a contract and a compiled contract
+struct NativeEntryPoint {
+    /// The selector is the key to find the function in the contract
+    selector: EntryPointSelector,
+    /// and the function_id is the key to find the function in the compiled contract
+    function_id: FunctionId,
+}
+
+impl NativeEntryPoint {
+    fn try_from(
+        lookup: &HashMap<usize, &FunctionId>,
+        cep: &ContractEntryPoint,
+    ) -> Result<NativeEntryPoint, NativeEntryPointError> {
+        let &function_id = lookup
+            .get(&cep.function_idx)
+            .ok_or(NativeEntryPointError::FunctionIdNotFound(cep.function_idx))?;
+        let selector = contract_entrypoint_to_entrypoint_selector(cep);
+        Ok(NativeEntryPoint { selector, function_id: function_id.clone() })
+    }
+}
@@ -0,0 +1,314 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex};
+
+use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
+use serde::Serialize;
+use starknet_api::core::{
+    ClassHash, CompiledClassHash, ContractAddress, EntryPointSelector, Nonce,
+};
+use starknet_api::state::StorageKey;
+use starknet_types_core::felt::Felt;
+
+use crate::execution::call_info::CallInfo;
+use crate::execution::contract_class::{ContractClass, NativeContractClassV1};
+use crate::execution::entry_point::{
+    CallEntryPoint, EntryPointExecutionContext, EntryPointExecutionResult,
+};
+use crate::execution::entry_point_execution;
+use crate::execution::execution_utils::execute_native_entry_point_call;
+use crate::state::cached_state::StorageEntry;
+use crate::state::errors::StateError;
+use crate::state::state_api::{State, StateReader, StateResult};
+use crate::state::state_wrapper::DynStateWrapper;
+
+#[cfg(test)]
+#[path = "differential_execution_test.rs"]
+pub mod test;
+
+pub type StorageWrites = HashMap<(ContractAddress, StorageKey), Felt>;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+pub enum DivergenceKind {
+    // One execution succeeded while the other failed with an error.
+    ExecutionResult,
+    FailureFlag,
+    Retdata,
+    Events,
+    L2ToL1Messages,
+    StorageReadValues,
+    StorageWrites,
+    GasConsumed,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct Divergence {
+    pub kind: DivergenceKind,
+    pub vm: String,
+    pub native: String,
+}
+
+/// Describes how the VM and native executions of a single entry point call differ.
+/// Nested calls are compared separately, each in its own report.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct DivergenceReport {
+    pub class_hash: Option<ClassHash>,
+    pub storage_address: ContractAddress,
+    pub entry_point_selector: EntryPointSelector,
+    pub divergences: Vec<Divergence>,
+}
+
+/// Collects the divergence reports of a differential execution; shared by all the transactions
+/// executed with the same block context.
+#[derive(Clone, Debug, Default)]
+pub struct DivergenceReporter {
+    reports: Arc<Mutex<Vec<DivergenceReport>>>,
+}
+
+impl DivergenceReporter {
+    pub fn report(&self, report: DivergenceReport) {
+        log::warn!(
+            "VM and native executions diverged: {}",
+            serde_json::to_string(&report).expect("Failed to serialize divergence report.")
+        );
+        self.reports.lock().expect("Divergence reports are poisoned.").push(report);
+    }
+
+    pub fn take_reports(&self) -> Vec<DivergenceReport> {
+        std::mem::take(&mut *self.reports.lock().expect("Divergence reports are poisoned."))
+    }
+}
+
+/// Executes the given entry point both natively (the canonical execution) and on the Cairo VM,
+/// using the Casm class compiled from the same Sierra program, and reports any divergence between
+/// the two. The VM execution runs first on an isolated view of the state, and leaves no trace on
+/// the state, the execution context or the given resources.
+/// The VM execution runs its whole call tree on the VM, including nested calls to classes compiled
+/// to native code; hence, the compared storage writes cover the entire subtree. In addition, each
+/// nested call of the canonical execution is compared on its own.
+pub fn execute_entry_point_call(
+    call: CallEntryPoint,
+    contract_class: NativeContractClassV1,
+    state: &mut dyn State,
+    resources: &mut ExecutionResources,
+    context: &mut EntryPointExecutionContext,
+    divergence_reporter: &DivergenceReporter,
+) -> EntryPointExecutionResult<CallInfo> {
+    let (vm_result, vm_storage_writes) =
+        execute_on_vm_in_isolation(call.clone(), &contract_class, state, resources, context);
+
+    let mut native_state = DynStateWrapper::new(state);
+    let native_result = execute_native_entry_point_call(
+        call.clone(),
+        contract_class,
+        &mut native_state,
+        resources,
+        context,
+    );
+
+    let divergences = find_divergences(
+        &vm_result,
+        &vm_storage_writes,
+        &native_result,
+        &native_state.storage_updates,
+    );
+    if !divergences.is_empty() {
+        divergence_reporter.report(DivergenceReport {
+            class_hash: call.class_hash,
+            storage_address: call.storage_address,
+            entry_point_selector: call.entry_point_selector,
+            divergences,
+        });
+    }
+
+    if native_result.is_ok() {
+        native_state.commit()?;
+    }
+    native_result
+}
+
+fn execute_on_vm_in_isolation(
+    call: CallEntryPoint,
+    contract_class: &NativeContractClassV1,
+    state: &mut dyn State,
+    resources: &ExecutionResources,
+    context: &mut EntryPointExecutionContext,
+) -> (EntryPointExecutionResult<CallInfo>, StorageWrites) {
+    // Snapshot the context counters, to be restored after the VM execution.
+    let vm_run_resources = context.vm_run_resources.clone();
+    let n_emitted_events = context.n_emitted_events;
+    let n_sent_messages_to_l1 = context.n_sent_messages_to_l1;
+
+    let mut isolated_state = IsolatedState(DynStateWrapper::new(state));
+    context.in_shadow_execution = true;
+    let vm_result = execute_casm_equivalent_entry_point_call(
+        call,
+        contract_class,
+        &mut isolated_state,
+        &mut resources.clone(),
+        context,
+    );
+    context.in_shadow_execution = false;
+
+    context.vm_run_resources = vm_run_resources;
+    context.n_emitted_events = n_emitted_events;
+    context.n_sent_messages_to_l1 = n_sent_messages_to_l1;
+
+    (vm_result, isolated_state.0.storage_updates)
+}
+
+/// Executes the given entry point on the Cairo VM, using the Casm class compiled from the same
+/// Sierra program as the given native class.
+pub fn execute_casm_equivalent_entry_point_call(
+    call: CallEntryPoint,
+    contract_class: &NativeContractClassV1,
+    state: &mut dyn State,
+    resources: &mut ExecutionResources,
+    context: &mut EntryPointExecutionContext,
+) -> EntryPointExecutionResult<CallInfo> {
+    // The Casm class is compiled once per native class, rather than upon each call.
+    let contract_class = contract_class.casm_equivalent_class().clone();
+    entry_point_execution::execute_entry_point_call(call, contract_class, state, resources, context)
+}
+
+pub fn find_divergences(
+    vm_result: &EntryPointExecutionResult<CallInfo>,
+    vm_storage_writes: &StorageWrites,
+    native_result: &EntryPointExecutionResult<CallInfo>,
+    native_storage_writes: &StorageWrites,
+) -> Vec<Divergence> {
+    let (vm_call_info, native_call_info) = match (vm_result, native_result) {
+        (Ok(vm_call_info), Ok(native_call_info)) => (vm_call_info, native_call_info),
+        // Errors are not compared; they are expected to differ between the two executions.
+        (Err(_), Err(_)) => return vec![],
+        _ => {
+            return vec![Divergence {
+                kind: DivergenceKind::ExecutionResult,
+                vm: format_result(vm_result),
+                native: format_result(native_result),
+            }];
+        }
+    };
+
+    let (vm, native) = (&vm_call_info.execution, &native_call_info.execution);
+    let mut divergences = vec![];
+    let mut compare = |kind, vm_value: &dyn Debug, native_value: &dyn Debug| {
+        let (vm_value, native_value) = (format!("{vm_value:?}"), format!("{native_value:?}"));
+        if vm_value != native_value {
+            divergences.push(Divergence { kind, vm: vm_value, native: native_value });
+        }
+    };
+    compare(DivergenceKind::FailureFlag, &vm.failed, &native.failed);
+    compare(DivergenceKind::Retdata, &vm.retdata, &native.retdata);
+    compare(DivergenceKind::Events, &vm.events, &native.events);
+    compare(DivergenceKind::L2ToL1Messages, &vm.l2_to_l1_messages, &native.l2_to_l1_messages);
+    compare(
+        DivergenceKind::StorageReadValues,
+        &vm_call_info.storage_read_values,
+        &native_call_info.storage_read_values,
+    );
+    // Sort the writes, for a deterministic representation.
+    compare(
+        DivergenceKind::StorageWrites,
+        &vm_storage_writes.iter().collect::<BTreeMap<_, _>>(),
+        &native_storage_writes.iter().collect::<BTreeMap<_, _>>(),
+    );
+    compare(DivergenceKind::GasConsumed, &vm.gas_consumed, &native.gas_consumed);
+
+    divergences
+}
+
+fn format_result(result: &EntryPointExecutionResult<CallInfo>) -> String {
+    match result {
+        Ok(call_info) => format!("{:?}", call_info.execution),
+        Err(error) => error.to_string(),
+    }
+}
+
+/// A transactional view of the state, whose updates are never committed. Unlike
+/// [`DynStateWrapper`], visited PCs are not propagated to the underlying state, as they must not
+/// affect the block's bouncer.
+struct IsolatedState<'a>(DynStateWrapper<'a>);
+
+impl StateReader for IsolatedState<'_> {
+    fn get_storage_at(
+        &self,
+        contract_address: ContractAddress,
+        key: StorageKey,
+    ) -> StateResult<Felt> {
+        self.0.get_storage_at(contract_address, key)
+    }
+
+    fn get_nonce_at(&self, contract_address: ContractAddress) -> StateResult<Nonce> {
+        self.0.get_nonce_at(contract_address)
+    }
+
+    fn get_storage_values(&self, storage_entries: &[StorageEntry]) -> StateResult<Vec<Felt>> {
+        self.0.get_storage_values(storage_entries)
+    }
+
+    fn get_nonces(&self, contract_addresses: &[ContractAddress]) -> StateResult<Vec<Nonce>> {
+        self.0.get_nonces(contract_addresses)
+    }
+
+    fn get_class_hash_at(&self, contract_address: ContractAddress) -> StateResult<ClassHash> {
+        self.0.get_class_hash_at(contract_address)
+    }
+
+    fn get_compiled_contract_class(&self, class_hash: ClassHash) -> StateResult<ContractClass> {
+        self.0.get_compiled_contract_class(class_hash)
+    }
+
+    fn get_compiled_class_hash(&self, class_hash: ClassHash) -> StateResult<CompiledClassHash> {
+        self.0.get_compiled_class_hash(class_hash)
+    }
+
+    fn get_fee_token_balance(
+        &mut self,
+        contract_address: ContractAddress,
+        fee_token_address: ContractAddress,
+    ) -> Result<(Felt, Felt), StateError> {
+        self.0.get_fee_token_balance(contract_address, fee_token_address)
+    }
+}
+
+impl State for IsolatedState<'_> {
+    fn set_storage_at(
+        &mut self,
+        contract_address: ContractAddress,
+        key: StorageKey,
+        value: Felt,
+    ) -> StateResult<()> {
+        self.0.set_storage_at(contract_address, key, value)
+    }
+
+    fn increment_nonce(&mut self, contract_address: ContractAddress) -> StateResult<()> {
+        self.0.increment_nonce(contract_address)
+    }
+
+    fn set_class_hash_at(
+        &mut self,
+        contract_address: ContractAddress,
+        class_hash: ClassHash,
+    ) -> StateResult<()> {
+        self.0.set_class_hash_at(contract_address, class_hash)
+    }
+
+    fn set_contract_class(
+        &mut self,
+        class_hash: ClassHash,
+        contract_class: ContractClass,
+    ) -> StateResult<()> {
+        self.0.set_contract_class(class_hash, contract_class)
+    }
+
+    fn set_compiled_class_hash(
+        &mut self,
+        class_hash: ClassHash,
+        compiled_class_hash: CompiledClassHash,
+    ) -> StateResult<()> {
+        self.0.set_compiled_class_hash(class_hash, compiled_class_hash)
+    }
+
+    fn add_visited_pcs(&mut self, _class_hash: ClassHash, _pcs: &HashSet<usize>) {}
+}
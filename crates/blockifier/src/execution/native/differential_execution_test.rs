@@ -0,0 +1,203 @@
+use std::sync::{Arc, Mutex};
+
+use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
+use pretty_assertions::assert_eq;
+use starknet_api::core::PatriciaKey;
+use starknet_api::state::StorageKey;
+use starknet_api::{calldata, contract_address, felt, patricia_key};
+
+use super::{find_divergences, Divergence, DivergenceKind, DivergenceReporter, StorageWrites};
+use crate::abi::abi_utils::selector_from_name;
+use crate::context::{BlockContext, ChainInfo, TransactionContext};
+use crate::execution::call_info::{CallExecution, CallInfo, Retdata};
+use crate::execution::entry_point::{CallEntryPoint, EntryPointExecutionContext};
+use crate::execution::errors::EntryPointExecutionError;
+use crate::execution::syscalls::interceptor::{
+    InterceptedSyscall, SyscallInterception, SyscallInterceptor,
+};
+use crate::execution::syscalls::SyscallSelector;
+use crate::retdata;
+use crate::state::state_api::StateReader;
+use crate::test_utils::contracts::FeatureContract;
+use crate::test_utils::initial_test_state::test_state;
+use crate::test_utils::{create_calldata, trivial_external_entry_point_new, BALANCE};
+use crate::transaction::objects::{DeprecatedTransactionInfo, TransactionInfo};
+
+fn call_info(retdata: Retdata, gas_consumed: u64) -> CallInfo {
+    CallInfo {
+        execution: CallExecution { retdata, gas_consumed, ..Default::default() },
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_find_divergences() {
+    let storage_writes = StorageWrites::from([(
+        (contract_address!("0x1"), StorageKey(patricia_key!("0x2"))),
+        felt!(3_u8),
+    )]);
+    let vm_result = Ok(call_info(retdata![felt!(1_u8)], 100));
+
+    // Identical executions.
+    assert_eq!(
+        find_divergences(
+            &vm_result,
+            &storage_writes,
+            &Ok(call_info(retdata![felt!(1_u8)], 100)),
+            &storage_writes
+        ),
+        vec![]
+    );
+
+    // Different retdata, gas and storage writes.
+    let divergences = find_divergences(
+        &vm_result,
+        &storage_writes,
+        &Ok(call_info(retdata![felt!(2_u8)], 90)),
+        &StorageWrites::default(),
+    );
+    assert_eq!(
+        divergences.iter().map(|divergence| divergence.kind).collect::<Vec<_>>(),
+        vec![DivergenceKind::Retdata, DivergenceKind::StorageWrites, DivergenceKind::GasConsumed]
+    );
+    assert_eq!(
+        divergences[2],
+        Divergence {
+            kind: DivergenceKind::GasConsumed,
+            vm: "100".to_string(),
+            native: "90".to_string()
+        }
+    );
+
+    // Only one of the executions failed.
+    let native_error = EntryPointExecutionError::InternalError("Native error.".to_string());
+    let divergences = find_divergences(
+        &vm_result,
+        &storage_writes,
+        &Err(native_error),
+        &StorageWrites::default(),
+    );
+    assert_eq!(divergences.len(), 1);
+    assert_eq!(divergences[0].kind, DivergenceKind::ExecutionResult);
+    assert_eq!(divergences[0].native, "Internal error: Native error.");
+}
+
+fn differential_execution_context(
+    divergence_reporter: &DivergenceReporter,
+) -> EntryPointExecutionContext {
+    let block_context = BlockContext {
+        divergence_reporter: Some(divergence_reporter.clone()),
+        ..BlockContext::create_for_testing()
+    };
+    let tx_context = TransactionContext {
+        block_context,
+        tx_info: TransactionInfo::Deprecated(DeprecatedTransactionInfo::default()),
+    };
+    EntryPointExecutionContext::new_invoke(Arc::new(tx_context), true).unwrap()
+}
+
+#[test]
+fn test_differential_execution() {
+    let test_contract = FeatureContract::SierraTestContract;
+    let chain_info = &ChainInfo::create_for_testing();
+    let mut state = test_state(chain_info, BALANCE, &[(test_contract, 1)]);
+    let (key, value) = (1234_u16, 18_u8);
+    let entry_point_call = CallEntryPoint {
+        calldata: calldata![felt!(key), felt!(value)],
+        entry_point_selector: selector_from_name("test_storage_read_write"),
+        ..trivial_external_entry_point_new(test_contract)
+    };
+    let expected_call_info = entry_point_call
+        .clone()
+        .execute_directly(&mut test_state(chain_info, BALANCE, &[(test_contract, 1)]))
+        .unwrap();
+
+    let divergence_reporter = DivergenceReporter::default();
+    let mut context = differential_execution_context(&divergence_reporter);
+    let call_info =
+        entry_point_call.execute(&mut state, &mut ExecutionResources::default(), &mut context);
+
+    // The canonical result is unaffected, and its storage writes are committed.
+    assert_eq!(call_info.unwrap(), expected_call_info);
+    assert_eq!(
+        state
+            .get_storage_at(test_contract.get_instance_address(0), StorageKey(patricia_key!(key)))
+            .unwrap(),
+        felt!(value)
+    );
+    assert_eq!(divergence_reporter.take_reports(), vec![]);
+}
+
+#[test]
+fn test_differential_execution_of_nested_calls() {
+    let test_contract = FeatureContract::SierraTestContract;
+    let chain_info = &ChainInfo::create_for_testing();
+    let mut state = test_state(chain_info, BALANCE, &[(test_contract, 2)]);
+    let inner_contract_address = test_contract.get_instance_address(1);
+    let (key, value) = (405_u16, 48_u8);
+    let entry_point_call = CallEntryPoint {
+        calldata: create_calldata(
+            inner_contract_address,
+            "test_storage_read_write",
+            &[felt!(key), felt!(value)],
+        ),
+        entry_point_selector: selector_from_name("test_call_contract"),
+        ..trivial_external_entry_point_new(test_contract)
+    };
+    let expected_call_info = entry_point_call
+        .clone()
+        .execute_directly(&mut test_state(chain_info, BALANCE, &[(test_contract, 2)]))
+        .unwrap();
+
+    // The VM side runs the whole call tree on the VM; both the outer and the inner calls match
+    // their native counterparts.
+    let divergence_reporter = DivergenceReporter::default();
+    let mut context = differential_execution_context(&divergence_reporter);
+    let call_info =
+        entry_point_call.execute(&mut state, &mut ExecutionResources::default(), &mut context);
+
+    assert_eq!(call_info.unwrap(), expected_call_info);
+    assert_eq!(
+        state.get_storage_at(inner_contract_address, StorageKey(patricia_key!(key))).unwrap(),
+        felt!(value)
+    );
+    assert_eq!(divergence_reporter.take_reports(), vec![]);
+}
+
+// Logs the selectors of the intercepted syscalls.
+#[derive(Debug, Default)]
+struct LoggingInterceptor {
+    selectors: Mutex<Vec<SyscallSelector>>,
+}
+
+impl SyscallInterceptor for LoggingInterceptor {
+    fn pre_syscall(&self, syscall: &InterceptedSyscall) -> SyscallInterception {
+        self.selectors.lock().unwrap().push(syscall.selector);
+        SyscallInterception::Proceed
+    }
+}
+
+#[test]
+fn test_differential_execution_syscall_interception() {
+    let test_contract = FeatureContract::SierraTestContract;
+    let chain_info = &ChainInfo::create_for_testing();
+    let mut state = test_state(chain_info, BALANCE, &[(test_contract, 1)]);
+    let entry_point_call = CallEntryPoint {
+        calldata: calldata![felt!(1234_u16), felt!(18_u8)],
+        entry_point_selector: selector_from_name("test_storage_read_write"),
+        ..trivial_external_entry_point_new(test_contract)
+    };
+
+    // Only the syscalls of the canonical (native) execution are intercepted.
+    let divergence_reporter = DivergenceReporter::default();
+    let mut context = differential_execution_context(&divergence_reporter);
+    let interceptor = Arc::new(LoggingInterceptor::default());
+    context.syscall_interceptor = Some(interceptor.clone());
+    entry_point_call.execute(&mut state, &mut ExecutionResources::default(), &mut context).unwrap();
+
+    assert_eq!(
+        *interceptor.selectors.lock().unwrap(),
+        vec![SyscallSelector::StorageWrite, SyscallSelector::StorageRead]
+    );
+    assert_eq!(divergence_reporter.take_reports(), vec![]);
+}
@@ -3,6 +3,7 @@ use log::debug;
 
 use super::syscall_handler::NativeSyscallHandler;
 use super::utils::run_native_executor;
+use super::worker;
 use crate::execution::call_info::CallInfo;
 use crate::execution::contract_class::NativeContractClassV1;
 use crate::execution::entry_point::{
@@ -10,6 +11,10 @@ use crate::execution::entry_point::{
 };
 use crate::state::state_api::State;
 
+#[cfg(test)]
+#[path = "entry_point_execution_test.rs"]
+pub mod test;
+
 pub fn execute_entry_point_call(
     call: CallEntryPoint,
     contract_class: NativeContractClassV1,
@@ -17,6 +22,17 @@ pub fn execute_entry_point_call(
     resources: &mut ExecutionResources,
     context: &mut EntryPointExecutionContext,
 ) -> EntryPointExecutionResult<CallInfo> {
+    if let Some(native_worker) = context.tx_context.block_context.native_worker.clone() {
+        return worker::execute_entry_point_call(
+            call,
+            contract_class,
+            state,
+            resources,
+            context,
+            &native_worker,
+        );
+    }
+
     let function_id =
         contract_class.get_entrypoint(call.entry_point_type, call.entry_point_selector)?;
 
@@ -25,6 +41,7 @@ pub fn execute_entry_point_call(
         call.caller_address,
         call.storage_address,
         call.entry_point_selector,
+        call.initial_gas,
         resources,
         context,
     );
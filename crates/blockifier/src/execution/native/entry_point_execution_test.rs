@@ -0,0 +1,134 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use assert_matches::assert_matches;
+use cairo_vm::vm::errors::cairo_run_errors::CairoRunError;
+use cairo_vm::vm::errors::vm_errors::VirtualMachineError;
+use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
+use starknet_api::{calldata, felt};
+
+use crate::abi::abi_utils::selector_from_name;
+use crate::blockifier::config::NativeExecutionConfig;
+use crate::context::{BlockContext, ChainInfo, TransactionContext};
+use crate::execution::call_info::CallInfo;
+use crate::execution::entry_point::{
+    CallEntryPoint, EntryPointExecutionContext, EntryPointExecutionResult,
+};
+use crate::execution::errors::EntryPointExecutionError;
+use crate::test_utils::contracts::FeatureContract;
+use crate::test_utils::initial_test_state::test_state;
+use crate::test_utils::{trivial_external_entry_point_new, BALANCE};
+use crate::transaction::objects::{DeprecatedTransactionInfo, TransactionInfo};
+use crate::versioned_constants::VersionedConstants;
+
+fn execute_with_limits(
+    entry_point_call: CallEntryPoint,
+    block_context: BlockContext,
+    max_n_steps: Option<usize>,
+) -> EntryPointExecutionResult<CallInfo> {
+    let test_contract = FeatureContract::SierraTestContract;
+    let mut state = test_state(&ChainInfo::create_for_testing(), BALANCE, &[(test_contract, 1)]);
+    let tx_context = TransactionContext {
+        block_context,
+        tx_info: TransactionInfo::Deprecated(DeprecatedTransactionInfo::default()),
+    };
+    let mut context = EntryPointExecutionContext::new_invoke(Arc::new(tx_context), false).unwrap();
+    if let Some(max_n_steps) = max_n_steps {
+        let n_remaining_steps = context.n_remaining_steps();
+        context.subtract_steps(n_remaining_steps - max_n_steps);
+    }
+
+    entry_point_call.execute(&mut state, &mut ExecutionResources::default(), &mut context)
+}
+
+#[test]
+fn test_recursion_depth_exceeded() {
+    let test_contract = FeatureContract::SierraTestContract;
+    let max_recursion_depth = 5;
+    let block_context = BlockContext {
+        versioned_constants: VersionedConstants {
+            max_recursion_depth,
+            ..VersionedConstants::create_for_testing()
+        },
+        ..BlockContext::create_for_testing()
+    };
+    let recursive_syscall_entry_point_call = |depth: usize| {
+        let selector = selector_from_name("recursive_syscall");
+        CallEntryPoint {
+            entry_point_selector: selector,
+            calldata: calldata![
+                *test_contract.get_instance_address(0).0.key(),
+                selector.0,
+                felt!(u64::try_from(depth).unwrap())
+            ],
+            ..trivial_external_entry_point_new(test_contract)
+        }
+    };
+
+    // The outermost call, followed by the maximal number of nested calls.
+    execute_with_limits(
+        recursive_syscall_entry_point_call(max_recursion_depth - 1),
+        block_context.clone(),
+        None,
+    )
+    .unwrap();
+
+    let error = execute_with_limits(
+        recursive_syscall_entry_point_call(max_recursion_depth),
+        block_context,
+        None,
+    )
+    .unwrap_err();
+    assert!(error.to_string().contains("recursion depth exceeded"));
+}
+
+#[test]
+fn test_step_limit() {
+    let test_contract = FeatureContract::SierraTestContract;
+    let entry_point_call = CallEntryPoint {
+        entry_point_selector: selector_from_name("recurse"),
+        calldata: calldata![felt!(1000_u16)],
+        ..trivial_external_entry_point_new(test_contract)
+    };
+
+    let call_info =
+        execute_with_limits(entry_point_call.clone(), BlockContext::create_for_testing(), None)
+            .unwrap();
+    let n_steps = call_info.resources.n_steps;
+
+    execute_with_limits(
+        entry_point_call.clone(),
+        BlockContext::create_for_testing(),
+        Some(n_steps),
+    )
+    .unwrap();
+    let error = execute_with_limits(
+        entry_point_call,
+        BlockContext::create_for_testing(),
+        Some(n_steps - 1),
+    )
+    .unwrap_err();
+    assert_matches!(
+        error,
+        EntryPointExecutionError::CairoRunError(CairoRunError::VirtualMachine(
+            VirtualMachineError::UnfinishedExecution
+        ))
+    );
+}
+
+#[test]
+fn test_execution_timeout() {
+    let test_contract = FeatureContract::SierraTestContract;
+    let entry_point_call = CallEntryPoint {
+        entry_point_selector: selector_from_name("test_storage_read_write"),
+        calldata: calldata![felt!(1234_u16), felt!(18_u8)],
+        ..trivial_external_entry_point_new(test_contract)
+    };
+    let block_context = BlockContext {
+        native_execution_config: NativeExecutionConfig { max_execution_time: Some(Duration::ZERO) },
+        ..BlockContext::create_for_testing()
+    };
+
+    let error = execute_with_limits(entry_point_call, block_context, None).unwrap_err();
+    assert_matches!(error, EntryPointExecutionError::NativeExecutionTimeout);
+}
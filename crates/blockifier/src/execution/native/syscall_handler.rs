@@ -2,14 +2,18 @@ use std::collections::HashSet;
 use std::hash::RandomState;
 use std::marker::PhantomData;
 use std::sync::Arc;
+use std::time::Instant;
 
 use ark_ec::short_weierstrass::{Affine, Projective, SWCurveConfig};
 use cairo_native::starknet::{
     BlockInfo, ExecutionInfo, ExecutionInfoV2, Secp256k1Point, Secp256r1Point,
     StarknetSyscallHandler, SyscallResult, TxInfo, TxV2Info, U256,
 };
+use cairo_vm::vm::errors::cairo_run_errors::CairoRunError;
+use cairo_vm::vm::errors::vm_errors::VirtualMachineError;
 use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
 use num_traits::{ToPrimitive, Zero};
+use starknet_api::block::BlockNumber;
 use starknet_api::core::{
     calculate_contract_address, ClassHash, ContractAddress, EntryPointSelector, EthAddress,
     PatriciaKey,
@@ -24,20 +28,26 @@ use starknet_types_core::felt::Felt;
 
 use super::utils::{
     big4int_to_u256, calculate_resource_bounds, contract_address_to_native_felt,
-    default_tx_v2_info, encode_str_as_felts, u256_to_biguint,
+    default_tx_v2_info, encode_str_as_felts, estimate_execution_resources, u256_to_biguint,
 };
 use crate::abi::constants;
+use crate::blockifier::observer::ExecutionEvent;
 use crate::execution::call_info::{CallInfo, MessageToL1, OrderedEvent, OrderedL2ToL1Message};
 use crate::execution::common_hints::ExecutionMode;
 use crate::execution::contract_class::ContractClass;
 use crate::execution::entry_point::{
     CallEntryPoint, CallType, ConstructorContext, EntryPointExecutionContext,
+    EntryPointExecutionResult,
 };
+use crate::execution::errors::EntryPointExecutionError;
 use crate::execution::execution_utils::{execute_deployment, max_fee_for_execution_info};
 use crate::execution::syscalls::hint_processor::{
     SyscallCounter, SyscallExecutionError, BLOCK_NUMBER_OUT_OF_RANGE_ERROR,
     INVALID_INPUT_LENGTH_ERROR, OUT_OF_GAS_ERROR,
 };
+use crate::execution::syscalls::interceptor::{
+    InterceptedRequest, InterceptedSyscall, SyscallInterception,
+};
 use crate::execution::syscalls::{exceeds_event_size_limit, SyscallSelector};
 use crate::state::state_api::State;
 use crate::transaction::objects::TransactionInfo;
@@ -52,7 +62,10 @@ pub struct NativeSyscallHandler<'state> {
     pub caller_address: ContractAddress,
     pub contract_address: ContractAddress,
     pub entry_point_selector: Felt,
+    pub initial_gas: u64,
     pub syscall_counter: SyscallCounter,
+    // The gas charged by syscalls, including their base cost (pre-charged by the Cairo code).
+    pub syscalls_gas_consumed: u64,
 
     // Execution results
     pub events: Vec<OrderedEvent>,
@@ -62,6 +75,10 @@ pub struct NativeSyscallHandler<'state> {
     // Additional execution result info
     pub storage_read_values: Vec<Felt>,
     pub accessed_storage_keys: HashSet<StorageKey, RandomState>,
+
+    // An error that reverts the entire transaction in the VM, rather than failing the current
+    // call; it is reported once the native execution returns.
+    pub unrecoverable_error: Option<SyscallExecutionError>,
 }
 
 impl<'state> NativeSyscallHandler<'state> {
@@ -70,6 +87,7 @@ impl<'state> NativeSyscallHandler<'state> {
         caller_address: ContractAddress,
         contract_address: ContractAddress,
         entry_point_selector: EntryPointSelector,
+        initial_gas: u64,
         execution_resources: &'state mut ExecutionResources,
         execution_context: &'state mut EntryPointExecutionContext,
     ) -> NativeSyscallHandler<'state> {
@@ -78,6 +96,7 @@ impl<'state> NativeSyscallHandler<'state> {
             caller_address,
             contract_address,
             entry_point_selector: entry_point_selector.0,
+            initial_gas,
             execution_resources,
             execution_context,
             events: Vec::new(),
@@ -86,6 +105,8 @@ impl<'state> NativeSyscallHandler<'state> {
             storage_read_values: Vec::new(),
             accessed_storage_keys: HashSet::new(),
             syscall_counter: Default::default(),
+            syscalls_gas_consumed: 0,
+            unrecoverable_error: None,
         }
     }
 
@@ -93,15 +114,14 @@ impl<'state> NativeSyscallHandler<'state> {
         &mut self,
         entry_point: CallEntryPoint,
         remaining_gas: &mut u128,
-    ) -> SyscallResult<CallInfo> {
-        let call_info = entry_point
-            .execute(self.state, self.execution_resources, self.execution_context)
-            .map_err(|e| encode_str_as_felts(&e.to_string()))?;
-        let retdata = call_info.execution.retdata.0.clone();
+    ) -> Result<CallInfo, SyscallExecutionError> {
+        let call_info =
+            entry_point.execute(self.state, self.execution_resources, self.execution_context)?;
 
         if call_info.execution.failed {
-            // In VM it's wrapped into `SyscallExecutionError::SyscallError`
-            return Err(retdata);
+            return Err(SyscallExecutionError::SyscallError {
+                error_data: call_info.execution.retdata.0,
+            });
         }
 
         self.update_remaining_gas(remaining_gas, &call_info);
@@ -111,6 +131,59 @@ impl<'state> NativeSyscallHandler<'state> {
         Ok(call_info)
     }
 
+    /// Records an error that the VM does not recover from, and returns the revert data to pass to
+    /// the Cairo code. Only the first such error is kept, as it is the one the VM would have
+    /// stopped at.
+    pub fn handle_error(&mut self, error: SyscallExecutionError) -> Vec<Felt> {
+        if let SyscallExecutionError::SyscallError { error_data } = error {
+            return error_data;
+        }
+
+        let revert_data = revert_data(&error);
+        if self.unrecoverable_error.is_none() {
+            self.unrecoverable_error = Some(error);
+        }
+
+        revert_data
+    }
+
+    /// Returns the gas consumed by the Cairo code of the current call, out of the given total
+    /// consumption (i.e., excluding syscalls and inner calls).
+    pub fn cairo_gas_consumed(&self, gas_consumed: u64) -> u64 {
+        let inner_calls_gas_consumed: u64 =
+            self.inner_calls.iter().map(|call_info| call_info.execution.gas_consumed).sum();
+        gas_consumed
+            .saturating_sub(inner_calls_gas_consumed)
+            .saturating_sub(self.syscalls_gas_consumed)
+    }
+
+    /// Fails the execution if it ran past the deadline of the context, or if the steps estimated
+    /// for the Cairo code of the current call exceed the remaining steps, as the VM would.
+    /// Since native execution is metered by gas, this bounds it by the same step limit.
+    pub fn check_execution_limits(&self, remaining_gas: u128) -> EntryPointExecutionResult<()> {
+        if self
+            .execution_context
+            .native_execution_deadline
+            .is_some_and(|deadline| Instant::now() >= deadline)
+        {
+            return Err(EntryPointExecutionError::NativeExecutionTimeout);
+        }
+
+        let remaining_gas = u64::try_from(remaining_gas).unwrap_or(u64::MAX);
+        let cairo_gas_consumed =
+            self.cairo_gas_consumed(self.initial_gas.saturating_sub(remaining_gas));
+        let n_steps =
+            estimate_execution_resources(cairo_gas_consumed, self.execution_context.gas_costs())
+                .n_steps;
+        if n_steps > self.execution_context.n_remaining_steps() {
+            return Err(
+                CairoRunError::VirtualMachine(VirtualMachineError::UnfinishedExecution).into()
+            );
+        }
+
+        Ok(())
+    }
+
     pub fn update_remaining_gas(&mut self, remaining_gas: &mut u128, call_info: &CallInfo) {
         // create a new variable with converted type
         let mut remaining_gas_u64 = u64::try_from(*remaining_gas).unwrap();
@@ -131,6 +204,77 @@ impl<'state> NativeSyscallHandler<'state> {
         self.increment_syscall_count_by(selector, 1);
     }
 
+    // Executes a syscall through the syscall interceptor of the context, if any, which may veto or
+    // substitute it, and may override its outcome; as the VM does in `execute_syscall`.
+    fn intercept_syscall<T: InterceptedResponse>(
+        &mut self,
+        selector: SyscallSelector,
+        request: InterceptedRequest,
+        execute: impl FnOnce(&mut Self) -> SyscallResult<T>,
+    ) -> SyscallResult<T> {
+        let Some(interceptor) = self.execution_context.syscall_interceptor() else {
+            return execute(self);
+        };
+        let syscall = InterceptedSyscall {
+            selector,
+            storage_address: self.contract_address,
+            caller_address: self.caller_address,
+            execution_mode: self.execution_context.execution_mode,
+            request,
+        };
+
+        // The executed response, kept as is unless overridden.
+        let mut executed_response = None;
+        let mut outcome = match interceptor.pre_syscall(&syscall) {
+            SyscallInterception::Proceed => {
+                let had_unrecoverable_error = self.unrecoverable_error.is_some();
+                match execute(self) {
+                    Ok(response) => {
+                        let flat_response = response.to_felts();
+                        executed_response = Some((response, flat_response.clone()));
+                        Ok(flat_response)
+                    }
+                    // Errors that abort the execution are not intercepted.
+                    Err(error_data)
+                        if !had_unrecoverable_error && self.unrecoverable_error.is_some() =>
+                    {
+                        return Err(error_data);
+                    }
+                    Err(error_data) => Err(error_data),
+                }
+            }
+            SyscallInterception::Veto { error_data } => Err(error_data),
+            SyscallInterception::Respond(response) => Ok(response),
+        };
+        interceptor.post_syscall(&syscall, &mut outcome);
+
+        match (outcome, executed_response) {
+            (Ok(flat_response), Some((response, executed_flat_response)))
+                if flat_response == executed_flat_response =>
+            {
+                Ok(response)
+            }
+            (Ok(flat_response), _) => T::from_felts(&flat_response).ok_or_else(|| {
+                self.handle_error(SyscallExecutionError::InvalidSubstituteResponse {
+                    response: flat_response,
+                })
+            }),
+            (Err(error_data), _) => Err(error_data),
+        }
+    }
+
+    // Executes a syscall whose request and response are not exposed to syscall interceptors.
+    fn intercept_opaque_syscall<T>(
+        &mut self,
+        selector: SyscallSelector,
+        execute: impl FnOnce(&mut Self) -> SyscallResult<T>,
+    ) -> SyscallResult<T> {
+        self.intercept_syscall(selector, InterceptedRequest::Opaque, |handler| {
+            execute(handler).map(OpaqueResponse)
+        })
+        .map(|OpaqueResponse(response)| response)
+    }
+
     // We need to have this function since in VM we have `execute_syscall` method, which is handling
     // all gas-related logics and additional metadata (like `SyscallCounter`). In the native,
     // syscalls are called directly, so we need to implement this logic here
@@ -140,6 +284,9 @@ impl<'state> NativeSyscallHandler<'state> {
         syscall_selector: SyscallSelector,
         syscall_gas_cost: u64,
     ) -> SyscallResult<()> {
+        self.check_execution_limits(*remaining_gas)
+            .map_err(|error| self.handle_error(error.into()))?;
+
         // Increment the syscall counter. For Keccak syscall count is calculated by the number of
         // steps
         if syscall_selector != SyscallSelector::Keccak {
@@ -156,11 +303,90 @@ impl<'state> NativeSyscallHandler<'state> {
         }
 
         *remaining_gas -= required_gas;
+        self.syscalls_gas_consumed += syscall_gas_cost;
 
         Ok(())
     }
 }
 
+/// The data the Cairo code panics with on a failed syscall: the revert reason of the innermost
+/// failed call, or the error message.
+fn revert_data(error: &SyscallExecutionError) -> Vec<Felt> {
+    match error {
+        SyscallExecutionError::CallContractExecutionError { error, .. }
+        | SyscallExecutionError::LibraryCallExecutionError { error, .. } => revert_data(error),
+        SyscallExecutionError::SyscallError { error_data } => error_data.clone(),
+        _ => encode_str_as_felts(&error.to_string()),
+    }
+}
+
+/// A syscall response, as exposed to syscall interceptors; see
+/// [crate::execution::syscalls::interceptor::SyscallOutcome].
+trait InterceptedResponse: Sized {
+    fn to_felts(&self) -> Vec<Felt>;
+
+    fn from_felts(response: &[Felt]) -> Option<Self>;
+}
+
+impl InterceptedResponse for () {
+    fn to_felts(&self) -> Vec<Felt> {
+        vec![]
+    }
+
+    fn from_felts(response: &[Felt]) -> Option<Self> {
+        response.is_empty().then_some(())
+    }
+}
+
+impl InterceptedResponse for Felt {
+    fn to_felts(&self) -> Vec<Felt> {
+        vec![*self]
+    }
+
+    fn from_felts(response: &[Felt]) -> Option<Self> {
+        match response {
+            [value] => Some(*value),
+            _ => None,
+        }
+    }
+}
+
+impl InterceptedResponse for Vec<Felt> {
+    fn to_felts(&self) -> Vec<Felt> {
+        self.clone()
+    }
+
+    fn from_felts(response: &[Felt]) -> Option<Self> {
+        Some(response.to_vec())
+    }
+}
+
+// The response of `deploy`: the contract address and the constructor retdata.
+impl InterceptedResponse for (Felt, Vec<Felt>) {
+    fn to_felts(&self) -> Vec<Felt> {
+        [vec![self.0], self.1.clone()].concat()
+    }
+
+    fn from_felts(response: &[Felt]) -> Option<Self> {
+        response
+            .split_first()
+            .map(|(contract_address, retdata)| (*contract_address, retdata.to_vec()))
+    }
+}
+
+// A response that is not exposed; it is given as empty, and cannot be substituted.
+struct OpaqueResponse<T>(T);
+
+impl<T> InterceptedResponse for OpaqueResponse<T> {
+    fn to_felts(&self) -> Vec<Felt> {
+        vec![]
+    }
+
+    fn from_felts(_response: &[Felt]) -> Option<Self> {
+        None
+    }
+}
+
 impl<'state> StarknetSyscallHandler for &mut NativeSyscallHandler<'state> {
     fn get_block_hash(
         &mut self,
@@ -173,42 +399,49 @@ impl<'state> StarknetSyscallHandler for &mut NativeSyscallHandler<'state> {
             self.execution_context.gas_costs().get_block_hash_gas_cost,
         )?;
 
-        if self.execution_context.execution_mode == ExecutionMode::Validate {
-            let err = SyscallExecutionError::InvalidSyscallInExecutionMode {
-                syscall_name: "get_block_hash".to_string(),
-                execution_mode: ExecutionMode::Validate,
-            };
-
-            return Err(encode_str_as_felts(&err.to_string()));
-        }
-
-        let current_block_number =
-            self.execution_context.tx_context.block_context.block_info.block_number.0;
+        self.intercept_syscall(
+            SyscallSelector::GetBlockHash,
+            InterceptedRequest::GetBlockHash { block_number: BlockNumber(block_number) },
+            |handler| {
+                if handler.execution_context.execution_mode == ExecutionMode::Validate {
+                    let err = SyscallExecutionError::InvalidSyscallInExecutionMode {
+                        syscall_name: "get_block_hash".to_string(),
+                        execution_mode: ExecutionMode::Validate,
+                    };
+
+                    return Err(handler.handle_error(err));
+                }
 
-        if current_block_number < constants::STORED_BLOCK_HASH_BUFFER
-            || block_number > current_block_number - constants::STORED_BLOCK_HASH_BUFFER
-        {
-            // `panic` is unreachable in this case, also this is covered by tests so we can safely
-            // unwrap
-            let out_of_range_felt = Felt::from_hex(BLOCK_NUMBER_OUT_OF_RANGE_ERROR).unwrap();
-
-            // This error is wrapped into a `SyscallExecutionError::SyscallError` in the VM
-            // implementation, but here it would be more convenient to return it directly, since
-            // wrapping it like VM does will result in a double encoding to felts, which adds extra
-            // layer of complication
-            return Err(vec![out_of_range_felt]);
-        }
+                let current_block_number =
+                    handler.execution_context.tx_context.block_context.block_info.block_number.0;
+
+                if current_block_number < constants::STORED_BLOCK_HASH_BUFFER
+                    || block_number > current_block_number - constants::STORED_BLOCK_HASH_BUFFER
+                {
+                    // `panic` is unreachable in this case, also this is covered by tests so we can
+                    // safely unwrap
+                    let out_of_range_felt =
+                        Felt::from_hex(BLOCK_NUMBER_OUT_OF_RANGE_ERROR).unwrap();
+
+                    // This error is wrapped into a `SyscallExecutionError::SyscallError` in the VM
+                    // implementation, but here it would be more convenient to return it directly,
+                    // since wrapping it like VM does will result in a double
+                    // encoding to felts, which adds extra layer of complication
+                    return Err(vec![out_of_range_felt]);
+                }
 
-        let key = StorageKey::try_from(Felt::from(block_number))
-            .map_err(|e| encode_str_as_felts(&e.to_string()))?;
-        let block_hash_address =
-            ContractAddress::try_from(Felt::from(constants::BLOCK_HASH_CONTRACT_ADDRESS))
-                .map_err(|e| encode_str_as_felts(&e.to_string()))?;
+                let key = StorageKey::try_from(Felt::from(block_number))
+                    .map_err(|e| handler.handle_error(e.into()))?;
+                let block_hash_address =
+                    ContractAddress::try_from(Felt::from(constants::BLOCK_HASH_CONTRACT_ADDRESS))
+                        .map_err(|e| handler.handle_error(e.into()))?;
 
-        match self.state.get_storage_at(block_hash_address, key) {
-            Ok(value) => Ok(value),
-            Err(e) => Err(encode_str_as_felts(&e.to_string())),
-        }
+                match handler.state.get_storage_at(block_hash_address, key) {
+                    Ok(value) => Ok(value),
+                    Err(e) => Err(handler.handle_error(e.into())),
+                }
+            },
+        )
     }
 
     fn get_execution_info(&mut self, remaining_gas: &mut u128) -> SyscallResult<ExecutionInfo> {
@@ -218,60 +451,70 @@ impl<'state> StarknetSyscallHandler for &mut NativeSyscallHandler<'state> {
             self.execution_context.gas_costs().get_execution_info_gas_cost,
         )?;
 
-        let block_info = &self.execution_context.tx_context.block_context.block_info;
-        let native_block_info: BlockInfo = if self.execution_context.execution_mode
-            == ExecutionMode::Validate
-        {
-            // TODO: Literal copy from get execution info v2, could be refactored
-            let versioned_constants = self.execution_context.versioned_constants();
-            let block_number = block_info.block_number.0;
-            let block_timestamp = block_info.block_timestamp.0;
-            // Round down to the nearest multiple of validate_block_number_rounding.
-            let validate_block_number_rounding =
-                versioned_constants.get_validate_block_number_rounding();
-            let rounded_block_number =
-                (block_number / validate_block_number_rounding) * validate_block_number_rounding;
-            // Round down to the nearest multiple of validate_timestamp_rounding.
-            let validate_timestamp_rounding = versioned_constants.get_validate_timestamp_rounding();
-            let rounded_timestamp =
-                (block_timestamp / validate_timestamp_rounding) * validate_timestamp_rounding;
-            BlockInfo {
-                block_number: rounded_block_number,
-                block_timestamp: rounded_timestamp,
-                sequencer_address: Felt::ZERO,
-            }
-        } else {
-            BlockInfo {
-                block_number: block_info.block_number.0,
-                block_timestamp: block_info.block_timestamp.0,
-                sequencer_address: contract_address_to_native_felt(block_info.sequencer_address),
-            }
-        };
-
-        let tx_info = &self.execution_context.tx_context.tx_info;
-        let native_tx_info = TxInfo {
-            version: tx_info.version().0,
-            account_contract_address: contract_address_to_native_felt(tx_info.sender_address()),
-            max_fee: tx_info.max_fee().unwrap_or_default().0,
-            signature: tx_info.signature().0,
-            transaction_hash: tx_info.transaction_hash().0,
-            chain_id: Felt::from_hex(
-                &self.execution_context.tx_context.block_context.chain_info.chain_id.as_hex(),
-            )
-            .unwrap(),
-            nonce: tx_info.nonce().0,
-        };
+        self.intercept_opaque_syscall(SyscallSelector::GetExecutionInfo, |handler| {
+            let block_info = &handler.execution_context.tx_context.block_context.block_info;
+            let native_block_info: BlockInfo =
+                if handler.execution_context.execution_mode == ExecutionMode::Validate {
+                    // TODO: Literal copy from get execution info v2, could be refactored
+                    let versioned_constants = handler.execution_context.versioned_constants();
+                    let block_number = block_info.block_number.0;
+                    let block_timestamp = block_info.block_timestamp.0;
+                    // Round down to the nearest multiple of validate_block_number_rounding.
+                    let validate_block_number_rounding =
+                        versioned_constants.get_validate_block_number_rounding();
+                    let rounded_block_number = (block_number / validate_block_number_rounding)
+                        * validate_block_number_rounding;
+                    // Round down to the nearest multiple of validate_timestamp_rounding.
+                    let validate_timestamp_rounding =
+                        versioned_constants.get_validate_timestamp_rounding();
+                    let rounded_timestamp = (block_timestamp / validate_timestamp_rounding)
+                        * validate_timestamp_rounding;
+                    BlockInfo {
+                        block_number: rounded_block_number,
+                        block_timestamp: rounded_timestamp,
+                        sequencer_address: Felt::ZERO,
+                    }
+                } else {
+                    BlockInfo {
+                        block_number: block_info.block_number.0,
+                        block_timestamp: block_info.block_timestamp.0,
+                        sequencer_address: contract_address_to_native_felt(
+                            block_info.sequencer_address,
+                        ),
+                    }
+                };
+
+            let tx_info = &handler.execution_context.tx_context.tx_info;
+            let native_tx_info = TxInfo {
+                version: tx_info.version().0,
+                account_contract_address: contract_address_to_native_felt(tx_info.sender_address()),
+                max_fee: tx_info.max_fee().unwrap_or_default().0,
+                signature: tx_info.signature().0,
+                transaction_hash: tx_info.transaction_hash().0,
+                chain_id: Felt::from_hex(
+                    &handler
+                        .execution_context
+                        .tx_context
+                        .block_context
+                        .chain_info
+                        .chain_id
+                        .as_hex(),
+                )
+                .unwrap(),
+                nonce: tx_info.nonce().0,
+            };
 
-        let caller_address = contract_address_to_native_felt(self.caller_address);
-        let contract_address = contract_address_to_native_felt(self.contract_address);
-        let entry_point_selector = self.entry_point_selector;
+            let caller_address = contract_address_to_native_felt(handler.caller_address);
+            let contract_address = contract_address_to_native_felt(handler.contract_address);
+            let entry_point_selector = handler.entry_point_selector;
 
-        Ok(ExecutionInfo {
-            block_info: native_block_info,
-            tx_info: native_tx_info,
-            caller_address,
-            contract_address,
-            entry_point_selector,
+            Ok(ExecutionInfo {
+                block_info: native_block_info,
+                tx_info: native_tx_info,
+                caller_address,
+                contract_address,
+                entry_point_selector,
+            })
         })
     }
 
@@ -285,78 +528,88 @@ impl<'state> StarknetSyscallHandler for &mut NativeSyscallHandler<'state> {
             self.execution_context.gas_costs().get_execution_info_gas_cost,
         )?;
 
-        // Get Block Info
-        let block_info = &self.execution_context.tx_context.block_context.block_info;
-        let native_block_info: BlockInfo = if self.execution_context.execution_mode
-            == ExecutionMode::Validate
-        {
-            let versioned_constants = self.execution_context.versioned_constants();
-            let block_number = block_info.block_number.0;
-            let block_timestamp = block_info.block_timestamp.0;
-            // Round down to the nearest multiple of validate_block_number_rounding.
-            let validate_block_number_rounding =
-                versioned_constants.get_validate_block_number_rounding();
-            let rounded_block_number =
-                (block_number / validate_block_number_rounding) * validate_block_number_rounding;
-            // Round down to the nearest multiple of validate_timestamp_rounding.
-            let validate_timestamp_rounding = versioned_constants.get_validate_timestamp_rounding();
-            let rounded_timestamp =
-                (block_timestamp / validate_timestamp_rounding) * validate_timestamp_rounding;
-            BlockInfo {
-                block_number: rounded_block_number,
-                block_timestamp: rounded_timestamp,
-                sequencer_address: Felt::ZERO,
-            }
-        } else {
-            BlockInfo {
-                block_number: block_info.block_number.0,
-                block_timestamp: block_info.block_timestamp.0,
-                sequencer_address: contract_address_to_native_felt(block_info.sequencer_address),
-            }
-        };
-
-        // Get Transaction Info
-        let tx_info = &self.execution_context.tx_context.tx_info;
-        let mut native_tx_info = TxV2Info {
-            version: tx_info.signed_version().0,
-            account_contract_address: contract_address_to_native_felt(tx_info.sender_address()),
-            max_fee: max_fee_for_execution_info(tx_info).to_u128().unwrap(),
-            signature: tx_info.signature().0,
-            transaction_hash: tx_info.transaction_hash().0,
-            chain_id: Felt::from_hex(
-                &self.execution_context.tx_context.block_context.chain_info.chain_id.as_hex(),
-            )
-            .unwrap(),
-            nonce: tx_info.nonce().0,
-            ..default_tx_v2_info()
-        };
-        // If handling V3 transaction fill the "default" fields
-        if let TransactionInfo::Current(context) = tx_info {
-            let to_u32 = |x| match x {
-                DataAvailabilityMode::L1 => 0,
-                DataAvailabilityMode::L2 => 1,
-            };
-            native_tx_info = TxV2Info {
-                resource_bounds: calculate_resource_bounds(context)?,
-                tip: context.tip.0.into(),
-                paymaster_data: context.paymaster_data.0.clone(),
-                nonce_data_availability_mode: to_u32(context.nonce_data_availability_mode),
-                fee_data_availability_mode: to_u32(context.fee_data_availability_mode),
-                account_deployment_data: context.account_deployment_data.0.clone(),
-                ..native_tx_info
+        self.intercept_opaque_syscall(SyscallSelector::GetExecutionInfo, |handler| {
+            // Get Block Info
+            let block_info = &handler.execution_context.tx_context.block_context.block_info;
+            let native_block_info: BlockInfo =
+                if handler.execution_context.execution_mode == ExecutionMode::Validate {
+                    let versioned_constants = handler.execution_context.versioned_constants();
+                    let block_number = block_info.block_number.0;
+                    let block_timestamp = block_info.block_timestamp.0;
+                    // Round down to the nearest multiple of validate_block_number_rounding.
+                    let validate_block_number_rounding =
+                        versioned_constants.get_validate_block_number_rounding();
+                    let rounded_block_number = (block_number / validate_block_number_rounding)
+                        * validate_block_number_rounding;
+                    // Round down to the nearest multiple of validate_timestamp_rounding.
+                    let validate_timestamp_rounding =
+                        versioned_constants.get_validate_timestamp_rounding();
+                    let rounded_timestamp = (block_timestamp / validate_timestamp_rounding)
+                        * validate_timestamp_rounding;
+                    BlockInfo {
+                        block_number: rounded_block_number,
+                        block_timestamp: rounded_timestamp,
+                        sequencer_address: Felt::ZERO,
+                    }
+                } else {
+                    BlockInfo {
+                        block_number: block_info.block_number.0,
+                        block_timestamp: block_info.block_timestamp.0,
+                        sequencer_address: contract_address_to_native_felt(
+                            block_info.sequencer_address,
+                        ),
+                    }
+                };
+
+            // Get Transaction Info
+            let tx_info = &handler.execution_context.tx_context.tx_info;
+            let mut native_tx_info = TxV2Info {
+                version: tx_info.signed_version().0,
+                account_contract_address: contract_address_to_native_felt(tx_info.sender_address()),
+                max_fee: max_fee_for_execution_info(tx_info).to_u128().unwrap(),
+                signature: tx_info.signature().0,
+                transaction_hash: tx_info.transaction_hash().0,
+                chain_id: Felt::from_hex(
+                    &handler
+                        .execution_context
+                        .tx_context
+                        .block_context
+                        .chain_info
+                        .chain_id
+                        .as_hex(),
+                )
+                .unwrap(),
+                nonce: tx_info.nonce().0,
+                ..default_tx_v2_info()
             };
-        }
+            // If handling V3 transaction fill the "default" fields
+            if let TransactionInfo::Current(context) = tx_info {
+                let to_u32 = |x| match x {
+                    DataAvailabilityMode::L1 => 0,
+                    DataAvailabilityMode::L2 => 1,
+                };
+                native_tx_info = TxV2Info {
+                    resource_bounds: calculate_resource_bounds(context)?,
+                    tip: context.tip.0.into(),
+                    paymaster_data: context.paymaster_data.0.clone(),
+                    nonce_data_availability_mode: to_u32(context.nonce_data_availability_mode),
+                    fee_data_availability_mode: to_u32(context.fee_data_availability_mode),
+                    account_deployment_data: context.account_deployment_data.0.clone(),
+                    ..native_tx_info
+                };
+            }
 
-        let caller_address = contract_address_to_native_felt(self.caller_address);
-        let contract_address = contract_address_to_native_felt(self.contract_address);
-        let entry_point_selector = self.entry_point_selector;
+            let caller_address = contract_address_to_native_felt(handler.caller_address);
+            let contract_address = contract_address_to_native_felt(handler.contract_address);
+            let entry_point_selector = handler.entry_point_selector;
 
-        Ok(ExecutionInfoV2 {
-            block_info: native_block_info,
-            tx_info: native_tx_info,
-            caller_address,
-            contract_address,
-            entry_point_selector,
+            Ok(ExecutionInfoV2 {
+                block_info: native_block_info,
+                tx_info: native_tx_info,
+                caller_address,
+                contract_address,
+                entry_point_selector,
+            })
         })
     }
 
@@ -374,59 +627,74 @@ impl<'state> StarknetSyscallHandler for &mut NativeSyscallHandler<'state> {
             self.execution_context.gas_costs().deploy_gas_cost,
         )?;
 
-        let deployer_address =
-            if deploy_from_zero { ContractAddress::default() } else { self.contract_address };
-
         let class_hash = ClassHash(class_hash);
         let wrapper_calldata = Calldata(Arc::new(calldata.to_vec()));
-        let calculated_contract_address = calculate_contract_address(
-            ContractAddressSalt(contract_address_salt),
+        let request = InterceptedRequest::Deploy {
             class_hash,
-            &wrapper_calldata,
-            deployer_address,
-        )
-        .map_err(|err| encode_str_as_felts(&err.to_string()))?;
-
-        let ctor_context = ConstructorContext {
-            class_hash,
-            code_address: Some(calculated_contract_address),
-            storage_address: calculated_contract_address,
-            caller_address: deployer_address,
+            contract_address_salt: ContractAddressSalt(contract_address_salt),
+            constructor_calldata: wrapper_calldata.clone(),
+            deploy_from_zero,
         };
 
-        let call_info = execute_deployment(
-            self.state,
-            self.execution_resources,
-            self.execution_context,
-            ctor_context,
-            wrapper_calldata,
-            // Warning: converting of reference would create a new reference to different data,
-            // example:
-            //     let mut a: u128 = 1;
-            //     let a_ref: &mut u128 = &mut a;
-            //
-            //     let mut b: u64 = u64::try_from(*a_ref).unwrap();
-            //
-            //     assert_eq!(b, 1);
-            //
-            //     b += 1;
-            //
-            //     assert_eq!(b, 2);
-            //     assert_eq!(a, 1);
-            // in this case we don't pass a reference, so everything is OK, but still can cause
-            // conversion issues
-            u64::try_from(*remaining_gas).unwrap(),
-        )
-        .map_err(|error| encode_str_as_felts(&error.to_string()))?;
+        self.intercept_syscall(SyscallSelector::Deploy, request, |handler| {
+            let deployer_address = if deploy_from_zero {
+                ContractAddress::default()
+            } else {
+                handler.contract_address
+            };
 
-        self.update_remaining_gas(remaining_gas, &call_info);
+            let calculated_contract_address = calculate_contract_address(
+                ContractAddressSalt(contract_address_salt),
+                class_hash,
+                &wrapper_calldata,
+                deployer_address,
+            )
+            .map_err(|err| handler.handle_error(err.into()))?;
+
+            let ctor_context = ConstructorContext {
+                class_hash,
+                code_address: Some(calculated_contract_address),
+                storage_address: calculated_contract_address,
+                caller_address: deployer_address,
+            };
+
+            let call_info = execute_deployment(
+                handler.state,
+                handler.execution_resources,
+                handler.execution_context,
+                ctor_context,
+                wrapper_calldata,
+                // Warning: converting of reference would create a new reference to different data,
+                // example:
+                //     let mut a: u128 = 1;
+                //     let a_ref: &mut u128 = &mut a;
+                //
+                //     let mut b: u64 = u64::try_from(*a_ref).unwrap();
+                //
+                //     assert_eq!(b, 1);
+                //
+                //     b += 1;
+                //
+                //     assert_eq!(b, 2);
+                //     assert_eq!(a, 1);
+                // in this case we don't pass a reference, so everything is OK, but still can cause
+                // conversion issues
+                u64::try_from(*remaining_gas).unwrap(),
+            )
+            .map_err(|error| {
+                handler
+                    .handle_error(SyscallExecutionError::ConstructorEntryPointExecutionError(error))
+            })?;
 
-        let return_data = call_info.execution.retdata.0[..].to_vec();
-        let contract_address_felt = Felt::from(calculated_contract_address);
+            handler.update_remaining_gas(remaining_gas, &call_info);
 
-        self.inner_calls.push(call_info);
+            let return_data = call_info.execution.retdata.0[..].to_vec();
+            let contract_address_felt = Felt::from(calculated_contract_address);
 
-        Ok((contract_address_felt, return_data))
+            handler.inner_calls.push(call_info);
+
+            Ok((contract_address_felt, return_data))
+        })
     }
 
     fn replace_class(&mut self, class_hash: Felt, remaining_gas: &mut u128) -> SyscallResult<()> {
@@ -436,24 +704,31 @@ impl<'state> StarknetSyscallHandler for &mut NativeSyscallHandler<'state> {
             self.execution_context.gas_costs().replace_class_gas_cost,
         )?;
 
-        let class_hash = ClassHash(class_hash);
-        let contract_class = self
-            .state
-            .get_compiled_contract_class(class_hash)
-            .map_err(|e| encode_str_as_felts(&e.to_string()))?;
-
-        match contract_class {
-            ContractClass::V0(_) => Err(encode_str_as_felts(
-                &SyscallExecutionError::ForbiddenClassReplacement { class_hash }.to_string(),
-            )),
-            ContractClass::V1(_) | ContractClass::V1Native(_) => {
-                self.state
-                    .set_class_hash_at(self.contract_address, class_hash)
-                    .map_err(|e| encode_str_as_felts(&e.to_string()))?;
-
-                Ok(())
-            }
-        }
+        self.intercept_syscall(
+            SyscallSelector::ReplaceClass,
+            InterceptedRequest::ReplaceClass { class_hash: ClassHash(class_hash) },
+            |handler| {
+                let class_hash = ClassHash(class_hash);
+                let contract_class = handler
+                    .state
+                    .get_compiled_contract_class(class_hash)
+                    .map_err(|e| handler.handle_error(e.into()))?;
+
+                match contract_class {
+                    ContractClass::V0(_) => Err(handler.handle_error(
+                        SyscallExecutionError::ForbiddenClassReplacement { class_hash },
+                    )),
+                    ContractClass::V1(_) | ContractClass::V1Native(_) => {
+                        handler
+                            .state
+                            .set_class_hash_at(handler.contract_address, class_hash)
+                            .map_err(|e| handler.handle_error(e.into()))?;
+
+                        Ok(())
+                    }
+                }
+            },
+        )
     }
 
     fn library_call(
@@ -470,27 +745,43 @@ impl<'state> StarknetSyscallHandler for &mut NativeSyscallHandler<'state> {
         )?;
 
         let class_hash = ClassHash(class_hash);
+        let selector = EntryPointSelector(function_selector);
 
         let wrapper_calldata = Calldata(Arc::new(calldata.to_vec()));
-
-        let entry_point = CallEntryPoint {
-            class_hash: Some(class_hash),
-            code_address: None,
-            entry_point_type: EntryPointType::External,
-            entry_point_selector: EntryPointSelector(function_selector),
-            calldata: wrapper_calldata,
-            // The call context remains the same in a library call.
-            storage_address: self.contract_address,
-            caller_address: self.caller_address,
-            call_type: CallType::Delegate,
-            initial_gas: u64::try_from(*remaining_gas).unwrap(),
+        let request = InterceptedRequest::LibraryCall {
+            class_hash,
+            entry_point_selector: selector,
+            calldata: wrapper_calldata.clone(),
         };
 
-        let retdata = self
-            .execute_inner_call(entry_point, remaining_gas)
-            .map(|call_info| call_info.execution.retdata.0.clone())?;
+        self.intercept_syscall(SyscallSelector::LibraryCall, request, |handler| {
+            let entry_point = CallEntryPoint {
+                class_hash: Some(class_hash),
+                code_address: None,
+                entry_point_type: EntryPointType::External,
+                entry_point_selector: selector,
+                calldata: wrapper_calldata,
+                // The call context remains the same in a library call.
+                storage_address: handler.contract_address,
+                caller_address: handler.caller_address,
+                call_type: CallType::Delegate,
+                initial_gas: u64::try_from(*remaining_gas).unwrap(),
+            };
 
-        Ok(retdata)
+            let retdata = handler
+                .execute_inner_call(entry_point, remaining_gas)
+                .map(|call_info| call_info.execution.retdata.0)
+                .map_err(|error| {
+                    let error = error.as_lib_call_execution_error(
+                        class_hash,
+                        handler.contract_address,
+                        selector,
+                    );
+                    handler.handle_error(error)
+                })?;
+
+            Ok(retdata)
+        })
     }
 
     fn call_contract(
@@ -506,39 +797,59 @@ impl<'state> StarknetSyscallHandler for &mut NativeSyscallHandler<'state> {
             self.execution_context.gas_costs().call_contract_gas_cost,
         )?;
 
-        let contract_address = ContractAddress::try_from(address)
-            .map_err(|error| encode_str_as_felts(&error.to_string()))?;
-
-        if self.execution_context.execution_mode == ExecutionMode::Validate
-            && self.contract_address != contract_address
-        {
-            let err = SyscallExecutionError::InvalidSyscallInExecutionMode {
-                syscall_name: "call_contract".to_string(),
-                execution_mode: ExecutionMode::Validate,
-            };
-
-            return Err(encode_str_as_felts(&err.to_string()));
-        }
-
+        let contract_address =
+            ContractAddress::try_from(address).map_err(|error| self.handle_error(error.into()))?;
+        let selector = EntryPointSelector(entry_point_selector);
         let wrapper_calldata = Calldata(Arc::new(calldata.to_vec()));
-
-        let entry_point = CallEntryPoint {
-            class_hash: None,
-            code_address: Some(contract_address),
-            entry_point_type: EntryPointType::External,
-            entry_point_selector: EntryPointSelector(entry_point_selector),
-            calldata: wrapper_calldata,
-            storage_address: contract_address,
-            caller_address: self.contract_address,
-            call_type: CallType::Call,
-            initial_gas: u64::try_from(*remaining_gas).unwrap(),
+        let request = InterceptedRequest::CallContract {
+            contract_address,
+            entry_point_selector: selector,
+            calldata: wrapper_calldata.clone(),
         };
 
-        let retdata = self
-            .execute_inner_call(entry_point, remaining_gas)
-            .map(|call_info| call_info.execution.retdata.0.clone())?;
+        self.intercept_syscall(SyscallSelector::CallContract, request, |handler| {
+            if handler.execution_context.execution_mode == ExecutionMode::Validate
+                && handler.contract_address != contract_address
+            {
+                let err = SyscallExecutionError::InvalidSyscallInExecutionMode {
+                    syscall_name: "call_contract".to_string(),
+                    execution_mode: ExecutionMode::Validate,
+                };
 
-        Ok(retdata)
+                return Err(handler.handle_error(err));
+            }
+
+            let class_hash = handler
+                .state
+                .get_class_hash_at(contract_address)
+                .map_err(|error| handler.handle_error(error.into()))?;
+
+            let entry_point = CallEntryPoint {
+                class_hash: None,
+                code_address: Some(contract_address),
+                entry_point_type: EntryPointType::External,
+                entry_point_selector: selector,
+                calldata: wrapper_calldata,
+                storage_address: contract_address,
+                caller_address: handler.contract_address,
+                call_type: CallType::Call,
+                initial_gas: u64::try_from(*remaining_gas).unwrap(),
+            };
+
+            let retdata = handler
+                .execute_inner_call(entry_point, remaining_gas)
+                .map(|call_info| call_info.execution.retdata.0)
+                .map_err(|error| {
+                    let error = error.as_call_contract_execution_error(
+                        class_hash,
+                        contract_address,
+                        selector,
+                    );
+                    handler.handle_error(error)
+                })?;
+
+            Ok(retdata)
+        })
     }
 
     fn storage_read(
@@ -553,17 +864,28 @@ impl<'state> StarknetSyscallHandler for &mut NativeSyscallHandler<'state> {
             self.execution_context.gas_costs().storage_read_gas_cost,
         )?;
 
-        let key = StorageKey(
-            PatriciaKey::try_from(address).map_err(|e| encode_str_as_felts(&e.to_string()))?,
-        );
-
-        let read_result = self.state.get_storage_at(self.contract_address, key);
-        let value = read_result.map_err(|e| encode_str_as_felts(&e.to_string()))?;
+        let key =
+            StorageKey(PatriciaKey::try_from(address).map_err(|e| self.handle_error(e.into()))?);
 
-        self.accessed_storage_keys.insert(key);
-        self.storage_read_values.push(value);
-
-        Ok(value)
+        self.intercept_syscall(
+            SyscallSelector::StorageRead,
+            InterceptedRequest::StorageRead { key },
+            |handler| {
+                let read_result = handler.state.get_storage_at(handler.contract_address, key);
+                let value = read_result.map_err(|e| handler.handle_error(e.into()))?;
+
+                handler.accessed_storage_keys.insert(key);
+                handler.storage_read_values.push(value);
+                let contract_address = handler.contract_address;
+                handler.execution_context.record(|| ExecutionEvent::StorageRead {
+                    contract_address,
+                    key,
+                    value,
+                });
+
+                Ok(value)
+            },
+        )
     }
 
     fn storage_write(
@@ -579,15 +901,24 @@ impl<'state> StarknetSyscallHandler for &mut NativeSyscallHandler<'state> {
             self.execution_context.gas_costs().storage_write_gas_cost,
         )?;
 
-        let key = StorageKey(
-            PatriciaKey::try_from(address).map_err(|e| encode_str_as_felts(&e.to_string()))?,
-        );
-        self.accessed_storage_keys.insert(key);
+        let key =
+            StorageKey(PatriciaKey::try_from(address).map_err(|e| self.handle_error(e.into()))?);
+        let request = InterceptedRequest::StorageWrite { key, value };
 
-        let write_result = self.state.set_storage_at(self.contract_address, key, value);
-        write_result.map_err(|e| encode_str_as_felts(&e.to_string()))?;
+        self.intercept_syscall(SyscallSelector::StorageWrite, request, |handler| {
+            handler.accessed_storage_keys.insert(key);
 
-        Ok(())
+            let write_result = handler.state.set_storage_at(handler.contract_address, key, value);
+            write_result.map_err(|e| handler.handle_error(e.into()))?;
+            let contract_address = handler.contract_address;
+            handler.execution_context.record(|| ExecutionEvent::StorageWrite {
+                contract_address,
+                key,
+                value,
+            });
+
+            Ok(())
+        })
     }
 
     fn emit_event(
@@ -602,23 +933,31 @@ impl<'state> StarknetSyscallHandler for &mut NativeSyscallHandler<'state> {
             self.execution_context.gas_costs().emit_event_gas_cost,
         )?;
 
-        let order = self.execution_context.n_emitted_events;
         let event = EventContent {
             keys: keys.iter().copied().map(EventKey).collect(),
             data: EventData(data.to_vec()),
         };
+        let request = InterceptedRequest::EmitEvent { content: event.clone() };
 
-        exceeds_event_size_limit(
-            self.execution_context.versioned_constants(),
-            self.execution_context.n_emitted_events + 1,
-            &event,
-        )
-        .map_err(|e| encode_str_as_felts(&e.to_string()))?;
+        self.intercept_syscall(SyscallSelector::EmitEvent, request, |handler| {
+            let order = handler.execution_context.n_emitted_events;
 
-        self.events.push(OrderedEvent { order, event });
-        self.execution_context.n_emitted_events += 1;
+            exceeds_event_size_limit(
+                handler.execution_context.versioned_constants(),
+                handler.execution_context.n_emitted_events + 1,
+                &event,
+            )
+            .map_err(|e| handler.handle_error(e.into()))?;
 
-        Ok(())
+            let from_address = handler.contract_address;
+            handler
+                .execution_context
+                .record(|| ExecutionEvent::Event { from_address, content: event.clone() });
+            handler.events.push(OrderedEvent { order, event });
+            handler.execution_context.n_emitted_events += 1;
+
+            Ok(())
+        })
     }
 
     fn send_message_to_l1(
@@ -633,20 +972,24 @@ impl<'state> StarknetSyscallHandler for &mut NativeSyscallHandler<'state> {
             self.execution_context.gas_costs().send_message_to_l1_gas_cost,
         )?;
 
-        let order = self.execution_context.n_sent_messages_to_l1;
+        let to_address =
+            EthAddress::try_from(to_address).map_err(|e| self.handle_error(e.into()))?;
+        let message = MessageToL1 { to_address, payload: L2ToL1Payload(payload.to_vec()) };
+        let request = InterceptedRequest::SendMessageToL1 { message: message.clone() };
 
-        self.l2_to_l1_messages.push(OrderedL2ToL1Message {
-            order,
-            message: MessageToL1 {
-                to_address: EthAddress::try_from(to_address)
-                    .map_err(|e| encode_str_as_felts(&e.to_string()))?,
-                payload: L2ToL1Payload(payload.to_vec()),
-            },
-        });
+        self.intercept_syscall(SyscallSelector::SendMessageToL1, request, |handler| {
+            let order = handler.execution_context.n_sent_messages_to_l1;
+            let from_address = handler.contract_address;
+            handler.execution_context.record(|| ExecutionEvent::L2ToL1Message {
+                from_address,
+                message: message.clone(),
+            });
+            handler.l2_to_l1_messages.push(OrderedL2ToL1Message { order, message });
 
-        self.execution_context.n_sent_messages_to_l1 += 1;
+            handler.execution_context.n_sent_messages_to_l1 += 1;
 
-        Ok(())
+            Ok(())
+        })
     }
 
     fn keccak(&mut self, input: &[u64], remaining_gas: &mut u128) -> SyscallResult<U256> {
@@ -656,42 +999,44 @@ impl<'state> StarknetSyscallHandler for &mut NativeSyscallHandler<'state> {
             self.execution_context.gas_costs().keccak_gas_cost,
         )?;
 
-        const KECCAK_FULL_RATE_IN_WORDS: usize = 17;
+        self.intercept_opaque_syscall(SyscallSelector::Keccak, |handler| {
+            const KECCAK_FULL_RATE_IN_WORDS: usize = 17;
 
-        let length = input.len();
-        let (n_rounds, remainder) = num_integer::div_rem(length, KECCAK_FULL_RATE_IN_WORDS);
+            let length = input.len();
+            let (n_rounds, remainder) = num_integer::div_rem(length, KECCAK_FULL_RATE_IN_WORDS);
 
-        if remainder != 0 {
-            // In VM this error is wrapped into `SyscallExecutionError::SyscallError`
-            return Err(vec![Felt::from_hex(INVALID_INPUT_LENGTH_ERROR).unwrap()]);
-        }
+            if remainder != 0 {
+                // In VM this error is wrapped into `SyscallExecutionError::SyscallError`
+                return Err(vec![Felt::from_hex(INVALID_INPUT_LENGTH_ERROR).unwrap()]);
+            }
 
-        // TODO(Ori, 1/2/2024): Write an indicative expect message explaining why the conversion
-        // works.
-        let n_rounds_as_u64 = u64::try_from(n_rounds).expect("Failed to convert usize to u64.");
-        let gas_cost = u128::from(
-            n_rounds_as_u64 * self.execution_context.gas_costs().keccak_round_cost_gas_cost,
-        );
+            // TODO(Ori, 1/2/2024): Write an indicative expect message explaining why the conversion
+            // works.
+            let n_rounds_as_u64 = u64::try_from(n_rounds).expect("Failed to convert usize to u64.");
+            let gas_cost =
+                n_rounds_as_u64 * handler.execution_context.gas_costs().keccak_round_cost_gas_cost;
 
-        if gas_cost > *remaining_gas {
-            // In VM this error is wrapped into `SyscallExecutionError::SyscallError`
-            return Err(vec![Felt::from_hex(OUT_OF_GAS_ERROR).unwrap()]);
-        }
-        *remaining_gas -= gas_cost;
+            if u128::from(gas_cost) > *remaining_gas {
+                // In VM this error is wrapped into `SyscallExecutionError::SyscallError`
+                return Err(vec![Felt::from_hex(OUT_OF_GAS_ERROR).unwrap()]);
+            }
+            *remaining_gas -= u128::from(gas_cost);
+            handler.syscalls_gas_consumed += gas_cost;
 
-        self.increment_syscall_count_by(&SyscallSelector::Keccak, n_rounds);
+            handler.increment_syscall_count_by(&SyscallSelector::Keccak, n_rounds);
 
-        let mut state = [0u64; 25];
-        for chunk in input.chunks(KECCAK_FULL_RATE_IN_WORDS) {
-            for (i, val) in chunk.iter().enumerate() {
-                state[i] ^= val;
+            let mut state = [0u64; 25];
+            for chunk in input.chunks(KECCAK_FULL_RATE_IN_WORDS) {
+                for (i, val) in chunk.iter().enumerate() {
+                    state[i] ^= val;
+                }
+                keccak::f1600(&mut state)
             }
-            keccak::f1600(&mut state)
-        }
 
-        Ok(U256 {
-            hi: u128::from(state[2]) | (u128::from(state[3]) << 64),
-            lo: u128::from(state[0]) | (u128::from(state[1]) << 64),
+            Ok(U256 {
+                hi: u128::from(state[2]) | (u128::from(state[3]) << 64),
+                lo: u128::from(state[0]) | (u128::from(state[1]) << 64),
+            })
         })
     }
 
@@ -710,7 +1055,9 @@ impl<'state> StarknetSyscallHandler for &mut NativeSyscallHandler<'state> {
             self.execution_context.gas_costs().secp256k1_new_gas_cost,
         )?;
 
-        Secp256Point::new(x, y).map(|op| op.map(|p| p.into()))
+        self.intercept_opaque_syscall(SyscallSelector::Secp256k1New, |handler| {
+            Secp256Point::new(x, y).map(|op| op.map(|p| p.into()))
+        })
     }
 
     fn secp256k1_add(
@@ -725,7 +1072,9 @@ impl<'state> StarknetSyscallHandler for &mut NativeSyscallHandler<'state> {
             self.execution_context.gas_costs().secp256k1_add_gas_cost,
         )?;
 
-        Ok(Secp256Point::add(p0.into(), p1.into()).into())
+        self.intercept_opaque_syscall(SyscallSelector::Secp256k1Add, |handler| {
+            Ok(Secp256Point::add(p0.into(), p1.into()).into())
+        })
     }
 
     fn secp256k1_mul(
@@ -740,7 +1089,9 @@ impl<'state> StarknetSyscallHandler for &mut NativeSyscallHandler<'state> {
             self.execution_context.gas_costs().secp256k1_mul_gas_cost,
         )?;
 
-        Ok(Secp256Point::mul(p.into(), m).into())
+        self.intercept_opaque_syscall(SyscallSelector::Secp256k1Mul, |handler| {
+            Ok(Secp256Point::mul(p.into(), m).into())
+        })
     }
 
     fn secp256k1_get_point_from_x(
@@ -755,7 +1106,9 @@ impl<'state> StarknetSyscallHandler for &mut NativeSyscallHandler<'state> {
             self.execution_context.gas_costs().secp256k1_get_point_from_x_gas_cost,
         )?;
 
-        Secp256Point::get_point_from_x(x, y_parity).map(|op| op.map(|p| p.into()))
+        self.intercept_opaque_syscall(SyscallSelector::Secp256k1GetPointFromX, |handler| {
+            Secp256Point::get_point_from_x(x, y_parity).map(|op| op.map(|p| p.into()))
+        })
     }
 
     fn secp256k1_get_xy(
@@ -769,7 +1122,7 @@ impl<'state> StarknetSyscallHandler for &mut NativeSyscallHandler<'state> {
             self.execution_context.gas_costs().secp256k1_get_xy_gas_cost,
         )?;
 
-        Ok((p.x, p.y))
+        self.intercept_opaque_syscall(SyscallSelector::Secp256k1GetXy, |handler| Ok((p.x, p.y)))
     }
 
     fn secp256r1_new(
@@ -784,7 +1137,9 @@ impl<'state> StarknetSyscallHandler for &mut NativeSyscallHandler<'state> {
             self.execution_context.gas_costs().secp256r1_new_gas_cost,
         )?;
 
-        Secp256Point::new(x, y).map(|op| op.map(|p| p.into()))
+        self.intercept_opaque_syscall(SyscallSelector::Secp256r1New, |handler| {
+            Secp256Point::new(x, y).map(|op| op.map(|p| p.into()))
+        })
     }
 
     fn secp256r1_add(
@@ -799,7 +1154,9 @@ impl<'state> StarknetSyscallHandler for &mut NativeSyscallHandler<'state> {
             self.execution_context.gas_costs().secp256r1_add_gas_cost,
         )?;
 
-        Ok(Secp256Point::add(p0.into(), p1.into()).into())
+        self.intercept_opaque_syscall(SyscallSelector::Secp256r1Add, |handler| {
+            Ok(Secp256Point::add(p0.into(), p1.into()).into())
+        })
     }
 
     fn secp256r1_mul(
@@ -814,7 +1171,9 @@ impl<'state> StarknetSyscallHandler for &mut NativeSyscallHandler<'state> {
             self.execution_context.gas_costs().secp256r1_mul_gas_cost,
         )?;
 
-        Ok(Secp256Point::mul(p.into(), m).into())
+        self.intercept_opaque_syscall(SyscallSelector::Secp256r1Mul, |handler| {
+            Ok(Secp256Point::mul(p.into(), m).into())
+        })
     }
 
     fn secp256r1_get_point_from_x(
@@ -829,7 +1188,9 @@ impl<'state> StarknetSyscallHandler for &mut NativeSyscallHandler<'state> {
             self.execution_context.gas_costs().secp256r1_get_point_from_x_gas_cost,
         )?;
 
-        Secp256Point::get_point_from_x(x, y_parity).map(|op| op.map(|p| p.into()))
+        self.intercept_opaque_syscall(SyscallSelector::Secp256r1GetPointFromX, |handler| {
+            Secp256Point::get_point_from_x(x, y_parity).map(|op| op.map(|p| p.into()))
+        })
     }
 
     fn secp256r1_get_xy(
@@ -843,7 +1204,7 @@ impl<'state> StarknetSyscallHandler for &mut NativeSyscallHandler<'state> {
             self.execution_context.gas_costs().secp256r1_get_xy_gas_cost,
         )?;
 
-        Ok((p.x, p.y))
+        self.intercept_opaque_syscall(SyscallSelector::Secp256r1GetXy, |handler| Ok((p.x, p.y)))
     }
 
     fn sha256_process_block(
@@ -860,15 +1221,18 @@ impl<'state> StarknetSyscallHandler for &mut NativeSyscallHandler<'state> {
             self.execution_context.gas_costs().sha256_process_block_gas_cost,
         )?;
 
-        let data_as_bytes = sha2::digest::generic_array::GenericArray::from_exact_iter(
-            current_block.iter().flat_map(|x| x.to_be_bytes()),
-        )
-        .expect(
-            "u32.to_be_bytes() returns 4 bytes, and data.len() == 16. So data contains 64 bytes.",
-        );
-        let mut state: [u32; SHA256_STATE_SIZE] = *prev_state;
-        sha2::compress256(&mut state, &[data_as_bytes]);
-        Ok(state)
+        self.intercept_opaque_syscall(SyscallSelector::Sha256ProcessBlock, |handler| {
+            let data_as_bytes = sha2::digest::generic_array::GenericArray::from_exact_iter(
+                current_block.iter().flat_map(|x| x.to_be_bytes()),
+            )
+            .expect(
+                "u32.to_be_bytes() returns 4 bytes, and data.len() == 16. So data contains 64 \
+                 bytes.",
+            );
+            let mut state: [u32; SHA256_STATE_SIZE] = *prev_state;
+            sha2::compress256(&mut state, &[data_as_bytes]);
+            Ok(state)
+        })
     }
 }
 
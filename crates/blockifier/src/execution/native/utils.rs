@@ -1,5 +1,4 @@
-use std::collections::{HashMap, HashSet};
-use std::hash::RandomState;
+use std::collections::HashMap;
 
 use ark_ff::BigInt;
 use cairo_lang_sierra::ids::FunctionId;
@@ -11,28 +10,23 @@ use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
 use itertools::Itertools;
 use num_bigint::BigUint;
 use num_traits::ToBytes;
+use serde::{Deserialize, Serialize};
 use starknet_api::core::{ContractAddress, EntryPointSelector};
-use starknet_api::state::StorageKey;
 use starknet_api::transaction::Resource;
 use starknet_types_core::felt::Felt;
 
-use crate::execution::call_info::{
-    CallExecution, CallInfo, OrderedEvent, OrderedL2ToL1Message, Retdata,
-};
+use crate::execution::call_info::{CallExecution, CallInfo, Retdata};
 use crate::execution::entry_point::{CallEntryPoint, EntryPointExecutionResult};
 use crate::execution::errors::EntryPointExecutionError;
 use crate::execution::native::syscall_handler::NativeSyscallHandler;
-use crate::execution::syscalls::hint_processor::{L1_GAS, L2_GAS};
+use crate::execution::syscalls::hint_processor::{SyscallExecutionError, L1_GAS, L2_GAS};
 use crate::transaction::objects::CurrentTransactionInfo;
+use crate::versioned_constants::GasCosts;
 
 #[cfg(test)]
 #[path = "utils_test.rs"]
 pub mod test;
 
-// An arbitrary number, chosen to avoid accidentally aligning with actually calculated gas
-// To be deleted once cairo native gas handling can be used
-pub const NATIVE_GAS_PLACEHOLDER: u64 = 12;
-
 pub fn contract_address_to_native_felt(contract_address: ContractAddress) -> Felt {
     *contract_address.0.key()
 }
@@ -44,73 +38,139 @@ pub fn contract_entrypoint_to_entrypoint_selector(
     EntryPointSelector(selector_felt)
 }
 
+/// The output of a native entry point execution.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct NativeExecutionOutput {
+    pub return_values: Vec<Felt>,
+    pub remaining_gas: u128,
+    pub failure_flag: bool,
+}
+
+impl From<ContractExecutionResult> for NativeExecutionOutput {
+    fn from(result: ContractExecutionResult) -> Self {
+        Self {
+            return_values: result.return_values,
+            remaining_gas: result.remaining_gas,
+            failure_flag: result.failure_flag,
+        }
+    }
+}
+
 pub fn run_native_executor(
     native_executor: &AotNativeExecutor,
     function_id: &FunctionId,
     call: CallEntryPoint,
     mut syscall_handler: NativeSyscallHandler<'_>,
 ) -> EntryPointExecutionResult<CallInfo> {
-    let execution_result = native_executor.invoke_contract_dynamic(
-        function_id,
-        &call.calldata.0,
-        Some(call.initial_gas.into()),
-        &mut syscall_handler,
-    );
+    // Fix the resources, in order to calculate the usage of this run at the end.
+    let previous_resources = syscall_handler.execution_resources.clone();
 
-    let run_result = match execution_result {
-        Ok(res) if res.failure_flag => Err(EntryPointExecutionError::NativeExecutionError {
-            info: if !res.return_values.is_empty() {
-                decode_felts_as_str(&res.return_values)
-            } else {
-                String::from("Unknown error")
-            },
-        }),
-        Err(runner_err) => {
-            Err(EntryPointExecutionError::NativeUnexpectedError { source: runner_err })
-        }
-        Ok(res) => Ok(res),
-    }?;
-
-    create_callinfo(
-        call.clone(),
-        run_result,
-        syscall_handler.events,
-        syscall_handler.l2_to_l1_messages,
-        syscall_handler.inner_calls,
-        syscall_handler.storage_read_values,
-        syscall_handler.accessed_storage_keys,
-    )
+    let execution_result = native_executor
+        .invoke_contract_dynamic(
+            function_id,
+            &call.calldata.0,
+            Some(call.initial_gas.into()),
+            &mut syscall_handler,
+        )
+        .map(NativeExecutionOutput::from)
+        .map_err(|source| EntryPointExecutionError::NativeUnexpectedError { source });
+
+    finalize_native_execution(call, execution_result, previous_resources, syscall_handler)
+}
+
+/// Builds the call info of a native execution, given the output of the native code, wherever it
+/// ran, and the syscall handler that served it.
+pub fn finalize_native_execution(
+    call: CallEntryPoint,
+    execution_result: EntryPointExecutionResult<NativeExecutionOutput>,
+    previous_resources: ExecutionResources,
+    mut syscall_handler: NativeSyscallHandler<'_>,
+) -> EntryPointExecutionResult<CallInfo> {
+    // Errors the VM does not recover from fail the call regardless of how the Cairo code handled
+    // them, as in the VM.
+    if let Some(error) = syscall_handler.unrecoverable_error.take() {
+        return Err(match error {
+            // Execution limits are reported as is, as in the VM.
+            SyscallExecutionError::EntryPointExecutionError(error) => error,
+            error => EntryPointExecutionError::NativeUnrecoverableError(Box::new(error)),
+        });
+    }
+
+    let run_result = execution_result?;
+    if run_result.failure_flag {
+        return Err(EntryPointExecutionError::ExecutionFailed {
+            error_data: run_result.return_values,
+        });
+    }
+
+    create_callinfo(call, run_result, previous_resources, syscall_handler)
 }
 
 pub fn create_callinfo(
     call: CallEntryPoint,
-    run_result: ContractExecutionResult,
-    events: Vec<OrderedEvent>,
-    l2_to_l1_messages: Vec<OrderedL2ToL1Message>,
-    inner_calls: Vec<CallInfo>,
-    storage_read_values: Vec<Felt>,
-    accessed_storage_keys: HashSet<StorageKey, RandomState>,
+    run_result: NativeExecutionOutput,
+    previous_resources: ExecutionResources,
+    mut syscall_handler: NativeSyscallHandler<'_>,
 ) -> Result<CallInfo, EntryPointExecutionError> {
+    let remaining_gas = u64::try_from(run_result.remaining_gas)
+        .ok()
+        .filter(|remaining_gas| *remaining_gas <= call.initial_gas)
+        .ok_or(EntryPointExecutionError::InternalError(format!(
+            "Unexpected remaining gas: {}.",
+            run_result.remaining_gas
+        )))?;
+    let gas_consumed = call.initial_gas - remaining_gas;
+
+    // Take into account the estimated VM resources of the current call, without inner calls, and
+    // charge the estimated steps against the step limit.
+    syscall_handler.check_execution_limits(run_result.remaining_gas)?;
+    let cairo_resources = estimate_execution_resources(
+        syscall_handler.cairo_gas_consumed(gas_consumed),
+        syscall_handler.execution_context.gas_costs(),
+    );
+    syscall_handler.execution_context.subtract_steps(cairo_resources.n_steps);
+    *syscall_handler.execution_resources += &cairo_resources;
+    // Take into account the syscall resources of the current call.
+    *syscall_handler.execution_resources += &syscall_handler
+        .execution_context
+        .versioned_constants()
+        .get_additional_os_syscall_resources(&syscall_handler.syscall_counter)?;
+
+    let full_call_resources = &*syscall_handler.execution_resources - &previous_resources;
     Ok(CallInfo {
         call,
         execution: CallExecution {
             retdata: Retdata(run_result.return_values),
-            events,
-            l2_to_l1_messages,
+            events: syscall_handler.events,
+            l2_to_l1_messages: syscall_handler.l2_to_l1_messages,
             failed: run_result.failure_flag,
-            gas_consumed: NATIVE_GAS_PLACEHOLDER,
-        },
-        resources: ExecutionResources {
-            n_steps: 0,
-            n_memory_holes: 0,
-            builtin_instance_counter: HashMap::default(),
+            gas_consumed,
         },
-        inner_calls,
-        storage_read_values,
-        accessed_storage_keys,
+        resources: full_call_resources.filter_unused_builtins(),
+        inner_calls: syscall_handler.inner_calls,
+        storage_read_values: syscall_handler.storage_read_values,
+        accessed_storage_keys: syscall_handler.accessed_storage_keys,
     })
 }
 
+/// Estimates the VM resources equivalent to the given amount of gas, consumed by the Cairo code of
+/// a native call (i.e., excluding syscalls and inner calls).
+/// Native execution tracks neither Cairo steps nor builtin usage; the gas is attributed to steps
+/// only, at the VM step gas cost. Builtin usage of syscalls is accounted for separately, exactly as
+/// in the VM.
+/// Note: the builtins used by the Cairo code itself (e.g., range checks, hashes, bitwise
+/// operations) are not estimated; their usage is missing from the returned resources (and their
+/// gas is counted as steps instead), so resource-based weights (e.g., the bouncer's) undercount
+/// builtin-heavy native calls.
+pub fn estimate_execution_resources(gas_consumed: u64, gas_costs: &GasCosts) -> ExecutionResources {
+    let n_steps = gas_consumed / gas_costs.step_gas_cost;
+    ExecutionResources {
+        n_steps: usize::try_from(n_steps).expect("Failed to convert u64 to usize."),
+        n_memory_holes: 0,
+        builtin_instance_counter: HashMap::default(),
+    }
+}
+
 pub fn u256_to_biguint(u256: U256) -> BigUint {
     let lo = BigUint::from(u256.lo);
     let hi = BigUint::from(u256.hi);
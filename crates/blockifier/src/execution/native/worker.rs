@@ -0,0 +1,838 @@
+use std::collections::{HashMap, HashSet};
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::os::fd::AsRawFd;
+use std::os::unix::net::UnixStream;
+use std::os::unix::process::CommandExt;
+use std::process::{Child, Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use cairo_native::starknet::{
+    ExecutionInfo, ExecutionInfoV2, Secp256k1Point, Secp256r1Point, StarknetSyscallHandler,
+    SyscallResult, U256,
+};
+use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
+use log::debug;
+use starknet_api::core::ClassHash;
+use starknet_types_core::felt::Felt;
+
+use super::artifact_cache::{load_artifact, NativeArtifactCache};
+use super::syscall_handler::NativeSyscallHandler;
+use super::utils::{encode_str_as_felts, finalize_native_execution, NativeExecutionOutput};
+use super::worker_protocol::{
+    read_message, write_message, ExecuteRequest, ParentMessage, SyscallOutput, SyscallRequest,
+    WirePoint, WireU256, WorkerMessage, WORKER_CHANNEL_FD,
+};
+use super::worker_sandbox::restrict_process;
+use crate::blockifier::config::NativeWorkerConfig;
+use crate::execution::call_info::CallInfo;
+use crate::execution::contract_class::NativeContractClassV1;
+use crate::execution::entry_point::{
+    CallEntryPoint, EntryPointExecutionContext, EntryPointExecutionResult,
+};
+use crate::execution::errors::{EntryPointExecutionError, NativeWorkerError};
+use crate::state::state_api::State;
+
+#[cfg(test)]
+#[path = "worker_test.rs"]
+pub mod test;
+
+/// A pool of sandboxed worker processes that execute classes compiled to native code, so that a
+/// crash of native code does not take the sequencer down with it.
+/// Syscalls of the executed code are proxied back to, and served by, the sequencer process.
+#[derive(Clone, Debug)]
+pub struct NativeWorker {
+    config: NativeWorkerConfig,
+    artifact_cache: NativeArtifactCache,
+    idle_processes: Arc<Mutex<Vec<WorkerProcess>>>,
+}
+
+impl NativeWorker {
+    pub fn new(config: NativeWorkerConfig) -> Self {
+        let artifact_cache = NativeArtifactCache::new(config.artifact_cache_config.clone());
+        Self { config, artifact_cache, idle_processes: Arc::default() }
+    }
+
+    fn checkout(&self) -> Result<WorkerProcess, NativeWorkerError> {
+        let idle_process = self.idle_processes.lock().expect("Worker pool is poisoned.").pop();
+        match idle_process {
+            Some(process) => Ok(process),
+            None => WorkerProcess::spawn(&self.config),
+        }
+    }
+
+    fn checkin(&self, process: WorkerProcess) {
+        // Processes that failed are dropped, and thus killed.
+        if process.healthy {
+            self.idle_processes.lock().expect("Worker pool is poisoned.").push(process);
+        }
+    }
+}
+
+/// A running worker process, along with the classes it has already loaded.
+#[derive(Debug)]
+pub struct WorkerProcess {
+    child: Child,
+    writer: BufWriter<UnixStream>,
+    reader: BufReader<UnixStream>,
+    response_timeout: Duration,
+    loaded_classes: HashSet<ClassHash>,
+    healthy: bool,
+}
+
+impl WorkerProcess {
+    fn spawn(config: &NativeWorkerConfig) -> Result<Self, NativeWorkerError> {
+        let (channel, worker_channel) =
+            UnixStream::pair().map_err(NativeWorkerError::SpawnError)?;
+        channel.set_read_timeout(Some(config.response_timeout))?;
+        channel.set_write_timeout(Some(config.response_timeout))?;
+
+        let mut command = Command::new(&config.executable_path);
+        // Anything the native code prints goes to the sequencer's standard error, away from the
+        // channel.
+        command.stdin(Stdio::null()).stdout(io::stderr());
+        let worker_channel_fd = worker_channel.as_raw_fd();
+        // Safety: the closure only makes async-signal-safe calls.
+        unsafe {
+            command.pre_exec(move || {
+                // Pass the channel at its well-known descriptor, which, unlike the original, is
+                // inherited by the worker binary.
+                let result = if worker_channel_fd == WORKER_CHANNEL_FD {
+                    libc::fcntl(WORKER_CHANNEL_FD, libc::F_SETFD, 0)
+                } else {
+                    libc::dup2(worker_channel_fd, WORKER_CHANNEL_FD)
+                };
+                if result == -1 {
+                    return Err(io::Error::last_os_error());
+                }
+                Ok(())
+            });
+            let sandbox_config = config.sandbox_config.clone();
+            command.pre_exec(move || restrict_process(&sandbox_config));
+        }
+        let child = command.spawn().map_err(NativeWorkerError::SpawnError)?;
+        // Only the worker holds its end, so that its death closes the channel.
+        drop(worker_channel);
+
+        Ok(Self {
+            child,
+            writer: BufWriter::new(channel.try_clone()?),
+            reader: BufReader::new(channel),
+            response_timeout: config.response_timeout,
+            loaded_classes: HashSet::new(),
+            healthy: true,
+        })
+    }
+
+    fn send(&mut self, message: &ParentMessage) -> Result<(), NativeWorkerError> {
+        write_message(&mut self.writer, message).map_err(|error| self.on_failure(error))
+    }
+
+    fn receive(&mut self) -> Result<WorkerMessage, NativeWorkerError> {
+        match read_message(&mut self.reader) {
+            Ok(Some(message)) => Ok(message),
+            Ok(None) => Err(self.on_failure(io::Error::from(io::ErrorKind::UnexpectedEof).into())),
+            Err(error) => Err(self.on_failure(error)),
+        }
+    }
+
+    /// Marks the process as unusable and terminates it. A broken channel is reported as a crash,
+    /// and a timed out one as an unresponsive worker.
+    fn on_failure(&mut self, error: NativeWorkerError) -> NativeWorkerError {
+        self.healthy = false;
+        // The process is either dead, hung, or can no longer be trusted.
+        let _ = self.child.kill();
+        let exit_status = self.child.wait();
+        match error {
+            NativeWorkerError::IoError(error)
+                if matches!(error.kind(), io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut) =>
+            {
+                NativeWorkerError::Unresponsive { timeout: self.response_timeout }
+            }
+            NativeWorkerError::IoError(_) => match exit_status {
+                Ok(exit_status) => {
+                    NativeWorkerError::Crashed { exit_status: exit_status.to_string() }
+                }
+                Err(error) => error.into(),
+            },
+            error => error,
+        }
+    }
+
+    /// Makes the class available for execution in the process, compiling it into the artifact
+    /// cache first if needed.
+    fn load_class(
+        &mut self,
+        class_hash: ClassHash,
+        contract_class: &NativeContractClassV1,
+        worker: &NativeWorker,
+    ) -> Result<(), NativeWorkerError> {
+        if self.loaded_classes.contains(&class_hash) {
+            return Ok(());
+        }
+
+        let sierra_contract_class = contract_class.to_sierra_contract_class();
+        let artifact_path = worker
+            .artifact_cache
+            .get_or_compile_artifact(class_hash, &sierra_contract_class, worker.config.opt_level)
+            .map_err(|error| NativeWorkerError::ClassLoadingError {
+                class_hash,
+                info: error.to_string(),
+            })?;
+        self.send(&ParentMessage::LoadClass { class_hash, sierra_contract_class, artifact_path })?;
+        match self.receive()? {
+            WorkerMessage::Loaded(Ok(())) => {
+                self.loaded_classes.insert(class_hash);
+                Ok(())
+            }
+            WorkerMessage::Loaded(Err(info)) => {
+                Err(NativeWorkerError::ClassLoadingError { class_hash, info })
+            }
+            message => Err(self.on_failure(NativeWorkerError::ProtocolError(format!(
+                "Expected a class loading result, got: {message:?}."
+            )))),
+        }
+    }
+}
+
+impl Drop for WorkerProcess {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// Executes an entry point of a native class in a worker process.
+/// Nested calls of the execution that reach native classes are executed by the same process.
+pub fn execute_entry_point_call(
+    call: CallEntryPoint,
+    contract_class: NativeContractClassV1,
+    state: &mut dyn State,
+    resources: &mut ExecutionResources,
+    context: &mut EntryPointExecutionContext,
+    worker: &NativeWorker,
+) -> EntryPointExecutionResult<CallInfo> {
+    let is_outermost_call = context.native_worker_process.is_none();
+    if is_outermost_call {
+        context.native_worker_process = Some(worker.checkout()?);
+    }
+
+    let result = execute_in_process(call, contract_class, state, resources, context, worker);
+
+    if is_outermost_call {
+        if let Some(process) = context.native_worker_process.take() {
+            worker.checkin(process);
+        }
+    }
+    result
+}
+
+fn execute_in_process(
+    call: CallEntryPoint,
+    contract_class: NativeContractClassV1,
+    state: &mut dyn State,
+    resources: &mut ExecutionResources,
+    context: &mut EntryPointExecutionContext,
+    worker: &NativeWorker,
+) -> EntryPointExecutionResult<CallInfo> {
+    let class_hash = call.class_hash.ok_or_else(|| {
+        EntryPointExecutionError::InternalError("Class hash must be set at this point.".to_string())
+    })?;
+    // Fail early on unknown entry points, as in-process execution does.
+    contract_class.get_entrypoint(call.entry_point_type, call.entry_point_selector)?;
+    worker_process(context)?.load_class(class_hash, &contract_class, worker)?;
+
+    let mut syscall_handler = NativeSyscallHandler::new(
+        state,
+        call.caller_address,
+        call.storage_address,
+        call.entry_point_selector,
+        call.initial_gas,
+        resources,
+        context,
+    );
+    // Fix the resources, in order to calculate the usage of this run at the end.
+    let previous_resources = syscall_handler.execution_resources.clone();
+
+    debug!("Blockifier-Native: running the Native Executor in a worker process");
+    let request = ExecuteRequest {
+        class_hash,
+        entry_point_type: call.entry_point_type,
+        entry_point_selector: call.entry_point_selector,
+        calldata: call.calldata.0.to_vec(),
+        initial_gas: call.initial_gas,
+    };
+    let execution_result =
+        run_in_worker(request, &mut syscall_handler).map_err(EntryPointExecutionError::from);
+    debug!("Blockifier-Native: worker process finished running");
+
+    finalize_native_execution(call, execution_result, previous_resources, syscall_handler)
+}
+
+fn worker_process(
+    context: &mut EntryPointExecutionContext,
+) -> Result<&mut WorkerProcess, NativeWorkerError> {
+    context
+        .native_worker_process
+        .as_mut()
+        .ok_or_else(|| NativeWorkerError::ProtocolError("No worker process.".to_string()))
+}
+
+/// Runs the request to completion, serving the syscalls of the worker in the meantime.
+// The process is accessed through the context, rather than held, as syscalls may execute nested
+// calls in it.
+fn run_in_worker(
+    request: ExecuteRequest,
+    syscall_handler: &mut NativeSyscallHandler<'_>,
+) -> Result<NativeExecutionOutput, NativeWorkerError> {
+    worker_process(syscall_handler.execution_context)?.send(&ParentMessage::Execute(request))?;
+    loop {
+        match worker_process(syscall_handler.execution_context)?.receive()? {
+            WorkerMessage::Syscall { request, mut remaining_gas } => {
+                let result = serve_syscall(syscall_handler, request, &mut remaining_gas);
+                worker_process(syscall_handler.execution_context)?
+                    .send(&ParentMessage::SyscallResponse { result, remaining_gas })?;
+            }
+            WorkerMessage::Finished(result) => {
+                return result.map_err(NativeWorkerError::ExecutionError);
+            }
+            message => {
+                let process = worker_process(syscall_handler.execution_context)?;
+                return Err(process.on_failure(NativeWorkerError::ProtocolError(format!(
+                    "Expected a syscall or an execution result, got: {message:?}."
+                ))));
+            }
+        }
+    }
+}
+
+fn serve_syscall(
+    syscall_handler: &mut NativeSyscallHandler<'_>,
+    request: SyscallRequest,
+    remaining_gas: &mut u128,
+) -> SyscallResult<SyscallOutput> {
+    let mut handler = syscall_handler;
+    let output = match request {
+        SyscallRequest::GetBlockHash { block_number } => {
+            SyscallOutput::Felt(handler.get_block_hash(block_number, remaining_gas)?)
+        }
+        SyscallRequest::GetExecutionInfo => {
+            SyscallOutput::ExecutionInfo(handler.get_execution_info(remaining_gas)?.into())
+        }
+        SyscallRequest::GetExecutionInfoV2 => {
+            SyscallOutput::ExecutionInfoV2(handler.get_execution_info_v2(remaining_gas)?.into())
+        }
+        SyscallRequest::Deploy {
+            class_hash,
+            contract_address_salt,
+            calldata,
+            deploy_from_zero,
+        } => {
+            let (contract_address, retdata) = handler.deploy(
+                class_hash,
+                contract_address_salt,
+                &calldata,
+                deploy_from_zero,
+                remaining_gas,
+            )?;
+            SyscallOutput::Deploy { contract_address, retdata }
+        }
+        SyscallRequest::ReplaceClass { class_hash } => {
+            handler.replace_class(class_hash, remaining_gas)?;
+            SyscallOutput::Unit
+        }
+        SyscallRequest::LibraryCall { class_hash, function_selector, calldata } => {
+            SyscallOutput::Felts(handler.library_call(
+                class_hash,
+                function_selector,
+                &calldata,
+                remaining_gas,
+            )?)
+        }
+        SyscallRequest::CallContract { address, entry_point_selector, calldata } => {
+            SyscallOutput::Felts(handler.call_contract(
+                address,
+                entry_point_selector,
+                &calldata,
+                remaining_gas,
+            )?)
+        }
+        SyscallRequest::StorageRead { address_domain, address } => {
+            SyscallOutput::Felt(handler.storage_read(address_domain, address, remaining_gas)?)
+        }
+        SyscallRequest::StorageWrite { address_domain, address, value } => {
+            handler.storage_write(address_domain, address, value, remaining_gas)?;
+            SyscallOutput::Unit
+        }
+        SyscallRequest::EmitEvent { keys, data } => {
+            handler.emit_event(&keys, &data, remaining_gas)?;
+            SyscallOutput::Unit
+        }
+        SyscallRequest::SendMessageToL1 { to_address, payload } => {
+            handler.send_message_to_l1(to_address, &payload, remaining_gas)?;
+            SyscallOutput::Unit
+        }
+        SyscallRequest::Keccak { input } => {
+            SyscallOutput::U256(handler.keccak(&input, remaining_gas)?.into())
+        }
+        SyscallRequest::Secp256k1New { x, y } => SyscallOutput::Point(
+            handler.secp256k1_new(x.into(), y.into(), remaining_gas)?.map(Into::into),
+        ),
+        SyscallRequest::Secp256k1Add { p0, p1 } => SyscallOutput::Point(Some(
+            handler.secp256k1_add(p0.into(), p1.into(), remaining_gas)?.into(),
+        )),
+        SyscallRequest::Secp256k1Mul { p, m } => SyscallOutput::Point(Some(
+            handler.secp256k1_mul(p.into(), m.into(), remaining_gas)?.into(),
+        )),
+        SyscallRequest::Secp256k1GetPointFromX { x, y_parity } => SyscallOutput::Point(
+            handler.secp256k1_get_point_from_x(x.into(), y_parity, remaining_gas)?.map(Into::into),
+        ),
+        SyscallRequest::Secp256k1GetXy { p } => {
+            let (x, y) = handler.secp256k1_get_xy(p.into(), remaining_gas)?;
+            SyscallOutput::Xy(x.into(), y.into())
+        }
+        SyscallRequest::Secp256r1New { x, y } => SyscallOutput::Point(
+            handler.secp256r1_new(x.into(), y.into(), remaining_gas)?.map(Into::into),
+        ),
+        SyscallRequest::Secp256r1Add { p0, p1 } => SyscallOutput::Point(Some(
+            handler.secp256r1_add(p0.into(), p1.into(), remaining_gas)?.into(),
+        )),
+        SyscallRequest::Secp256r1Mul { p, m } => SyscallOutput::Point(Some(
+            handler.secp256r1_mul(p.into(), m.into(), remaining_gas)?.into(),
+        )),
+        SyscallRequest::Secp256r1GetPointFromX { x, y_parity } => SyscallOutput::Point(
+            handler.secp256r1_get_point_from_x(x.into(), y_parity, remaining_gas)?.map(Into::into),
+        ),
+        SyscallRequest::Secp256r1GetXy { p } => {
+            let (x, y) = handler.secp256r1_get_xy(p.into(), remaining_gas)?;
+            SyscallOutput::Xy(x.into(), y.into())
+        }
+        SyscallRequest::Sha256ProcessBlock { prev_state, current_block } => {
+            SyscallOutput::Sha256State(handler.sha256_process_block(
+                &prev_state,
+                &current_block,
+                remaining_gas,
+            )?)
+        }
+    };
+
+    Ok(output)
+}
+
+/// Serves a sequencer process over the given channel, until the channel is closed. This is the
+/// main loop of a worker process.
+pub fn run_worker(reader: impl Read, writer: impl Write) -> Result<(), NativeWorkerError> {
+    let mut worker = Worker { reader, writer, classes: HashMap::new(), channel_error: None };
+    while let Some(message) = read_message(&mut worker.reader)? {
+        worker.handle(message)?;
+    }
+
+    Ok(())
+}
+
+struct Worker<R, W> {
+    reader: R,
+    writer: W,
+    classes: HashMap<ClassHash, NativeContractClassV1>,
+    // Set when the channel to the sequencer process breaks during an execution; the native code
+    // cannot be interrupted, so the error is reported once it returns.
+    channel_error: Option<NativeWorkerError>,
+}
+
+impl<R: Read, W: Write> Worker<R, W> {
+    fn handle(&mut self, message: ParentMessage) -> Result<(), NativeWorkerError> {
+        match message {
+            ParentMessage::LoadClass { class_hash, sierra_contract_class, artifact_path } => {
+                let result = load_artifact(&artifact_path, sierra_contract_class)
+                    .map(|contract_class| {
+                        self.classes.insert(class_hash, contract_class);
+                    })
+                    .map_err(|error| error.to_string());
+                write_message(&mut self.writer, &WorkerMessage::Loaded(result))
+            }
+            ParentMessage::Execute(request) => {
+                let result = self.execute(request)?;
+                write_message(&mut self.writer, &WorkerMessage::Finished(result))
+            }
+            ParentMessage::SyscallResponse { .. } => {
+                Err(NativeWorkerError::ProtocolError("Unexpected syscall response.".to_string()))
+            }
+        }
+    }
+
+    /// Executes the request. Fails only if the channel to the sequencer process broke in the
+    /// meantime; execution errors are part of the result, to be reported to the sequencer.
+    fn execute(
+        &mut self,
+        request: ExecuteRequest,
+    ) -> Result<Result<NativeExecutionOutput, String>, NativeWorkerError> {
+        let result = self.invoke(request);
+        match self.channel_error.take() {
+            Some(error) => Err(error),
+            None => Ok(result),
+        }
+    }
+
+    fn invoke(&mut self, request: ExecuteRequest) -> Result<NativeExecutionOutput, String> {
+        let contract_class = self
+            .classes
+            .get(&request.class_hash)
+            .cloned()
+            .ok_or_else(|| format!("Class {} is not loaded.", request.class_hash))?;
+        let function_id = contract_class
+            .get_entrypoint(request.entry_point_type, request.entry_point_selector)
+            .map_err(|error| error.to_string())?;
+
+        let mut syscall_proxy = SyscallProxy { worker: self };
+        contract_class
+            .executor
+            .invoke_contract_dynamic(
+                function_id,
+                &request.calldata,
+                Some(request.initial_gas.into()),
+                &mut syscall_proxy,
+            )
+            .map(NativeExecutionOutput::from)
+            .map_err(|error| error.to_string())
+    }
+
+    /// Forwards a syscall to the sequencer process and waits for its result, executing any nested
+    /// calls in the meantime.
+    fn proxy_syscall(
+        &mut self,
+        request: SyscallRequest,
+        remaining_gas: &mut u128,
+    ) -> Result<SyscallResult<SyscallOutput>, NativeWorkerError> {
+        write_message(
+            &mut self.writer,
+            &WorkerMessage::Syscall { request, remaining_gas: *remaining_gas },
+        )?;
+        loop {
+            match read_message(&mut self.reader)? {
+                Some(ParentMessage::SyscallResponse { result, remaining_gas: gas }) => {
+                    *remaining_gas = gas;
+                    return Ok(result);
+                }
+                Some(message) => self.handle(message)?,
+                None => {
+                    return Err(NativeWorkerError::ProtocolError("Connection closed.".to_string()));
+                }
+            }
+        }
+    }
+}
+
+/// Serves the syscalls of natively executed code in a worker process, by proxying them to the
+/// sequencer process.
+struct SyscallProxy<'worker, R, W> {
+    worker: &'worker mut Worker<R, W>,
+}
+
+impl<'worker, R: Read, W: Write> SyscallProxy<'worker, R, W> {
+    fn call(
+        &mut self,
+        request: SyscallRequest,
+        remaining_gas: &mut u128,
+    ) -> SyscallResult<SyscallOutput> {
+        // The sequencer process is gone; fail this and any further syscall, so that the native
+        // code returns as soon as possible.
+        if self.worker.channel_error.is_some() {
+            return Err(encode_str_as_felts("Native worker lost its sequencer process."));
+        }
+        match self.worker.proxy_syscall(request, remaining_gas) {
+            Ok(result) => result,
+            Err(error) => {
+                let error_data = encode_str_as_felts(&format!(
+                    "Native worker lost its sequencer process: {error}"
+                ));
+                self.worker.channel_error = Some(error);
+                Err(error_data)
+            }
+        }
+    }
+}
+
+fn unexpected_output(output: SyscallOutput) -> Vec<Felt> {
+    encode_str_as_felts(&output.unexpected().to_string())
+}
+
+fn expect_unit(output: SyscallOutput) -> SyscallResult<()> {
+    match output {
+        SyscallOutput::Unit => Ok(()),
+        output => Err(unexpected_output(output)),
+    }
+}
+
+fn expect_felt(output: SyscallOutput) -> SyscallResult<Felt> {
+    match output {
+        SyscallOutput::Felt(felt) => Ok(felt),
+        output => Err(unexpected_output(output)),
+    }
+}
+
+fn expect_felts(output: SyscallOutput) -> SyscallResult<Vec<Felt>> {
+    match output {
+        SyscallOutput::Felts(felts) => Ok(felts),
+        output => Err(unexpected_output(output)),
+    }
+}
+
+fn expect_point<P: From<WirePoint>>(output: SyscallOutput) -> SyscallResult<Option<P>> {
+    match output {
+        SyscallOutput::Point(point) => Ok(point.map(Into::into)),
+        output => Err(unexpected_output(output)),
+    }
+}
+
+fn expect_xy(output: SyscallOutput) -> SyscallResult<(U256, U256)> {
+    match output {
+        SyscallOutput::Xy(x, y) => Ok((x.into(), y.into())),
+        output => Err(unexpected_output(output)),
+    }
+}
+
+fn some_point<P>(point: Option<P>) -> SyscallResult<P> {
+    point.ok_or_else(|| encode_str_as_felts("Expected a point."))
+}
+
+impl<'worker, R: Read, W: Write> StarknetSyscallHandler for &mut SyscallProxy<'worker, R, W> {
+    fn get_block_hash(
+        &mut self,
+        block_number: u64,
+        remaining_gas: &mut u128,
+    ) -> SyscallResult<Felt> {
+        expect_felt(self.call(SyscallRequest::GetBlockHash { block_number }, remaining_gas)?)
+    }
+
+    fn get_execution_info(&mut self, remaining_gas: &mut u128) -> SyscallResult<ExecutionInfo> {
+        match self.call(SyscallRequest::GetExecutionInfo, remaining_gas)? {
+            SyscallOutput::ExecutionInfo(execution_info) => Ok(execution_info.into()),
+            output => Err(unexpected_output(output)),
+        }
+    }
+
+    fn get_execution_info_v2(
+        &mut self,
+        remaining_gas: &mut u128,
+    ) -> SyscallResult<ExecutionInfoV2> {
+        match self.call(SyscallRequest::GetExecutionInfoV2, remaining_gas)? {
+            SyscallOutput::ExecutionInfoV2(execution_info) => Ok(execution_info.into()),
+            output => Err(unexpected_output(output)),
+        }
+    }
+
+    fn deploy(
+        &mut self,
+        class_hash: Felt,
+        contract_address_salt: Felt,
+        calldata: &[Felt],
+        deploy_from_zero: bool,
+        remaining_gas: &mut u128,
+    ) -> SyscallResult<(Felt, Vec<Felt>)> {
+        let request = SyscallRequest::Deploy {
+            class_hash,
+            contract_address_salt,
+            calldata: calldata.to_vec(),
+            deploy_from_zero,
+        };
+        match self.call(request, remaining_gas)? {
+            SyscallOutput::Deploy { contract_address, retdata } => Ok((contract_address, retdata)),
+            output => Err(unexpected_output(output)),
+        }
+    }
+
+    fn replace_class(&mut self, class_hash: Felt, remaining_gas: &mut u128) -> SyscallResult<()> {
+        expect_unit(self.call(SyscallRequest::ReplaceClass { class_hash }, remaining_gas)?)
+    }
+
+    fn library_call(
+        &mut self,
+        class_hash: Felt,
+        function_selector: Felt,
+        calldata: &[Felt],
+        remaining_gas: &mut u128,
+    ) -> SyscallResult<Vec<Felt>> {
+        let request = SyscallRequest::LibraryCall {
+            class_hash,
+            function_selector,
+            calldata: calldata.to_vec(),
+        };
+        expect_felts(self.call(request, remaining_gas)?)
+    }
+
+    fn call_contract(
+        &mut self,
+        address: Felt,
+        entry_point_selector: Felt,
+        calldata: &[Felt],
+        remaining_gas: &mut u128,
+    ) -> SyscallResult<Vec<Felt>> {
+        let request = SyscallRequest::CallContract {
+            address,
+            entry_point_selector,
+            calldata: calldata.to_vec(),
+        };
+        expect_felts(self.call(request, remaining_gas)?)
+    }
+
+    fn storage_read(
+        &mut self,
+        address_domain: u32,
+        address: Felt,
+        remaining_gas: &mut u128,
+    ) -> SyscallResult<Felt> {
+        expect_felt(
+            self.call(SyscallRequest::StorageRead { address_domain, address }, remaining_gas)?,
+        )
+    }
+
+    fn storage_write(
+        &mut self,
+        address_domain: u32,
+        address: Felt,
+        value: Felt,
+        remaining_gas: &mut u128,
+    ) -> SyscallResult<()> {
+        let request = SyscallRequest::StorageWrite { address_domain, address, value };
+        expect_unit(self.call(request, remaining_gas)?)
+    }
+
+    fn emit_event(
+        &mut self,
+        keys: &[Felt],
+        data: &[Felt],
+        remaining_gas: &mut u128,
+    ) -> SyscallResult<()> {
+        let request = SyscallRequest::EmitEvent { keys: keys.to_vec(), data: data.to_vec() };
+        expect_unit(self.call(request, remaining_gas)?)
+    }
+
+    fn send_message_to_l1(
+        &mut self,
+        to_address: Felt,
+        payload: &[Felt],
+        remaining_gas: &mut u128,
+    ) -> SyscallResult<()> {
+        let request = SyscallRequest::SendMessageToL1 { to_address, payload: payload.to_vec() };
+        expect_unit(self.call(request, remaining_gas)?)
+    }
+
+    fn keccak(&mut self, input: &[u64], remaining_gas: &mut u128) -> SyscallResult<U256> {
+        match self.call(SyscallRequest::Keccak { input: input.to_vec() }, remaining_gas)? {
+            SyscallOutput::U256(value) => Ok(value.into()),
+            output => Err(unexpected_output(output)),
+        }
+    }
+
+    fn secp256k1_new(
+        &mut self,
+        x: U256,
+        y: U256,
+        remaining_gas: &mut u128,
+    ) -> SyscallResult<Option<Secp256k1Point>> {
+        let request = SyscallRequest::Secp256k1New { x: x.into(), y: y.into() };
+        expect_point(self.call(request, remaining_gas)?)
+    }
+
+    fn secp256k1_add(
+        &mut self,
+        p0: Secp256k1Point,
+        p1: Secp256k1Point,
+        remaining_gas: &mut u128,
+    ) -> SyscallResult<Secp256k1Point> {
+        let request = SyscallRequest::Secp256k1Add { p0: p0.into(), p1: p1.into() };
+        some_point(expect_point(self.call(request, remaining_gas)?)?)
+    }
+
+    fn secp256k1_mul(
+        &mut self,
+        p: Secp256k1Point,
+        m: U256,
+        remaining_gas: &mut u128,
+    ) -> SyscallResult<Secp256k1Point> {
+        let request = SyscallRequest::Secp256k1Mul { p: p.into(), m: WireU256::from(m) };
+        some_point(expect_point(self.call(request, remaining_gas)?)?)
+    }
+
+    fn secp256k1_get_point_from_x(
+        &mut self,
+        x: U256,
+        y_parity: bool,
+        remaining_gas: &mut u128,
+    ) -> SyscallResult<Option<Secp256k1Point>> {
+        let request = SyscallRequest::Secp256k1GetPointFromX { x: x.into(), y_parity };
+        expect_point(self.call(request, remaining_gas)?)
+    }
+
+    fn secp256k1_get_xy(
+        &mut self,
+        p: Secp256k1Point,
+        remaining_gas: &mut u128,
+    ) -> SyscallResult<(U256, U256)> {
+        expect_xy(self.call(SyscallRequest::Secp256k1GetXy { p: p.into() }, remaining_gas)?)
+    }
+
+    fn secp256r1_new(
+        &mut self,
+        x: U256,
+        y: U256,
+        remaining_gas: &mut u128,
+    ) -> SyscallResult<Option<Secp256r1Point>> {
+        let request = SyscallRequest::Secp256r1New { x: x.into(), y: y.into() };
+        expect_point(self.call(request, remaining_gas)?)
+    }
+
+    fn secp256r1_add(
+        &mut self,
+        p0: Secp256r1Point,
+        p1: Secp256r1Point,
+        remaining_gas: &mut u128,
+    ) -> SyscallResult<Secp256r1Point> {
+        let request = SyscallRequest::Secp256r1Add { p0: p0.into(), p1: p1.into() };
+        some_point(expect_point(self.call(request, remaining_gas)?)?)
+    }
+
+    fn secp256r1_mul(
+        &mut self,
+        p: Secp256r1Point,
+        m: U256,
+        remaining_gas: &mut u128,
+    ) -> SyscallResult<Secp256r1Point> {
+        let request = SyscallRequest::Secp256r1Mul { p: p.into(), m: WireU256::from(m) };
+        some_point(expect_point(self.call(request, remaining_gas)?)?)
+    }
+
+    fn secp256r1_get_point_from_x(
+        &mut self,
+        x: U256,
+        y_parity: bool,
+        remaining_gas: &mut u128,
+    ) -> SyscallResult<Option<Secp256r1Point>> {
+        let request = SyscallRequest::Secp256r1GetPointFromX { x: x.into(), y_parity };
+        expect_point(self.call(request, remaining_gas)?)
+    }
+
+    fn secp256r1_get_xy(
+        &mut self,
+        p: Secp256r1Point,
+        remaining_gas: &mut u128,
+    ) -> SyscallResult<(U256, U256)> {
+        expect_xy(self.call(SyscallRequest::Secp256r1GetXy { p: p.into() }, remaining_gas)?)
+    }
+
+    fn sha256_process_block(
+        &mut self,
+        prev_state: &[u32; 8],
+        current_block: &[u32; 16],
+        remaining_gas: &mut u128,
+    ) -> SyscallResult<[u32; 8]> {
+        let request = SyscallRequest::Sha256ProcessBlock {
+            prev_state: *prev_state,
+            current_block: *current_block,
+        };
+        match self.call(request, remaining_gas)? {
+            SyscallOutput::Sha256State(state) => Ok(state),
+            output => Err(unexpected_output(output)),
+        }
+    }
+}
@@ -0,0 +1,489 @@
+use std::io::{self, Read, Write};
+use std::os::fd::RawFd;
+use std::path::PathBuf;
+
+use cairo_lang_starknet_classes::contract_class::ContractClass as SierraContractClass;
+use cairo_native::starknet::{
+    BlockInfo, ExecutionInfo, ExecutionInfoV2, ResourceBounds, Secp256k1Point, Secp256r1Point,
+    TxInfo, TxV2Info, U256,
+};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use starknet_api::core::{ClassHash, EntryPointSelector};
+use starknet_api::deprecated_contract_class::EntryPointType;
+use starknet_types_core::felt::Felt;
+
+use super::utils::NativeExecutionOutput;
+use crate::execution::errors::NativeWorkerError;
+
+#[cfg(test)]
+#[path = "worker_protocol_test.rs"]
+pub mod test;
+
+// The protocol between the sequencer process and a native worker process.
+// Messages are sent over a dedicated socket (rather than the standard streams, which the native
+// code may write to); they are JSON-encoded, and are framed by their length (as a little-endian
+// u32). The parent drives the conversation: it loads classes and requests executions; while
+// executing, the worker may only send syscall requests, each answered by the parent, and finally
+// the execution output. A syscall may itself trigger nested executions (e.g., `call_contract`),
+// which follow the same rules.
+
+/// The file descriptor at which a worker process finds its channel to the sequencer process.
+pub const WORKER_CHANNEL_FD: RawFd = 3;
+
+/// The maximal length of a message, in bytes; bounds the memory allocated for a message before it
+/// is read (the largest messages carry Sierra classes).
+pub const MAX_MESSAGE_SIZE: usize = 1 << 27;
+
+/// Writes a single message to the channel.
+pub fn write_message<T: Serialize>(
+    writer: &mut impl Write,
+    message: &T,
+) -> Result<(), NativeWorkerError> {
+    let payload = serde_json::to_vec(message)?;
+    if payload.len() > MAX_MESSAGE_SIZE {
+        return Err(message_too_long(payload.len()));
+    }
+    let length = u32::try_from(payload.len()).expect("Maximal message size fits in u32.");
+    writer.write_all(&length.to_le_bytes())?;
+    writer.write_all(&payload)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Reads a single message from the channel. Returns `None` if the channel was closed.
+pub fn read_message<T: DeserializeOwned>(
+    reader: &mut impl Read,
+) -> Result<Option<T>, NativeWorkerError> {
+    let mut length = [0_u8; 4];
+    match reader.read_exact(&mut length) {
+        Ok(()) => {}
+        Err(error) if error.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(error) => return Err(error.into()),
+    }
+
+    // The length is checked before allocating, as the peer may be corrupted.
+    let length = usize::try_from(u32::from_le_bytes(length)).expect("u32 fits in usize.");
+    if length > MAX_MESSAGE_SIZE {
+        return Err(message_too_long(length));
+    }
+    let mut payload = vec![0_u8; length];
+    reader.read_exact(&mut payload)?;
+    Ok(Some(serde_json::from_slice(&payload)?))
+}
+
+fn message_too_long(length: usize) -> NativeWorkerError {
+    NativeWorkerError::ProtocolError(format!(
+        "Message too long: {length} bytes; the maximum is {MAX_MESSAGE_SIZE} bytes."
+    ))
+}
+
+/// A message sent from the sequencer process to a worker.
+#[derive(Debug, Deserialize, Serialize)]
+pub enum ParentMessage {
+    /// Loads the given class from its compiled artifact, to make it available for execution.
+    LoadClass {
+        class_hash: ClassHash,
+        sierra_contract_class: SierraContractClass,
+        artifact_path: PathBuf,
+    },
+    Execute(ExecuteRequest),
+    SyscallResponse {
+        result: Result<SyscallOutput, Vec<Felt>>,
+        remaining_gas: u128,
+    },
+}
+
+/// A message sent from a worker to the sequencer process.
+#[derive(Debug, Deserialize, Serialize)]
+pub enum WorkerMessage {
+    Loaded(Result<(), String>),
+    Syscall { request: SyscallRequest, remaining_gas: u128 },
+    Finished(Result<NativeExecutionOutput, String>),
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ExecuteRequest {
+    pub class_hash: ClassHash,
+    pub entry_point_type: EntryPointType,
+    pub entry_point_selector: EntryPointSelector,
+    pub calldata: Vec<Felt>,
+    pub initial_gas: u64,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub enum SyscallRequest {
+    GetBlockHash {
+        block_number: u64,
+    },
+    GetExecutionInfo,
+    GetExecutionInfoV2,
+    Deploy {
+        class_hash: Felt,
+        contract_address_salt: Felt,
+        calldata: Vec<Felt>,
+        deploy_from_zero: bool,
+    },
+    ReplaceClass {
+        class_hash: Felt,
+    },
+    LibraryCall {
+        class_hash: Felt,
+        function_selector: Felt,
+        calldata: Vec<Felt>,
+    },
+    CallContract {
+        address: Felt,
+        entry_point_selector: Felt,
+        calldata: Vec<Felt>,
+    },
+    StorageRead {
+        address_domain: u32,
+        address: Felt,
+    },
+    StorageWrite {
+        address_domain: u32,
+        address: Felt,
+        value: Felt,
+    },
+    EmitEvent {
+        keys: Vec<Felt>,
+        data: Vec<Felt>,
+    },
+    SendMessageToL1 {
+        to_address: Felt,
+        payload: Vec<Felt>,
+    },
+    Keccak {
+        input: Vec<u64>,
+    },
+    Secp256k1New {
+        x: WireU256,
+        y: WireU256,
+    },
+    Secp256k1Add {
+        p0: WirePoint,
+        p1: WirePoint,
+    },
+    Secp256k1Mul {
+        p: WirePoint,
+        m: WireU256,
+    },
+    Secp256k1GetPointFromX {
+        x: WireU256,
+        y_parity: bool,
+    },
+    Secp256k1GetXy {
+        p: WirePoint,
+    },
+    Secp256r1New {
+        x: WireU256,
+        y: WireU256,
+    },
+    Secp256r1Add {
+        p0: WirePoint,
+        p1: WirePoint,
+    },
+    Secp256r1Mul {
+        p: WirePoint,
+        m: WireU256,
+    },
+    Secp256r1GetPointFromX {
+        x: WireU256,
+        y_parity: bool,
+    },
+    Secp256r1GetXy {
+        p: WirePoint,
+    },
+    Sha256ProcessBlock {
+        prev_state: [u32; 8],
+        current_block: [u32; 16],
+    },
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub enum SyscallOutput {
+    Unit,
+    Felt(Felt),
+    Felts(Vec<Felt>),
+    Deploy { contract_address: Felt, retdata: Vec<Felt> },
+    ExecutionInfo(WireExecutionInfo),
+    ExecutionInfoV2(WireExecutionInfoV2),
+    U256(WireU256),
+    Point(Option<WirePoint>),
+    Xy(WireU256, WireU256),
+    Sha256State([u32; 8]),
+}
+
+impl SyscallOutput {
+    /// Returns an error describing an output that does not match the request it answers.
+    pub fn unexpected(self) -> NativeWorkerError {
+        NativeWorkerError::ProtocolError(format!("Unexpected syscall output: {self:?}."))
+    }
+}
+
+// Serializable counterparts of the native syscall types.
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+pub struct WireU256 {
+    pub lo: u128,
+    pub hi: u128,
+}
+
+impl From<U256> for WireU256 {
+    fn from(value: U256) -> Self {
+        Self { lo: value.lo, hi: value.hi }
+    }
+}
+
+impl From<WireU256> for U256 {
+    fn from(value: WireU256) -> Self {
+        Self { lo: value.lo, hi: value.hi }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+pub struct WirePoint {
+    pub x: WireU256,
+    pub y: WireU256,
+}
+
+impl From<Secp256k1Point> for WirePoint {
+    fn from(point: Secp256k1Point) -> Self {
+        Self { x: point.x.into(), y: point.y.into() }
+    }
+}
+
+impl From<WirePoint> for Secp256k1Point {
+    fn from(point: WirePoint) -> Self {
+        Self { x: point.x.into(), y: point.y.into() }
+    }
+}
+
+impl From<Secp256r1Point> for WirePoint {
+    fn from(point: Secp256r1Point) -> Self {
+        Self { x: point.x.into(), y: point.y.into() }
+    }
+}
+
+impl From<WirePoint> for Secp256r1Point {
+    fn from(point: WirePoint) -> Self {
+        Self { x: point.x.into(), y: point.y.into() }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct WireBlockInfo {
+    pub block_number: u64,
+    pub block_timestamp: u64,
+    pub sequencer_address: Felt,
+}
+
+impl From<BlockInfo> for WireBlockInfo {
+    fn from(info: BlockInfo) -> Self {
+        Self {
+            block_number: info.block_number,
+            block_timestamp: info.block_timestamp,
+            sequencer_address: info.sequencer_address,
+        }
+    }
+}
+
+impl From<WireBlockInfo> for BlockInfo {
+    fn from(info: WireBlockInfo) -> Self {
+        Self {
+            block_number: info.block_number,
+            block_timestamp: info.block_timestamp,
+            sequencer_address: info.sequencer_address,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct WireTxInfo {
+    pub version: Felt,
+    pub account_contract_address: Felt,
+    pub max_fee: u128,
+    pub signature: Vec<Felt>,
+    pub transaction_hash: Felt,
+    pub chain_id: Felt,
+    pub nonce: Felt,
+}
+
+impl From<TxInfo> for WireTxInfo {
+    fn from(info: TxInfo) -> Self {
+        Self {
+            version: info.version,
+            account_contract_address: info.account_contract_address,
+            max_fee: info.max_fee,
+            signature: info.signature,
+            transaction_hash: info.transaction_hash,
+            chain_id: info.chain_id,
+            nonce: info.nonce,
+        }
+    }
+}
+
+impl From<WireTxInfo> for TxInfo {
+    fn from(info: WireTxInfo) -> Self {
+        Self {
+            version: info.version,
+            account_contract_address: info.account_contract_address,
+            max_fee: info.max_fee,
+            signature: info.signature,
+            transaction_hash: info.transaction_hash,
+            chain_id: info.chain_id,
+            nonce: info.nonce,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct WireResourceBounds {
+    pub resource: Felt,
+    pub max_amount: u64,
+    pub max_price_per_unit: u128,
+}
+
+impl From<ResourceBounds> for WireResourceBounds {
+    fn from(bounds: ResourceBounds) -> Self {
+        Self {
+            resource: bounds.resource,
+            max_amount: bounds.max_amount,
+            max_price_per_unit: bounds.max_price_per_unit,
+        }
+    }
+}
+
+impl From<WireResourceBounds> for ResourceBounds {
+    fn from(bounds: WireResourceBounds) -> Self {
+        Self {
+            resource: bounds.resource,
+            max_amount: bounds.max_amount,
+            max_price_per_unit: bounds.max_price_per_unit,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct WireTxV2Info {
+    pub version: Felt,
+    pub account_contract_address: Felt,
+    pub max_fee: u128,
+    pub signature: Vec<Felt>,
+    pub transaction_hash: Felt,
+    pub chain_id: Felt,
+    pub nonce: Felt,
+    pub resource_bounds: Vec<WireResourceBounds>,
+    pub tip: u128,
+    pub paymaster_data: Vec<Felt>,
+    pub nonce_data_availability_mode: u32,
+    pub fee_data_availability_mode: u32,
+    pub account_deployment_data: Vec<Felt>,
+}
+
+impl From<TxV2Info> for WireTxV2Info {
+    fn from(info: TxV2Info) -> Self {
+        Self {
+            version: info.version,
+            account_contract_address: info.account_contract_address,
+            max_fee: info.max_fee,
+            signature: info.signature,
+            transaction_hash: info.transaction_hash,
+            chain_id: info.chain_id,
+            nonce: info.nonce,
+            resource_bounds: info.resource_bounds.into_iter().map(Into::into).collect(),
+            tip: info.tip,
+            paymaster_data: info.paymaster_data,
+            nonce_data_availability_mode: info.nonce_data_availability_mode,
+            fee_data_availability_mode: info.fee_data_availability_mode,
+            account_deployment_data: info.account_deployment_data,
+        }
+    }
+}
+
+impl From<WireTxV2Info> for TxV2Info {
+    fn from(info: WireTxV2Info) -> Self {
+        Self {
+            version: info.version,
+            account_contract_address: info.account_contract_address,
+            max_fee: info.max_fee,
+            signature: info.signature,
+            transaction_hash: info.transaction_hash,
+            chain_id: info.chain_id,
+            nonce: info.nonce,
+            resource_bounds: info.resource_bounds.into_iter().map(Into::into).collect(),
+            tip: info.tip,
+            paymaster_data: info.paymaster_data,
+            nonce_data_availability_mode: info.nonce_data_availability_mode,
+            fee_data_availability_mode: info.fee_data_availability_mode,
+            account_deployment_data: info.account_deployment_data,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct WireExecutionInfo {
+    pub block_info: WireBlockInfo,
+    pub tx_info: WireTxInfo,
+    pub caller_address: Felt,
+    pub contract_address: Felt,
+    pub entry_point_selector: Felt,
+}
+
+impl From<ExecutionInfo> for WireExecutionInfo {
+    fn from(info: ExecutionInfo) -> Self {
+        Self {
+            block_info: info.block_info.into(),
+            tx_info: info.tx_info.into(),
+            caller_address: info.caller_address,
+            contract_address: info.contract_address,
+            entry_point_selector: info.entry_point_selector,
+        }
+    }
+}
+
+impl From<WireExecutionInfo> for ExecutionInfo {
+    fn from(info: WireExecutionInfo) -> Self {
+        Self {
+            block_info: info.block_info.into(),
+            tx_info: info.tx_info.into(),
+            caller_address: info.caller_address,
+            contract_address: info.contract_address,
+            entry_point_selector: info.entry_point_selector,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct WireExecutionInfoV2 {
+    pub block_info: WireBlockInfo,
+    pub tx_info: WireTxV2Info,
+    pub caller_address: Felt,
+    pub contract_address: Felt,
+    pub entry_point_selector: Felt,
+}
+
+impl From<ExecutionInfoV2> for WireExecutionInfoV2 {
+    fn from(info: ExecutionInfoV2) -> Self {
+        Self {
+            block_info: info.block_info.into(),
+            tx_info: info.tx_info.into(),
+            caller_address: info.caller_address,
+            contract_address: info.contract_address,
+            entry_point_selector: info.entry_point_selector,
+        }
+    }
+}
+
+impl From<WireExecutionInfoV2> for ExecutionInfoV2 {
+    fn from(info: WireExecutionInfoV2) -> Self {
+        Self {
+            block_info: info.block_info.into(),
+            tx_info: info.tx_info.into(),
+            caller_address: info.caller_address,
+            contract_address: info.contract_address,
+            entry_point_selector: info.entry_point_selector,
+        }
+    }
+}
@@ -0,0 +1,84 @@
+use std::io::Cursor;
+
+use assert_matches::assert_matches;
+use starknet_api::core::{ClassHash, EntryPointSelector};
+use starknet_api::deprecated_contract_class::EntryPointType;
+use starknet_types_core::felt::Felt;
+
+use super::{
+    read_message, write_message, ExecuteRequest, ParentMessage, SyscallOutput, SyscallRequest,
+    WorkerMessage, MAX_MESSAGE_SIZE,
+};
+use crate::execution::errors::NativeWorkerError;
+
+#[test]
+fn test_message_framing() {
+    let mut channel = Vec::new();
+    write_message(
+        &mut channel,
+        &ParentMessage::Execute(ExecuteRequest {
+            class_hash: ClassHash(Felt::from(1_u8)),
+            entry_point_type: EntryPointType::External,
+            entry_point_selector: EntryPointSelector(Felt::from(2_u8)),
+            calldata: vec![Felt::from(3_u8), Felt::from(4_u8)],
+            initial_gas: 5,
+        }),
+    )
+    .unwrap();
+    write_message(
+        &mut channel,
+        &ParentMessage::SyscallResponse {
+            result: Ok(SyscallOutput::Felts(vec![Felt::MAX])),
+            remaining_gas: u128::MAX,
+        },
+    )
+    .unwrap();
+
+    let mut reader = Cursor::new(channel);
+    let request = assert_matches!(
+        read_message(&mut reader).unwrap(),
+        Some(ParentMessage::Execute(request)) => request
+    );
+    assert_eq!(request.calldata, vec![Felt::from(3_u8), Felt::from(4_u8)]);
+    assert_eq!(request.initial_gas, 5);
+    assert_matches!(
+        read_message(&mut reader).unwrap(),
+        Some(ParentMessage::SyscallResponse {
+            result: Ok(SyscallOutput::Felts(felts)),
+            remaining_gas: u128::MAX,
+        }) if felts == vec![Felt::MAX]
+    );
+    // The channel is closed.
+    assert_matches!(read_message::<ParentMessage>(&mut reader).unwrap(), None);
+}
+
+#[test]
+fn test_truncated_message() {
+    let mut channel = Vec::new();
+    write_message(
+        &mut channel,
+        &WorkerMessage::Syscall {
+            request: SyscallRequest::StorageRead { address_domain: 0, address: Felt::ONE },
+            remaining_gas: 1,
+        },
+    )
+    .unwrap();
+    channel.truncate(channel.len() - 1);
+
+    assert!(read_message::<WorkerMessage>(&mut Cursor::new(channel)).is_err());
+}
+
+#[test]
+fn test_oversized_message() {
+    // The length prefix is rejected before the payload is allocated or read.
+    let length = u32::try_from(MAX_MESSAGE_SIZE + 1).unwrap();
+    let channel = length.to_le_bytes().to_vec();
+    assert_matches!(
+        read_message::<WorkerMessage>(&mut Cursor::new(channel)),
+        Err(NativeWorkerError::ProtocolError(_))
+    );
+    assert_matches!(
+        read_message::<WorkerMessage>(&mut Cursor::new(u32::MAX.to_le_bytes().to_vec())),
+        Err(NativeWorkerError::ProtocolError(_))
+    );
+}
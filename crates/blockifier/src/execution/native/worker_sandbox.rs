@@ -0,0 +1,329 @@
+#[cfg(all(test, target_os = "linux", any(target_arch = "x86_64", target_arch = "aarch64")))]
+#[path = "worker_sandbox_test.rs"]
+pub mod test;
+
+// The sandbox of a native worker process, applied in two steps:
+// 1. Before the worker binary is executed, by the sequencer process (`restrict_process`): resource
+//    limits, a switch to an unprivileged user, and no new privileges. These are inherited by the
+//    binary.
+// 2. By the worker binary itself, before it serves any request (`restrict_syscalls`): a syscall
+//    filter, which cannot be installed earlier as it forbids executing the binary.
+// Native code runs only after both steps. Only x86-64 and AArch64 Linux are supported; elsewhere,
+// both steps fail, and so do the workers.
+
+#[cfg(all(target_os = "linux", any(target_arch = "x86_64", target_arch = "aarch64")))]
+pub use linux::{restrict_process, restrict_syscalls};
+#[cfg(not(all(target_os = "linux", any(target_arch = "x86_64", target_arch = "aarch64"))))]
+pub use unsupported::{restrict_process, restrict_syscalls};
+
+#[cfg(not(all(target_os = "linux", any(target_arch = "x86_64", target_arch = "aarch64"))))]
+mod unsupported {
+    use std::io;
+
+    use crate::blockifier::config::NativeWorkerSandboxConfig;
+
+    pub fn restrict_process(_config: &NativeWorkerSandboxConfig) -> io::Result<()> {
+        Err(unsupported())
+    }
+
+    pub fn restrict_syscalls() -> io::Result<()> {
+        Err(unsupported())
+    }
+
+    fn unsupported() -> io::Error {
+        io::Error::new(
+            io::ErrorKind::Unsupported,
+            "Native workers can only be sandboxed on x86-64 and AArch64 Linux.",
+        )
+    }
+}
+
+#[cfg(all(target_os = "linux", any(target_arch = "x86_64", target_arch = "aarch64")))]
+mod linux {
+    use std::io;
+
+    use crate::blockifier::config::NativeWorkerSandboxConfig;
+
+    /// Applies the process-wide restrictions of the sandbox to the calling process. Meant to run
+    /// in a forked child, right before the worker binary is executed; only makes
+    /// async-signal-safe calls.
+    pub fn restrict_process(config: &NativeWorkerSandboxConfig) -> io::Result<()> {
+        // Safety: plain syscalls, with valid arguments.
+        unsafe {
+            // A crash must not dump the memory of the worker, which may hold transaction data.
+            check(libc::setrlimit(libc::RLIMIT_CORE, &limit(0)))?;
+            if let Some(max_memory) = config.max_memory {
+                check(libc::setrlimit(libc::RLIMIT_AS, &limit(max_memory)))?;
+            }
+            if let Some((uid, gid)) = config.user {
+                check(libc::setgroups(0, std::ptr::null()))?;
+                check(libc::setgid(gid))?;
+                check(libc::setuid(uid))?;
+            }
+        }
+        set_no_new_privs()
+    }
+
+    /// Installs the syscall filter of the sandbox on the calling process. Denies, among others,
+    /// spawning processes, networking, signaling or inspecting other processes, gaining
+    /// privileges and modifying the file system; everything else (in particular, reading files
+    /// and mapping executable memory, as required to load compiled classes) is allowed.
+    /// Irreversible; meant to be called by the worker binary, before serving any request.
+    pub fn restrict_syscalls() -> io::Result<()> {
+        install_syscall_filter(&syscall_filter(std::process::id()))
+    }
+
+    fn check(result: libc::c_int) -> io::Result<()> {
+        match result {
+            -1 => Err(io::Error::last_os_error()),
+            _ => Ok(()),
+        }
+    }
+
+    fn limit(value: u64) -> libc::rlimit {
+        libc::rlimit { rlim_cur: value, rlim_max: value }
+    }
+
+    fn set_no_new_privs() -> io::Result<()> {
+        let (enable, unused): (libc::c_ulong, libc::c_ulong) = (1, 0);
+        // Safety: a plain syscall, with valid arguments.
+        check(unsafe { libc::prctl(libc::PR_SET_NO_NEW_PRIVS, enable, unused, unused, unused) })
+    }
+
+    // Classic BPF instructions (see linux/filter.h).
+    const BPF_LD_W_ABS: u16 = 0x20;
+    const BPF_JMP_JEQ_K: u16 = 0x15;
+    const BPF_JMP_JGE_K: u16 = 0x35;
+    const BPF_JMP_JSET_K: u16 = 0x45;
+    const BPF_RET_K: u16 = 0x06;
+
+    // See linux/seccomp.h.
+    const SECCOMP_MODE_FILTER: libc::c_ulong = 2;
+    const SECCOMP_RET_KILL_PROCESS: u32 = 0x8000_0000;
+    const SECCOMP_RET_ERRNO: u32 = 0x0005_0000;
+    const SECCOMP_RET_ALLOW: u32 = 0x7fff_0000;
+
+    // Offsets in the filtered `struct seccomp_data`.
+    const NR_OFFSET: u32 = 0;
+    const ARCH_OFFSET: u32 = 4;
+    const ARGS_OFFSET: u32 = 16;
+
+    #[cfg(target_arch = "x86_64")]
+    const AUDIT_ARCH: u32 = 0xc000_003e;
+    #[cfg(target_arch = "aarch64")]
+    const AUDIT_ARCH: u32 = 0xc000_00b7;
+
+    const WRITE_FLAGS: u32 =
+        (libc::O_WRONLY | libc::O_RDWR | libc::O_CREAT | libc::O_TRUNC | libc::O_APPEND) as u32;
+
+    enum Rule {
+        Deny { nr: libc::c_long, errno: libc::c_int },
+        // Denies the syscall unless the given argument equals the given value.
+        DenyUnlessArgEquals { nr: libc::c_long, arg: u32, value: u32 },
+        // Denies the syscall if any (or, if `deny_if_set` is false, none) of the given flags is
+        // set in the given argument.
+        DenyByArgFlags { nr: libc::c_long, arg: u32, flags: u32, deny_if_set: bool },
+    }
+
+    fn deny(nr: libc::c_long) -> Rule {
+        Rule::Deny { nr, errno: libc::EPERM }
+    }
+
+    fn rules(pid: u32) -> Vec<Rule> {
+        let mut rules = vec![
+            // Process creation; threads are allowed. Arguments of `clone3` cannot be inspected;
+            // it is reported as unsupported, so that callers fall back to `clone`.
+            deny(libc::SYS_execve),
+            deny(libc::SYS_execveat),
+            Rule::DenyByArgFlags {
+                nr: libc::SYS_clone,
+                arg: 0,
+                flags: libc::CLONE_THREAD as u32,
+                deny_if_set: false,
+            },
+            Rule::Deny { nr: libc::SYS_clone3, errno: libc::ENOSYS },
+            // Other processes.
+            Rule::DenyUnlessArgEquals { nr: libc::SYS_kill, arg: 0, value: pid },
+            Rule::DenyUnlessArgEquals { nr: libc::SYS_tgkill, arg: 0, value: pid },
+            deny(libc::SYS_tkill),
+            deny(libc::SYS_ptrace),
+            deny(libc::SYS_process_vm_readv),
+            deny(libc::SYS_process_vm_writev),
+            // Networking; the channel to the sequencer process is inherited, already connected.
+            deny(libc::SYS_socket),
+            deny(libc::SYS_socketpair),
+            deny(libc::SYS_connect),
+            deny(libc::SYS_bind),
+            deny(libc::SYS_listen),
+            deny(libc::SYS_accept),
+            deny(libc::SYS_accept4),
+            // Privileges and isolation.
+            deny(libc::SYS_setuid),
+            deny(libc::SYS_setgid),
+            deny(libc::SYS_setreuid),
+            deny(libc::SYS_setregid),
+            deny(libc::SYS_setresuid),
+            deny(libc::SYS_setresgid),
+            deny(libc::SYS_setgroups),
+            deny(libc::SYS_unshare),
+            deny(libc::SYS_setns),
+            deny(libc::SYS_mount),
+            deny(libc::SYS_umount2),
+            deny(libc::SYS_pivot_root),
+            deny(libc::SYS_chroot),
+            // The kernel.
+            deny(libc::SYS_reboot),
+            deny(libc::SYS_kexec_load),
+            deny(libc::SYS_init_module),
+            deny(libc::SYS_finit_module),
+            deny(libc::SYS_delete_module),
+            deny(libc::SYS_swapon),
+            deny(libc::SYS_swapoff),
+            deny(libc::SYS_bpf),
+            deny(libc::SYS_perf_event_open),
+            deny(libc::SYS_userfaultfd),
+            deny(libc::SYS_keyctl),
+            deny(libc::SYS_add_key),
+            deny(libc::SYS_request_key),
+            // The file system. Arguments of `openat2` cannot be inspected; it is reported as
+            // unsupported, so that callers fall back to `openat`.
+            Rule::DenyByArgFlags {
+                nr: libc::SYS_openat,
+                arg: 2,
+                flags: WRITE_FLAGS,
+                deny_if_set: true,
+            },
+            Rule::Deny { nr: libc::SYS_openat2, errno: libc::ENOSYS },
+            deny(libc::SYS_unlinkat),
+            deny(libc::SYS_renameat2),
+            deny(libc::SYS_mkdirat),
+            deny(libc::SYS_mknodat),
+            deny(libc::SYS_linkat),
+            deny(libc::SYS_symlinkat),
+            deny(libc::SYS_fchmodat),
+            deny(libc::SYS_fchownat),
+            deny(libc::SYS_truncate),
+        ];
+
+        rules.extend(legacy_rules());
+        rules
+    }
+
+    /// Rules of legacy syscalls, superseded by the above on newer architectures.
+    #[cfg(target_arch = "x86_64")]
+    fn legacy_rules() -> Vec<Rule> {
+        vec![
+            deny(libc::SYS_fork),
+            deny(libc::SYS_vfork),
+            Rule::DenyByArgFlags {
+                nr: libc::SYS_open,
+                arg: 1,
+                flags: WRITE_FLAGS,
+                deny_if_set: true,
+            },
+            deny(libc::SYS_creat),
+            deny(libc::SYS_unlink),
+            deny(libc::SYS_rename),
+            deny(libc::SYS_renameat),
+            deny(libc::SYS_mkdir),
+            deny(libc::SYS_rmdir),
+            deny(libc::SYS_mknod),
+            deny(libc::SYS_link),
+            deny(libc::SYS_symlink),
+            deny(libc::SYS_chmod),
+            deny(libc::SYS_chown),
+            deny(libc::SYS_lchown),
+        ]
+    }
+
+    #[cfg(target_arch = "aarch64")]
+    fn legacy_rules() -> Vec<Rule> {
+        Vec::new()
+    }
+
+    fn statement(code: u16, k: u32) -> libc::sock_filter {
+        libc::sock_filter { code, jt: 0, jf: 0, k }
+    }
+
+    fn jump(code: u16, k: u32, jt: u8, jf: u8) -> libc::sock_filter {
+        libc::sock_filter { code, jt, jf, k }
+    }
+
+    /// The low half of the given syscall argument (both architectures are little-endian).
+    fn load_arg(arg: u32) -> libc::sock_filter {
+        statement(BPF_LD_W_ABS, ARGS_OFFSET + 8 * arg)
+    }
+
+    fn syscall_number(nr: libc::c_long) -> u32 {
+        u32::try_from(nr).expect("Syscall numbers fit in 32 bits.")
+    }
+
+    /// Compiles the syscall filter of a process with the given ID.
+    pub(super) fn syscall_filter(pid: u32) -> Vec<libc::sock_filter> {
+        let ret_errno = |errno: libc::c_int| {
+            statement(BPF_RET_K, SECCOMP_RET_ERRNO | u32::try_from(errno).expect("Valid errno."))
+        };
+        let load_nr = statement(BPF_LD_W_ABS, NR_OFFSET);
+
+        let mut filter = vec![
+            // Syscalls of other ABIs have different numbers, and are not allowed at all.
+            statement(BPF_LD_W_ABS, ARCH_OFFSET),
+            jump(BPF_JMP_JEQ_K, AUDIT_ARCH, 1, 0),
+            statement(BPF_RET_K, SECCOMP_RET_KILL_PROCESS),
+            load_nr,
+        ];
+        #[cfg(target_arch = "x86_64")]
+        filter.extend([
+            // The x32 ABI.
+            jump(BPF_JMP_JGE_K, 0x4000_0000, 0, 1),
+            statement(BPF_RET_K, SECCOMP_RET_KILL_PROCESS),
+        ]);
+
+        // Each rule starts, and ends, with the syscall number loaded.
+        for rule in rules(pid) {
+            match rule {
+                Rule::Deny { nr, errno } => {
+                    filter.extend([jump(BPF_JMP_JEQ_K, syscall_number(nr), 0, 1), ret_errno(errno)])
+                }
+                Rule::DenyUnlessArgEquals { nr, arg, value } => filter.extend([
+                    jump(BPF_JMP_JEQ_K, syscall_number(nr), 0, 4),
+                    load_arg(arg),
+                    jump(BPF_JMP_JEQ_K, value, 1, 0),
+                    ret_errno(libc::EPERM),
+                    load_nr,
+                ]),
+                Rule::DenyByArgFlags { nr, arg, flags, deny_if_set } => filter.extend([
+                    jump(BPF_JMP_JEQ_K, syscall_number(nr), 0, 4),
+                    load_arg(arg),
+                    if deny_if_set {
+                        jump(BPF_JMP_JSET_K, flags, 0, 1)
+                    } else {
+                        jump(BPF_JMP_JSET_K, flags, 1, 0)
+                    },
+                    ret_errno(libc::EPERM),
+                    load_nr,
+                ]),
+            }
+        }
+
+        filter.push(statement(BPF_RET_K, SECCOMP_RET_ALLOW));
+        filter
+    }
+
+    /// Installs the given syscall filter on the calling thread, and on any thread it creates.
+    pub(super) fn install_syscall_filter(filter: &[libc::sock_filter]) -> io::Result<()> {
+        let program = libc::sock_fprog {
+            len: u16::try_from(filter.len()).expect("The syscall filter is short."),
+            filter: filter.as_ptr().cast_mut(),
+        };
+        set_no_new_privs()?;
+        // Safety: the program outlives the call, which copies it.
+        check(unsafe {
+            libc::prctl(
+                libc::PR_SET_SECCOMP,
+                SECCOMP_MODE_FILTER,
+                &program as *const libc::sock_fprog,
+            )
+        })
+    }
+}
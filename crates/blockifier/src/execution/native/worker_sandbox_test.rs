@@ -0,0 +1,60 @@
+use std::io;
+use std::os::unix::process::CommandExt;
+use std::process::Command;
+
+use pretty_assertions::assert_eq;
+
+use super::linux::{install_syscall_filter, syscall_filter};
+use super::restrict_process;
+use crate::blockifier::config::NativeWorkerSandboxConfig;
+
+#[test]
+fn test_process_restrictions() {
+    let sandbox_config = NativeWorkerSandboxConfig { max_memory: Some(1 << 30), user: None };
+    let mut command = Command::new("sh");
+    command.args(["-c", "ulimit -c; ulimit -v; grep NoNewPrivs /proc/self/status"]);
+    // Safety: `restrict_process` is meant to run before `exec`.
+    unsafe {
+        command.pre_exec(move || restrict_process(&sandbox_config));
+    }
+
+    let output = command.output().unwrap();
+    assert!(output.status.success());
+    // The address space limit is reported in KiB.
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "0\n1048576\nNoNewPrivs:\t1\n");
+}
+
+#[test]
+fn test_syscall_filter() {
+    // Built before forking, as the child may not allocate.
+    let filter = syscall_filter(std::process::id());
+    let denied = |result: libc::c_int| {
+        result == -1 && io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+    };
+
+    // Safety: the child only makes async-signal-safe calls, and exits with the result of the
+    // checks, one bit per failed check.
+    let pid = unsafe { libc::fork() };
+    if pid == 0 {
+        unsafe {
+            if install_syscall_filter(&filter).is_err() {
+                libc::_exit(1);
+            }
+            let checks = [
+                denied(libc::socket(libc::AF_INET, libc::SOCK_STREAM, 0)),
+                denied(libc::open(c"/dev/null".as_ptr(), libc::O_WRONLY)),
+                libc::open(c"/dev/null".as_ptr(), libc::O_RDONLY) >= 0,
+                denied(libc::kill(libc::getppid(), 0)),
+                denied(libc::fork()),
+            ];
+            let failures =
+                checks.iter().enumerate().filter(|(_, passed)| !**passed).map(|(i, _)| 2 << i);
+            libc::_exit(failures.sum());
+        }
+    }
+
+    let mut status = 0;
+    assert_eq!(unsafe { libc::waitpid(pid, &mut status, 0) }, pid);
+    assert!(libc::WIFEXITED(status));
+    assert_eq!(libc::WEXITSTATUS(status), 0);
+}
@@ -0,0 +1,112 @@
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use assert_matches::assert_matches;
+use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
+use starknet_api::{calldata, felt};
+
+use super::NativeWorker;
+use crate::abi::abi_utils::selector_from_name;
+use crate::blockifier::config::{
+    NativeArtifactCacheConfig, NativeWorkerConfig, NativeWorkerSandboxConfig,
+};
+use crate::context::{BlockContext, ChainInfo, TransactionContext};
+use crate::execution::call_info::CallInfo;
+use crate::execution::entry_point::{
+    CallEntryPoint, EntryPointExecutionContext, EntryPointExecutionResult,
+};
+use crate::execution::errors::{EntryPointExecutionError, NativeWorkerError};
+use crate::test_utils::contracts::FeatureContract;
+use crate::test_utils::initial_test_state::test_state;
+use crate::test_utils::{trivial_external_entry_point_new, BALANCE};
+use crate::transaction::objects::{DeprecatedTransactionInfo, TransactionInfo};
+
+fn worker_config(executable_path: &str, artifact_cache_dir: &Path) -> NativeWorkerConfig {
+    NativeWorkerConfig {
+        executable_path: PathBuf::from(executable_path),
+        opt_level: 0,
+        artifact_cache_config: NativeArtifactCacheConfig {
+            path: artifact_cache_dir.to_path_buf(),
+            max_size: u64::MAX,
+        },
+        response_timeout: Duration::from_secs(60),
+        sandbox_config: NativeWorkerSandboxConfig::default(),
+    }
+}
+
+fn execute_with_worker(native_worker: &NativeWorker) -> EntryPointExecutionResult<CallInfo> {
+    let test_contract = FeatureContract::SierraTestContract;
+    let mut state = test_state(&ChainInfo::create_for_testing(), BALANCE, &[(test_contract, 1)]);
+    let tx_context = TransactionContext {
+        block_context: BlockContext {
+            native_worker: Some(native_worker.clone()),
+            ..BlockContext::create_for_testing()
+        },
+        tx_info: TransactionInfo::Deprecated(DeprecatedTransactionInfo::default()),
+    };
+    let mut context = EntryPointExecutionContext::new_invoke(Arc::new(tx_context), false).unwrap();
+    let entry_point_call = CallEntryPoint {
+        entry_point_selector: selector_from_name("test_storage_read_write"),
+        calldata: calldata![felt!(1234_u16), felt!(18_u8)],
+        ..trivial_external_entry_point_new(test_contract)
+    };
+
+    entry_point_call.execute(&mut state, &mut ExecutionResources::default(), &mut context)
+}
+
+#[test]
+fn test_worker_crash_is_recoverable() {
+    let artifact_cache_dir = tempfile::tempdir().unwrap();
+    // A worker that exits right away, as if it crashed.
+    let native_worker = NativeWorker::new(worker_config("false", artifact_cache_dir.path()));
+
+    let error = execute_with_worker(&native_worker).unwrap_err();
+    assert_matches!(
+        error,
+        EntryPointExecutionError::NativeWorkerError(NativeWorkerError::Crashed { .. })
+    );
+    // The crashed process is not reused.
+    assert!(native_worker.idle_processes.lock().unwrap().is_empty());
+}
+
+#[test]
+fn test_worker_spawn_failure() {
+    let artifact_cache_dir = tempfile::tempdir().unwrap();
+    let native_worker = NativeWorker::new(worker_config(
+        "/nonexistent/native_executor_worker",
+        artifact_cache_dir.path(),
+    ));
+
+    let error = execute_with_worker(&native_worker).unwrap_err();
+    assert_matches!(
+        error,
+        EntryPointExecutionError::NativeWorkerError(NativeWorkerError::SpawnError(_))
+    );
+}
+
+#[test]
+fn test_unresponsive_worker_is_killed() {
+    let artifact_cache_dir = tempfile::tempdir().unwrap();
+    // A worker that never answers.
+    let executable_path = artifact_cache_dir.path().join("hung_worker");
+    fs::write(&executable_path, "#!/bin/sh\nexec sleep 60\n").unwrap();
+    fs::set_permissions(&executable_path, fs::Permissions::from_mode(0o755)).unwrap();
+    let timeout = Duration::from_millis(100);
+    let native_worker = NativeWorker::new(NativeWorkerConfig {
+        response_timeout: timeout,
+        ..worker_config(executable_path.to_str().unwrap(), artifact_cache_dir.path())
+    });
+
+    let error = execute_with_worker(&native_worker).unwrap_err();
+    assert_matches!(
+        error,
+        EntryPointExecutionError::NativeWorkerError(NativeWorkerError::Unresponsive {
+            timeout: error_timeout
+        }) if error_timeout == timeout
+    );
+    // The killed process is not reused; a new one is spawned for the next execution.
+    assert!(native_worker.idle_processes.lock().unwrap().is_empty());
+}
@@ -433,6 +433,10 @@ fn extract_entry_point_execution_error_into_stack_trace(
         EntryPointExecutionError::CairoRunError(cairo_run_error) => {
             extract_cairo_run_error_into_stack_trace(error_stack, depth, cairo_run_error)
         }
+        #[cfg(feature = "native")]
+        EntryPointExecutionError::NativeUnrecoverableError(syscall_error) => {
+            extract_syscall_execution_error_into_stack_trace(error_stack, depth, syscall_error)
+        }
         _ => error_stack.push(format!("{}\n", entry_point_error).into()),
     }
 }
@@ -135,6 +135,67 @@ Execution failed. Failure reason: 0x6661696c ('fail').
     assert_eq!(tx_execution_error.to_string(), expected_trace);
 }
 
+#[cfg(feature = "native")]
+#[rstest]
+fn test_native_stack_trace(block_context: BlockContext) {
+    let chain_info = ChainInfo::create_for_testing();
+    let account = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo1);
+    let test_contract = FeatureContract::SierraTestContract;
+    let mut state = test_state(&chain_info, BALANCE, &[(account, 1), (test_contract, 2)]);
+    let account_address = account.get_instance_address(0);
+    let test_contract_address = test_contract.get_instance_address(0);
+    let test_contract_address_2 = test_contract.get_instance_address(1);
+    let account_address_felt = *account_address.0.key();
+    let test_contract_address_felt = *test_contract_address.0.key();
+    let test_contract_address_2_felt = *test_contract_address_2.0.key();
+    let test_contract_hash = test_contract.get_class_hash().0;
+    let account_contract_hash = account.get_class_hash().0;
+
+    // Nest calls: __execute__ -> test_call_contract -> fail, where the two innermost calls are
+    // executed natively.
+    let call_contract_function_name = "test_call_contract";
+    let inner_entry_point_selector_felt = selector_from_name("fail").0;
+    let calldata = create_calldata(
+        test_contract_address, // contract_address
+        call_contract_function_name,
+        &[
+            test_contract_address_2_felt,    // Contract address.
+            inner_entry_point_selector_felt, // Function selector.
+            felt!(0_u8),                     // Innermost calldata length.
+        ],
+    );
+
+    let tx_execution_error = run_invoke_tx(
+        &mut state,
+        &block_context,
+        invoke_tx_args! {
+            sender_address: account_address,
+            calldata,
+            version: TransactionVersion::ZERO,
+        },
+    )
+    .unwrap_err();
+
+    let execute_selector_felt = selector_from_name(EXECUTE_ENTRY_POINT_NAME).0;
+    let external_entry_point_selector_felt = selector_from_name(call_contract_function_name).0;
+
+    // Native frames carry no Cairo traceback, but are otherwise identical to the VM ones.
+    let expected_trace = format!(
+        "Transaction execution has failed:
+0: Error in the called contract (contract address: {account_address_felt:#064x}, class hash: \
+         {account_contract_hash:#064x}, selector: {execute_selector_felt:#064x}):
+Error at pc=0:767:
+1: Error in the called contract (contract address: {test_contract_address_felt:#064x}, class hash: \
+         {test_contract_hash:#064x}, selector: {external_entry_point_selector_felt:#064x}):
+2: Error in the called contract (contract address: {test_contract_address_2_felt:#064x}, class \
+         hash: {test_contract_hash:#064x}, selector: {inner_entry_point_selector_felt:#064x}):
+Execution failed. Failure reason: 0x6661696c ('fail').
+"
+    );
+
+    assert_eq!(tx_execution_error.to_string(), expected_trace);
+}
+
 #[rstest]
 #[case(CairoVersion::Cairo0, "invoke_call_chain", "Couldn't compute operand op0. Unknown value for memory cell 1:37", (1081_u16, 1127_u16))]
 #[case(CairoVersion::Cairo0, "fail", "An ASSERT_EQ instruction failed: 1 != 0.", (1184_u16, 1135_u16))]
@@ -23,6 +23,7 @@ use starknet_types_core::felt::{Felt, FromStrError};
 use thiserror::Error;
 
 use crate::abi::sierra_types::SierraTypeError;
+use crate::blockifier::observer::ExecutionEvent;
 use crate::execution::call_info::{CallInfo, OrderedEvent, OrderedL2ToL1Message};
 use crate::execution::common_hints::{ExecutionMode, HintExecutionResult};
 use crate::execution::entry_point::{CallEntryPoint, CallType, EntryPointExecutionContext};
@@ -31,6 +32,9 @@ use crate::execution::execution_utils::{
     felt_from_ptr, felt_range_from_ptr, max_fee_for_execution_info, write_maybe_relocatable,
     ReadOnlySegment, ReadOnlySegments,
 };
+use crate::execution::syscalls::interceptor::{
+    InterceptedSyscall, SyscallInterception, SyscallInterceptor,
+};
 use crate::execution::syscalls::secp::{
     secp256k1_add, secp256k1_get_point_from_x, secp256k1_get_xy, secp256k1_mul, secp256k1_new,
     secp256r1_add, secp256r1_get_point_from_x, secp256r1_get_xy, secp256r1_mul, secp256r1_new,
@@ -81,6 +85,8 @@ pub enum SyscallExecutionError {
     },
     #[error("Invalid syscall input: {input:?}; {info}")]
     InvalidSyscallInput { input: Felt, info: String },
+    #[error("Invalid substitute syscall response: {response:?}.")]
+    InvalidSubstituteResponse { response: Vec<Felt> },
     #[error("Invalid syscall selector: {0:?}.")]
     InvalidSyscallSelector(Felt),
     #[error("Unauthorized syscall {syscall_name} in execution mode {execution_mode}.")]
@@ -308,110 +314,133 @@ impl<'a> SyscallHintProcessor<'a> {
         match selector {
             SyscallSelector::CallContract => self.execute_syscall(
                 vm,
+                selector,
                 call_contract,
                 self.context.gas_costs().call_contract_gas_cost,
             ),
             SyscallSelector::Deploy => {
-                self.execute_syscall(vm, deploy, self.context.gas_costs().deploy_gas_cost)
-            }
-            SyscallSelector::EmitEvent => {
-                self.execute_syscall(vm, emit_event, self.context.gas_costs().emit_event_gas_cost)
+                self.execute_syscall(vm, selector, deploy, self.context.gas_costs().deploy_gas_cost)
             }
+            SyscallSelector::EmitEvent => self.execute_syscall(
+                vm,
+                selector,
+                emit_event,
+                self.context.gas_costs().emit_event_gas_cost,
+            ),
             SyscallSelector::GetBlockHash => self.execute_syscall(
                 vm,
+                selector,
                 get_block_hash,
                 self.context.gas_costs().get_block_hash_gas_cost,
             ),
             SyscallSelector::GetExecutionInfo => self.execute_syscall(
                 vm,
+                selector,
                 get_execution_info,
                 self.context.gas_costs().get_execution_info_gas_cost,
             ),
             SyscallSelector::Keccak => {
-                self.execute_syscall(vm, keccak, self.context.gas_costs().keccak_gas_cost)
+                self.execute_syscall(vm, selector, keccak, self.context.gas_costs().keccak_gas_cost)
             }
             SyscallSelector::Sha256ProcessBlock => self.execute_syscall(
                 vm,
+                selector,
                 sha_256_process_block,
                 self.context.gas_costs().sha256_process_block_gas_cost,
             ),
             SyscallSelector::LibraryCall => self.execute_syscall(
                 vm,
+                selector,
                 library_call,
                 self.context.gas_costs().library_call_gas_cost,
             ),
             SyscallSelector::LibraryCallL1Handler => self.execute_syscall(
                 vm,
+                selector,
                 library_call_l1_handler,
                 self.context.gas_costs().library_call_gas_cost,
             ),
             SyscallSelector::ReplaceClass => self.execute_syscall(
                 vm,
+                selector,
                 replace_class,
                 self.context.gas_costs().replace_class_gas_cost,
             ),
             SyscallSelector::Secp256k1Add => self.execute_syscall(
                 vm,
+                selector,
                 secp256k1_add,
                 self.context.gas_costs().secp256k1_add_gas_cost,
             ),
             SyscallSelector::Secp256k1GetPointFromX => self.execute_syscall(
                 vm,
+                selector,
                 secp256k1_get_point_from_x,
                 self.context.gas_costs().secp256k1_get_point_from_x_gas_cost,
             ),
             SyscallSelector::Secp256k1GetXy => self.execute_syscall(
                 vm,
+                selector,
                 secp256k1_get_xy,
                 self.context.gas_costs().secp256k1_get_xy_gas_cost,
             ),
             SyscallSelector::Secp256k1Mul => self.execute_syscall(
                 vm,
+                selector,
                 secp256k1_mul,
                 self.context.gas_costs().secp256k1_mul_gas_cost,
             ),
             SyscallSelector::Secp256k1New => self.execute_syscall(
                 vm,
+                selector,
                 secp256k1_new,
                 self.context.gas_costs().secp256k1_new_gas_cost,
             ),
             SyscallSelector::Secp256r1Add => self.execute_syscall(
                 vm,
+                selector,
                 secp256r1_add,
                 self.context.gas_costs().secp256r1_add_gas_cost,
             ),
             SyscallSelector::Secp256r1GetPointFromX => self.execute_syscall(
                 vm,
+                selector,
                 secp256r1_get_point_from_x,
                 self.context.gas_costs().secp256r1_get_point_from_x_gas_cost,
             ),
             SyscallSelector::Secp256r1GetXy => self.execute_syscall(
                 vm,
+                selector,
                 secp256r1_get_xy,
                 self.context.gas_costs().secp256r1_get_xy_gas_cost,
             ),
             SyscallSelector::Secp256r1Mul => self.execute_syscall(
                 vm,
+                selector,
                 secp256r1_mul,
                 self.context.gas_costs().secp256r1_mul_gas_cost,
             ),
             SyscallSelector::Secp256r1New => self.execute_syscall(
                 vm,
+                selector,
                 secp256r1_new,
                 self.context.gas_costs().secp256r1_new_gas_cost,
             ),
             SyscallSelector::SendMessageToL1 => self.execute_syscall(
                 vm,
+                selector,
                 send_message_to_l1,
                 self.context.gas_costs().send_message_to_l1_gas_cost,
             ),
             SyscallSelector::StorageRead => self.execute_syscall(
                 vm,
+                selector,
                 storage_read,
                 self.context.gas_costs().storage_read_gas_cost,
             ),
             SyscallSelector::StorageWrite => self.execute_syscall(
                 vm,
+                selector,
                 storage_write,
                 self.context.gas_costs().storage_write_gas_cost,
             ),
@@ -466,6 +495,7 @@ impl<'a> SyscallHintProcessor<'a> {
     fn execute_syscall<Request, Response, ExecuteCallback>(
         &mut self,
         vm: &mut VirtualMachine,
+        selector: SyscallSelector,
         execute_callback: ExecuteCallback,
         syscall_gas_cost: u64,
     ) -> HintExecutionResult
@@ -498,7 +528,17 @@ impl<'a> SyscallHintProcessor<'a> {
 
         // Execute.
         let mut remaining_gas = gas_counter - required_gas;
-        let original_response = execute_callback(request, vm, self, &mut remaining_gas);
+        let original_response = match self.context.syscall_interceptor() {
+            Some(interceptor) => self.execute_intercepted_syscall(
+                vm,
+                interceptor.as_ref(),
+                selector,
+                request,
+                execute_callback,
+                &mut remaining_gas,
+            ),
+            None => execute_callback(request, vm, self, &mut remaining_gas),
+        };
         let response = match original_response {
             Ok(response) => {
                 SyscallResponseWrapper::Success { gas_counter: remaining_gas, response }
@@ -514,6 +554,65 @@ impl<'a> SyscallHintProcessor<'a> {
         Ok(())
     }
 
+    // Executes the syscall through the given interceptor, which may veto or substitute it, and may
+    // override its outcome.
+    fn execute_intercepted_syscall<Request, Response, ExecuteCallback>(
+        &mut self,
+        vm: &mut VirtualMachine,
+        interceptor: &dyn SyscallInterceptor,
+        selector: SyscallSelector,
+        request: Request,
+        execute_callback: ExecuteCallback,
+        remaining_gas: &mut u64,
+    ) -> SyscallResult<Response>
+    where
+        Request: SyscallRequest,
+        Response: SyscallResponse,
+        ExecuteCallback: FnOnce(
+            Request,
+            &mut VirtualMachine,
+            &mut SyscallHintProcessor<'_>,
+            &mut u64, // Remaining gas.
+        ) -> SyscallResult<Response>,
+    {
+        let syscall = InterceptedSyscall {
+            selector,
+            storage_address: self.storage_address(),
+            caller_address: self.caller_address(),
+            execution_mode: self.execution_mode(),
+            request: request.intercepted(),
+        };
+
+        // The executed response, kept as is unless overridden.
+        let mut executed_response = None;
+        let mut outcome = match interceptor.pre_syscall(&syscall) {
+            SyscallInterception::Proceed => {
+                match execute_callback(request, vm, self, remaining_gas) {
+                    Ok(response) => {
+                        let flat_response = response.to_felts(vm)?;
+                        executed_response = Some((response, flat_response.clone()));
+                        Ok(flat_response)
+                    }
+                    Err(SyscallExecutionError::SyscallError { error_data }) => Err(error_data),
+                    Err(error) => return Err(error),
+                }
+            }
+            SyscallInterception::Veto { error_data } => Err(error_data),
+            SyscallInterception::Respond(response) => Ok(response),
+        };
+        interceptor.post_syscall(&syscall, &mut outcome);
+
+        match (outcome, executed_response) {
+            (Ok(flat_response), Some((response, executed_flat_response)))
+                if flat_response == executed_flat_response =>
+            {
+                Ok(response)
+            }
+            (Ok(flat_response), _) => Response::from_felts(flat_response, vm, self),
+            (Err(error_data), _) => Err(SyscallExecutionError::SyscallError { error_data }),
+        }
+    }
+
     fn read_next_syscall_selector(&mut self, vm: &mut VirtualMachine) -> SyscallResult<Felt> {
         let selector = felt_from_ptr(vm, &mut self.syscall_ptr)?;
 
@@ -659,8 +758,10 @@ impl<'a> SyscallHintProcessor<'a> {
         key: StorageKey,
     ) -> SyscallResult<StorageReadResponse> {
         self.accessed_keys.insert(key);
-        let value = self.state.get_storage_at(self.storage_address(), key)?;
+        let contract_address = self.storage_address();
+        let value = self.state.get_storage_at(contract_address, key)?;
         self.read_values.push(value);
+        self.context.record(|| ExecutionEvent::StorageRead { contract_address, key, value });
 
         Ok(StorageReadResponse { value })
     }
@@ -671,7 +772,9 @@ impl<'a> SyscallHintProcessor<'a> {
         value: Felt,
     ) -> SyscallResult<StorageWriteResponse> {
         self.accessed_keys.insert(key);
-        self.state.set_storage_at(self.storage_address(), key, value)?;
+        let contract_address = self.storage_address();
+        self.state.set_storage_at(contract_address, key, value)?;
+        self.context.record(|| ExecutionEvent::StorageWrite { contract_address, key, value });
 
         Ok(StorageWriteResponse {})
     }
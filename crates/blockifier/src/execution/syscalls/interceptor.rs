@@ -0,0 +1,107 @@
+use std::fmt::Debug;
+
+use starknet_api::block::BlockNumber;
+use starknet_api::core::{ClassHash, ContractAddress, EntryPointSelector};
+use starknet_api::state::StorageKey;
+use starknet_api::transaction::{Calldata, ContractAddressSalt, EventContent};
+use starknet_types_core::felt::Felt;
+
+use crate::execution::call_info::MessageToL1;
+use crate::execution::common_hints::ExecutionMode;
+use crate::execution::syscalls::SyscallSelector;
+
+/// The outcome of a syscall, as seen by an interceptor: its response, flattened to felts, or its
+/// error data.
+/// The responses are flattened as follows:
+/// * `call_contract`, `library_call`, `library_call_l1_handler`, and the Cairo 0 `delegate_call`
+///   and `delegate_l1_handler`: the retdata.
+/// * `deploy`: the contract address, followed by the constructor retdata (of Cairo 1 contracts
+///   only; the `deploy` response of Cairo 0 contracts is only the contract address).
+/// * `get_block_hash`: the block hash.
+/// * `storage_read`: the value.
+/// * `emit_event`, `replace_class`, `send_message_to_l1`, `storage_write`: empty.
+///
+/// The responses of other syscalls are opaque, i.e., given as empty, and cannot be substituted.
+pub type SyscallOutcome = Result<Vec<Felt>, Vec<Felt>>;
+
+/// A syscall, about to be (or just) executed.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InterceptedSyscall {
+    pub selector: SyscallSelector,
+    /// The contract on whose behalf the syscall is invoked.
+    pub storage_address: ContractAddress,
+    pub caller_address: ContractAddress,
+    pub execution_mode: ExecutionMode,
+    pub request: InterceptedRequest,
+}
+
+/// The request of an intercepted syscall.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum InterceptedRequest {
+    /// Of `call_contract`, and of the Cairo 0 `delegate_call` and `delegate_l1_handler`.
+    CallContract {
+        contract_address: ContractAddress,
+        entry_point_selector: EntryPointSelector,
+        calldata: Calldata,
+    },
+    Deploy {
+        class_hash: ClassHash,
+        contract_address_salt: ContractAddressSalt,
+        constructor_calldata: Calldata,
+        deploy_from_zero: bool,
+    },
+    EmitEvent {
+        content: EventContent,
+    },
+    GetBlockHash {
+        block_number: BlockNumber,
+    },
+    /// Of both `library_call` and `library_call_l1_handler`.
+    LibraryCall {
+        class_hash: ClassHash,
+        entry_point_selector: EntryPointSelector,
+        calldata: Calldata,
+    },
+    ReplaceClass {
+        class_hash: ClassHash,
+    },
+    SendMessageToL1 {
+        message: MessageToL1,
+    },
+    StorageRead {
+        key: StorageKey,
+    },
+    StorageWrite {
+        key: StorageKey,
+        value: Felt,
+    },
+    /// The requests of other syscalls are not exposed.
+    Opaque,
+}
+
+/// The decision of an interceptor on a syscall about to be executed.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SyscallInterception {
+    /// Executes the syscall.
+    Proceed,
+    /// Fails the syscall with the given error data, without executing it.
+    Veto { error_data: Vec<Felt> },
+    /// Returns the given response (see [SyscallOutcome]), without executing the syscall.
+    Respond(Vec<Felt>),
+}
+
+/// Observes, and possibly overrides, the syscalls of both Cairo 0 and Cairo 1 contracts;
+/// registered on the execution context (see `BlockContext::set_syscall_interceptor`), and invoked
+/// alike by the VM and the native backends.
+/// The syscall gas cost is charged before the interceptor is invoked; a syscall that is vetoed or
+/// responded to consumes no further gas. Cairo 0 syscalls charge no gas, and cannot fail: a
+/// failed outcome (e.g., a veto) aborts the execution of the Cairo 0 call.
+pub trait SyscallInterceptor: Debug + Send + Sync {
+    fn pre_syscall(&self, _syscall: &InterceptedSyscall) -> SyscallInterception {
+        SyscallInterception::Proceed
+    }
+
+    /// Invoked with the outcome of the syscall (executed or not), which may be overridden.
+    /// Not invoked if the syscall fails with an error that aborts the execution.
+    fn post_syscall(&self, _syscall: &InterceptedSyscall, _outcome: &mut SyscallOutcome) {}
+}
@@ -18,18 +18,22 @@ use self::hint_processor::{
     SyscallExecutionError, SyscallHintProcessor, BLOCK_NUMBER_OUT_OF_RANGE_ERROR,
 };
 use crate::abi::constants;
+use crate::blockifier::observer::ExecutionEvent;
 use crate::execution::call_info::{MessageToL1, OrderedEvent, OrderedL2ToL1Message};
 use crate::execution::contract_class::ContractClass;
 use crate::execution::deprecated_syscalls::DeprecatedSyscallSelector;
 use crate::execution::entry_point::{CallEntryPoint, CallType, ConstructorContext};
 use crate::execution::execution_utils::{
-    execute_deployment, felt_from_ptr, write_felt, write_maybe_relocatable, ReadOnlySegment,
+    execute_deployment, felt_from_ptr, felt_range_from_ptr, write_felt, write_maybe_relocatable,
+    ReadOnlySegment,
 };
 use crate::execution::syscalls::hint_processor::{INVALID_INPUT_LENGTH_ERROR, OUT_OF_GAS_ERROR};
+use crate::execution::syscalls::interceptor::InterceptedRequest;
 use crate::transaction::transaction_utils::update_remaining_gas;
 use crate::versioned_constants::{EventLimits, VersionedConstants};
 
 pub mod hint_processor;
+pub mod interceptor;
 pub mod secp;
 
 #[cfg(test)]
@@ -42,10 +46,32 @@ pub type SyscallSelector = DeprecatedSyscallSelector;
 
 pub trait SyscallRequest: Sized {
     fn read(_vm: &VirtualMachine, _ptr: &mut Relocatable) -> SyscallResult<Self>;
+
+    /// The request, as exposed to a syscall interceptor.
+    fn intercepted(&self) -> InterceptedRequest {
+        InterceptedRequest::Opaque
+    }
 }
 
 pub trait SyscallResponse {
     fn write(self, _vm: &mut VirtualMachine, _ptr: &mut Relocatable) -> WriteResponseResult;
+
+    /// The response, as exposed to a syscall interceptor; see [interceptor::SyscallOutcome].
+    fn to_felts(&self, _vm: &VirtualMachine) -> SyscallResult<Vec<Felt>> {
+        Ok(vec![])
+    }
+
+    /// Builds a response substituted by a syscall interceptor; see [interceptor::SyscallOutcome].
+    fn from_felts(
+        response: Vec<Felt>,
+        _vm: &mut VirtualMachine,
+        _syscall_handler: &mut SyscallHintProcessor<'_>,
+    ) -> SyscallResult<Self>
+    where
+        Self: Sized,
+    {
+        Err(SyscallExecutionError::InvalidSubstituteResponse { response })
+    }
 }
 
 // Syscall header structs.
@@ -117,6 +143,17 @@ impl SyscallResponse for EmptyResponse {
     fn write(self, _vm: &mut VirtualMachine, _ptr: &mut Relocatable) -> WriteResponseResult {
         Ok(())
     }
+
+    fn from_felts(
+        response: Vec<Felt>,
+        _vm: &mut VirtualMachine,
+        _syscall_handler: &mut SyscallHintProcessor<'_>,
+    ) -> SyscallResult<Self> {
+        match response.is_empty() {
+            true => Ok(EmptyResponse),
+            false => Err(SyscallExecutionError::InvalidSubstituteResponse { response }),
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -128,6 +165,20 @@ impl SyscallResponse for SingleSegmentResponse {
     fn write(self, vm: &mut VirtualMachine, ptr: &mut Relocatable) -> WriteResponseResult {
         write_segment(vm, ptr, self.segment)
     }
+
+    fn to_felts(&self, vm: &VirtualMachine) -> SyscallResult<Vec<Felt>> {
+        Ok(felt_range_from_ptr(vm, self.segment.start_ptr, self.segment.length)?)
+    }
+
+    fn from_felts(
+        response: Vec<Felt>,
+        vm: &mut VirtualMachine,
+        syscall_handler: &mut SyscallHintProcessor<'_>,
+    ) -> SyscallResult<Self> {
+        Ok(SingleSegmentResponse {
+            segment: create_retdata_segment(vm, syscall_handler, &response)?,
+        })
+    }
 }
 
 // CallContract syscall.
@@ -146,6 +197,14 @@ impl SyscallRequest for CallContractRequest {
 
         Ok(CallContractRequest { contract_address, function_selector, calldata })
     }
+
+    fn intercepted(&self) -> InterceptedRequest {
+        InterceptedRequest::CallContract {
+            contract_address: self.contract_address,
+            entry_point_selector: self.function_selector,
+            calldata: self.calldata.clone(),
+        }
+    }
 }
 
 pub type CallContractResponse = SingleSegmentResponse;
@@ -210,6 +269,15 @@ impl SyscallRequest for DeployRequest {
             )?,
         })
     }
+
+    fn intercepted(&self) -> InterceptedRequest {
+        InterceptedRequest::Deploy {
+            class_hash: self.class_hash,
+            contract_address_salt: self.contract_address_salt,
+            constructor_calldata: self.constructor_calldata.clone(),
+            deploy_from_zero: self.deploy_from_zero,
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -223,6 +291,31 @@ impl SyscallResponse for DeployResponse {
         write_felt(vm, ptr, *self.contract_address.0.key())?;
         write_segment(vm, ptr, self.constructor_retdata)
     }
+
+    fn to_felts(&self, vm: &VirtualMachine) -> SyscallResult<Vec<Felt>> {
+        let mut response = vec![*self.contract_address.0.key()];
+        response.extend(felt_range_from_ptr(
+            vm,
+            self.constructor_retdata.start_ptr,
+            self.constructor_retdata.length,
+        )?);
+        Ok(response)
+    }
+
+    fn from_felts(
+        response: Vec<Felt>,
+        vm: &mut VirtualMachine,
+        syscall_handler: &mut SyscallHintProcessor<'_>,
+    ) -> SyscallResult<Self> {
+        let Some((contract_address, constructor_retdata)) = response.split_first() else {
+            return Err(SyscallExecutionError::InvalidSubstituteResponse { response });
+        };
+
+        Ok(DeployResponse {
+            contract_address: ContractAddress::try_from(*contract_address)?,
+            constructor_retdata: create_retdata_segment(vm, syscall_handler, constructor_retdata)?,
+        })
+    }
 }
 
 pub fn deploy(
@@ -283,6 +376,10 @@ impl SyscallRequest for EmitEventRequest {
 
         Ok(EmitEventRequest { content: EventContent { keys, data } })
     }
+
+    fn intercepted(&self) -> InterceptedRequest {
+        InterceptedRequest::EmitEvent { content: self.content.clone() }
+    }
 }
 
 type EmitEventResponse = EmptyResponse;
@@ -318,12 +415,15 @@ pub fn emit_event(
     syscall_handler: &mut SyscallHintProcessor<'_>,
     _remaining_gas: &mut u64,
 ) -> SyscallResult<EmitEventResponse> {
+    let from_address = syscall_handler.storage_address();
     let execution_context = &mut syscall_handler.context;
     exceeds_event_size_limit(
         execution_context.versioned_constants(),
         execution_context.n_emitted_events + 1,
         &request.content,
     )?;
+    execution_context
+        .record(|| ExecutionEvent::Event { from_address, content: request.content.clone() });
     let ordered_event =
         OrderedEvent { order: execution_context.n_emitted_events, event: request.content };
     syscall_handler.events.push(ordered_event);
@@ -351,6 +451,10 @@ impl SyscallRequest for GetBlockHashRequest {
 
         Ok(GetBlockHashRequest { block_number })
     }
+
+    fn intercepted(&self) -> InterceptedRequest {
+        InterceptedRequest::GetBlockHash { block_number: self.block_number }
+    }
 }
 
 #[derive(Debug, Eq, PartialEq)]
@@ -363,6 +467,21 @@ impl SyscallResponse for GetBlockHashResponse {
         write_felt(vm, ptr, self.block_hash.0)?;
         Ok(())
     }
+
+    fn to_felts(&self, _vm: &VirtualMachine) -> SyscallResult<Vec<Felt>> {
+        Ok(vec![self.block_hash.0])
+    }
+
+    fn from_felts(
+        response: Vec<Felt>,
+        _vm: &mut VirtualMachine,
+        _syscall_handler: &mut SyscallHintProcessor<'_>,
+    ) -> SyscallResult<Self> {
+        match response[..] {
+            [block_hash] => Ok(GetBlockHashResponse { block_hash: BlockHash(block_hash) }),
+            _ => Err(SyscallExecutionError::InvalidSubstituteResponse { response }),
+        }
+    }
 }
 
 /// Returns the block hash of a given block_number.
@@ -444,6 +563,14 @@ impl SyscallRequest for LibraryCallRequest {
 
         Ok(LibraryCallRequest { class_hash, function_selector, calldata })
     }
+
+    fn intercepted(&self) -> InterceptedRequest {
+        InterceptedRequest::LibraryCall {
+            class_hash: self.class_hash,
+            entry_point_selector: self.function_selector,
+            calldata: self.calldata.clone(),
+        }
+    }
 }
 
 type LibraryCallResponse = CallContractResponse;
@@ -503,6 +630,10 @@ impl SyscallRequest for ReplaceClassRequest {
 
         Ok(ReplaceClassRequest { class_hash })
     }
+
+    fn intercepted(&self) -> InterceptedRequest {
+        InterceptedRequest::ReplaceClass { class_hash: self.class_hash }
+    }
 }
 
 pub type ReplaceClassResponse = EmptyResponse;
@@ -521,7 +652,14 @@ pub fn replace_class(
         ContractClass::V0(_) => {
             Err(SyscallExecutionError::ForbiddenClassReplacement { class_hash })
         }
-        ContractClass::V1(_) | ContractClass::V1Native(_) => {
+        ContractClass::V1(_) => {
+            syscall_handler
+                .state
+                .set_class_hash_at(syscall_handler.storage_address(), class_hash)?;
+            Ok(ReplaceClassResponse {})
+        }
+        #[cfg(feature = "native")]
+        ContractClass::V1Native(_) => {
             syscall_handler
                 .state
                 .set_class_hash_at(syscall_handler.storage_address(), class_hash)?;
@@ -545,6 +683,10 @@ impl SyscallRequest for SendMessageToL1Request {
 
         Ok(SendMessageToL1Request { message: MessageToL1 { to_address, payload } })
     }
+
+    fn intercepted(&self) -> InterceptedRequest {
+        InterceptedRequest::SendMessageToL1 { message: self.message.clone() }
+    }
 }
 
 type SendMessageToL1Response = EmptyResponse;
@@ -555,6 +697,11 @@ pub fn send_message_to_l1(
     syscall_handler: &mut SyscallHintProcessor<'_>,
     _remaining_gas: &mut u64,
 ) -> SyscallResult<SendMessageToL1Response> {
+    let from_address = syscall_handler.storage_address();
+    syscall_handler.context.record(|| ExecutionEvent::L2ToL1Message {
+        from_address,
+        message: request.message.clone(),
+    });
     let execution_context = &mut syscall_handler.context;
     let ordered_message_to_l1 = OrderedL2ToL1Message {
         order: execution_context.n_sent_messages_to_l1,
@@ -584,6 +731,10 @@ impl SyscallRequest for StorageReadRequest {
         let address = StorageKey::try_from(felt_from_ptr(vm, ptr)?)?;
         Ok(StorageReadRequest { address_domain, address })
     }
+
+    fn intercepted(&self) -> InterceptedRequest {
+        InterceptedRequest::StorageRead { key: self.address }
+    }
 }
 
 #[derive(Debug, Eq, PartialEq)]
@@ -596,6 +747,21 @@ impl SyscallResponse for StorageReadResponse {
         write_felt(vm, ptr, self.value)?;
         Ok(())
     }
+
+    fn to_felts(&self, _vm: &VirtualMachine) -> SyscallResult<Vec<Felt>> {
+        Ok(vec![self.value])
+    }
+
+    fn from_felts(
+        response: Vec<Felt>,
+        _vm: &mut VirtualMachine,
+        _syscall_handler: &mut SyscallHintProcessor<'_>,
+    ) -> SyscallResult<Self> {
+        match response[..] {
+            [value] => Ok(StorageReadResponse { value }),
+            _ => Err(SyscallExecutionError::InvalidSubstituteResponse { response }),
+        }
+    }
 }
 
 pub fn storage_read(
@@ -626,6 +792,10 @@ impl SyscallRequest for StorageWriteRequest {
         let value = felt_from_ptr(vm, ptr)?;
         Ok(StorageWriteRequest { address_domain, address, value })
     }
+
+    fn intercepted(&self) -> InterceptedRequest {
+        InterceptedRequest::StorageWrite { key: self.address, value: self.value }
+    }
 }
 
 pub type StorageWriteResponse = EmptyResponse;
@@ -7,40 +7,42 @@ use crate::abi::abi_utils::selector_from_name;
 use crate::context::ChainInfo;
 use crate::execution::call_info::{CallExecution, Retdata};
 use crate::execution::entry_point::CallEntryPoint;
-use crate::execution::native::utils::NATIVE_GAS_PLACEHOLDER;
 use crate::retdata;
 use crate::test_utils::contracts::FeatureContract;
 use crate::test_utils::initial_test_state::test_state;
-use crate::test_utils::{create_calldata, trivial_external_entry_point_new, CairoVersion, BALANCE};
+use crate::test_utils::{
+    create_calldata, expected_gas_or_casm_equivalent, trivial_external_entry_point_new,
+    CairoVersion, BALANCE,
+};
 
-#[test_case(
+#[cfg_attr(feature = "native", test_case(
     FeatureContract::SierraTestContract,
     FeatureContract::SierraTestContract,
-    NATIVE_GAS_PLACEHOLDER;
+    None;
     "Call Contract between two contracts using Native"
-)]
-#[test_case(
+))]
+#[cfg_attr(feature = "native", test_case(
     FeatureContract::SierraTestContract,
     FeatureContract::TestContract(CairoVersion::Cairo1),
-    NATIVE_GAS_PLACEHOLDER;
+    None;
     "Call Contract with caller using Native and callee using VM"
-)]
-#[test_case(
+))]
+#[cfg_attr(feature = "native", test_case(
     FeatureContract::TestContract(CairoVersion::Cairo1),
     FeatureContract::SierraTestContract,
-    78530 + NATIVE_GAS_PLACEHOLDER;
+    None;
     "Call Contract with caller using VM and callee using Native")
-]
+)]
 #[test_case(
     FeatureContract::TestContract(CairoVersion::Cairo1),
     FeatureContract::TestContract(CairoVersion::Cairo1),
-    REQUIRED_GAS_CALL_CONTRACT_TEST;
+    Some(REQUIRED_GAS_CALL_CONTRACT_TEST);
     "Call Contract between two contracts using VM"
 )]
 fn test_call_contract(
     outer_contract: FeatureContract,
     inner_contract: FeatureContract,
-    expected_gas: u64,
+    expected_gas: Option<u64>,
 ) {
     let chain_info = &ChainInfo::create_for_testing();
     let mut state = test_state(chain_info, BALANCE, &[(outer_contract, 1), (inner_contract, 1)]);
@@ -59,6 +61,11 @@ fn test_call_contract(
         calldata,
         ..trivial_external_entry_point_new(outer_contract)
     };
+    let expected_gas = expected_gas_or_casm_equivalent(
+        expected_gas,
+        || test_state(chain_info, BALANCE, &[(outer_contract, 1), (inner_contract, 1)]),
+        &entry_point_call,
+    );
 
     assert_eq!(
         entry_point_call.execute_directly(&mut state).unwrap().execution,
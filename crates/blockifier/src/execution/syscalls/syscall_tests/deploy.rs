@@ -8,16 +8,18 @@ use crate::abi::abi_utils::selector_from_name;
 use crate::context::ChainInfo;
 use crate::execution::call_info::{CallExecution, Retdata};
 use crate::execution::entry_point::CallEntryPoint;
-use crate::execution::native::utils::NATIVE_GAS_PLACEHOLDER;
 use crate::retdata;
 use crate::state::state_api::StateReader;
 use crate::test_utils::contracts::FeatureContract;
 use crate::test_utils::initial_test_state::test_state;
-use crate::test_utils::{calldata_for_deploy_test, trivial_external_entry_point_new, CairoVersion};
+use crate::test_utils::{
+    calldata_for_deploy_test, execute_with_casm_equivalent_classes,
+    trivial_external_entry_point_new, CairoVersion,
+};
 
 // TODO add all combinations of Native and Vm deployer and deployee
 #[test_case(FeatureContract::TestContract(CairoVersion::Cairo1);"VM")]
-#[test_case(FeatureContract::SierraTestContract;"Native")]
+#[cfg_attr(feature = "native", test_case(FeatureContract::SierraTestContract;"Native"))]
 fn no_constructor(deployer_contract: FeatureContract) {
     // TODO(Yoni): share the init code of the tests in this file.
 
@@ -55,7 +57,7 @@ fn no_constructor(deployer_contract: FeatureContract) {
 }
 
 #[test_case(FeatureContract::TestContract(CairoVersion::Cairo1);"VM")]
-#[test_case(FeatureContract::SierraTestContract;"Native")]
+#[cfg_attr(feature = "native", test_case(FeatureContract::SierraTestContract;"Native"))]
 fn no_constructor_nonempty_calldata(deployer_contract: FeatureContract) {
     let empty_contract = FeatureContract::Empty(CairoVersion::Cairo1);
     let class_hash = empty_contract.get_class_hash();
@@ -81,9 +83,9 @@ fn no_constructor_nonempty_calldata(deployer_contract: FeatureContract) {
     ));
 }
 
-#[test_case(FeatureContract::TestContract(CairoVersion::Cairo1), 10140;"VM")]
-#[test_case(FeatureContract::SierraTestContract, NATIVE_GAS_PLACEHOLDER;"Native")]
-fn with_constructor(deployer_contract: FeatureContract, expected_gas: u64) {
+#[test_case(FeatureContract::TestContract(CairoVersion::Cairo1), Some(10140);"VM")]
+#[cfg_attr(feature = "native", test_case(FeatureContract::SierraTestContract, None;"Native"))]
+fn with_constructor(deployer_contract: FeatureContract, expected_gas: Option<u64>) {
     let empty_contract = FeatureContract::Empty(CairoVersion::Cairo1);
     let mut state = test_state(
         &ChainInfo::create_for_testing(),
@@ -104,6 +106,18 @@ fn with_constructor(deployer_contract: FeatureContract, expected_gas: u64) {
         calldata,
         ..trivial_external_entry_point_new(deployer_contract)
     };
+    // Native execution is charged as the Casm class compiled from the same Sierra.
+    let expected_gas = expected_gas.unwrap_or_else(|| {
+        let casm_call_info = execute_with_casm_equivalent_classes(
+            test_state(
+                &ChainInfo::create_for_testing(),
+                0,
+                &[(deployer_contract, 1), (empty_contract, 0)],
+            ),
+            entry_point_call.clone(),
+        );
+        casm_call_info.inner_calls[0].execution.gas_consumed
+    });
 
     // No errors expected.
     let contract_address = calculate_contract_address(
@@ -128,7 +142,7 @@ fn with_constructor(deployer_contract: FeatureContract, expected_gas: u64) {
 }
 
 #[test_case(FeatureContract::TestContract(CairoVersion::Cairo1);"VM")]
-#[test_case(FeatureContract::SierraTestContract;"Native")]
+#[cfg_attr(feature = "native", test_case(FeatureContract::SierraTestContract;"Native"))]
 fn to_unavailable_address(deployer_contract: FeatureContract) {
     let empty_contract = FeatureContract::Empty(CairoVersion::Cairo1);
     let mut state = test_state(
@@ -10,11 +10,12 @@ use crate::context::ChainInfo;
 use crate::execution::call_info::{CallExecution, CallInfo, OrderedEvent};
 use crate::execution::entry_point::CallEntryPoint;
 use crate::execution::errors::EntryPointExecutionError;
-use crate::execution::native::utils::NATIVE_GAS_PLACEHOLDER;
 use crate::execution::syscalls::hint_processor::EmitEventError;
 use crate::test_utils::contracts::FeatureContract;
 use crate::test_utils::initial_test_state::test_state;
-use crate::test_utils::{trivial_external_entry_point_new, CairoVersion, BALANCE};
+use crate::test_utils::{
+    expected_gas_or_casm_equivalent, trivial_external_entry_point_new, CairoVersion, BALANCE,
+};
 use crate::versioned_constants::VersionedConstants;
 
 const KEYS: [Felt; 2] = [Felt::from_hex_unchecked("0x2019"), Felt::from_hex_unchecked("0x2020")];
@@ -25,12 +26,17 @@ const DATA: [Felt; 3] = [
 ];
 const N_EMITTED_EVENTS: [Felt; 1] = [Felt::from_hex_unchecked("0x1")];
 
-#[test_case(FeatureContract::SierraTestContract, NATIVE_GAS_PLACEHOLDER; "Native")]
-#[test_case(FeatureContract::TestContract(CairoVersion::Cairo1), 49860; "VM")]
-fn positive_flow(test_contract: FeatureContract, expected_gas: u64) {
+#[cfg_attr(feature = "native", test_case(FeatureContract::SierraTestContract, None; "Native"))]
+#[test_case(FeatureContract::TestContract(CairoVersion::Cairo1), Some(49860); "VM")]
+fn positive_flow(test_contract: FeatureContract, expected_gas: Option<u64>) {
     // TODO(Ori, 1/2/2024): Write an indicative expect message explaining why the conversion
     // works.
     let call_info = emit_events(test_contract, &N_EMITTED_EVENTS, &KEYS, &DATA).unwrap();
+    let expected_gas = expected_gas_or_casm_equivalent(
+        expected_gas,
+        || test_state(&ChainInfo::create_for_testing(), BALANCE, &[(test_contract, 1)]),
+        &emit_events_entry_point(test_contract, &N_EMITTED_EVENTS, &KEYS, &DATA),
+    );
     let event = EventContent {
         keys: KEYS.into_iter().map(EventKey).collect(),
         data: EventData(DATA.to_vec()),
@@ -46,7 +52,7 @@ fn positive_flow(test_contract: FeatureContract, expected_gas: u64) {
     );
 }
 
-#[test_case(FeatureContract::SierraTestContract; "Native")]
+#[cfg_attr(feature = "native", test_case(FeatureContract::SierraTestContract; "Native"))]
 #[test_case(FeatureContract::TestContract(CairoVersion::Cairo1); "VM")]
 fn data_length_exceeds_limit(test_contract: FeatureContract) {
     let versioned_constants = VersionedConstants::create_for_testing();
@@ -61,7 +67,7 @@ fn data_length_exceeds_limit(test_contract: FeatureContract) {
     assert!(error.to_string().contains(&expected_error.to_string()));
 }
 
-#[test_case(FeatureContract::SierraTestContract; "Native")]
+#[cfg_attr(feature = "native", test_case(FeatureContract::SierraTestContract; "Native"))]
 #[test_case(FeatureContract::TestContract(CairoVersion::Cairo1); "VM")]
 fn keys_length_exceeds_limit(test_contract: FeatureContract) {
     let versioned_constants = VersionedConstants::create_for_testing();
@@ -77,7 +83,7 @@ fn keys_length_exceeds_limit(test_contract: FeatureContract) {
     assert!(error.to_string().contains(&expected_error.to_string()));
 }
 
-#[test_case(FeatureContract::SierraTestContract; "Native")]
+#[cfg_attr(feature = "native", test_case(FeatureContract::SierraTestContract; "Native"))]
 #[test_case(FeatureContract::TestContract(CairoVersion::Cairo1); "VM")]
 fn event_number_exceeds_limit(test_contract: FeatureContract) {
     let versioned_constants = VersionedConstants::create_for_testing();
@@ -102,6 +108,16 @@ fn emit_events(
 ) -> Result<CallInfo, EntryPointExecutionError> {
     let chain_info = &ChainInfo::create_for_testing();
     let mut state = test_state(chain_info, BALANCE, &[(test_contract, 1)]);
+    emit_events_entry_point(test_contract, n_emitted_events, keys, data)
+        .execute_directly(&mut state)
+}
+
+fn emit_events_entry_point(
+    test_contract: FeatureContract,
+    n_emitted_events: &[Felt],
+    keys: &[Felt],
+    data: &[Felt],
+) -> CallEntryPoint {
     let calldata = Calldata(
         concat(vec![
             n_emitted_events.to_owned(),
@@ -113,11 +129,9 @@ fn emit_events(
         .into(),
     );
 
-    let entry_point_call = CallEntryPoint {
+    CallEntryPoint {
         entry_point_selector: selector_from_name("test_emit_events"),
         calldata,
         ..trivial_external_entry_point_new(test_contract)
-    };
-
-    entry_point_call.execute_directly(&mut state)
+    }
 }
@@ -11,14 +11,14 @@ use crate::abi::constants;
 use crate::context::ChainInfo;
 use crate::execution::call_info::{CallExecution, Retdata};
 use crate::execution::entry_point::CallEntryPoint;
-use crate::execution::native::utils::NATIVE_GAS_PLACEHOLDER;
 use crate::state::cached_state::CachedState;
 use crate::state::state_api::State;
 use crate::test_utils::contracts::FeatureContract;
 use crate::test_utils::dict_state_reader::DictStateReader;
 use crate::test_utils::initial_test_state::test_state;
 use crate::test_utils::{
-    trivial_external_entry_point_new, CairoVersion, BALANCE, CURRENT_BLOCK_NUMBER,
+    expected_gas_or_casm_equivalent, trivial_external_entry_point_new, CairoVersion, BALANCE,
+    CURRENT_BLOCK_NUMBER,
 };
 use crate::{check_entry_point_execution_error_for_custom_hint, retdata};
 
@@ -38,9 +38,9 @@ fn initialize_state(test_contract: FeatureContract) -> (CachedState<DictStateRea
     (state, block_number, block_hash)
 }
 
-#[test_case(FeatureContract::SierraTestContract, NATIVE_GAS_PLACEHOLDER; "Native")]
-#[test_case(FeatureContract::TestContract(CairoVersion::Cairo1), 9680; "VM")]
-fn positive_flow(test_contract: FeatureContract, expected_gas: u64) {
+#[cfg_attr(feature = "native", test_case(FeatureContract::SierraTestContract, None; "Native"))]
+#[test_case(FeatureContract::TestContract(CairoVersion::Cairo1), Some(9680); "VM")]
+fn positive_flow(test_contract: FeatureContract, expected_gas: Option<u64>) {
     let (mut state, block_number, block_hash) = initialize_state(test_contract);
 
     let calldata = calldata![block_number];
@@ -49,6 +49,11 @@ fn positive_flow(test_contract: FeatureContract, expected_gas: u64) {
         calldata,
         ..trivial_external_entry_point_new(test_contract)
     };
+    let expected_gas = expected_gas_or_casm_equivalent(
+        expected_gas,
+        || initialize_state(test_contract).0,
+        &entry_point_call,
+    );
 
     assert_eq!(
         entry_point_call.clone().execute_directly(&mut state).unwrap().execution,
@@ -59,7 +64,7 @@ fn positive_flow(test_contract: FeatureContract, expected_gas: u64) {
     );
 }
 
-#[test_case(FeatureContract::SierraTestContract; "Native")]
+#[cfg_attr(feature = "native", test_case(FeatureContract::SierraTestContract; "Native"))]
 #[test_case(FeatureContract::TestContract(CairoVersion::Cairo1); "VM")]
 fn negative_flow_execution_mode_validate(test_contract: FeatureContract) {
     let (mut state, block_number, _) = initialize_state(test_contract);
@@ -73,9 +78,7 @@ fn negative_flow_execution_mode_validate(test_contract: FeatureContract) {
 
     let error = entry_point_call.execute_directly_in_validate_mode(&mut state).unwrap_err();
 
-    // While executing with native it fails with `Unexpected structure for error:
-    // NativeExecutionError { info: "Unauthorized syscall get_block_hash in execution mode
-    // Validate." }`
+    // Native execution reports the syscall error directly, without the VM hint wrapping.
     if matches!(test_contract, FeatureContract::TestContract(_)) {
         check_entry_point_execution_error_for_custom_hint!(
             &error,
@@ -90,7 +93,7 @@ fn negative_flow_execution_mode_validate(test_contract: FeatureContract) {
     );
 }
 
-#[test_case(FeatureContract::SierraTestContract; "Native")]
+#[cfg_attr(feature = "native", test_case(FeatureContract::SierraTestContract; "Native"))]
 #[test_case(FeatureContract::TestContract(CairoVersion::Cairo1); "VM")]
 fn negative_flow_block_number_out_of_range(test_contract: FeatureContract) {
     let (mut state, _, _) = initialize_state(test_contract);
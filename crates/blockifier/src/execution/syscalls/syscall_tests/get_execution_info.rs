@@ -30,54 +30,54 @@ use crate::transaction::objects::{
     CommonAccountFields, CurrentTransactionInfo, DeprecatedTransactionInfo, TransactionInfo,
 };
 
-#[test_case(
+#[cfg_attr(feature = "native", test_case(
     FeatureContract::SierraExecutionInfoV1Contract,
     ExecutionMode::Validate,
     TransactionVersion::ONE,
     false;
-    "Native [V1]: Validate execution mode: block info fields should be zeroed. Transaction V1.")]
-#[test_case(
+    "Native [V1]: Validate execution mode: block info fields should be zeroed. Transaction V1."))]
+#[cfg_attr(feature = "native", test_case(
     FeatureContract::SierraExecutionInfoV1Contract,
     ExecutionMode::Execute,
     TransactionVersion::ONE,
     false;
-    "Native [V1]: Execute execution mode: block info should be as usual. Transaction V1.")]
-#[test_case(
+    "Native [V1]: Execute execution mode: block info should be as usual. Transaction V1."))]
+#[cfg_attr(feature = "native", test_case(
     FeatureContract::SierraExecutionInfoV1Contract,
     ExecutionMode::Validate,
     TransactionVersion::THREE,
     false;
-    "Native [V1]: Validate execution mode: block info fields should be zeroed. Transaction V3.")]
-#[test_case(
+    "Native [V1]: Validate execution mode: block info fields should be zeroed. Transaction V3."))]
+#[cfg_attr(feature = "native", test_case(
     FeatureContract::SierraExecutionInfoV1Contract,
     ExecutionMode::Execute,
     TransactionVersion::THREE,
     false;
-    "Native [V1]: Execute execution mode: block info should be as usual. Transaction V3.")]
-#[test_case(
+    "Native [V1]: Execute execution mode: block info should be as usual. Transaction V3."))]
+#[cfg_attr(feature = "native", test_case(
     FeatureContract::SierraTestContract,
     ExecutionMode::Validate,
     TransactionVersion::ONE,
     false;
-    "Native: Validate execution mode: block info fields should be zeroed. Transaction V1.")]
-#[test_case(
+    "Native: Validate execution mode: block info fields should be zeroed. Transaction V1."))]
+#[cfg_attr(feature = "native", test_case(
     FeatureContract::SierraTestContract,
     ExecutionMode::Execute,
     TransactionVersion::ONE,
     false;
-    "Native: Execute execution mode: block info should be as usual. Transaction V1.")]
-#[test_case(
+    "Native: Execute execution mode: block info should be as usual. Transaction V1."))]
+#[cfg_attr(feature = "native", test_case(
     FeatureContract::SierraTestContract,
     ExecutionMode::Validate,
     TransactionVersion::THREE,
     false;
-    "Native: Validate execution mode: block info fields should be zeroed. Transaction V3.")]
-#[test_case(
+    "Native: Validate execution mode: block info fields should be zeroed. Transaction V3."))]
+#[cfg_attr(feature = "native", test_case(
     FeatureContract::SierraTestContract,
     ExecutionMode::Execute,
     TransactionVersion::THREE,
     false;
-    "Native: Execute execution mode: block info should be as usual. Transaction V3.")]
+    "Native: Execute execution mode: block info should be as usual. Transaction V3."))]
 // TODO Native
 #[test_case(
     FeatureContract::TestContract(CairoVersion::Cairo1),
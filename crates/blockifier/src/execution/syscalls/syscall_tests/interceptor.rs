@@ -0,0 +1,153 @@
+use std::sync::{Arc, Mutex};
+
+use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
+use pretty_assertions::assert_eq;
+use starknet_api::state::StorageKey;
+use starknet_api::transaction::Calldata;
+use starknet_api::{calldata, felt};
+use starknet_types_core::felt::Felt;
+use test_case::test_case;
+
+use crate::abi::abi_utils::selector_from_name;
+use crate::context::{BlockContext, ChainInfo, TransactionContext};
+use crate::execution::call_info::{CallInfo, Retdata};
+use crate::execution::entry_point::{
+    CallEntryPoint, EntryPointExecutionContext, EntryPointExecutionResult,
+};
+use crate::execution::syscalls::interceptor::{
+    InterceptedRequest, InterceptedSyscall, SyscallInterception, SyscallInterceptor, SyscallOutcome,
+};
+use crate::execution::syscalls::SyscallSelector;
+use crate::retdata;
+use crate::state::state_api::{State, StateReader};
+use crate::test_utils::contracts::FeatureContract;
+use crate::test_utils::initial_test_state::test_state;
+use crate::test_utils::{create_calldata, trivial_external_entry_point_new, CairoVersion, BALANCE};
+use crate::transaction::objects::{DeprecatedTransactionInfo, TransactionInfo};
+
+const MOCKED_RETDATA: u8 = 77;
+const VETO_ERROR: u8 = 13;
+
+// Mocks calls to other contracts, logs storage writes, vetoes writes of `VETO_ERROR`, and
+// increments the storage read values.
+#[derive(Debug, Default)]
+struct TestInterceptor {
+    storage_writes: Mutex<Vec<(StorageKey, Felt)>>,
+}
+
+impl SyscallInterceptor for TestInterceptor {
+    fn pre_syscall(&self, syscall: &InterceptedSyscall) -> SyscallInterception {
+        match syscall.request {
+            InterceptedRequest::CallContract { contract_address, .. }
+                if contract_address != syscall.storage_address =>
+            {
+                SyscallInterception::Respond(vec![felt!(MOCKED_RETDATA)])
+            }
+            InterceptedRequest::StorageWrite { value, .. } if value == felt!(VETO_ERROR) => {
+                SyscallInterception::Veto { error_data: vec![felt!(VETO_ERROR)] }
+            }
+            _ => SyscallInterception::Proceed,
+        }
+    }
+
+    fn post_syscall(&self, syscall: &InterceptedSyscall, outcome: &mut SyscallOutcome) {
+        match (&syscall.request, outcome) {
+            (InterceptedRequest::StorageWrite { key, value }, Ok(_)) => {
+                self.storage_writes.lock().unwrap().push((*key, *value));
+            }
+            (InterceptedRequest::StorageRead { .. }, Ok(response)) => {
+                assert_eq!(syscall.selector, SyscallSelector::StorageRead);
+                response[0] += Felt::ONE;
+            }
+            _ => {}
+        }
+    }
+}
+
+fn execute_intercepted(
+    entry_point_call: CallEntryPoint,
+    state: &mut dyn State,
+    interceptor: Arc<TestInterceptor>,
+) -> EntryPointExecutionResult<CallInfo> {
+    let mut block_context = BlockContext::create_for_testing();
+    block_context.set_syscall_interceptor(interceptor);
+    let tx_context = TransactionContext {
+        block_context,
+        tx_info: TransactionInfo::Deprecated(DeprecatedTransactionInfo::default()),
+    };
+    let mut context = EntryPointExecutionContext::new_invoke(Arc::new(tx_context), true).unwrap();
+    entry_point_call.execute(state, &mut ExecutionResources::default(), &mut context)
+}
+
+#[cfg_attr(feature = "native", test_case(FeatureContract::SierraTestContract; "Native"))]
+#[test_case(FeatureContract::TestContract(CairoVersion::Cairo1); "VM")]
+#[test_case(FeatureContract::TestContract(CairoVersion::Cairo0); "VM Cairo0")]
+fn test_intercepted_storage(test_contract: FeatureContract) {
+    let chain_info = &ChainInfo::create_for_testing();
+    let mut state = test_state(chain_info, BALANCE, &[(test_contract, 1)]);
+    let interceptor = Arc::new(TestInterceptor::default());
+    let key = felt!(1234_u16);
+    let storage_key = StorageKey::try_from(key).unwrap();
+
+    // The write is logged, and the read value is overridden.
+    let value = felt!(18_u8);
+    let entry_point_call = CallEntryPoint {
+        calldata: calldata![key, value],
+        entry_point_selector: selector_from_name("test_storage_read_write"),
+        ..trivial_external_entry_point_new(test_contract)
+    };
+    let storage_address = entry_point_call.storage_address;
+    let call_info = execute_intercepted(entry_point_call, &mut state, interceptor.clone()).unwrap();
+    assert_eq!(call_info.execution.retdata, retdata![value + Felt::ONE]);
+    assert_eq!(*interceptor.storage_writes.lock().unwrap(), vec![(storage_key, value)]);
+    assert_eq!(state.get_storage_at(storage_address, storage_key).unwrap(), value);
+
+    // A vetoed write fails the call (aborts it, in Cairo 0), and is not applied.
+    let entry_point_call = CallEntryPoint {
+        calldata: calldata![key, felt!(VETO_ERROR)],
+        entry_point_selector: selector_from_name("test_storage_read_write"),
+        ..trivial_external_entry_point_new(test_contract)
+    };
+    let result = execute_intercepted(entry_point_call, &mut state, interceptor.clone());
+    match test_contract.cairo_version() {
+        CairoVersion::Cairo0 => assert!(result.is_err()),
+        CairoVersion::Cairo1 => {
+            let call_info = result.unwrap();
+            assert!(call_info.execution.failed);
+            assert_eq!(call_info.execution.retdata, retdata![felt!(VETO_ERROR)]);
+        }
+    }
+    assert_eq!(interceptor.storage_writes.lock().unwrap().len(), 1);
+    assert_eq!(state.get_storage_at(storage_address, storage_key).unwrap(), value);
+}
+
+#[cfg_attr(feature = "native", test_case(FeatureContract::SierraTestContract; "Native"))]
+#[test_case(FeatureContract::TestContract(CairoVersion::Cairo1); "VM")]
+#[test_case(FeatureContract::TestContract(CairoVersion::Cairo0); "VM Cairo0")]
+fn test_intercepted_call_contract(test_contract: FeatureContract) {
+    let chain_info = &ChainInfo::create_for_testing();
+    let mut state = test_state(chain_info, BALANCE, &[(test_contract, 2)]);
+    let interceptor = Arc::new(TestInterceptor::default());
+
+    // The inner call is mocked; it is not executed.
+    let inner_contract_address = test_contract.get_instance_address(1);
+    let entry_point_call = CallEntryPoint {
+        calldata: create_calldata(
+            inner_contract_address,
+            "test_storage_read_write",
+            &[felt!(405_u16), felt!(48_u8)],
+        ),
+        entry_point_selector: selector_from_name("test_call_contract"),
+        ..trivial_external_entry_point_new(test_contract)
+    };
+    let call_info = execute_intercepted(entry_point_call, &mut state, interceptor.clone()).unwrap();
+    assert_eq!(call_info.execution.retdata, retdata![felt!(MOCKED_RETDATA)]);
+    assert!(call_info.inner_calls.is_empty());
+    assert!(interceptor.storage_writes.lock().unwrap().is_empty());
+    assert_eq!(
+        state
+            .get_storage_at(inner_contract_address, StorageKey::try_from(felt!(405_u16)).unwrap())
+            .unwrap(),
+        felt!(0_u8)
+    );
+}
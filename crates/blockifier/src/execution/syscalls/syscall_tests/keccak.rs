@@ -5,15 +5,16 @@ use crate::abi::abi_utils::selector_from_name;
 use crate::context::ChainInfo;
 use crate::execution::call_info::{CallExecution, Retdata};
 use crate::execution::entry_point::CallEntryPoint;
-use crate::execution::native::utils::NATIVE_GAS_PLACEHOLDER;
 use crate::retdata;
 use crate::test_utils::contracts::FeatureContract;
 use crate::test_utils::initial_test_state::test_state;
-use crate::test_utils::{trivial_external_entry_point_new, CairoVersion, BALANCE};
+use crate::test_utils::{
+    expected_gas_or_casm_equivalent, trivial_external_entry_point_new, CairoVersion, BALANCE,
+};
 
-#[test_case(FeatureContract::SierraTestContract, NATIVE_GAS_PLACEHOLDER; "Native")]
-#[test_case(FeatureContract::TestContract(CairoVersion::Cairo1), 255110; "VM")]
-fn test_keccak(test_contract: FeatureContract, expected_gas: u64) {
+#[cfg_attr(feature = "native", test_case(FeatureContract::SierraTestContract, None; "Native"))]
+#[test_case(FeatureContract::TestContract(CairoVersion::Cairo1), Some(255110); "VM")]
+fn test_keccak(test_contract: FeatureContract, expected_gas: Option<u64>) {
     let chain_info = &ChainInfo::create_for_testing();
     let mut state = test_state(chain_info, BALANCE, &[(test_contract, 1)]);
 
@@ -23,6 +24,11 @@ fn test_keccak(test_contract: FeatureContract, expected_gas: u64) {
         calldata,
         ..trivial_external_entry_point_new(test_contract)
     };
+    let expected_gas = expected_gas_or_casm_equivalent(
+        expected_gas,
+        || test_state(chain_info, BALANCE, &[(test_contract, 1)]),
+        &entry_point_call,
+    );
 
     assert_eq!(
         entry_point_call.execute_directly(&mut state).unwrap().execution,
@@ -13,7 +13,6 @@ use crate::abi::abi_utils::selector_from_name;
 use crate::context::ChainInfo;
 use crate::execution::call_info::{CallExecution, CallInfo, Retdata};
 use crate::execution::entry_point::{CallEntryPoint, CallType};
-use crate::execution::native::utils::NATIVE_GAS_PLACEHOLDER;
 use crate::execution::syscalls::syscall_tests::constants::{
     REQUIRED_GAS_LIBRARY_CALL_TEST, REQUIRED_GAS_STORAGE_READ_WRITE_TEST,
 };
@@ -22,7 +21,8 @@ use crate::retdata;
 use crate::test_utils::contracts::FeatureContract;
 use crate::test_utils::initial_test_state::test_state;
 use crate::test_utils::{
-    get_syscall_resources, trivial_external_entry_point_new, CairoVersion, BALANCE,
+    execute_with_casm_equivalent_classes, get_syscall_resources, trivial_external_entry_point_new,
+    CairoVersion, BALANCE,
 };
 
 #[test_case(FeatureContract::TestContract(CairoVersion::Cairo1), REQUIRED_GAS_LIBRARY_CALL_TEST; "VM")]
@@ -56,7 +56,7 @@ fn test_library_call(test_contract: FeatureContract, expected_gas: u64) {
     );
 }
 
-#[test_case(FeatureContract::SierraTestContract; "Native")]
+#[cfg_attr(feature = "native", test_case(FeatureContract::SierraTestContract; "Native"))]
 #[test_case(FeatureContract::TestContract(CairoVersion::Cairo1); "VM")]
 fn test_library_call_assert_fails(test_contract: FeatureContract) {
     let chain_info = &ChainInfo::create_for_testing();
@@ -80,9 +80,9 @@ fn test_library_call_assert_fails(test_contract: FeatureContract) {
     assert!(err.to_string().contains("x != y"));
 }
 
-#[test_case(FeatureContract::SierraTestContract, NATIVE_GAS_PLACEHOLDER; "Native")]
-#[test_case(FeatureContract::TestContract(CairoVersion::Cairo1), 276880; "VM")]
-fn test_nested_library_call(test_contract: FeatureContract, expected_gas: u64) {
+#[cfg_attr(feature = "native", test_case(FeatureContract::SierraTestContract, None; "Native"))]
+#[test_case(FeatureContract::TestContract(CairoVersion::Cairo1), Some(276880); "VM")]
+fn test_nested_library_call(test_contract: FeatureContract, expected_gas: Option<u64>) {
     let chain_info = &ChainInfo::create_for_testing();
     let mut state = test_state(chain_info, BALANCE, &[(test_contract, 1)]);
 
@@ -98,11 +98,7 @@ fn test_nested_library_call(test_contract: FeatureContract, expected_gas: u64) {
         felt!(value)                  // Calldata: value.
     ];
 
-    // Todo(rodrigo): Execution resources from the VM & Native are mesaured differently
-    // helper function to change the expected resource values from both of executions
-    let if_native = |a, b| {
-        if matches!(test_contract, FeatureContract::SierraTestContract) { a } else { b }
-    };
+    let is_native = matches!(test_contract, FeatureContract::SierraTestContract);
 
     // Create expected call info tree.
     let main_entry_point = CallEntryPoint {
@@ -112,13 +108,28 @@ fn test_nested_library_call(test_contract: FeatureContract, expected_gas: u64) {
         initial_gas: 9999906600,
         ..trivial_external_entry_point_new(test_contract)
     };
+
+    // Native execution is charged as the Casm class compiled from the same Sierra; take the
+    // expected gas values of native runs from the equivalent Casm call info tree.
+    let casm_call_info = is_native.then(|| {
+        execute_with_casm_equivalent_classes(
+            test_state(chain_info, BALANCE, &[(test_contract, 1)]),
+            main_entry_point.clone(),
+        )
+    });
+    let if_native = |get_native_value: fn(&CallInfo) -> u64, vm_value| {
+        casm_call_info.as_ref().map_or(vm_value, get_native_value)
+    };
     let nested_storage_entry_point = CallEntryPoint {
         entry_point_selector: inner_entry_point_selector,
         calldata: calldata![felt!(key + 1), felt!(value + 1)],
         class_hash: Some(test_class_hash),
         code_address: None,
         call_type: CallType::Delegate,
-        initial_gas: if_native(9999725020, 9999745020),
+        initial_gas: if_native(
+            |casm| casm.inner_calls[0].inner_calls[0].call.initial_gas,
+            9999745020,
+        ),
         ..trivial_external_entry_point_new(test_contract)
     };
     let library_entry_point = CallEntryPoint {
@@ -133,23 +144,18 @@ fn test_nested_library_call(test_contract: FeatureContract, expected_gas: u64) {
         class_hash: Some(test_class_hash),
         code_address: None,
         call_type: CallType::Delegate,
-        initial_gas: if_native(9999813550, 9999823550),
+        initial_gas: if_native(|casm| casm.inner_calls[0].call.initial_gas, 9999823550),
         ..trivial_external_entry_point_new(test_contract)
     };
     let storage_entry_point = CallEntryPoint {
         calldata: calldata![felt!(key), felt!(value)],
-        initial_gas: if_native(9999752538, 9999656870),
+        initial_gas: if_native(|casm| casm.inner_calls[1].call.initial_gas, 9999656870),
         ..nested_storage_entry_point
     };
 
-    // Todo(rodrigo): Execution resources from the VM & Native are mesaured differently
-    // Resources are not tracked when using Native
+    // Native execution resources are estimated from the consumed gas, and are not compared.
     let default_resources_if_native = |resources| {
-        if matches!(test_contract, FeatureContract::SierraTestContract) {
-            ExecutionResources::default()
-        } else {
-            resources
-        }
+        if is_native { ExecutionResources::default() } else { resources }
     };
 
     let storage_entry_point_resources = default_resources_if_native(ExecutionResources {
@@ -161,7 +167,10 @@ fn test_nested_library_call(test_contract: FeatureContract, expected_gas: u64) {
         call: nested_storage_entry_point,
         execution: CallExecution {
             retdata: retdata![felt!(value + 1)],
-            gas_consumed: if_native(NATIVE_GAS_PLACEHOLDER, REQUIRED_GAS_STORAGE_READ_WRITE_TEST),
+            gas_consumed: if_native(
+                |casm| casm.inner_calls[0].inner_calls[0].execution.gas_consumed,
+                REQUIRED_GAS_STORAGE_READ_WRITE_TEST,
+            ),
             ..CallExecution::default()
         },
         resources: storage_entry_point_resources.clone(),
@@ -182,7 +191,10 @@ fn test_nested_library_call(test_contract: FeatureContract, expected_gas: u64) {
         call: library_entry_point,
         execution: CallExecution {
             retdata: retdata![felt!(value + 1)],
-            gas_consumed: if_native(NATIVE_GAS_PLACEHOLDER, REQUIRED_GAS_LIBRARY_CALL_TEST),
+            gas_consumed: if_native(
+                |casm| casm.inner_calls[0].execution.gas_consumed,
+                REQUIRED_GAS_LIBRARY_CALL_TEST,
+            ),
             ..CallExecution::default()
         },
         resources: library_call_resources,
@@ -194,7 +206,10 @@ fn test_nested_library_call(test_contract: FeatureContract, expected_gas: u64) {
         call: storage_entry_point,
         execution: CallExecution {
             retdata: retdata![felt!(value)],
-            gas_consumed: if_native(NATIVE_GAS_PLACEHOLDER, REQUIRED_GAS_STORAGE_READ_WRITE_TEST),
+            gas_consumed: if_native(
+                |casm| casm.inner_calls[1].execution.gas_consumed,
+                REQUIRED_GAS_STORAGE_READ_WRITE_TEST,
+            ),
             ..CallExecution::default()
         },
         resources: storage_entry_point_resources,
@@ -215,7 +230,8 @@ fn test_nested_library_call(test_contract: FeatureContract, expected_gas: u64) {
         call: main_entry_point.clone(),
         execution: CallExecution {
             retdata: retdata![felt!(value)],
-            gas_consumed: expected_gas,
+            gas_consumed: expected_gas
+                .unwrap_or_else(|| casm_call_info.as_ref().unwrap().execution.gas_consumed),
             ..CallExecution::default()
         },
         resources: main_call_resources,
@@ -223,5 +239,14 @@ fn test_nested_library_call(test_contract: FeatureContract, expected_gas: u64) {
         ..Default::default()
     };
 
-    assert_eq!(main_entry_point.execute_directly(&mut state).unwrap(), expected_call_info);
+    let mut call_info = main_entry_point.execute_directly(&mut state).unwrap();
+    if is_native {
+        clear_resources(&mut call_info);
+    }
+    assert_eq!(call_info, expected_call_info);
+}
+
+fn clear_resources(call_info: &mut CallInfo) {
+    call_info.resources = ExecutionResources::default();
+    call_info.inner_calls.iter_mut().for_each(clear_resources);
 }
@@ -4,6 +4,7 @@ mod deploy;
 mod emit_event;
 mod get_block_hash;
 mod get_execution_info;
+mod interceptor;
 mod keccak;
 mod library_call;
 mod out_of_gas;
@@ -6,13 +6,14 @@ use crate::abi::abi_utils::selector_from_name;
 use crate::context::ChainInfo;
 use crate::execution::call_info::CallExecution;
 use crate::execution::entry_point::CallEntryPoint;
-use crate::execution::native::utils::NATIVE_GAS_PLACEHOLDER;
 use crate::state::state_api::StateReader;
 use crate::test_utils::contracts::FeatureContract;
 use crate::test_utils::initial_test_state::test_state;
-use crate::test_utils::{trivial_external_entry_point_new, CairoVersion, BALANCE};
+use crate::test_utils::{
+    expected_gas_or_casm_equivalent, trivial_external_entry_point_new, CairoVersion, BALANCE,
+};
 
-#[test_case(FeatureContract::SierraTestContract; "Native")]
+#[cfg_attr(feature = "native", test_case(FeatureContract::SierraTestContract; "Native"))]
 #[test_case(FeatureContract::TestContract(CairoVersion::Cairo1); "VM")]
 fn undeclared_class_hash(test_contract: FeatureContract) {
     let mut state = test_state(&ChainInfo::create_for_testing(), BALANCE, &[(test_contract, 1)]);
@@ -26,7 +27,7 @@ fn undeclared_class_hash(test_contract: FeatureContract) {
     assert!(error.contains("is not declared"));
 }
 
-#[test_case(FeatureContract::SierraTestContract; "Native")]
+#[cfg_attr(feature = "native", test_case(FeatureContract::SierraTestContract; "Native"))]
 #[test_case(FeatureContract::TestContract(CairoVersion::Cairo1); "VM")]
 fn cairo0_class_hash(test_contract: FeatureContract) {
     let empty_contract_cairo0 = FeatureContract::Empty(CairoVersion::Cairo0);
@@ -48,9 +49,9 @@ fn cairo0_class_hash(test_contract: FeatureContract) {
     assert!(error.contains("Cannot replace V1 class hash with V0 class hash"));
 }
 
-#[test_case(FeatureContract::SierraTestContract, NATIVE_GAS_PLACEHOLDER; "Native")]
-#[test_case(FeatureContract::TestContract(CairoVersion::Cairo1), 9750; "VM")]
-fn positive_flow(test_contract: FeatureContract, gas_consumed: u64) {
+#[cfg_attr(feature = "native", test_case(FeatureContract::SierraTestContract, None; "Native"))]
+#[test_case(FeatureContract::TestContract(CairoVersion::Cairo1), Some(9750); "VM")]
+fn positive_flow(test_contract: FeatureContract, gas_consumed: Option<u64>) {
     let empty_contract = FeatureContract::Empty(CairoVersion::Cairo1);
     let empty_contract_cairo0 = FeatureContract::Empty(CairoVersion::Cairo0);
     let mut state = test_state(
@@ -68,6 +69,17 @@ fn positive_flow(test_contract: FeatureContract, gas_consumed: u64) {
         entry_point_selector: selector_from_name("test_replace_class"),
         ..trivial_external_entry_point_new(test_contract)
     };
+    let gas_consumed = expected_gas_or_casm_equivalent(
+        gas_consumed,
+        || {
+            test_state(
+                &ChainInfo::create_for_testing(),
+                BALANCE,
+                &[(test_contract, 1), (empty_contract, 0), (empty_contract_cairo0, 0)],
+            )
+        },
+        &entry_point_call,
+    );
     assert_eq!(
         entry_point_call.execute_directly(&mut state).unwrap().execution,
         CallExecution { gas_consumed, ..Default::default() }
@@ -3,5 +3,11 @@ pub mod cached_state;
 pub mod error_format_test;
 pub mod errors;
 pub mod global_cache;
+pub mod patricia_tree;
+pub mod pending_chain;
+pub mod recording_state_reader;
+pub mod snapshot_state_reader;
 pub mod state_api;
+pub mod state_commitment;
+pub mod state_override;
 pub mod state_wrapper;
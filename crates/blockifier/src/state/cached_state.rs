@@ -1,8 +1,13 @@
 use std::cell::RefCell;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::hash::Hash;
+use std::mem;
 
 use derive_more::IntoIterator;
 use indexmap::IndexMap;
+use itertools::Itertools;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use starknet_api::core::{ClassHash, CompiledClassHash, ContractAddress, Nonce};
 use starknet_api::state::StorageKey;
 use starknet_types_core::felt::Felt;
@@ -11,7 +16,9 @@ use crate::abi::abi_utils::get_fee_token_var_address;
 use crate::context::TransactionContext;
 use crate::execution::contract_class::ContractClass;
 use crate::state::errors::StateError;
-use crate::state::state_api::{State, StateReader, StateResult, UpdatableState};
+use crate::state::state_api::{
+    check_batch_read, IterableStateReader, State, StateReader, StateResult, UpdatableState,
+};
 use crate::transaction::objects::TransactionExecutionInfo;
 use crate::utils::{strict_subtract_mappings, subtract_mappings};
 
@@ -34,6 +41,8 @@ pub struct CachedState<S: StateReader> {
     pub(crate) class_hash_to_class: RefCell<ContractClassMapping>,
     /// A map from class hash to the set of PC values that were visited in the class.
     pub visited_pcs: HashMap<ClassHash, HashSet<usize>>,
+    // Note: only changes made through the `CachedState` API are tracked by savepoints.
+    savepoints: Savepoints,
 }
 
 impl<S: StateReader> CachedState<S> {
@@ -43,6 +52,73 @@ impl<S: StateReader> CachedState<S> {
             cache: RefCell::new(StateCache::default()),
             class_hash_to_class: RefCell::new(HashMap::default()),
             visited_pcs: HashMap::default(),
+            savepoints: Savepoints::default(),
+        }
+    }
+
+    /// Creates a named savepoint, to which the cache, the contract classes and the visited PCs can
+    /// later be rolled back. Savepoints nest; if a name is reused, the most recent savepoint of
+    /// that name is addressed.
+    pub fn create_savepoint(&mut self, name: &str) {
+        self.savepoints
+            .0
+            .get_mut()
+            .push(Savepoint { name: name.to_string(), undo_log: SavepointUndoLog::default() });
+    }
+
+    /// Restores the state to the given savepoint, at a cost proportional to the changes made
+    /// since. Savepoints created after it are discarded; the savepoint itself is kept.
+    pub fn rollback_to_savepoint(&mut self, name: &str) -> StateResult<()> {
+        let index = self.savepoint_index(name)?;
+        let savepoints = self.savepoints.0.get_mut();
+        let mut undo_logs: Vec<SavepointUndoLog> =
+            savepoints.drain(index + 1..).map(|savepoint| savepoint.undo_log).collect();
+        undo_logs.push(mem::take(&mut savepoints[index].undo_log));
+
+        // Undo the innermost changes first.
+        for undo_log in undo_logs.into_iter().rev() {
+            self.undo(undo_log);
+        }
+        Ok(())
+    }
+
+    /// Discards the given savepoint, and all savepoints created after it, keeping the changes made
+    /// since.
+    pub fn release_savepoint(&mut self, name: &str) -> StateResult<()> {
+        let index = self.savepoint_index(name)?;
+        let savepoints = self.savepoints.0.get_mut();
+        let released_savepoints: Vec<Savepoint> = savepoints.drain(index..).collect();
+        if let Some(enclosing_savepoint) = savepoints.last_mut() {
+            for savepoint in released_savepoints {
+                savepoint.undo_log.merge_into(&mut enclosing_savepoint.undo_log);
+            }
+        }
+        Ok(())
+    }
+
+    fn savepoint_index(&mut self, name: &str) -> StateResult<usize> {
+        self.savepoints
+            .0
+            .get_mut()
+            .iter()
+            .rposition(|savepoint| savepoint.name == name)
+            .ok_or_else(|| StateError::UnknownSavepoint(name.to_string()))
+    }
+
+    fn undo(&mut self, undo_log: SavepointUndoLog) {
+        let cache = self.cache.get_mut();
+        undo_log.initial_reads.restore(&mut cache.initial_reads);
+        undo_log.writes.restore(&mut cache.writes);
+        restore_previous_values(self.class_hash_to_class.get_mut(), undo_log.class_hash_to_class);
+        for (class_hash, pcs) in undo_log.visited_pcs {
+            if let Some(class_visited_pcs) = self.visited_pcs.get_mut(&class_hash) {
+                for pc in pcs {
+                    class_visited_pcs.remove(&pc);
+                }
+            }
+        }
+        for class_hash in undo_log.visited_classes {
+            self.visited_pcs.remove(&class_hash);
         }
     }
 
@@ -68,9 +144,20 @@ impl<S: StateReader> CachedState<S> {
         for (&key, &value) in &write_updates.declared_contracts {
             assert_eq!(value, local_contract_cache_updates.contains_key(&key));
         }
-        let mut cache = self.cache.borrow_mut();
+        let cache = self.cache.get_mut();
+        let class_hash_to_class = self.class_hash_to_class.get_mut();
+        self.savepoints.record(|undo_log| {
+            undo_log.writes.record_all(&cache.writes, write_updates);
+            for &class_hash in local_contract_cache_updates.keys() {
+                record_previous_value(
+                    &mut undo_log.class_hash_to_class,
+                    class_hash_to_class,
+                    class_hash,
+                );
+            }
+        });
         cache.writes.extend(write_updates);
-        self.class_hash_to_class.get_mut().extend(local_contract_cache_updates);
+        class_hash_to_class.extend(local_contract_cache_updates);
     }
 
     pub fn update_visited_pcs_cache(&mut self, visited_pcs: &HashMap<ClassHash, HashSet<usize>>) {
@@ -97,6 +184,11 @@ impl<S: StateReader> CachedState<S> {
         for contract_storage_key in cache.writes.storage.keys() {
             if !cache.initial_reads.storage.contains_key(contract_storage_key) {
                 // First access to this cell was write; cache initial value.
+                self.savepoints.record(|undo_log| {
+                    undo_log
+                        .initial_reads
+                        .record_storage(&cache.initial_reads, *contract_storage_key)
+                });
                 cache.initial_reads.storage.insert(
                     *contract_storage_key,
                     self.state.get_storage_at(contract_storage_key.0, contract_storage_key.1)?,
@@ -137,6 +229,9 @@ impl<S: StateReader> StateReader for CachedState<S> {
 
         if cache.get_storage_at(contract_address, key).is_none() {
             let storage_value = self.state.get_storage_at(contract_address, key)?;
+            self.savepoints.record(|undo_log| {
+                undo_log.initial_reads.record_storage(&cache.initial_reads, (contract_address, key))
+            });
             cache.set_storage_initial_value(contract_address, key, storage_value);
         }
 
@@ -151,6 +246,9 @@ impl<S: StateReader> StateReader for CachedState<S> {
 
         if cache.get_nonce_at(contract_address).is_none() {
             let nonce = self.state.get_nonce_at(contract_address)?;
+            self.savepoints.record(|undo_log| {
+                undo_log.initial_reads.record_nonce(&cache.initial_reads, contract_address)
+            });
             cache.set_nonce_initial_value(contract_address, nonce);
         }
 
@@ -161,11 +259,83 @@ impl<S: StateReader> StateReader for CachedState<S> {
         Ok(*nonce)
     }
 
+    fn get_storage_values(&self, storage_entries: &[StorageEntry]) -> StateResult<Vec<Felt>> {
+        let mut cache = self.cache.borrow_mut();
+
+        // Read all uncached values at once.
+        let missing_entries: Vec<StorageEntry> = storage_entries
+            .iter()
+            .filter(|(contract_address, key)| {
+                cache.get_storage_at(*contract_address, *key).is_none()
+            })
+            .copied()
+            .unique()
+            .collect();
+        if !missing_entries.is_empty() {
+            let storage_values = check_batch_read(
+                missing_entries.len(),
+                self.state.get_storage_values(&missing_entries)?,
+            )?;
+            for (storage_entry, storage_value) in missing_entries.into_iter().zip(storage_values) {
+                self.savepoints.record(|undo_log| {
+                    undo_log.initial_reads.record_storage(&cache.initial_reads, storage_entry)
+                });
+                let (contract_address, key) = storage_entry;
+                cache.set_storage_initial_value(contract_address, key, storage_value);
+            }
+        }
+
+        Ok(storage_entries
+            .iter()
+            .map(|(contract_address, key)| {
+                *cache.get_storage_at(*contract_address, *key).unwrap_or_else(|| {
+                    panic!("Cannot retrieve '{contract_address:?}' and '{key:?}' from the cache.")
+                })
+            })
+            .collect())
+    }
+
+    fn get_nonces(&self, contract_addresses: &[ContractAddress]) -> StateResult<Vec<Nonce>> {
+        let mut cache = self.cache.borrow_mut();
+
+        // Read all uncached values at once.
+        let missing_addresses: Vec<ContractAddress> = contract_addresses
+            .iter()
+            .filter(|contract_address| cache.get_nonce_at(**contract_address).is_none())
+            .copied()
+            .unique()
+            .collect();
+        if !missing_addresses.is_empty() {
+            let nonces = check_batch_read(
+                missing_addresses.len(),
+                self.state.get_nonces(&missing_addresses)?,
+            )?;
+            for (contract_address, nonce) in missing_addresses.into_iter().zip(nonces) {
+                self.savepoints.record(|undo_log| {
+                    undo_log.initial_reads.record_nonce(&cache.initial_reads, contract_address)
+                });
+                cache.set_nonce_initial_value(contract_address, nonce);
+            }
+        }
+
+        Ok(contract_addresses
+            .iter()
+            .map(|contract_address| {
+                *cache.get_nonce_at(*contract_address).unwrap_or_else(|| {
+                    panic!("Cannot retrieve '{contract_address:?}' from the cache.")
+                })
+            })
+            .collect())
+    }
+
     fn get_class_hash_at(&self, contract_address: ContractAddress) -> StateResult<ClassHash> {
         let mut cache = self.cache.borrow_mut();
 
         if cache.get_class_hash_at(contract_address).is_none() {
             let class_hash = self.state.get_class_hash_at(contract_address)?;
+            self.savepoints.record(|undo_log| {
+                undo_log.initial_reads.record_class_hash(&cache.initial_reads, contract_address)
+            });
             cache.set_class_hash_initial_value(contract_address, class_hash);
         }
 
@@ -184,6 +354,14 @@ impl<S: StateReader> StateReader for CachedState<S> {
         {
             match self.state.get_compiled_contract_class(class_hash) {
                 Err(StateError::UndeclaredClassHash(class_hash)) => {
+                    self.savepoints.record(|undo_log| {
+                        undo_log
+                            .initial_reads
+                            .record_declared_contract(&cache.initial_reads, class_hash);
+                        undo_log
+                            .initial_reads
+                            .record_compiled_class_hash(&cache.initial_reads, class_hash);
+                    });
                     cache.set_declared_contract_initial_values(class_hash, false);
                     cache.set_compiled_class_hash_initial_value(
                         class_hash,
@@ -193,6 +371,12 @@ impl<S: StateReader> StateReader for CachedState<S> {
                 }
                 Err(error) => Err(error)?,
                 Ok(contract_class) => {
+                    self.savepoints.record(|undo_log| {
+                        undo_log
+                            .initial_reads
+                            .record_declared_contract(&cache.initial_reads, class_hash);
+                        undo_log.class_hash_to_class.entry(class_hash).or_insert(None);
+                    });
                     cache.set_declared_contract_initial_values(class_hash, true);
                     vacant_entry.insert(contract_class);
                 }
@@ -212,6 +396,9 @@ impl<S: StateReader> StateReader for CachedState<S> {
 
         if cache.get_compiled_class_hash(class_hash).is_none() {
             let compiled_class_hash = self.state.get_compiled_class_hash(class_hash)?;
+            self.savepoints.record(|undo_log| {
+                undo_log.initial_reads.record_compiled_class_hash(&cache.initial_reads, class_hash)
+            });
             cache.set_compiled_class_hash_initial_value(class_hash, compiled_class_hash);
         }
 
@@ -222,6 +409,47 @@ impl<S: StateReader> StateReader for CachedState<S> {
     }
 }
 
+impl<S: IterableStateReader> IterableStateReader for CachedState<S> {
+    fn get_contract_addresses(&self) -> StateResult<Vec<ContractAddress>> {
+        let cache = self.cache.borrow();
+        let writes = &cache.writes;
+        let contract_addresses: BTreeSet<ContractAddress> = self
+            .state
+            .get_contract_addresses()?
+            .into_iter()
+            .chain(writes.class_hashes.keys().copied())
+            .chain(writes.nonces.keys().copied())
+            .chain(writes.storage.keys().map(|(contract_address, _)| *contract_address))
+            .collect();
+        Ok(contract_addresses.into_iter().collect())
+    }
+
+    fn get_storage_entries(
+        &self,
+        contract_address: ContractAddress,
+    ) -> StateResult<Vec<(StorageKey, Felt)>> {
+        let mut storage_entries: BTreeMap<StorageKey, Felt> =
+            self.state.get_storage_entries(contract_address)?.into_iter().collect();
+        // Overlay the written values.
+        for ((address, key), value) in &self.cache.borrow().writes.storage {
+            if *address == contract_address {
+                storage_entries.insert(*key, *value);
+            }
+        }
+        Ok(storage_entries.into_iter().filter(|(_, value)| *value != Felt::ZERO).collect())
+    }
+
+    fn get_declared_class_hashes(&self) -> StateResult<Vec<ClassHash>> {
+        let class_hashes: BTreeSet<ClassHash> = self
+            .state
+            .get_declared_class_hashes()?
+            .into_iter()
+            .chain(self.class_hash_to_class.borrow().keys().copied())
+            .collect();
+        Ok(class_hashes.into_iter().collect())
+    }
+}
+
 impl<S: StateReader> State for CachedState<S> {
     fn set_storage_at(
         &mut self,
@@ -229,7 +457,11 @@ impl<S: StateReader> State for CachedState<S> {
         key: StorageKey,
         value: Felt,
     ) -> StateResult<()> {
-        self.cache.get_mut().set_storage_value(contract_address, key, value);
+        let cache = self.cache.get_mut();
+        self.savepoints.record(|undo_log| {
+            undo_log.writes.record_storage(&cache.writes, (contract_address, key))
+        });
+        cache.set_storage_value(contract_address, key, value);
 
         Ok(())
     }
@@ -237,7 +469,10 @@ impl<S: StateReader> State for CachedState<S> {
     fn increment_nonce(&mut self, contract_address: ContractAddress) -> StateResult<()> {
         let current_nonce = self.get_nonce_at(contract_address)?;
         let next_nonce = Nonce(current_nonce.0 + Felt::ONE);
-        self.cache.get_mut().set_nonce_value(contract_address, next_nonce);
+        let cache = self.cache.get_mut();
+        self.savepoints
+            .record(|undo_log| undo_log.writes.record_nonce(&cache.writes, contract_address));
+        cache.set_nonce_value(contract_address, next_nonce);
 
         Ok(())
     }
@@ -251,7 +486,10 @@ impl<S: StateReader> State for CachedState<S> {
             return Err(StateError::OutOfRangeContractAddress);
         }
 
-        self.cache.get_mut().set_class_hash_write(contract_address, class_hash);
+        let cache = self.cache.get_mut();
+        self.savepoints
+            .record(|undo_log| undo_log.writes.record_class_hash(&cache.writes, contract_address));
+        cache.set_class_hash_write(contract_address, class_hash);
         Ok(())
     }
 
@@ -260,8 +498,17 @@ impl<S: StateReader> State for CachedState<S> {
         class_hash: ClassHash,
         contract_class: ContractClass,
     ) -> StateResult<()> {
-        self.class_hash_to_class.get_mut().insert(class_hash, contract_class);
-        let mut cache = self.cache.borrow_mut();
+        let class_hash_to_class = self.class_hash_to_class.get_mut();
+        let cache = self.cache.get_mut();
+        self.savepoints.record(|undo_log| {
+            record_previous_value(
+                &mut undo_log.class_hash_to_class,
+                class_hash_to_class,
+                class_hash,
+            );
+            undo_log.writes.record_declared_contract(&cache.writes, class_hash);
+        });
+        class_hash_to_class.insert(class_hash, contract_class);
         cache.declare_contract(class_hash);
         Ok(())
     }
@@ -271,11 +518,17 @@ impl<S: StateReader> State for CachedState<S> {
         class_hash: ClassHash,
         compiled_class_hash: CompiledClassHash,
     ) -> StateResult<()> {
-        self.cache.get_mut().set_compiled_class_hash_write(class_hash, compiled_class_hash);
+        let cache = self.cache.get_mut();
+        self.savepoints.record(|undo_log| {
+            undo_log.writes.record_compiled_class_hash(&cache.writes, class_hash)
+        });
+        cache.set_compiled_class_hash_write(class_hash, compiled_class_hash);
         Ok(())
     }
 
     fn add_visited_pcs(&mut self, class_hash: ClassHash, pcs: &HashSet<usize>) {
+        self.savepoints
+            .record(|undo_log| undo_log.record_visited_pcs(&self.visited_pcs, class_hash, pcs));
         self.visited_pcs.entry(class_hash).or_default().extend(pcs);
     }
 }
@@ -288,6 +541,7 @@ impl Default for CachedState<crate::test_utils::dict_state_reader::DictStateRead
             cache: Default::default(),
             class_hash_to_class: Default::default(),
             visited_pcs: Default::default(),
+            savepoints: Default::default(),
         }
     }
 }
@@ -315,12 +569,17 @@ impl From<StorageView> for IndexMap<ContractAddress, IndexMap<StorageKey, Felt>>
 }
 
 #[cfg_attr(any(feature = "testing", test), derive(Clone))]
-#[derive(Debug, Default, PartialEq, Eq)]
+#[derive(Debug, Default, Deserialize, PartialEq, Eq, Serialize)]
 pub struct StateMaps {
+    #[serde(with = "sorted_entries")]
     pub nonces: HashMap<ContractAddress, Nonce>,
+    #[serde(with = "sorted_entries")]
     pub class_hashes: HashMap<ContractAddress, ClassHash>,
+    #[serde(with = "sorted_entries")]
     pub storage: HashMap<StorageEntry, Felt>,
+    #[serde(with = "sorted_entries")]
     pub compiled_class_hashes: HashMap<ClassHash, CompiledClassHash>,
+    #[serde(with = "sorted_entries")]
     pub declared_contracts: HashMap<ClassHash, bool>,
 }
 
@@ -356,8 +615,9 @@ impl StateMaps {
 /// Caches read and write requests.
 /// The tracked changes are needed for block state commitment.
 
-// Invariant: keys cannot be deleted from fields (only used internally by the cached state).
-#[derive(Debug, Default, PartialEq, Eq)]
+// Invariant: keys cannot be deleted from fields (only used internally by the cached state), except
+// when rolling back to a savepoint.
+#[derive(Debug, Default, Deserialize, PartialEq, Eq, Serialize)]
 pub struct StateCache {
     // Reader's cached information; initial values, read before any write operation (per cell).
     pub(crate) initial_reads: StateMaps,
@@ -467,6 +727,169 @@ impl StateCache {
     }
 }
 
+#[derive(Debug)]
+struct Savepoint {
+    name: String,
+    undo_log: SavepointUndoLog,
+}
+
+/// The savepoints of a `CachedState`, from the outermost to the innermost.
+#[derive(Debug, Default)]
+struct Savepoints(RefCell<Vec<Savepoint>>);
+
+impl Savepoints {
+    /// Records, in the undo log of the innermost savepoint (if any), the values about to be
+    /// modified. Outer savepoints are updated when the inner ones are released.
+    fn record(&self, record: impl FnOnce(&mut SavepointUndoLog)) {
+        if let Some(savepoint) = self.0.borrow_mut().last_mut() {
+            record(&mut savepoint.undo_log);
+        }
+    }
+}
+
+/// The values of the cached state entries modified since a savepoint was created, as they were at
+/// its creation.
+#[derive(Debug, Default)]
+struct SavepointUndoLog {
+    initial_reads: StateMapsUndoLog,
+    writes: StateMapsUndoLog,
+    class_hash_to_class: HashMap<ClassHash, Option<ContractClass>>,
+    // PCs visited for the first time since the savepoint was created.
+    visited_pcs: HashMap<ClassHash, HashSet<usize>>,
+    // Classes visited for the first time since the savepoint was created.
+    visited_classes: HashSet<ClassHash>,
+}
+
+impl SavepointUndoLog {
+    fn record_visited_pcs(
+        &mut self,
+        visited_pcs: &HashMap<ClassHash, HashSet<usize>>,
+        class_hash: ClassHash,
+        pcs: &HashSet<usize>,
+    ) {
+        let new_pcs = self.visited_pcs.entry(class_hash).or_default();
+        match visited_pcs.get(&class_hash) {
+            Some(class_visited_pcs) => new_pcs.extend(pcs.difference(class_visited_pcs)),
+            None => {
+                new_pcs.extend(pcs);
+                self.visited_classes.insert(class_hash);
+            }
+        }
+    }
+
+    /// Merges this log into the log of the enclosing savepoint; entries already recorded there
+    /// hold older values, and take precedence.
+    fn merge_into(self, enclosing_undo_log: &mut Self) {
+        self.initial_reads.merge_into(&mut enclosing_undo_log.initial_reads);
+        self.writes.merge_into(&mut enclosing_undo_log.writes);
+        merge_previous_values(
+            &mut enclosing_undo_log.class_hash_to_class,
+            self.class_hash_to_class,
+        );
+        for (class_hash, pcs) in self.visited_pcs {
+            enclosing_undo_log.visited_pcs.entry(class_hash).or_default().extend(pcs);
+        }
+        enclosing_undo_log.visited_classes.extend(self.visited_classes);
+    }
+}
+
+/// Previous values of `StateMaps` entries; `None` stands for an absent entry.
+#[derive(Debug, Default)]
+struct StateMapsUndoLog {
+    nonces: HashMap<ContractAddress, Option<Nonce>>,
+    class_hashes: HashMap<ContractAddress, Option<ClassHash>>,
+    storage: HashMap<StorageEntry, Option<Felt>>,
+    compiled_class_hashes: HashMap<ClassHash, Option<CompiledClassHash>>,
+    declared_contracts: HashMap<ClassHash, Option<bool>>,
+}
+
+impl StateMapsUndoLog {
+    fn record_nonce(&mut self, maps: &StateMaps, contract_address: ContractAddress) {
+        record_previous_value(&mut self.nonces, &maps.nonces, contract_address);
+    }
+
+    fn record_class_hash(&mut self, maps: &StateMaps, contract_address: ContractAddress) {
+        record_previous_value(&mut self.class_hashes, &maps.class_hashes, contract_address);
+    }
+
+    fn record_storage(&mut self, maps: &StateMaps, storage_entry: StorageEntry) {
+        record_previous_value(&mut self.storage, &maps.storage, storage_entry);
+    }
+
+    fn record_compiled_class_hash(&mut self, maps: &StateMaps, class_hash: ClassHash) {
+        record_previous_value(
+            &mut self.compiled_class_hashes,
+            &maps.compiled_class_hashes,
+            class_hash,
+        );
+    }
+
+    fn record_declared_contract(&mut self, maps: &StateMaps, class_hash: ClassHash) {
+        record_previous_value(&mut self.declared_contracts, &maps.declared_contracts, class_hash);
+    }
+
+    /// Records the entries of `maps` about to be overridden by `updates`.
+    fn record_all(&mut self, maps: &StateMaps, updates: &StateMaps) {
+        updates.nonces.keys().for_each(|&key| self.record_nonce(maps, key));
+        updates.class_hashes.keys().for_each(|&key| self.record_class_hash(maps, key));
+        updates.storage.keys().for_each(|&key| self.record_storage(maps, key));
+        updates
+            .compiled_class_hashes
+            .keys()
+            .for_each(|&key| self.record_compiled_class_hash(maps, key));
+        updates.declared_contracts.keys().for_each(|&key| self.record_declared_contract(maps, key));
+    }
+
+    fn restore(self, maps: &mut StateMaps) {
+        restore_previous_values(&mut maps.nonces, self.nonces);
+        restore_previous_values(&mut maps.class_hashes, self.class_hashes);
+        restore_previous_values(&mut maps.storage, self.storage);
+        restore_previous_values(&mut maps.compiled_class_hashes, self.compiled_class_hashes);
+        restore_previous_values(&mut maps.declared_contracts, self.declared_contracts);
+    }
+
+    fn merge_into(self, enclosing_undo_log: &mut Self) {
+        merge_previous_values(&mut enclosing_undo_log.nonces, self.nonces);
+        merge_previous_values(&mut enclosing_undo_log.class_hashes, self.class_hashes);
+        merge_previous_values(&mut enclosing_undo_log.storage, self.storage);
+        merge_previous_values(
+            &mut enclosing_undo_log.compiled_class_hashes,
+            self.compiled_class_hashes,
+        );
+        merge_previous_values(&mut enclosing_undo_log.declared_contracts, self.declared_contracts);
+    }
+}
+
+/// Records the value of the given key, unless a (previous) value is already recorded.
+fn record_previous_value<K: Copy + Eq + Hash, V: Clone>(
+    undo_log: &mut HashMap<K, Option<V>>,
+    map: &HashMap<K, V>,
+    key: K,
+) {
+    undo_log.entry(key).or_insert_with(|| map.get(&key).cloned());
+}
+
+fn restore_previous_values<K: Eq + Hash, V>(
+    map: &mut HashMap<K, V>,
+    undo_log: HashMap<K, Option<V>>,
+) {
+    for (key, previous_value) in undo_log {
+        match previous_value {
+            Some(value) => map.insert(key, value),
+            None => map.remove(&key),
+        };
+    }
+}
+
+fn merge_previous_values<K: Eq + Hash, V>(
+    enclosing_undo_log: &mut HashMap<K, Option<V>>,
+    undo_log: HashMap<K, Option<V>>,
+) {
+    for (key, previous_value) in undo_log {
+        enclosing_undo_log.entry(key).or_insert(previous_value);
+    }
+}
+
 /// Wraps a mutable reference to a `State` object, exposing its API.
 /// Used to pass ownership to a `CachedState`.
 pub struct MutRefState<'a, S: StateReader + ?Sized>(&'a mut S);
@@ -491,6 +914,14 @@ impl<'a, S: StateReader + ?Sized> StateReader for MutRefState<'a, S> {
         self.0.get_nonce_at(contract_address)
     }
 
+    fn get_storage_values(&self, storage_entries: &[StorageEntry]) -> StateResult<Vec<Felt>> {
+        self.0.get_storage_values(storage_entries)
+    }
+
+    fn get_nonces(&self, contract_addresses: &[ContractAddress]) -> StateResult<Vec<Nonce>> {
+        self.0.get_nonces(contract_addresses)
+    }
+
     fn get_class_hash_at(&self, contract_address: ContractAddress) -> StateResult<ClassHash> {
         self.0.get_class_hash_at(contract_address)
     }
@@ -537,14 +968,18 @@ type StorageDiff = IndexMap<ContractAddress, IndexMap<StorageKey, Felt>>;
 
 /// Holds uncommitted changes induced on Starknet contracts.
 #[cfg_attr(any(feature = "testing", test), derive(Clone))]
-#[derive(Debug, Eq, PartialEq)]
+#[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub struct CommitmentStateDiff {
     // Contract instance attributes (per address).
+    #[serde(with = "sorted_entries")]
     pub address_to_class_hash: IndexMap<ContractAddress, ClassHash>,
+    #[serde(with = "sorted_entries")]
     pub address_to_nonce: IndexMap<ContractAddress, Nonce>,
+    #[serde(with = "sorted_storage_updates")]
     pub storage_updates: IndexMap<ContractAddress, IndexMap<StorageKey, Felt>>,
 
     // Global attributes.
+    #[serde(with = "sorted_entries")]
     pub class_hash_to_compiled_class_hash: IndexMap<ClassHash, CompiledClassHash>,
 }
 
@@ -644,7 +1079,7 @@ impl StateChangesKeys {
 
 /// Holds the state changes.
 #[cfg_attr(any(feature = "testing", test), derive(Clone))]
-#[derive(Debug, Default, Eq, PartialEq)]
+#[derive(Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
 pub struct StateChanges(pub StateMaps);
 
 impl StateChanges {
@@ -728,3 +1163,88 @@ pub struct StateChangesCount {
     pub n_compiled_class_hash_updates: usize,
     pub n_modified_contracts: usize,
 }
+
+/// Stable encodings of state diffs, as JSON or in a compact binary format.
+/// Maps are encoded as sequences of entries sorted by key, so that equal values are encoded
+/// identically.
+pub trait CanonicalEncoding: Serialize + DeserializeOwned {
+    fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string(self)
+    }
+
+    fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+
+    fn to_bytes(&self) -> bincode::Result<Vec<u8>> {
+        bincode::serialize(self)
+    }
+
+    fn from_bytes(bytes: &[u8]) -> bincode::Result<Self> {
+        bincode::deserialize(bytes)
+    }
+}
+
+impl CanonicalEncoding for StateMaps {}
+impl CanonicalEncoding for StateCache {}
+impl CanonicalEncoding for StateChanges {}
+impl CanonicalEncoding for CommitmentStateDiff {}
+
+/// (De)serializes a map as a sequence of its entries, sorted by key.
+mod sorted_entries {
+    use super::*;
+
+    pub fn serialize<'a, M, K, V, S>(map: &'a M, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        &'a M: IntoIterator<Item = (&'a K, &'a V)>,
+        K: Ord + Serialize + 'a,
+        V: Serialize + 'a,
+        S: Serializer,
+    {
+        let mut entries: Vec<(&K, &V)> = map.into_iter().collect();
+        entries.sort_by(|(key, _), (other_key, _)| key.cmp(other_key));
+        serializer.collect_seq(entries)
+    }
+
+    pub fn deserialize<'de, M, K, V, D>(deserializer: D) -> Result<M, D::Error>
+    where
+        M: FromIterator<(K, V)>,
+        K: Deserialize<'de>,
+        V: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        Ok(Vec::<(K, V)>::deserialize(deserializer)?.into_iter().collect())
+    }
+}
+
+/// (De)serializes storage updates as a sequence of per-contract entries, sorted by address, each
+/// holding a sequence of storage entries, sorted by key.
+mod sorted_storage_updates {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(
+        storage_updates: &StorageDiff,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let mut entries: Vec<(&ContractAddress, Vec<(&StorageKey, &Felt)>)> = storage_updates
+            .iter()
+            .map(|(address, storage)| {
+                let mut storage_entries: Vec<_> = storage.iter().collect();
+                storage_entries.sort_by_key(|(key, _)| **key);
+                (address, storage_entries)
+            })
+            .collect();
+        entries.sort_by_key(|(address, _)| **address);
+        serializer.collect_seq(entries)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<StorageDiff, D::Error> {
+        let entries = Vec::<(ContractAddress, Vec<(StorageKey, Felt)>)>::deserialize(deserializer)?;
+        Ok(entries
+            .into_iter()
+            .map(|(address, storage_entries)| (address, storage_entries.into_iter().collect()))
+            .collect())
+    }
+}
@@ -1,3 +1,5 @@
+use std::fmt::Debug;
+
 use assert_matches::assert_matches;
 use indexmap::indexmap;
 use pretty_assertions::assert_eq;
@@ -601,3 +603,285 @@ fn test_state_maps() {
 
     assert_eq!(maps, empty);
 }
+
+/// Returns state maps with several entries per mapping, inserted in ascending or descending order.
+fn state_maps_for_encoding_test(descending: bool) -> StateMaps {
+    let mut indices: Vec<u128> = (1..=10).collect();
+    if descending {
+        indices.reverse();
+    }
+
+    let mut maps = StateMaps::default();
+    for index in indices {
+        let contract_address = ContractAddress::from(index);
+        let class_hash = ClassHash(Felt::from(index));
+        maps.nonces.insert(contract_address, Nonce(Felt::from(index)));
+        maps.class_hashes.insert(contract_address, class_hash);
+        maps.storage.insert((contract_address, StorageKey::from(index)), Felt::from(index));
+        maps.compiled_class_hashes.insert(class_hash, CompiledClassHash(Felt::from(index)));
+        maps.declared_contracts.insert(class_hash, index % 2 == 0);
+    }
+    maps
+}
+
+fn assert_canonical_encoding<T: CanonicalEncoding + Debug + PartialEq>(value: T, equal_value: T) {
+    assert_eq!(T::from_json(&value.to_json().unwrap()).unwrap(), value);
+    assert_eq!(T::from_bytes(&value.to_bytes().unwrap()).unwrap(), value);
+
+    // Equal values are encoded identically, regardless of their maps' insertion order.
+    assert_eq!(value, equal_value);
+    assert_eq!(value.to_json().unwrap(), equal_value.to_json().unwrap());
+    assert_eq!(value.to_bytes().unwrap(), equal_value.to_bytes().unwrap());
+}
+
+#[test]
+fn test_canonical_encoding() {
+    assert_canonical_encoding(
+        state_maps_for_encoding_test(false),
+        state_maps_for_encoding_test(true),
+    );
+    assert_canonical_encoding(
+        StateCache {
+            initial_reads: state_maps_for_encoding_test(false),
+            writes: state_maps_for_encoding_test(true),
+        },
+        StateCache {
+            initial_reads: state_maps_for_encoding_test(true),
+            writes: state_maps_for_encoding_test(false),
+        },
+    );
+    assert_canonical_encoding(
+        StateChanges(state_maps_for_encoding_test(false)),
+        StateChanges(state_maps_for_encoding_test(true)),
+    );
+    assert_canonical_encoding(
+        CommitmentStateDiff::from(state_maps_for_encoding_test(false)),
+        CommitmentStateDiff::from(state_maps_for_encoding_test(true)),
+    );
+}
+
+type SavepointSnapshot =
+    (StateMaps, StateMaps, ContractClassMapping, HashMap<ClassHash, HashSet<usize>>);
+
+/// Returns the parts of the given state restored by rolling back to a savepoint.
+fn savepoint_snapshot(state: &CachedState<DictStateReader>) -> SavepointSnapshot {
+    let cache = state.cache.borrow();
+    (
+        cache.initial_reads.clone(),
+        cache.writes.clone(),
+        state.class_hash_to_class.borrow().clone(),
+        state.visited_pcs.clone(),
+    )
+}
+
+#[test]
+fn test_rollback_to_nested_savepoints() {
+    let contract_address = contract_address!(CONTRACT_ADDRESS);
+    let key = storage_key!(0x10_u16);
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo0);
+    let class_hash = test_contract.get_class_hash();
+    let mut state = CachedState::from(DictStateReader::default());
+    state.set_storage_at(contract_address, key, felt!(1_u8)).unwrap();
+    state.add_visited_pcs(class_hash, &HashSet::from([0]));
+    let initial_snapshot = savepoint_snapshot(&state);
+
+    state.create_savepoint("outer");
+    state.set_storage_at(contract_address, key, felt!(2_u8)).unwrap();
+    state.increment_nonce(contract_address).unwrap();
+    state.add_visited_pcs(class_hash, &HashSet::from([0, 1]));
+    let outer_snapshot = savepoint_snapshot(&state);
+
+    state.create_savepoint("inner");
+    state.set_contract_class(class_hash, test_contract.get_class()).unwrap();
+    state.set_class_hash_at(contract_address, class_hash).unwrap();
+    state.set_compiled_class_hash(class_hash, compiled_class_hash!(1_u8)).unwrap();
+    state.add_visited_pcs(class_hash, &HashSet::from([2]));
+    state.add_visited_pcs(class_hash!(0x2_u8), &HashSet::from([0]));
+    state.get_storage_at(contract_address, storage_key!(0x11_u16)).unwrap();
+    assert_ne!(savepoint_snapshot(&state), outer_snapshot);
+
+    state.rollback_to_savepoint("inner").unwrap();
+    assert_eq!(savepoint_snapshot(&state), outer_snapshot);
+
+    // The savepoint is kept after rolling back to it.
+    state.set_storage_at(contract_address, key, felt!(3_u8)).unwrap();
+    state.rollback_to_savepoint("inner").unwrap();
+    assert_eq!(savepoint_snapshot(&state), outer_snapshot);
+
+    // Rolling back to an outer savepoint discards the inner ones.
+    state.set_storage_at(contract_address, key, felt!(4_u8)).unwrap();
+    state.rollback_to_savepoint("outer").unwrap();
+    assert_eq!(savepoint_snapshot(&state), initial_snapshot);
+    assert_matches!(
+        state.rollback_to_savepoint("inner"),
+        Err(StateError::UnknownSavepoint(name)) if name == "inner"
+    );
+}
+
+#[test]
+fn test_release_savepoint() {
+    let contract_address = contract_address!(CONTRACT_ADDRESS);
+    let key = storage_key!(0x10_u16);
+    let mut state = CachedState::from(DictStateReader::default());
+    let initial_snapshot = savepoint_snapshot(&state);
+
+    state.create_savepoint("outer");
+    state.set_storage_at(contract_address, key, felt!(1_u8)).unwrap();
+    state.create_savepoint("inner");
+    state.set_storage_at(contract_address, key, felt!(2_u8)).unwrap();
+    state.increment_nonce(contract_address).unwrap();
+    let inner_snapshot = savepoint_snapshot(&state);
+
+    // Releasing keeps the changes, which are then undone by rolling back to an outer savepoint.
+    state.release_savepoint("inner").unwrap();
+    assert_eq!(savepoint_snapshot(&state), inner_snapshot);
+    assert_matches!(state.release_savepoint("inner"), Err(StateError::UnknownSavepoint(_)));
+    state.rollback_to_savepoint("outer").unwrap();
+    assert_eq!(savepoint_snapshot(&state), initial_snapshot);
+
+    state.release_savepoint("outer").unwrap();
+    assert_matches!(state.rollback_to_savepoint("outer"), Err(StateError::UnknownSavepoint(_)));
+}
+
+#[test]
+fn test_iterable_cached_state() {
+    let contract_address = contract_address!("0x100");
+    let other_contract_address = contract_address!("0x200");
+    let deployed_contract_address = contract_address!("0x300");
+    let class_hash = class_hash!("0x10");
+    let declared_class_hash = class_hash!("0x20");
+    let contract_class = FeatureContract::Empty(CairoVersion::Cairo0).get_class();
+    let (key_0, key_1, key_2) = (storage_key!("0x1"), storage_key!("0x2"), storage_key!("0x3"));
+    let mut state = CachedState::from(DictStateReader {
+        storage_view: HashMap::from([
+            ((contract_address, key_0), felt!("0x1")),
+            ((contract_address, key_1), felt!("0x2")),
+            ((other_contract_address, key_0), felt!("0x3")),
+        ]),
+        address_to_class_hash: HashMap::from([(contract_address, class_hash)]),
+        class_hash_to_class: HashMap::from([(class_hash, contract_class.clone())]),
+        ..Default::default()
+    });
+
+    // Overwrite, clear and add storage values, deploy a contract and declare a class.
+    state.set_storage_at(contract_address, key_0, felt!("0x4")).unwrap();
+    state.set_storage_at(contract_address, key_1, Felt::ZERO).unwrap();
+    state.set_storage_at(contract_address, key_2, felt!("0x5")).unwrap();
+    state.set_class_hash_at(deployed_contract_address, class_hash).unwrap();
+    state.set_contract_class(declared_class_hash, contract_class).unwrap();
+
+    assert_eq!(
+        state.get_contract_addresses().unwrap(),
+        vec![contract_address, other_contract_address, deployed_contract_address]
+    );
+    assert_eq!(
+        state.get_storage_entries(contract_address).unwrap(),
+        vec![(key_0, felt!("0x4")), (key_2, felt!("0x5"))]
+    );
+    assert_eq!(
+        state.get_storage_entries(other_contract_address).unwrap(),
+        vec![(key_0, felt!("0x3"))]
+    );
+    assert_eq!(state.get_storage_entries(deployed_contract_address).unwrap(), vec![]);
+    assert_eq!(state.get_declared_class_hashes().unwrap(), vec![class_hash, declared_class_hash]);
+}
+
+#[test]
+fn test_batch_reads() {
+    let contract_address = contract_address!("0x100");
+    let other_contract_address = contract_address!("0x200");
+    let (key_0, key_1, key_2) = (storage_key!("0x1"), storage_key!("0x2"), storage_key!("0x3"));
+    let mut state = CachedState::from(DictStateReader {
+        storage_view: HashMap::from([
+            ((contract_address, key_0), felt!("0x1")),
+            ((contract_address, key_1), felt!("0x2")),
+        ]),
+        address_to_nonce: HashMap::from([(contract_address, nonce!(3_u8))]),
+        ..Default::default()
+    });
+    state.set_storage_at(contract_address, key_0, felt!("0x5")).unwrap();
+    state.create_savepoint("batch");
+
+    // Written values are served from the cache; duplicate keys are read once.
+    assert_eq!(
+        state
+            .get_storage_values(&[
+                (contract_address, key_0),
+                (contract_address, key_1),
+                (contract_address, key_2),
+                (contract_address, key_1),
+            ])
+            .unwrap(),
+        vec![felt!("0x5"), felt!("0x2"), Felt::ZERO, felt!("0x2")]
+    );
+    assert_eq!(
+        state.get_nonces(&[contract_address, other_contract_address]).unwrap(),
+        vec![nonce!(3_u8), nonce!(0_u8)]
+    );
+    assert_eq!(
+        state.cache.borrow().initial_reads.storage,
+        HashMap::from([
+            ((contract_address, key_1), felt!("0x2")),
+            ((contract_address, key_2), Felt::ZERO)
+        ])
+    );
+    assert_eq!(
+        state.cache.borrow().initial_reads.nonces,
+        HashMap::from([(contract_address, nonce!(3_u8)), (other_contract_address, nonce!(0_u8))])
+    );
+
+    // Batch reads are tracked by savepoints.
+    state.rollback_to_savepoint("batch").unwrap();
+    assert!(state.cache.borrow().initial_reads.storage.is_empty());
+    assert!(state.cache.borrow().initial_reads.nonces.is_empty());
+}
+
+// A state reader whose batch reads drop the last value.
+struct ShortBatchStateReader(DictStateReader);
+
+impl StateReader for ShortBatchStateReader {
+    fn get_storage_at(
+        &self,
+        contract_address: ContractAddress,
+        key: StorageKey,
+    ) -> StateResult<Felt> {
+        self.0.get_storage_at(contract_address, key)
+    }
+
+    fn get_nonce_at(&self, contract_address: ContractAddress) -> StateResult<Nonce> {
+        self.0.get_nonce_at(contract_address)
+    }
+
+    fn get_storage_values(&self, storage_entries: &[StorageEntry]) -> StateResult<Vec<Felt>> {
+        let mut values = self.0.get_storage_values(storage_entries)?;
+        values.pop();
+        Ok(values)
+    }
+
+    fn get_class_hash_at(&self, contract_address: ContractAddress) -> StateResult<ClassHash> {
+        self.0.get_class_hash_at(contract_address)
+    }
+
+    fn get_compiled_contract_class(&self, class_hash: ClassHash) -> StateResult<ContractClass> {
+        self.0.get_compiled_contract_class(class_hash)
+    }
+
+    fn get_compiled_class_hash(&self, class_hash: ClassHash) -> StateResult<CompiledClassHash> {
+        self.0.get_compiled_class_hash(class_hash)
+    }
+}
+
+#[test]
+fn test_batch_read_length_mismatch() {
+    let contract_address = contract_address!("0x100");
+    let state = CachedState::new(ShortBatchStateReader(DictStateReader::default()));
+
+    assert_matches!(
+        state.get_storage_values(&[
+            (contract_address, storage_key!("0x1")),
+            (contract_address, storage_key!("0x2")),
+        ]),
+        Err(StateError::StateReadError(_))
+    );
+    assert!(state.cache.borrow().initial_reads.storage.is_empty());
+}
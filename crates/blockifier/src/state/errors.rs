@@ -23,9 +23,23 @@ pub enum StateError {
     UnavailableContractAddress(ContractAddress),
     #[error("Class with hash {:#064x} is not declared.", **.0)]
     UndeclaredClassHash(ClassHash),
+    #[error("Savepoint {0} does not exist.")]
+    UnknownSavepoint(String),
     #[error(transparent)]
     StarknetApiError(#[from] StarknetApiError),
     /// Represents all unexpected errors that may occur while reading from state.
     #[error("Failed to read from state: {0}.")]
     StateReadError(String),
 }
+
+#[derive(Debug, Error)]
+pub enum StateFixtureError {
+    #[error(transparent)]
+    BincodeError(#[from] bincode::Error),
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+    #[error(transparent)]
+    SerdeError(#[from] serde_json::Error),
+    #[error(transparent)]
+    StateError(#[from] StateError),
+}
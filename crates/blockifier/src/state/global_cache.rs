@@ -1,12 +1,188 @@
+use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, Mutex, MutexGuard};
 
 use cached::{Cached, SizedCache};
-use starknet_api::core::ClassHash;
+use serde::Serialize;
+use starknet_api::block::BlockNumber;
+use starknet_api::core::{ClassHash, CompiledClassHash, ContractAddress, Nonce};
+use starknet_api::state::StorageKey;
+use starknet_types_core::felt::Felt;
 
 use crate::execution::contract_class::ContractClass;
+use crate::state::cached_state::{CommitmentStateDiff, StorageEntry};
+use crate::state::state_api::{read_batch_through, StateReader, StateResult};
+
+#[cfg(test)]
+#[path = "global_cache_test.rs"]
+mod test;
+
+/// A cached class, along with its estimated size.
+#[derive(Debug)]
+struct CachedContractClass {
+    contract_class: ContractClass,
+    size_in_bytes: usize,
+}
+
+impl CachedContractClass {
+    fn new(contract_class: ContractClass) -> Self {
+        let size_in_bytes = contract_class.estimated_size_in_bytes();
+        Self { contract_class, size_in_bytes }
+    }
+}
+
+/// Counters of the global contract cache; hits, misses and evictions accumulate from its creation.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, Serialize)]
+pub struct GlobalContractCacheMetrics {
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+    /// The number of cached classes, including pinned ones.
+    pub n_classes: usize,
+    /// The estimated size of the cached classes, including pinned ones.
+    pub n_bytes: usize,
+}
+
+/// An LRU cache for contract classes, bounded by both the number of classes and (optionally) their
+/// estimated size in bytes.
+/// Pinned classes are never evicted, and do not count towards these bounds.
+// Note: the key-value types must align with `ContractClassMapping`.
+#[derive(Debug)]
+pub struct ContractClassLRUCache {
+    // Never evicted by the underlying cache; classes are evicted explicitly, to track their size.
+    classes: SizedCache<ClassHash, CachedContractClass>,
+    max_bytes: Option<usize>,
+    // The estimated size of the (unpinned) classes.
+    n_bytes: usize,
+    pinned_class_hashes: HashSet<ClassHash>,
+    pinned_classes: HashMap<ClassHash, CachedContractClass>,
+    metrics: GlobalContractCacheMetrics,
+}
+
+impl ContractClassLRUCache {
+    fn new(cache_size: usize, max_bytes: Option<usize>) -> Self {
+        Self {
+            classes: SizedCache::with_size(cache_size),
+            max_bytes,
+            n_bytes: 0,
+            pinned_class_hashes: HashSet::new(),
+            pinned_classes: HashMap::new(),
+            metrics: GlobalContractCacheMetrics::default(),
+        }
+    }
+
+    pub fn get(&mut self, class_hash: &ClassHash) -> Option<&ContractClass> {
+        let cached_class = match self.pinned_classes.get(class_hash) {
+            Some(cached_class) => Some(cached_class),
+            None => self.classes.cache_get(class_hash),
+        };
+        match cached_class {
+            Some(_) => self.metrics.hits += 1,
+            None => self.metrics.misses += 1,
+        }
+
+        cached_class.map(|cached_class| &cached_class.contract_class)
+    }
+
+    pub fn set(&mut self, class_hash: ClassHash, contract_class: ContractClass) {
+        let cached_class = CachedContractClass::new(contract_class);
+        if self.pinned_class_hashes.contains(&class_hash) {
+            self.pinned_classes.insert(class_hash, cached_class);
+            return;
+        }
+
+        self.remove(&class_hash);
+        if self.max_bytes.is_some_and(|max_bytes| cached_class.size_in_bytes > max_bytes) {
+            // The class would evict the entire cache, and still not fit; nothing is evicted.
+            return;
+        }
+        self.evict_to_fit(cached_class.size_in_bytes);
+        self.n_bytes += cached_class.size_in_bytes;
+        self.classes.cache_set(class_hash, cached_class);
+    }
+
+    /// Replaces the cached class of the given class hash, if present; returns whether it was
+    /// replaced.
+    pub fn replace(&mut self, class_hash: ClassHash, contract_class: ContractClass) -> bool {
+        // Removed rather than looked up, as the lookup would scan the LRU order; the class is
+        // re-inserted right after.
+        let is_cached = match self.classes.cache_remove(&class_hash) {
+            Some(cached_class) => {
+                self.n_bytes -= cached_class.size_in_bytes;
+                true
+            }
+            None => self.pinned_classes.contains_key(&class_hash),
+        };
+        if is_cached {
+            self.set(class_hash, contract_class);
+        }
+
+        is_cached
+    }
+
+    pub fn remove(&mut self, class_hash: &ClassHash) {
+        self.pinned_classes.remove(class_hash);
+        if let Some(cached_class) = self.classes.cache_remove(class_hash) {
+            self.n_bytes -= cached_class.size_in_bytes;
+        }
+    }
+
+    /// Removes all classes; pinned class hashes remain pinned.
+    pub fn clear(&mut self) {
+        self.pinned_classes.clear();
+        self.classes.cache_clear();
+        self.n_bytes = 0;
+    }
+
+    /// Pins the given class hash, whether its class is currently cached or not.
+    pub fn pin(&mut self, class_hash: ClassHash) {
+        self.pinned_class_hashes.insert(class_hash);
+        if let Some(cached_class) = self.classes.cache_remove(&class_hash) {
+            self.n_bytes -= cached_class.size_in_bytes;
+            self.pinned_classes.insert(class_hash, cached_class);
+        }
+    }
+
+    /// Unpins the given class hash; its class, if cached, becomes the most recently used one.
+    pub fn unpin(&mut self, class_hash: &ClassHash) {
+        self.pinned_class_hashes.remove(class_hash);
+        if let Some(cached_class) = self.pinned_classes.remove(class_hash) {
+            self.set(*class_hash, cached_class.contract_class);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.pinned_classes.len() + self.classes.cache_size()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn metrics(&self) -> GlobalContractCacheMetrics {
+        let pinned_bytes: usize =
+            self.pinned_classes.values().map(|cached_class| cached_class.size_in_bytes).sum();
+        GlobalContractCacheMetrics {
+            n_classes: self.len(),
+            n_bytes: self.n_bytes + pinned_bytes,
+            ..self.metrics
+        }
+    }
+
+    /// Evicts least recently used classes, until a class of the given size fits in the cache.
+    fn evict_to_fit(&mut self, size_in_bytes: usize) {
+        let capacity = self.classes.cache_capacity().expect("The cache should be size-bounded.");
+        while self.classes.cache_size() >= capacity
+            || self.max_bytes.is_some_and(|max_bytes| self.n_bytes + size_in_bytes > max_bytes)
+        {
+            let Some(&lru_class_hash) = self.classes.key_order().last() else {
+                return;
+            };
+            self.remove(&lru_class_hash);
+            self.metrics.evictions += 1;
+        }
+    }
+}
 
-// Note: `ContractClassLRUCache` key-value types must align with `ContractClassMapping`.
-type ContractClassLRUCache = SizedCache<ClassHash, ContractClass>;
 pub type LockedContractClassCache<'a> = MutexGuard<'a, ContractClassLRUCache>;
 #[derive(Debug, Clone)]
 // Thread-safe LRU cache for contract classes, optimized for inter-language sharing when
@@ -24,18 +200,263 @@ impl GlobalContractCache {
     }
 
     pub fn get(&self, class_hash: &ClassHash) -> Option<ContractClass> {
-        self.lock().cache_get(class_hash).cloned()
+        self.lock().get(class_hash).cloned()
     }
 
     pub fn set(&self, class_hash: ClassHash, contract_class: ContractClass) {
-        self.lock().cache_set(class_hash, contract_class);
+        self.lock().set(class_hash, contract_class);
+    }
+
+    /// Replaces the cached class of the given class hash, if present; returns whether it was
+    /// replaced. Classes that were evicted (or cleared) in the meantime are not re-inserted.
+    pub fn replace(&self, class_hash: ClassHash, contract_class: ContractClass) -> bool {
+        self.lock().replace(class_hash, contract_class)
+    }
+
+    pub fn remove(&self, class_hash: &ClassHash) {
+        self.lock().remove(class_hash);
     }
 
     pub fn clear(&mut self) {
-        self.lock().cache_clear();
+        self.lock().clear();
+    }
+
+    pub fn pin(&self, class_hash: ClassHash) {
+        self.lock().pin(class_hash);
+    }
+
+    pub fn unpin(&self, class_hash: &ClassHash) {
+        self.lock().unpin(class_hash);
+    }
+
+    pub fn metrics(&self) -> GlobalContractCacheMetrics {
+        self.lock().metrics()
+    }
+
+    pub fn new(cache_size: usize) -> Self {
+        Self::new_with_max_bytes(cache_size, None)
+    }
+
+    /// Creates a cache that also evicts classes once their estimated total size exceeds
+    /// `max_bytes`, if given.
+    pub fn new_with_max_bytes(cache_size: usize, max_bytes: Option<usize>) -> Self {
+        Self(Arc::new(Mutex::new(ContractClassLRUCache::new(cache_size, max_bytes))))
     }
+}
+
+type StorageLRUCache = SizedCache<(ContractAddress, StorageKey), Felt>;
+type NonceLRUCache = SizedCache<ContractAddress, Nonce>;
+pub type LockedStateCache<'a> = MutexGuard<'a, StateLRUCache>;
+
+#[derive(Debug)]
+pub struct StateLRUCache {
+    // The block on top of whose state the cached values were read; values are only served to
+    // readers of that block.
+    block_number: Option<BlockNumber>,
+    pub storage: StorageLRUCache,
+    pub nonces: NonceLRUCache,
+}
+
+impl StateLRUCache {
+    fn clear(&mut self) {
+        self.block_number = None;
+        self.storage.cache_clear();
+        self.nonces.cache_clear();
+    }
+}
+
+/// Thread-safe LRU cache for storage values and nonces, shared across blocks.
+/// The cache follows the chain: it is updated with the state diff of each committed block, and must
+/// be cleared whenever a block is reverted.
+#[derive(Debug, Clone)]
+pub struct GlobalStateCache(pub Arc<Mutex<StateLRUCache>>);
 
+impl GlobalStateCache {
+    /// Creates a cache holding up to `cache_size` storage values and `cache_size` nonces.
     pub fn new(cache_size: usize) -> Self {
-        Self(Arc::new(Mutex::new(ContractClassLRUCache::with_size(cache_size))))
+        Self(Arc::new(Mutex::new(StateLRUCache {
+            block_number: None,
+            storage: StorageLRUCache::with_size(cache_size),
+            nonces: NonceLRUCache::with_size(cache_size),
+        })))
+    }
+
+    /// Locks the cache for atomic access.
+    pub fn lock(&self) -> LockedStateCache<'_> {
+        self.0.lock().expect("Global state cache is poisoned.")
+    }
+
+    pub fn get_storage_at(
+        &self,
+        block_number: BlockNumber,
+        contract_address: ContractAddress,
+        key: StorageKey,
+    ) -> Option<Felt> {
+        let mut cache = self.lock();
+        if cache.block_number != Some(block_number) {
+            return None;
+        }
+        cache.storage.cache_get(&(contract_address, key)).copied()
+    }
+
+    /// Caches a storage value read on top of the state of the given block. Ignored if the cache
+    /// has moved to a different block in the meantime.
+    pub fn set_storage_at(
+        &self,
+        block_number: BlockNumber,
+        contract_address: ContractAddress,
+        key: StorageKey,
+        value: Felt,
+    ) {
+        let mut cache = self.lock();
+        if cache.block_number == Some(block_number) {
+            cache.storage.cache_set((contract_address, key), value);
+        }
+    }
+
+    pub fn get_nonce_at(
+        &self,
+        block_number: BlockNumber,
+        contract_address: ContractAddress,
+    ) -> Option<Nonce> {
+        let mut cache = self.lock();
+        if cache.block_number != Some(block_number) {
+            return None;
+        }
+        cache.nonces.cache_get(&contract_address).copied()
+    }
+
+    /// Caches a nonce read on top of the state of the given block. Ignored if the cache has moved
+    /// to a different block in the meantime.
+    pub fn set_nonce_at(
+        &self,
+        block_number: BlockNumber,
+        contract_address: ContractAddress,
+        nonce: Nonce,
+    ) {
+        let mut cache = self.lock();
+        if cache.block_number == Some(block_number) {
+            cache.nonces.cache_set(contract_address, nonce);
+        }
+    }
+
+    /// Applies the state diff of the given (committed) block, so that the cache serves readers of
+    /// the next block. If the cache did not follow the given block, it is cleared first.
+    pub fn apply_state_diff(&self, block_number: BlockNumber, state_diff: &CommitmentStateDiff) {
+        let mut cache = self.lock();
+        if cache.block_number != Some(block_number) {
+            cache.clear();
+        }
+
+        for (contract_address, storage_updates) in &state_diff.storage_updates {
+            for (key, value) in storage_updates {
+                cache.storage.cache_set((*contract_address, *key), *value);
+            }
+        }
+        for (contract_address, nonce) in &state_diff.address_to_nonce {
+            cache.nonces.cache_set(*contract_address, *nonce);
+        }
+        cache.block_number = Some(BlockNumber(block_number.0 + 1));
+    }
+
+    /// Clears the cache; it serves no reader until the next state diff is applied.
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+}
+
+/// Serves storage values and nonces from a global state cache, if given, and delegates all other
+/// reads to the wrapped reader, which reads the state on top of which the given block is executed.
+pub struct GlobalStateCacheReader<S: StateReader> {
+    pub state_reader: S,
+    block_number: BlockNumber,
+    global_state_cache: Option<GlobalStateCache>,
+}
+
+impl<S: StateReader> GlobalStateCacheReader<S> {
+    pub fn new(
+        state_reader: S,
+        block_number: BlockNumber,
+        global_state_cache: Option<GlobalStateCache>,
+    ) -> Self {
+        Self { state_reader, block_number, global_state_cache }
+    }
+}
+
+impl<S: StateReader> StateReader for GlobalStateCacheReader<S> {
+    fn get_storage_at(
+        &self,
+        contract_address: ContractAddress,
+        key: StorageKey,
+    ) -> StateResult<Felt> {
+        let Some(global_state_cache) = &self.global_state_cache else {
+            return self.state_reader.get_storage_at(contract_address, key);
+        };
+        if let Some(value) =
+            global_state_cache.get_storage_at(self.block_number, contract_address, key)
+        {
+            return Ok(value);
+        }
+
+        let value = self.state_reader.get_storage_at(contract_address, key)?;
+        global_state_cache.set_storage_at(self.block_number, contract_address, key, value);
+        Ok(value)
+    }
+
+    fn get_nonce_at(&self, contract_address: ContractAddress) -> StateResult<Nonce> {
+        let Some(global_state_cache) = &self.global_state_cache else {
+            return self.state_reader.get_nonce_at(contract_address);
+        };
+        if let Some(nonce) = global_state_cache.get_nonce_at(self.block_number, contract_address) {
+            return Ok(nonce);
+        }
+
+        let nonce = self.state_reader.get_nonce_at(contract_address)?;
+        global_state_cache.set_nonce_at(self.block_number, contract_address, nonce);
+        Ok(nonce)
+    }
+
+    fn get_storage_values(&self, storage_entries: &[StorageEntry]) -> StateResult<Vec<Felt>> {
+        let Some(global_state_cache) = &self.global_state_cache else {
+            return self.state_reader.get_storage_values(storage_entries);
+        };
+        read_batch_through(
+            storage_entries,
+            |(contract_address, key)| {
+                global_state_cache.get_storage_at(self.block_number, *contract_address, *key)
+            },
+            |missing_entries| self.state_reader.get_storage_values(missing_entries),
+            |(contract_address, key), value| {
+                global_state_cache.set_storage_at(self.block_number, *contract_address, *key, value)
+            },
+        )
+    }
+
+    fn get_nonces(&self, contract_addresses: &[ContractAddress]) -> StateResult<Vec<Nonce>> {
+        let Some(global_state_cache) = &self.global_state_cache else {
+            return self.state_reader.get_nonces(contract_addresses);
+        };
+        read_batch_through(
+            contract_addresses,
+            |contract_address| {
+                global_state_cache.get_nonce_at(self.block_number, *contract_address)
+            },
+            |missing_addresses| self.state_reader.get_nonces(missing_addresses),
+            |contract_address, nonce| {
+                global_state_cache.set_nonce_at(self.block_number, *contract_address, nonce)
+            },
+        )
+    }
+
+    fn get_class_hash_at(&self, contract_address: ContractAddress) -> StateResult<ClassHash> {
+        self.state_reader.get_class_hash_at(contract_address)
+    }
+
+    fn get_compiled_contract_class(&self, class_hash: ClassHash) -> StateResult<ContractClass> {
+        self.state_reader.get_compiled_contract_class(class_hash)
+    }
+
+    fn get_compiled_class_hash(&self, class_hash: ClassHash) -> StateResult<CompiledClassHash> {
+        self.state_reader.get_compiled_class_hash(class_hash)
     }
 }
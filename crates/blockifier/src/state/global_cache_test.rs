@@ -0,0 +1,174 @@
+use std::mem;
+
+use cached::Cached;
+use cairo_vm::types::relocatable::MaybeRelocatable;
+use pretty_assertions::assert_eq;
+use starknet_api::block::BlockNumber;
+use starknet_api::core::{ContractAddress, PatriciaKey};
+use starknet_api::state::StorageKey;
+use starknet_api::{class_hash, contract_address, felt, patricia_key};
+
+use crate::state::cached_state::{CommitmentStateDiff, StateMaps};
+use crate::state::global_cache::{
+    GlobalContractCache, GlobalContractCacheMetrics, GlobalStateCache, GlobalStateCacheReader,
+    GLOBAL_CONTRACT_CACHE_SIZE_FOR_TEST,
+};
+use crate::state::state_api::StateReader;
+use crate::test_utils::contracts::FeatureContract;
+use crate::test_utils::dict_state_reader::DictStateReader;
+use crate::test_utils::CairoVersion;
+use crate::{nonce, storage_key};
+
+#[test]
+fn test_global_contract_cache_evicts_by_size() {
+    let small_class = FeatureContract::Empty(CairoVersion::Cairo1).get_class();
+    let large_class = FeatureContract::TestContract(CairoVersion::Cairo1).get_class();
+    let small_size = small_class.estimated_size_in_bytes();
+    let large_size = large_class.estimated_size_in_bytes();
+    assert!(small_size < large_size);
+
+    let global_contract_cache = GlobalContractCache::new_with_max_bytes(
+        GLOBAL_CONTRACT_CACHE_SIZE_FOR_TEST,
+        Some(small_size + large_size),
+    );
+    global_contract_cache.set(class_hash!("0x1"), small_class.clone());
+    global_contract_cache.set(class_hash!("0x2"), small_class.clone());
+    // Use the first class, so that the second one is the least recently used.
+    assert_eq!(global_contract_cache.get(&class_hash!("0x1")), Some(small_class.clone()));
+
+    // Only the least recently used class is evicted to make room for the large one.
+    global_contract_cache.set(class_hash!("0x3"), large_class.clone());
+    assert_eq!(global_contract_cache.get(&class_hash!("0x2")), None);
+    assert_eq!(global_contract_cache.get(&class_hash!("0x1")), Some(small_class.clone()));
+    assert_eq!(global_contract_cache.get(&class_hash!("0x3")), Some(large_class.clone()));
+    assert_eq!(
+        global_contract_cache.metrics(),
+        GlobalContractCacheMetrics {
+            hits: 3,
+            misses: 1,
+            evictions: 1,
+            n_classes: 2,
+            n_bytes: small_size + large_size,
+        }
+    );
+
+    // Classes that cannot fit are not cached, and evict nothing.
+    let global_contract_cache = GlobalContractCache::new_with_max_bytes(
+        GLOBAL_CONTRACT_CACHE_SIZE_FOR_TEST,
+        Some(small_size),
+    );
+    global_contract_cache.set(class_hash!("0x1"), small_class.clone());
+    global_contract_cache.set(class_hash!("0x3"), large_class.clone());
+    assert_eq!(global_contract_cache.get(&class_hash!("0x3")), None);
+    assert_eq!(global_contract_cache.get(&class_hash!("0x1")), Some(small_class.clone()));
+    assert_eq!(global_contract_cache.metrics().evictions, 0);
+
+    // Replacing a class updates the estimated size; uncached classes are not replaced.
+    let global_contract_cache = GlobalContractCache::new_with_max_bytes(
+        GLOBAL_CONTRACT_CACHE_SIZE_FOR_TEST,
+        Some(small_size + large_size),
+    );
+    global_contract_cache.set(class_hash!("0x1"), small_class);
+    assert!(global_contract_cache.replace(class_hash!("0x1"), large_class.clone()));
+    assert!(!global_contract_cache.replace(class_hash!("0x2"), large_class.clone()));
+    assert_eq!(global_contract_cache.get(&class_hash!("0x1")), Some(large_class));
+    assert_eq!(global_contract_cache.get(&class_hash!("0x2")), None);
+    assert_eq!(global_contract_cache.metrics().n_bytes, large_size);
+}
+
+#[test]
+fn test_cairo0_class_size_estimation() {
+    // The estimated size of Cairo 0 classes accounts for their hints and identifiers, on top of
+    // their bytecode.
+    let contract_class = FeatureContract::TestContract(CairoVersion::Cairo0).get_class();
+    let bytecode_size = contract_class.bytecode_length() * mem::size_of::<MaybeRelocatable>();
+    assert!(contract_class.estimated_size_in_bytes() > 2 * bytecode_size);
+}
+
+#[test]
+fn test_global_contract_cache_pinning() {
+    let contract_class = FeatureContract::Empty(CairoVersion::Cairo0).get_class();
+    let global_contract_cache = GlobalContractCache::new(1);
+    global_contract_cache.pin(class_hash!("0x1"));
+    for class_hash in [class_hash!("0x1"), class_hash!("0x2"), class_hash!("0x3")] {
+        global_contract_cache.set(class_hash, contract_class.clone());
+    }
+
+    // Pinned classes are not evicted, and do not count towards the cache size.
+    assert!(global_contract_cache.get(&class_hash!("0x1")).is_some());
+    assert!(global_contract_cache.get(&class_hash!("0x2")).is_none());
+    assert!(global_contract_cache.get(&class_hash!("0x3")).is_some());
+    assert_eq!(global_contract_cache.metrics().n_classes, 2);
+
+    // Once unpinned, classes are evictable.
+    global_contract_cache.unpin(&class_hash!("0x1"));
+    assert!(global_contract_cache.get(&class_hash!("0x3")).is_none());
+    assert!(global_contract_cache.get(&class_hash!("0x1")).is_some());
+    assert_eq!(global_contract_cache.metrics().evictions, 2);
+}
+
+fn state_diff(
+    contract_address: ContractAddress,
+    key: StorageKey,
+    value: u8,
+) -> CommitmentStateDiff {
+    StateMaps {
+        storage: [((contract_address, key), felt!(value))].into(),
+        nonces: [(contract_address, nonce!(value))].into(),
+        ..Default::default()
+    }
+    .into()
+}
+
+#[test]
+fn test_global_state_cache_follows_blocks() {
+    let contract_address = contract_address!("0x1");
+    let key = storage_key!("0x2");
+    let global_state_cache = GlobalStateCache::new(GLOBAL_CONTRACT_CACHE_SIZE_FOR_TEST);
+    let mut dict_state_reader = DictStateReader::default();
+    dict_state_reader.storage_view.insert((contract_address, key), felt!(1_u8));
+    dict_state_reader.address_to_nonce.insert(contract_address, nonce!(1_u8));
+    let reader_at = |block_number: u64, state_reader: DictStateReader| {
+        GlobalStateCacheReader::new(
+            state_reader,
+            BlockNumber(block_number),
+            Some(global_state_cache.clone()),
+        )
+    };
+
+    // Nothing is cached before the cache follows a block.
+    let mut reader = reader_at(10, dict_state_reader.clone());
+    assert_eq!(reader.get_storage_at(contract_address, key).unwrap(), felt!(1_u8));
+    assert_eq!(global_state_cache.lock().storage.cache_size(), 0);
+
+    // Once block 9 is finalized, reads on top of it are cached.
+    global_state_cache.apply_state_diff(BlockNumber(9), &StateMaps::default().into());
+    assert_eq!(reader.get_storage_at(contract_address, key).unwrap(), felt!(1_u8));
+    assert_eq!(reader.get_nonce_at(contract_address).unwrap(), nonce!(1_u8));
+    reader.state_reader.storage_view.insert((contract_address, key), felt!(5_u8));
+    reader.state_reader.address_to_nonce.insert(contract_address, nonce!(5_u8));
+    assert_eq!(reader.get_storage_at(contract_address, key).unwrap(), felt!(1_u8));
+    assert_eq!(reader.get_nonce_at(contract_address).unwrap(), nonce!(1_u8));
+
+    // Readers of other blocks are not served from the cache.
+    let reader_of_next_block = reader_at(11, reader.state_reader.clone());
+    assert_eq!(reader_of_next_block.get_storage_at(contract_address, key).unwrap(), felt!(5_u8));
+
+    // Finalizing block 10 moves the cache to the next block.
+    global_state_cache.apply_state_diff(BlockNumber(10), &state_diff(contract_address, key, 7));
+    assert_eq!(reader_of_next_block.get_storage_at(contract_address, key).unwrap(), felt!(7_u8));
+    assert_eq!(reader_of_next_block.get_nonce_at(contract_address).unwrap(), nonce!(7_u8));
+    assert_eq!(reader.get_storage_at(contract_address, key).unwrap(), felt!(5_u8));
+
+    // Applying a diff of a block the cache did not follow drops the previously cached values.
+    global_state_cache.apply_state_diff(BlockNumber(20), &StateMaps::default().into());
+    let reader_after_gap = reader_at(21, dict_state_reader.clone());
+    assert_eq!(reader_after_gap.get_storage_at(contract_address, key).unwrap(), felt!(1_u8));
+
+    // Reverting clears the cache.
+    global_state_cache.clear();
+    assert_eq!(global_state_cache.lock().storage.cache_size(), 0);
+    let mut reader_after_revert = reader_at(21, dict_state_reader);
+    reader_after_revert.state_reader.storage_view.insert((contract_address, key), felt!(9_u8));
+    assert_eq!(reader_after_revert.get_storage_at(contract_address, key).unwrap(), felt!(9_u8));
+}
@@ -0,0 +1,345 @@
+use std::cell::OnceCell;
+use std::fmt::{self, Debug};
+use std::marker::PhantomData;
+
+use serde::{Deserialize, Serialize};
+use starknet_types_core::felt::Felt;
+use starknet_types_core::hash::StarkHash;
+
+/// The height of Starknet's Merkle-Patricia trees; keys are 251-bit.
+pub const TREE_HEIGHT: usize = 251;
+
+/// A node on the path from a tree's root to a leaf, as given in proofs.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum ProofNode {
+    Binary {
+        left: Felt,
+        right: Felt,
+    },
+    /// A path of the given length, skipping over empty subtrees.
+    Edge {
+        child: Felt,
+        path: Felt,
+        length: u8,
+    },
+}
+
+impl ProofNode {
+    pub fn hash<H: StarkHash>(&self) -> Felt {
+        match self {
+            Self::Binary { left, right } => H::hash(left, right),
+            Self::Edge { child, path, length } => H::hash(child, path) + Felt::from(*length),
+        }
+    }
+}
+
+/// A binary Merkle-Patricia tree of height 251, as used for Starknet's state commitment, over the
+/// given hash function.
+/// Zero leaves are omitted, as they are indistinguishable from empty ones. Node hashes are cached:
+/// an update only invalidates those on the path to its leaf, which are recomputed on demand.
+pub struct PatriciaTree<H> {
+    root: Option<Node>,
+    hash_function: PhantomData<H>,
+}
+
+impl<H> Default for PatriciaTree<H> {
+    fn default() -> Self {
+        Self { root: None, hash_function: PhantomData }
+    }
+}
+
+impl<H> Clone for PatriciaTree<H> {
+    fn clone(&self) -> Self {
+        Self { root: self.root.clone(), hash_function: PhantomData }
+    }
+}
+
+impl<H> Debug for PatriciaTree<H> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PatriciaTree").field("root", &self.root).finish()
+    }
+}
+
+impl<H: StarkHash> PatriciaTree<H> {
+    pub fn get(&self, key: &Felt) -> Felt {
+        let Some(mut node) = self.root.as_ref() else {
+            return Felt::ZERO;
+        };
+
+        let key_bits = key.to_bytes_be();
+        let mut depth = 0;
+        loop {
+            match node {
+                Node::Leaf(value) => return *value,
+                Node::Binary { left, right, .. } => {
+                    node = if bit(&key_bits, depth) { right } else { left };
+                    depth += 1;
+                }
+                Node::Edge { path, length, child, .. } => {
+                    if path_of(&key_bits, depth, usize::from(*length)) != *path {
+                        return Felt::ZERO;
+                    }
+                    node = child;
+                    depth += usize::from(*length);
+                }
+            }
+        }
+    }
+
+    pub fn insert(&mut self, key: Felt, value: Felt) {
+        let key_bits = key.to_bytes_be();
+        let root = self.root.take();
+        self.root = if value == Felt::ZERO {
+            root.and_then(|root| root.remove(&key_bits, 0))
+        } else {
+            Some(Node::insert(root, &key_bits, 0, value))
+        };
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.root.is_none()
+    }
+
+    /// Returns the root of the tree; zero if empty.
+    pub fn root(&self) -> Felt {
+        self.root.as_ref().map_or(Felt::ZERO, Node::hash::<H>)
+    }
+
+    /// Returns, for each of the given keys, the nodes on the path from the root towards its leaf.
+    /// For keys not in the tree, the path ends with the edge node that diverges from the key.
+    pub fn proofs(&self, keys: &[Felt]) -> Vec<Vec<ProofNode>> {
+        let Some(root) = self.root.as_ref() else {
+            return vec![vec![]; keys.len()];
+        };
+
+        keys.iter()
+            .map(|key| {
+                let key_bits = key.to_bytes_be();
+                let (mut node, mut depth) = (root, 0);
+                let mut proof = vec![];
+                loop {
+                    match node {
+                        Node::Leaf(_) => break,
+                        Node::Binary { left, right, .. } => {
+                            proof.push(ProofNode::Binary {
+                                left: left.hash::<H>(),
+                                right: right.hash::<H>(),
+                            });
+                            node = if bit(&key_bits, depth) { right } else { left };
+                            depth += 1;
+                        }
+                        Node::Edge { path, length, child, .. } => {
+                            proof.push(ProofNode::Edge {
+                                child: child.hash::<H>(),
+                                path: *path,
+                                length: *length,
+                            });
+                            if path_of(&key_bits, depth, usize::from(*length)) != *path {
+                                break;
+                            }
+                            node = child;
+                            depth += usize::from(*length);
+                        }
+                    }
+                }
+                proof
+            })
+            .collect()
+    }
+
+    /// Verifies the given proof (see [Self::proofs]) against the given root, and returns the
+    /// proven value of the given key: zero if it is proven not to be in the tree.
+    /// Returns `None` if the proof is invalid.
+    pub fn verify_proof(root: Felt, key: Felt, proof: &[ProofNode]) -> Option<Felt> {
+        if proof.is_empty() {
+            return (root == Felt::ZERO).then_some(Felt::ZERO);
+        }
+
+        let key_bits = key.to_bytes_be();
+        let (mut expected_hash, mut depth) = (root, 0);
+        for (i, node) in proof.iter().enumerate() {
+            if depth >= TREE_HEIGHT || node.hash::<H>() != expected_hash {
+                return None;
+            }
+            match node {
+                ProofNode::Binary { left, right } => {
+                    expected_hash = if bit(&key_bits, depth) { *right } else { *left };
+                    depth += 1;
+                }
+                ProofNode::Edge { child, path, length } => {
+                    let length = usize::from(*length);
+                    if length == 0 || depth + length > TREE_HEIGHT {
+                        return None;
+                    }
+                    if path_of(&key_bits, depth, length) != *path {
+                        // The key diverges from the only path in this subtree.
+                        return (i == proof.len() - 1).then_some(Felt::ZERO);
+                    }
+                    expected_hash = *child;
+                    depth += length;
+                }
+            }
+        }
+
+        (depth == TREE_HEIGHT).then_some(expected_hash)
+    }
+}
+
+/// A node of a (non-empty) subtree; the hash is cached once computed, and is reset by rebuilding
+/// the node.
+#[derive(Clone, Debug)]
+enum Node {
+    Leaf(Felt),
+    Binary {
+        left: Box<Node>,
+        right: Box<Node>,
+        hash: OnceCell<Felt>,
+    },
+    /// Never leads to another edge.
+    Edge {
+        path: Felt,
+        length: u8,
+        child: Box<Node>,
+        hash: OnceCell<Felt>,
+    },
+}
+
+impl Node {
+    fn hash<H: StarkHash>(&self) -> Felt {
+        match self {
+            Self::Leaf(value) => *value,
+            Self::Binary { left, right, hash } => {
+                *hash.get_or_init(|| H::hash(&left.hash::<H>(), &right.hash::<H>()))
+            }
+            Self::Edge { path, length, child, hash } => {
+                *hash.get_or_init(|| H::hash(&child.hash::<H>(), path) + Felt::from(*length))
+            }
+        }
+    }
+
+    /// Sets the given leaf in the given subtree, whose keys share their first `depth` bits with the
+    /// given key. Only the nodes on the path to the leaf are rebuilt.
+    fn insert(node: Option<Self>, key: &[u8; 32], depth: usize, value: Felt) -> Self {
+        let Some(node) = node else {
+            let length = TREE_HEIGHT - depth;
+            return Self::edge(path_of(key, depth, length), length, Self::Leaf(value));
+        };
+
+        match node {
+            Self::Leaf(_) => Self::Leaf(value),
+            Self::Binary { left, right, .. } => {
+                if bit(key, depth) {
+                    Self::binary(*left, Self::insert(Some(*right), key, depth + 1, value))
+                } else {
+                    Self::binary(Self::insert(Some(*left), key, depth + 1, value), *right)
+                }
+            }
+            Self::Edge { path, length, child, .. } => {
+                let length = usize::from(length);
+                let path_bits = path.to_bytes_be();
+                let path_start = 256 - length;
+                let common_length = (0..length)
+                    .take_while(|i| bit_at(&path_bits, path_start + i) == bit(key, depth + i))
+                    .count();
+                if common_length == length {
+                    let child = Self::insert(Some(*child), key, depth + length, value);
+                    return Self::edge(path, length, child);
+                }
+
+                // Split the edge where the key diverges from it.
+                let suffix_length = length - common_length - 1;
+                let existing = Self::edge(
+                    bits_at(&path_bits, path_start + common_length + 1, suffix_length),
+                    suffix_length,
+                    *child,
+                );
+                let new = Self::insert(None, key, depth + common_length + 1, value);
+                let binary = if bit(key, depth + common_length) {
+                    Self::binary(existing, new)
+                } else {
+                    Self::binary(new, existing)
+                };
+                Self::edge(bits_at(&path_bits, path_start, common_length), common_length, binary)
+            }
+        }
+    }
+
+    /// Removes the given key's leaf from the subtree at the given depth; returns `None` if the
+    /// subtree becomes empty. Only the nodes on the path to the leaf are rebuilt.
+    fn remove(self, key: &[u8; 32], depth: usize) -> Option<Self> {
+        match self {
+            Self::Leaf(_) => None,
+            Self::Binary { left, right, .. } => {
+                let (left, right) = if bit(key, depth) {
+                    (Some(*left), Self::remove(*right, key, depth + 1))
+                } else {
+                    (Self::remove(*left, key, depth + 1), Some(*right))
+                };
+                match (left, right) {
+                    (Some(left), Some(right)) => Some(Self::binary(left, right)),
+                    (Some(left), None) => Some(Self::edge(Felt::ZERO, 1, left)),
+                    (None, Some(right)) => Some(Self::edge(Felt::ONE, 1, right)),
+                    (None, None) => unreachable!("Both children of a binary node are non-empty."),
+                }
+            }
+            Self::Edge { path, length, child, hash } => {
+                if path_of(key, depth, usize::from(length)) != path {
+                    return Some(Self::Edge { path, length, child, hash });
+                }
+                let child = Self::remove(*child, key, depth + usize::from(length))?;
+                Some(Self::edge(path, usize::from(length), child))
+            }
+        }
+    }
+
+    fn binary(left: Self, right: Self) -> Self {
+        Self::Binary { left: Box::new(left), right: Box::new(right), hash: OnceCell::new() }
+    }
+
+    /// Returns an edge of the given path to the given child, merged with the child if it is an
+    /// edge itself.
+    fn edge(path: Felt, length: usize, child: Self) -> Self {
+        if length == 0 {
+            return child;
+        }
+
+        let (path, length, child) = match child {
+            Self::Edge { path: child_path, length: child_length, child, .. } => (
+                path * Felt::TWO.pow(u128::from(child_length)) + child_path,
+                length + usize::from(child_length),
+                child,
+            ),
+            child => (path, length, Box::new(child)),
+        };
+        let length = u8::try_from(length).expect("Tree height fits in u8.");
+        Self::Edge { path, length, child, hash: OnceCell::new() }
+    }
+}
+
+// Returns the bit of the given (big-endian) key at the given depth, counted from the root.
+fn bit(key: &[u8; 32], depth: usize) -> bool {
+    bit_at(key, 256 - TREE_HEIGHT + depth)
+}
+
+// Returns the bits of the given key at the given range of depths, as a number.
+fn path_of(key: &[u8; 32], depth: usize, length: usize) -> Felt {
+    bits_at(key, 256 - TREE_HEIGHT + depth, length)
+}
+
+// Returns the bit of the given big-endian number at the given index, counted from the most
+// significant bit.
+fn bit_at(bytes: &[u8; 32], index: usize) -> bool {
+    (bytes[index / 8] >> (7 - index % 8)) & 1 == 1
+}
+
+// Returns the bits of the given big-endian number at the given range of indices, as a number.
+fn bits_at(bytes: &[u8; 32], index: usize, length: usize) -> Felt {
+    let mut bits = [0_u8; 32];
+    for i in 0..length {
+        if bit_at(bytes, index + i) {
+            let bits_index = 256 - length + i;
+            bits[bits_index / 8] |= 1 << (7 - bits_index % 8);
+        }
+    }
+    Felt::from_bytes_be(&bits)
+}
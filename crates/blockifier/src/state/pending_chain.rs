@@ -0,0 +1,260 @@
+use std::sync::Arc;
+
+use starknet_api::block::BlockNumber;
+use starknet_api::core::{ClassHash, CompiledClassHash, ContractAddress, Nonce};
+use starknet_api::state::StorageKey;
+use starknet_types_core::felt::Felt;
+use thiserror::Error;
+
+use crate::execution::contract_class::ContractClass;
+use crate::state::cached_state::{CachedState, ContractClassMapping, StateMaps, StorageEntry};
+use crate::state::errors::StateError;
+use crate::state::global_cache::GlobalContractCache;
+use crate::state::state_api::{read_batch_through, StateReader, StateResult};
+
+#[cfg(test)]
+#[path = "pending_chain_test.rs"]
+mod test;
+
+#[derive(Debug, Error)]
+pub enum PendingChainError {
+    #[error("The given block state is not built on top of the tip of the chain.")]
+    StaleBlockState,
+    #[error(transparent)]
+    StateError(#[from] StateError),
+    #[error("Block {0:?} is not pending.")]
+    UnknownBlock(BlockNumber),
+}
+
+pub type PendingChainResult<T> = Result<T, PendingChainError>;
+
+/// A block that was executed on top of the pending chain, but is not yet committed to the base
+/// state.
+#[derive(Debug)]
+pub struct PendingBlock {
+    pub block_number: BlockNumber,
+    pub state_diff: StateMaps,
+    /// The classes declared in this block.
+    pub declared_classes: ContractClassMapping,
+}
+
+/// An in-memory chain of pending blocks on top of a base (committed) state; each block is executed
+/// on the state resulting from its predecessors.
+/// Any suffix of the chain may be dropped (e.g., on reorg), and its prefix may be dropped once it
+/// is committed to the base state.
+pub struct PendingChain<S: StateReader> {
+    base_state_reader: Arc<S>,
+    // The number of the first block on top of the base state.
+    first_block_number: BlockNumber,
+    pending_blocks: Vec<Arc<PendingBlock>>,
+    // Classes declared in dropped blocks are removed from this cache.
+    global_contract_cache: Option<GlobalContractCache>,
+}
+
+impl<S: StateReader> PendingChain<S> {
+    pub fn new(
+        base_state_reader: S,
+        first_block_number: BlockNumber,
+        global_contract_cache: Option<GlobalContractCache>,
+    ) -> Self {
+        Self {
+            base_state_reader: Arc::new(base_state_reader),
+            first_block_number,
+            pending_blocks: Vec::new(),
+            global_contract_cache,
+        }
+    }
+
+    pub fn pending_blocks(&self) -> &[Arc<PendingBlock>] {
+        &self.pending_blocks
+    }
+
+    /// Returns the number of the block to be executed on top of the chain.
+    pub fn next_block_number(&self) -> BlockNumber {
+        BlockNumber(self.first_block_number.0 + self.pending_blocks.len() as u64)
+    }
+
+    /// Returns a state for executing the next block, on top of all pending blocks.
+    pub fn next_block_state(&self) -> CachedState<PendingChainReader<S>> {
+        CachedState::new(PendingChainReader {
+            base_state_reader: self.base_state_reader.clone(),
+            block_number: self.next_block_number(),
+            pending_blocks: self.pending_blocks.clone(),
+        })
+    }
+
+    /// Appends the given executed block state (see [Self::next_block_state]) to the chain, and
+    /// returns its block number.
+    /// Fails if other blocks were appended or dropped since the state was created.
+    pub fn append_block(
+        &mut self,
+        block_state: &mut CachedState<PendingChainReader<S>>,
+    ) -> PendingChainResult<BlockNumber> {
+        let reader = &block_state.state;
+        let is_on_tip = reader.block_number == self.next_block_number()
+            && reader.pending_blocks.len() == self.pending_blocks.len()
+            && reader
+                .pending_blocks
+                .iter()
+                .zip(&self.pending_blocks)
+                .all(|(block, tip_block)| Arc::ptr_eq(block, tip_block));
+        if !is_on_tip {
+            return Err(PendingChainError::StaleBlockState);
+        }
+
+        let state_diff = block_state.to_state_diff()?;
+        let class_hash_to_class = block_state.class_hash_to_class.borrow();
+        let declared_classes = state_diff
+            .declared_contracts
+            .iter()
+            .filter(|(_, is_declared)| **is_declared)
+            .map(|(class_hash, _)| {
+                let contract_class = class_hash_to_class
+                    .get(class_hash)
+                    .cloned()
+                    .expect("A declared class must appear in the cache.");
+                (*class_hash, contract_class)
+            })
+            .collect();
+
+        let block_number = self.next_block_number();
+        self.pending_blocks.push(Arc::new(PendingBlock {
+            block_number,
+            state_diff,
+            declared_classes,
+        }));
+        Ok(block_number)
+    }
+
+    /// Drops the given block and all blocks after it, and returns them.
+    /// Classes declared in the dropped blocks are removed from the global contract cache.
+    pub fn revert_to(
+        &mut self,
+        block_number: BlockNumber,
+    ) -> PendingChainResult<Vec<Arc<PendingBlock>>> {
+        let n_kept_blocks = self.n_blocks_before(block_number)?;
+        let dropped_blocks = self.pending_blocks.split_off(n_kept_blocks);
+        if let Some(global_contract_cache) = &self.global_contract_cache {
+            for class_hash in dropped_blocks.iter().flat_map(|block| block.declared_classes.keys())
+            {
+                global_contract_cache.remove(class_hash);
+            }
+        }
+
+        Ok(dropped_blocks)
+    }
+
+    /// Drops all blocks before the given one, once they are committed; i.e., the given base state
+    /// reader must reflect them. Returns the dropped blocks.
+    /// States created before this call remain valid, but cannot be appended to the chain.
+    pub fn commit_until(
+        &mut self,
+        block_number: BlockNumber,
+        base_state_reader: S,
+    ) -> PendingChainResult<Vec<Arc<PendingBlock>>> {
+        let n_committed_blocks = self.n_blocks_before(block_number)?;
+        let committed_blocks = self.pending_blocks.drain(..n_committed_blocks).collect();
+        self.base_state_reader = Arc::new(base_state_reader);
+        self.first_block_number = block_number;
+
+        Ok(committed_blocks)
+    }
+
+    // Returns the number of pending blocks preceding the given block, which may also be the next
+    // block.
+    fn n_blocks_before(&self, block_number: BlockNumber) -> PendingChainResult<usize> {
+        if block_number < self.first_block_number || block_number > self.next_block_number() {
+            return Err(PendingChainError::UnknownBlock(block_number));
+        }
+        Ok((block_number.0 - self.first_block_number.0) as usize)
+    }
+}
+
+/// Reads the state at a given block of a [PendingChain]: values written by pending blocks take
+/// precedence over the base state, later blocks over earlier ones.
+pub struct PendingChainReader<S: StateReader> {
+    base_state_reader: Arc<S>,
+    block_number: BlockNumber,
+    pending_blocks: Vec<Arc<PendingBlock>>,
+}
+
+impl<S: StateReader> PendingChainReader<S> {
+    /// Returns the number of the block this state is read for.
+    pub fn block_number(&self) -> BlockNumber {
+        self.block_number
+    }
+
+    // Returns the latest pending value, if any.
+    fn get_pending<T>(&self, get: impl Fn(&PendingBlock) -> Option<T>) -> Option<T> {
+        self.pending_blocks.iter().rev().find_map(|block| get(block))
+    }
+}
+
+impl<S: StateReader> StateReader for PendingChainReader<S> {
+    fn get_storage_at(
+        &self,
+        contract_address: ContractAddress,
+        key: StorageKey,
+    ) -> StateResult<Felt> {
+        match self
+            .get_pending(|block| block.state_diff.storage.get(&(contract_address, key)).copied())
+        {
+            Some(value) => Ok(value),
+            None => self.base_state_reader.get_storage_at(contract_address, key),
+        }
+    }
+
+    fn get_nonce_at(&self, contract_address: ContractAddress) -> StateResult<Nonce> {
+        match self.get_pending(|block| block.state_diff.nonces.get(&contract_address).copied()) {
+            Some(nonce) => Ok(nonce),
+            None => self.base_state_reader.get_nonce_at(contract_address),
+        }
+    }
+
+    fn get_storage_values(&self, storage_entries: &[StorageEntry]) -> StateResult<Vec<Felt>> {
+        read_batch_through(
+            storage_entries,
+            |storage_entry| {
+                self.get_pending(|block| block.state_diff.storage.get(storage_entry).copied())
+            },
+            |missing_entries| self.base_state_reader.get_storage_values(missing_entries),
+            |_, _| {},
+        )
+    }
+
+    fn get_nonces(&self, contract_addresses: &[ContractAddress]) -> StateResult<Vec<Nonce>> {
+        read_batch_through(
+            contract_addresses,
+            |contract_address| {
+                self.get_pending(|block| block.state_diff.nonces.get(contract_address).copied())
+            },
+            |missing_addresses| self.base_state_reader.get_nonces(missing_addresses),
+            |_, _| {},
+        )
+    }
+
+    fn get_class_hash_at(&self, contract_address: ContractAddress) -> StateResult<ClassHash> {
+        match self
+            .get_pending(|block| block.state_diff.class_hashes.get(&contract_address).copied())
+        {
+            Some(class_hash) => Ok(class_hash),
+            None => self.base_state_reader.get_class_hash_at(contract_address),
+        }
+    }
+
+    fn get_compiled_contract_class(&self, class_hash: ClassHash) -> StateResult<ContractClass> {
+        match self.get_pending(|block| block.declared_classes.get(&class_hash).cloned()) {
+            Some(contract_class) => Ok(contract_class),
+            None => self.base_state_reader.get_compiled_contract_class(class_hash),
+        }
+    }
+
+    fn get_compiled_class_hash(&self, class_hash: ClassHash) -> StateResult<CompiledClassHash> {
+        match self
+            .get_pending(|block| block.state_diff.compiled_class_hashes.get(&class_hash).copied())
+        {
+            Some(compiled_class_hash) => Ok(compiled_class_hash),
+            None => self.base_state_reader.get_compiled_class_hash(class_hash),
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use assert_matches::assert_matches;
+use pretty_assertions::assert_eq;
+use starknet_api::block::BlockNumber;
+use starknet_api::core::{ClassHash, ContractAddress, PatriciaKey};
+use starknet_api::state::StorageKey;
+use starknet_api::{class_hash, contract_address, felt, patricia_key};
+
+use crate::state::cached_state::CachedState;
+use crate::state::global_cache::{GlobalContractCache, GLOBAL_CONTRACT_CACHE_SIZE_FOR_TEST};
+use crate::state::pending_chain::{PendingChain, PendingChainError, PendingChainReader};
+use crate::state::state_api::{State, StateReader};
+use crate::storage_key;
+use crate::test_utils::contracts::FeatureContract;
+use crate::test_utils::dict_state_reader::DictStateReader;
+use crate::test_utils::CairoVersion;
+
+// Writes the given value under the given key, and declares the given class.
+fn execute_block(
+    block_state: &mut CachedState<PendingChainReader<DictStateReader>>,
+    contract_address: ContractAddress,
+    key: StorageKey,
+    value: u8,
+    class_hash: ClassHash,
+) {
+    block_state.set_storage_at(contract_address, key, felt!(value)).unwrap();
+    block_state
+        .set_contract_class(class_hash, FeatureContract::Empty(CairoVersion::Cairo0).get_class())
+        .unwrap();
+}
+
+#[test]
+fn test_pending_chain() {
+    let contract_address = contract_address!("0x1");
+    let key = storage_key!("0x2");
+    let (class_hash_0, class_hash_1) = (class_hash!("0x10"), class_hash!("0x11"));
+    let mut base_state_reader = DictStateReader::default();
+    base_state_reader.storage_view.insert((contract_address, key), felt!(1_u8));
+    let global_contract_cache = GlobalContractCache::new(GLOBAL_CONTRACT_CACHE_SIZE_FOR_TEST);
+    let mut pending_chain = PendingChain::new(
+        base_state_reader.clone(),
+        BlockNumber(10),
+        Some(global_contract_cache.clone()),
+    );
+
+    let mut block_state = pending_chain.next_block_state();
+    assert_eq!(block_state.get_storage_at(contract_address, key).unwrap(), felt!(1_u8));
+    execute_block(&mut block_state, contract_address, key, 2, class_hash_0);
+    assert_eq!(pending_chain.append_block(&mut block_state).unwrap(), BlockNumber(10));
+
+    // The next block is executed on top of the uncommitted previous one.
+    let mut block_state = pending_chain.next_block_state();
+    assert_eq!(block_state.state.block_number(), BlockNumber(11));
+    assert_eq!(block_state.get_storage_at(contract_address, key).unwrap(), felt!(2_u8));
+    assert!(block_state.get_compiled_contract_class(class_hash_0).is_ok());
+    execute_block(&mut block_state, contract_address, key, 3, class_hash_1);
+    assert_eq!(pending_chain.append_block(&mut block_state).unwrap(), BlockNumber(11));
+    let mut stale_block_state = pending_chain.next_block_state();
+
+    // Reorg: only the classes declared in dropped blocks are invalidated.
+    let contract_class = FeatureContract::Empty(CairoVersion::Cairo0).get_class();
+    global_contract_cache.set(class_hash_0, contract_class.clone());
+    global_contract_cache.set(class_hash_1, contract_class);
+    let dropped_blocks = pending_chain.revert_to(BlockNumber(11)).unwrap();
+    assert_eq!(dropped_blocks.len(), 1);
+    assert_eq!(dropped_blocks[0].block_number, BlockNumber(11));
+    assert!(global_contract_cache.get(&class_hash_0).is_some());
+    assert!(global_contract_cache.get(&class_hash_1).is_none());
+    assert_eq!(pending_chain.next_block_number(), BlockNumber(11));
+    let block_state = pending_chain.next_block_state();
+    assert_eq!(block_state.get_storage_at(contract_address, key).unwrap(), felt!(2_u8));
+    assert!(block_state.get_compiled_contract_class(class_hash_1).is_err());
+
+    // States built on dropped blocks cannot be appended.
+    assert_matches!(
+        pending_chain.append_block(&mut stale_block_state),
+        Err(PendingChainError::StaleBlockState)
+    );
+    assert_matches!(
+        pending_chain.revert_to(BlockNumber(12)),
+        Err(PendingChainError::UnknownBlock(BlockNumber(12)))
+    );
+
+    // Committing moves the pending blocks to the base state.
+    base_state_reader.storage_view.insert((contract_address, key), felt!(2_u8));
+    let committed_blocks = pending_chain.commit_until(BlockNumber(11), base_state_reader).unwrap();
+    assert_eq!(committed_blocks.len(), 1);
+    assert!(pending_chain.pending_blocks().is_empty());
+    assert_eq!(pending_chain.next_block_number(), BlockNumber(11));
+    let block_state = pending_chain.next_block_state();
+    assert_eq!(block_state.get_storage_at(contract_address, key).unwrap(), felt!(2_u8));
+}
@@ -0,0 +1,362 @@
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use cairo_lang_casm::hints::Hint;
+#[cfg(feature = "native")]
+use cairo_lang_starknet_classes::contract_class::ContractClass as SierraContractClass;
+use cairo_lang_starknet_classes::NestedIntList;
+use cairo_vm::types::errors::program_errors::ProgramError;
+use cairo_vm::types::program::Program;
+use serde::{Deserialize, Serialize};
+use starknet_api::core::{ClassHash, CompiledClassHash, ContractAddress, Nonce};
+use starknet_api::deprecated_contract_class::{EntryPoint, EntryPointType};
+use starknet_api::state::StorageKey;
+use starknet_types_core::felt::Felt;
+
+use crate::execution::contract_class::{
+    ContractClass, ContractClassV0, ContractClassV0Inner, ContractClassV1, ContractClassV1Inner,
+    EntryPointV1,
+};
+#[cfg(feature = "native")]
+use crate::execution::native::compiler::compile_sierra_to_native;
+use crate::state::cached_state::{ContractClassMapping, StorageEntry};
+use crate::state::errors::{StateError, StateFixtureError};
+use crate::state::state_api::{check_batch_read, StateReader, StateResult};
+
+#[cfg(test)]
+#[path = "recording_state_reader_test.rs"]
+mod test;
+
+/// A self-contained record of the state reads performed during an execution; see
+/// [RecordingStateReader] and [ReplayStateReader].
+/// Entries are sorted, so that recording the same reads always yields the same fixture.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct StateReaderFixture {
+    pub storage: Vec<(ContractAddress, StorageKey, Felt)>,
+    pub nonces: Vec<(ContractAddress, Nonce)>,
+    pub class_hashes: Vec<(ContractAddress, ClassHash)>,
+    pub compiled_class_hashes: Vec<(ClassHash, CompiledClassHash)>,
+    pub contract_classes: Vec<(ClassHash, RecordedContractClass)>,
+    // Class hashes whose contract class was read, but are not declared.
+    pub undeclared_class_hashes: Vec<ClassHash>,
+}
+
+impl StateReaderFixture {
+    pub fn from_file(path: &Path) -> Result<Self, StateFixtureError> {
+        Ok(serde_json::from_reader(BufReader::new(File::open(path)?))?)
+    }
+
+    pub fn write_to_file(&self, path: &Path) -> Result<(), StateFixtureError> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        serde_json::to_writer(&mut writer, self)?;
+        Ok(writer.flush()?)
+    }
+}
+
+/// The serializable form of a [ContractClass].
+/// Classes compiled to native code are recorded by their Sierra representation, and recompiled
+/// when replayed.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum RecordedContractClass {
+    V0 {
+        program: serde_json::Value,
+        entry_points_by_type: HashMap<EntryPointType, Vec<EntryPoint>>,
+    },
+    V1 {
+        program: serde_json::Value,
+        entry_points_by_type: HashMap<EntryPointType, Vec<EntryPointV1>>,
+        hints: HashMap<String, Hint>,
+        bytecode_segment_lengths: NestedIntList,
+    },
+    #[cfg(feature = "native")]
+    V1Native(SierraContractClass),
+}
+
+impl TryFrom<&ContractClass> for RecordedContractClass {
+    type Error = StateError;
+
+    fn try_from(contract_class: &ContractClass) -> StateResult<Self> {
+        Ok(match contract_class {
+            ContractClass::V0(class) => Self::V0 {
+                program: serialize_program(&class.program)?,
+                entry_points_by_type: class.entry_points_by_type.clone(),
+            },
+            ContractClass::V1(class) => Self::V1 {
+                program: serialize_program(&class.program)?,
+                entry_points_by_type: class.entry_points_by_type.clone(),
+                hints: class.hints.clone(),
+                bytecode_segment_lengths: class.bytecode_segment_lengths.clone(),
+            },
+            #[cfg(feature = "native")]
+            ContractClass::V1Native(class) => Self::V1Native(class.to_sierra_contract_class()),
+        })
+    }
+}
+
+impl TryFrom<RecordedContractClass> for ContractClass {
+    type Error = StateError;
+
+    fn try_from(recorded_class: RecordedContractClass) -> StateResult<Self> {
+        Ok(match recorded_class {
+            RecordedContractClass::V0 { program, entry_points_by_type } => {
+                ContractClassV0(Arc::new(ContractClassV0Inner {
+                    program: deserialize_program(&program)?,
+                    entry_points_by_type,
+                }))
+                .into()
+            }
+            RecordedContractClass::V1 {
+                program,
+                entry_points_by_type,
+                hints,
+                bytecode_segment_lengths,
+            } => ContractClassV1(Arc::new(ContractClassV1Inner {
+                program: deserialize_program(&program)?,
+                entry_points_by_type,
+                hints,
+                bytecode_segment_lengths,
+            }))
+            .into(),
+            #[cfg(feature = "native")]
+            RecordedContractClass::V1Native(sierra_contract_class) => {
+                compile_sierra_to_native(sierra_contract_class, cairo_native::OptLevel::Default)
+                    .map_err(|error| StateError::StateReadError(error.to_string()))?
+                    .into()
+            }
+        })
+    }
+}
+
+fn serialize_program(program: &Program) -> Result<serde_json::Value, ProgramError> {
+    Ok(serde_json::from_slice(&program.serialize()?)?)
+}
+
+fn deserialize_program(program: &serde_json::Value) -> Result<Program, ProgramError> {
+    Program::from_bytes(&serde_json::to_vec(program)?, None)
+}
+
+#[derive(Debug, Default)]
+struct RecordedReads {
+    storage: HashMap<StorageEntry, Felt>,
+    nonces: HashMap<ContractAddress, Nonce>,
+    class_hashes: HashMap<ContractAddress, ClassHash>,
+    compiled_class_hashes: HashMap<ClassHash, CompiledClassHash>,
+    contract_classes: ContractClassMapping,
+    undeclared_class_hashes: HashSet<ClassHash>,
+}
+
+/// A state reader that records every read served by the underlying reader, so that the execution
+/// can be reproduced without it (see [ReplayStateReader]).
+/// Failed reads are not recorded, except for reads of undeclared contract classes.
+pub struct RecordingStateReader<S: StateReader> {
+    pub state_reader: S,
+    recorded_reads: Mutex<RecordedReads>,
+}
+
+impl<S: StateReader> RecordingStateReader<S> {
+    pub fn new(state_reader: S) -> Self {
+        Self { state_reader, recorded_reads: Mutex::new(RecordedReads::default()) }
+    }
+
+    fn recorded_reads(&self) -> MutexGuard<'_, RecordedReads> {
+        self.recorded_reads.lock().expect("Recorded state reads are poisoned.")
+    }
+
+    /// Returns a fixture of all reads recorded so far.
+    pub fn fixture(&self) -> StateResult<StateReaderFixture> {
+        let recorded_reads = self.recorded_reads();
+        let mut contract_classes: Vec<(ClassHash, RecordedContractClass)> = recorded_reads
+            .contract_classes
+            .iter()
+            .map(|(class_hash, contract_class)| Ok((*class_hash, contract_class.try_into()?)))
+            .collect::<StateResult<_>>()?;
+        contract_classes.sort_by_key(|(class_hash, _)| *class_hash);
+
+        Ok(StateReaderFixture {
+            storage: sorted(
+                recorded_reads
+                    .storage
+                    .iter()
+                    .map(|((contract_address, key), value)| (*contract_address, *key, *value)),
+            ),
+            nonces: sorted(recorded_reads.nonces.iter().map(|(k, v)| (*k, *v))),
+            class_hashes: sorted(recorded_reads.class_hashes.iter().map(|(k, v)| (*k, *v))),
+            compiled_class_hashes: sorted(
+                recorded_reads.compiled_class_hashes.iter().map(|(k, v)| (*k, *v)),
+            ),
+            contract_classes,
+            undeclared_class_hashes: sorted(recorded_reads.undeclared_class_hashes.iter().copied()),
+        })
+    }
+}
+
+fn sorted<T: Ord>(entries: impl Iterator<Item = T>) -> Vec<T> {
+    let mut entries: Vec<T> = entries.collect();
+    entries.sort();
+    entries
+}
+
+impl<S: StateReader> StateReader for RecordingStateReader<S> {
+    fn get_storage_at(
+        &self,
+        contract_address: ContractAddress,
+        key: StorageKey,
+    ) -> StateResult<Felt> {
+        let value = self.state_reader.get_storage_at(contract_address, key)?;
+        self.recorded_reads().storage.insert((contract_address, key), value);
+        Ok(value)
+    }
+
+    fn get_nonce_at(&self, contract_address: ContractAddress) -> StateResult<Nonce> {
+        let nonce = self.state_reader.get_nonce_at(contract_address)?;
+        self.recorded_reads().nonces.insert(contract_address, nonce);
+        Ok(nonce)
+    }
+
+    fn get_storage_values(&self, storage_entries: &[StorageEntry]) -> StateResult<Vec<Felt>> {
+        let values = check_batch_read(
+            storage_entries.len(),
+            self.state_reader.get_storage_values(storage_entries)?,
+        )?;
+        self.recorded_reads().storage.extend(storage_entries.iter().copied().zip(values.clone()));
+        Ok(values)
+    }
+
+    fn get_nonces(&self, contract_addresses: &[ContractAddress]) -> StateResult<Vec<Nonce>> {
+        let nonces = check_batch_read(
+            contract_addresses.len(),
+            self.state_reader.get_nonces(contract_addresses)?,
+        )?;
+        self.recorded_reads().nonces.extend(contract_addresses.iter().copied().zip(nonces.clone()));
+        Ok(nonces)
+    }
+
+    fn get_class_hash_at(&self, contract_address: ContractAddress) -> StateResult<ClassHash> {
+        let class_hash = self.state_reader.get_class_hash_at(contract_address)?;
+        self.recorded_reads().class_hashes.insert(contract_address, class_hash);
+        Ok(class_hash)
+    }
+
+    fn get_compiled_contract_class(&self, class_hash: ClassHash) -> StateResult<ContractClass> {
+        match self.state_reader.get_compiled_contract_class(class_hash) {
+            Ok(contract_class) => {
+                self.recorded_reads().contract_classes.insert(class_hash, contract_class.clone());
+                Ok(contract_class)
+            }
+            Err(StateError::UndeclaredClassHash(class_hash)) => {
+                self.recorded_reads().undeclared_class_hashes.insert(class_hash);
+                Err(StateError::UndeclaredClassHash(class_hash))
+            }
+            Err(error) => Err(error),
+        }
+    }
+
+    fn get_compiled_class_hash(&self, class_hash: ClassHash) -> StateResult<CompiledClassHash> {
+        let compiled_class_hash = self.state_reader.get_compiled_class_hash(class_hash)?;
+        self.recorded_reads().compiled_class_hashes.insert(class_hash, compiled_class_hash);
+        Ok(compiled_class_hash)
+    }
+}
+
+/// A state reader that serves reads only from a [StateReaderFixture]; reads that were not
+/// recorded fail.
+#[derive(Clone, Debug, Default)]
+pub struct ReplayStateReader {
+    storage: HashMap<StorageEntry, Felt>,
+    nonces: HashMap<ContractAddress, Nonce>,
+    class_hashes: HashMap<ContractAddress, ClassHash>,
+    compiled_class_hashes: HashMap<ClassHash, CompiledClassHash>,
+    contract_classes: ContractClassMapping,
+    undeclared_class_hashes: HashSet<ClassHash>,
+}
+
+impl ReplayStateReader {
+    pub fn new(fixture: StateReaderFixture) -> StateResult<Self> {
+        Ok(Self {
+            storage: fixture
+                .storage
+                .into_iter()
+                .map(|(contract_address, key, value)| ((contract_address, key), value))
+                .collect(),
+            nonces: fixture.nonces.into_iter().collect(),
+            class_hashes: fixture.class_hashes.into_iter().collect(),
+            compiled_class_hashes: fixture.compiled_class_hashes.into_iter().collect(),
+            contract_classes: fixture
+                .contract_classes
+                .into_iter()
+                .map(|(class_hash, recorded_class)| Ok((class_hash, recorded_class.try_into()?)))
+                .collect::<StateResult<_>>()?,
+            undeclared_class_hashes: fixture.undeclared_class_hashes.into_iter().collect(),
+        })
+    }
+
+    pub fn from_file(path: &Path) -> Result<Self, StateFixtureError> {
+        Ok(Self::new(StateReaderFixture::from_file(path)?)?)
+    }
+}
+
+fn unrecorded_read(read: String) -> StateError {
+    StateError::StateReadError(format!("{read} was not recorded"))
+}
+
+impl StateReader for ReplayStateReader {
+    fn get_storage_at(
+        &self,
+        contract_address: ContractAddress,
+        key: StorageKey,
+    ) -> StateResult<Felt> {
+        self.storage
+            .get(&(contract_address, key))
+            .copied()
+            .ok_or_else(|| unrecorded_read(format!("Storage at {contract_address:?}, key {key:?}")))
+    }
+
+    fn get_nonce_at(&self, contract_address: ContractAddress) -> StateResult<Nonce> {
+        self.nonces
+            .get(&contract_address)
+            .copied()
+            .ok_or_else(|| unrecorded_read(format!("Nonce of {contract_address:?}")))
+    }
+
+    // Batches are served entry by entry, as all reads are in memory.
+    fn get_storage_values(&self, storage_entries: &[StorageEntry]) -> StateResult<Vec<Felt>> {
+        storage_entries
+            .iter()
+            .map(|(contract_address, key)| self.get_storage_at(*contract_address, *key))
+            .collect()
+    }
+
+    fn get_nonces(&self, contract_addresses: &[ContractAddress]) -> StateResult<Vec<Nonce>> {
+        contract_addresses
+            .iter()
+            .map(|contract_address| self.get_nonce_at(*contract_address))
+            .collect()
+    }
+
+    fn get_class_hash_at(&self, contract_address: ContractAddress) -> StateResult<ClassHash> {
+        self.class_hashes
+            .get(&contract_address)
+            .copied()
+            .ok_or_else(|| unrecorded_read(format!("Class hash of {contract_address:?}")))
+    }
+
+    fn get_compiled_contract_class(&self, class_hash: ClassHash) -> StateResult<ContractClass> {
+        if self.undeclared_class_hashes.contains(&class_hash) {
+            return Err(StateError::UndeclaredClassHash(class_hash));
+        }
+        self.contract_classes
+            .get(&class_hash)
+            .cloned()
+            .ok_or_else(|| unrecorded_read(format!("Contract class of {:#064x}", *class_hash)))
+    }
+
+    fn get_compiled_class_hash(&self, class_hash: ClassHash) -> StateResult<CompiledClassHash> {
+        self.compiled_class_hashes
+            .get(&class_hash)
+            .copied()
+            .ok_or_else(|| unrecorded_read(format!("Compiled class hash of {:#064x}", *class_hash)))
+    }
+}
@@ -0,0 +1,86 @@
+use assert_matches::assert_matches;
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+use starknet_api::class_hash;
+use starknet_api::transaction::TransactionVersion;
+
+use crate::context::BlockContext;
+use crate::invoke_tx_args;
+use crate::state::cached_state::CachedState;
+use crate::state::errors::StateError;
+use crate::state::recording_state_reader::{
+    RecordingStateReader, ReplayStateReader, StateReaderFixture,
+};
+use crate::state::state_api::StateReader;
+use crate::test_utils::contracts::FeatureContract;
+use crate::test_utils::initial_test_state::test_state;
+use crate::test_utils::{create_trivial_calldata, CairoVersion, BALANCE};
+use crate::transaction::test_utils::{account_invoke_tx, block_context, max_resource_bounds};
+use crate::transaction::transactions::ExecutableTransaction;
+
+#[rstest]
+fn test_replay_reproduces_execution(
+    block_context: BlockContext,
+    #[values(CairoVersion::Cairo0, CairoVersion::Cairo1)] cairo_version: CairoVersion,
+) {
+    let account = FeatureContract::AccountWithoutValidations(cairo_version);
+    let test_contract = FeatureContract::TestContract(cairo_version);
+    let state_reader =
+        test_state(&block_context.chain_info, BALANCE, &[(account, 1), (test_contract, 1)]).state;
+    let invoke_args = invoke_tx_args! {
+        sender_address: account.get_instance_address(0),
+        calldata: create_trivial_calldata(test_contract.get_instance_address(0)),
+        resource_bounds: max_resource_bounds(),
+        version: TransactionVersion::THREE,
+    };
+
+    // Record.
+    let mut recording_state = CachedState::new(RecordingStateReader::new(state_reader));
+    let recorded_execution_info = account_invoke_tx(invoke_args.clone())
+        .execute(&mut recording_state, &block_context, true, true)
+        .unwrap();
+    let fixture = recording_state.state.fixture().unwrap();
+
+    // Replay, from the serialized fixture.
+    let fixture: StateReaderFixture =
+        serde_json::from_str(&serde_json::to_string(&fixture).unwrap()).unwrap();
+    let mut replay_state = CachedState::new(ReplayStateReader::new(fixture).unwrap());
+    let replayed_execution_info = account_invoke_tx(invoke_args)
+        .execute(&mut replay_state, &block_context, true, true)
+        .unwrap();
+
+    assert_eq!(replayed_execution_info, recorded_execution_info);
+    assert_eq!(replay_state.to_state_diff().unwrap(), recording_state.to_state_diff().unwrap());
+}
+
+#[test]
+fn test_unrecorded_reads() {
+    let account = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo0);
+    let account_address = account.get_instance_address(0);
+    let undeclared_class_hash = class_hash!(0xdead_u16);
+    let recording_reader = RecordingStateReader::new(
+        test_state(&BlockContext::create_for_testing().chain_info, BALANCE, &[(account, 1)]).state,
+    );
+    recording_reader.get_nonce_at(account_address).unwrap();
+    assert_matches!(
+        recording_reader.get_compiled_contract_class(undeclared_class_hash),
+        Err(StateError::UndeclaredClassHash(_))
+    );
+
+    let replay_reader = ReplayStateReader::new(recording_reader.fixture().unwrap()).unwrap();
+    assert_eq!(replay_reader.get_nonce_at(account_address).unwrap(), Default::default());
+    // Undeclared classes are replayed as such.
+    assert_matches!(
+        replay_reader.get_compiled_contract_class(undeclared_class_hash),
+        Err(StateError::UndeclaredClassHash(class_hash)) if class_hash == undeclared_class_hash
+    );
+    // Reads that were not recorded fail.
+    assert_matches!(
+        replay_reader.get_class_hash_at(account_address),
+        Err(StateError::StateReadError(_))
+    );
+    assert_matches!(
+        replay_reader.get_compiled_contract_class(account.get_class_hash()),
+        Err(StateError::StateReadError(_))
+    );
+}
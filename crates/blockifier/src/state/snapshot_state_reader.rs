@@ -0,0 +1,248 @@
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+#[cfg(not(feature = "native"))]
+use cairo_lang_starknet_classes::casm_contract_class::CasmContractClass;
+use cairo_lang_starknet_classes::contract_class::ContractClass as SierraContractClass;
+use serde::{Deserialize, Serialize};
+use starknet_api::core::{ClassHash, CompiledClassHash, ContractAddress, Nonce};
+use starknet_api::state::StorageKey;
+use starknet_types_core::felt::Felt;
+
+use crate::execution::contract_class::{ContractClass, ContractClassV0, ContractClassV1};
+#[cfg(feature = "native")]
+use crate::execution::native::compiler::compile_sierra_to_native;
+use crate::state::cached_state::{
+    CanonicalEncoding, CommitmentStateDiff, ContractClassMapping, StorageEntry,
+};
+use crate::state::errors::{StateError, StateFixtureError};
+use crate::state::state_api::{StateReader, StateResult};
+
+#[cfg(test)]
+#[path = "snapshot_state_reader_test.rs"]
+mod test;
+
+/// A contract class file referenced by a [StateSnapshot]. Relative paths are resolved against the
+/// directory of the snapshot file.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum ContractClassFile {
+    /// A compiled Cairo 0 class.
+    Cairo0(PathBuf),
+    /// A Casm class.
+    Casm(PathBuf),
+    /// A Sierra class; compiled to native code if the `native` feature is enabled, and to Casm
+    /// otherwise.
+    Sierra(PathBuf),
+}
+
+impl ContractClassFile {
+    fn load(&self, snapshot_dir: &Path) -> Result<ContractClass, StateFixtureError> {
+        let load_raw_class = |path: &PathBuf| fs::read_to_string(snapshot_dir.join(path));
+        Ok(match self {
+            Self::Cairo0(path) => ContractClassV0::try_from_json_string(&load_raw_class(path)?)
+                .map_err(StateError::from)?
+                .into(),
+            Self::Casm(path) => ContractClassV1::try_from_json_string(&load_raw_class(path)?)
+                .map_err(StateError::from)?
+                .into(),
+            Self::Sierra(path) => {
+                let sierra_contract_class: SierraContractClass =
+                    serde_json::from_str(&load_raw_class(path)?)?;
+                compile_sierra_contract_class(sierra_contract_class)?
+            }
+        })
+    }
+}
+
+#[cfg(feature = "native")]
+fn compile_sierra_contract_class(
+    sierra_contract_class: SierraContractClass,
+) -> StateResult<ContractClass> {
+    Ok(compile_sierra_to_native(sierra_contract_class, cairo_native::OptLevel::Default)
+        .map_err(|error| StateError::StateReadError(error.to_string()))?
+        .into())
+}
+
+#[cfg(not(feature = "native"))]
+fn compile_sierra_contract_class(
+    sierra_contract_class: SierraContractClass,
+) -> StateResult<ContractClass> {
+    let casm_contract_class =
+        CasmContractClass::from_contract_class(sierra_contract_class, false, usize::MAX)
+            .map_err(|error| StateError::StateReadError(error.to_string()))?;
+    Ok(ContractClassV1::try_from(casm_contract_class)?.into())
+}
+
+/// A full state, as stored in a snapshot file; see [SnapshotStateReader].
+/// Snapshot files are JSON if their extension is `.json`, and binary otherwise.
+/// Entries are sorted, so that equal states are always stored identically.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct StateSnapshot {
+    pub storage: Vec<(ContractAddress, StorageKey, Felt)>,
+    pub nonces: Vec<(ContractAddress, Nonce)>,
+    pub class_hashes: Vec<(ContractAddress, ClassHash)>,
+    pub compiled_class_hashes: Vec<(ClassHash, CompiledClassHash)>,
+    pub contract_classes: Vec<(ClassHash, ContractClassFile)>,
+}
+
+impl CanonicalEncoding for StateSnapshot {}
+
+impl StateSnapshot {
+    pub fn from_file(path: &Path) -> Result<Self, StateFixtureError> {
+        let reader = BufReader::new(File::open(path)?);
+        Ok(if is_json(path) {
+            serde_json::from_reader(reader)?
+        } else {
+            bincode::deserialize_from(reader)?
+        })
+    }
+
+    pub fn write_to_file(&self, path: &Path) -> Result<(), StateFixtureError> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        if is_json(path) {
+            serde_json::to_writer(&mut writer, self)?;
+        } else {
+            bincode::serialize_into(&mut writer, self)?;
+        }
+        Ok(writer.flush()?)
+    }
+}
+
+fn is_json(path: &Path) -> bool {
+    path.extension().is_some_and(|extension| extension == "json")
+}
+
+/// A state reader over a [StateSnapshot], loaded with all of its contract classes.
+/// Like an uninitialized state, values missing from the snapshot are read as zero.
+#[derive(Clone, Debug)]
+pub struct SnapshotStateReader {
+    // Class file paths are resolved against this directory.
+    snapshot_dir: PathBuf,
+    storage: HashMap<StorageEntry, Felt>,
+    nonces: HashMap<ContractAddress, Nonce>,
+    class_hashes: HashMap<ContractAddress, ClassHash>,
+    compiled_class_hashes: HashMap<ClassHash, CompiledClassHash>,
+    contract_class_files: HashMap<ClassHash, ContractClassFile>,
+    contract_classes: ContractClassMapping,
+}
+
+impl SnapshotStateReader {
+    pub fn new(snapshot: StateSnapshot, snapshot_dir: &Path) -> Result<Self, StateFixtureError> {
+        let mut snapshot_state_reader = Self {
+            snapshot_dir: snapshot_dir.to_path_buf(),
+            storage: snapshot
+                .storage
+                .into_iter()
+                .map(|(contract_address, key, value)| ((contract_address, key), value))
+                .collect(),
+            nonces: snapshot.nonces.into_iter().collect(),
+            class_hashes: snapshot.class_hashes.into_iter().collect(),
+            compiled_class_hashes: snapshot.compiled_class_hashes.into_iter().collect(),
+            contract_class_files: HashMap::new(),
+            contract_classes: ContractClassMapping::new(),
+        };
+        for (class_hash, contract_class_file) in snapshot.contract_classes {
+            snapshot_state_reader.declare_class(class_hash, contract_class_file)?;
+        }
+
+        Ok(snapshot_state_reader)
+    }
+
+    pub fn from_file(path: &Path) -> Result<Self, StateFixtureError> {
+        let snapshot_dir = path.parent().unwrap_or(Path::new(""));
+        Self::new(StateSnapshot::from_file(path)?, snapshot_dir)
+    }
+
+    /// Loads the given class file, and declares it under the given class hash.
+    pub fn declare_class(
+        &mut self,
+        class_hash: ClassHash,
+        contract_class_file: ContractClassFile,
+    ) -> Result<(), StateFixtureError> {
+        let contract_class = contract_class_file.load(&self.snapshot_dir)?;
+        self.contract_classes.insert(class_hash, contract_class);
+        self.contract_class_files.insert(class_hash, contract_class_file);
+        Ok(())
+    }
+
+    /// Applies the given state diff. Classes declared in it must be declared separately (see
+    /// [Self::declare_class]), since the diff does not hold their files.
+    pub fn apply_state_diff(&mut self, state_diff: &CommitmentStateDiff) {
+        for (contract_address, storage_updates) in &state_diff.storage_updates {
+            for (key, value) in storage_updates {
+                self.storage.insert((*contract_address, *key), *value);
+            }
+        }
+        self.nonces.extend(&state_diff.address_to_nonce);
+        self.class_hashes.extend(&state_diff.address_to_class_hash);
+        self.compiled_class_hashes.extend(&state_diff.class_hash_to_compiled_class_hash);
+    }
+
+    /// Returns the snapshot of the current state.
+    pub fn snapshot(&self) -> StateSnapshot {
+        StateSnapshot {
+            storage: sorted(
+                self.storage
+                    .iter()
+                    .map(|((contract_address, key), value)| (*contract_address, *key, *value)),
+            ),
+            nonces: sorted(self.nonces.iter().map(|(k, v)| (*k, *v))),
+            class_hashes: sorted(self.class_hashes.iter().map(|(k, v)| (*k, *v))),
+            compiled_class_hashes: sorted(self.compiled_class_hashes.iter().map(|(k, v)| (*k, *v))),
+            contract_classes: {
+                let mut contract_classes: Vec<(ClassHash, ContractClassFile)> = self
+                    .contract_class_files
+                    .iter()
+                    .map(|(class_hash, contract_class_file)| {
+                        (*class_hash, contract_class_file.clone())
+                    })
+                    .collect();
+                contract_classes.sort_by_key(|(class_hash, _)| *class_hash);
+                contract_classes
+            },
+        }
+    }
+
+    /// Writes the snapshot of the current state to the given path. Relative class file paths are
+    /// written as is; i.e., they should be valid relative to the new snapshot file as well.
+    pub fn write_to_file(&self, path: &Path) -> Result<(), StateFixtureError> {
+        self.snapshot().write_to_file(path)
+    }
+}
+
+fn sorted<T: Ord>(entries: impl Iterator<Item = T>) -> Vec<T> {
+    let mut entries: Vec<T> = entries.collect();
+    entries.sort();
+    entries
+}
+
+impl StateReader for SnapshotStateReader {
+    fn get_storage_at(
+        &self,
+        contract_address: ContractAddress,
+        key: StorageKey,
+    ) -> StateResult<Felt> {
+        Ok(self.storage.get(&(contract_address, key)).copied().unwrap_or_default())
+    }
+
+    fn get_nonce_at(&self, contract_address: ContractAddress) -> StateResult<Nonce> {
+        Ok(self.nonces.get(&contract_address).copied().unwrap_or_default())
+    }
+
+    fn get_class_hash_at(&self, contract_address: ContractAddress) -> StateResult<ClassHash> {
+        Ok(self.class_hashes.get(&contract_address).copied().unwrap_or_default())
+    }
+
+    fn get_compiled_contract_class(&self, class_hash: ClassHash) -> StateResult<ContractClass> {
+        self.contract_classes
+            .get(&class_hash)
+            .cloned()
+            .ok_or(StateError::UndeclaredClassHash(class_hash))
+    }
+
+    fn get_compiled_class_hash(&self, class_hash: ClassHash) -> StateResult<CompiledClassHash> {
+        Ok(self.compiled_class_hashes.get(&class_hash).copied().unwrap_or_default())
+    }
+}
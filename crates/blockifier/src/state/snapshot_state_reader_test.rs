@@ -0,0 +1,129 @@
+use std::fs;
+use std::path::PathBuf;
+
+use assert_matches::assert_matches;
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+use starknet_api::felt;
+use starknet_api::state::StorageKey;
+
+use crate::state::cached_state::StateMaps;
+use crate::state::errors::StateError;
+use crate::state::snapshot_state_reader::{ContractClassFile, SnapshotStateReader, StateSnapshot};
+use crate::state::state_api::StateReader;
+use crate::test_utils::contracts::FeatureContract;
+use crate::test_utils::CairoVersion;
+use crate::{compiled_class_hash, nonce};
+
+fn absolute_compiled_path(feature_contract: FeatureContract) -> PathBuf {
+    fs::canonicalize(feature_contract.get_compiled_path()).unwrap()
+}
+
+#[rstest]
+fn test_snapshot_round_trip(#[values("snapshot.json", "snapshot.bin")] file_name: &str) {
+    let account = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo0);
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo1);
+    let sierra_test_contract = FeatureContract::SierraTestContract;
+    let account_address = account.get_instance_address(0);
+    let key = StorageKey::from(0x10_u128);
+    let mut snapshot = StateSnapshot {
+        storage: vec![(account_address, key, felt!("0x1"))],
+        nonces: vec![(account_address, nonce!(1_u8))],
+        class_hashes: vec![(account_address, account.get_class_hash())],
+        compiled_class_hashes: vec![(test_contract.get_class_hash(), compiled_class_hash!(1_u8))],
+        contract_classes: vec![
+            (account.get_class_hash(), ContractClassFile::Cairo0(absolute_compiled_path(account))),
+            (
+                test_contract.get_class_hash(),
+                ContractClassFile::Casm(absolute_compiled_path(test_contract)),
+            ),
+            (
+                sierra_test_contract.get_class_hash(),
+                ContractClassFile::Sierra(absolute_compiled_path(sierra_test_contract)),
+            ),
+        ],
+    };
+    snapshot.contract_classes.sort_by_key(|(class_hash, _)| *class_hash);
+
+    let snapshot_dir = tempfile::tempdir().unwrap();
+    let snapshot_path = snapshot_dir.path().join(file_name);
+    snapshot.write_to_file(&snapshot_path).unwrap();
+    let snapshot_state_reader = SnapshotStateReader::from_file(&snapshot_path).unwrap();
+
+    assert_eq!(snapshot_state_reader.snapshot(), snapshot);
+    assert_eq!(snapshot_state_reader.get_storage_at(account_address, key).unwrap(), felt!("0x1"));
+    assert_eq!(snapshot_state_reader.get_nonce_at(account_address).unwrap(), nonce!(1_u8));
+    assert_eq!(
+        snapshot_state_reader.get_class_hash_at(account_address).unwrap(),
+        account.get_class_hash()
+    );
+    assert_eq!(
+        snapshot_state_reader.get_compiled_class_hash(test_contract.get_class_hash()).unwrap(),
+        compiled_class_hash!(1_u8)
+    );
+    for feature_contract in [account, test_contract] {
+        assert_eq!(
+            snapshot_state_reader
+                .get_compiled_contract_class(feature_contract.get_class_hash())
+                .unwrap(),
+            feature_contract.get_class()
+        );
+    }
+    assert!(
+        snapshot_state_reader
+            .get_compiled_contract_class(sierra_test_contract.get_class_hash())
+            .is_ok()
+    );
+
+    // Values missing from the snapshot are read as those of an uninitialized state.
+    let test_contract_address = test_contract.get_instance_address(0);
+    assert_eq!(snapshot_state_reader.get_nonce_at(test_contract_address).unwrap(), nonce!(0_u8));
+    assert_matches!(
+        snapshot_state_reader.get_compiled_contract_class(
+            FeatureContract::Empty(CairoVersion::Cairo0).get_class_hash()
+        ),
+        Err(StateError::UndeclaredClassHash(_))
+    );
+}
+
+#[test]
+fn test_write_back_applied_state_diff() {
+    let account = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo0);
+    let account_address = account.get_instance_address(0);
+    let key = StorageKey::from(0x10_u128);
+    let snapshot_dir = tempfile::tempdir().unwrap();
+    // Class files may be given relative to the snapshot directory.
+    fs::copy(account.get_compiled_path(), snapshot_dir.path().join("account.json")).unwrap();
+
+    let mut snapshot_state_reader =
+        SnapshotStateReader::new(StateSnapshot::default(), snapshot_dir.path()).unwrap();
+    snapshot_state_reader
+        .declare_class(account.get_class_hash(), ContractClassFile::Cairo0("account.json".into()))
+        .unwrap();
+    snapshot_state_reader.apply_state_diff(
+        &StateMaps {
+            storage: [((account_address, key), felt!("0x7"))].into(),
+            nonces: [(account_address, nonce!(2_u8))].into(),
+            class_hashes: [(account_address, account.get_class_hash())].into(),
+            ..Default::default()
+        }
+        .into(),
+    );
+
+    let snapshot_path = snapshot_dir.path().join("snapshot.bin");
+    snapshot_state_reader.write_to_file(&snapshot_path).unwrap();
+    let reloaded_snapshot_state_reader = SnapshotStateReader::from_file(&snapshot_path).unwrap();
+
+    assert_eq!(reloaded_snapshot_state_reader.snapshot(), snapshot_state_reader.snapshot());
+    assert_eq!(
+        reloaded_snapshot_state_reader.get_storage_at(account_address, key).unwrap(),
+        felt!("0x7")
+    );
+    assert_eq!(reloaded_snapshot_state_reader.get_nonce_at(account_address).unwrap(), nonce!(2_u8));
+    assert_eq!(
+        reloaded_snapshot_state_reader
+            .get_compiled_contract_class(account.get_class_hash())
+            .unwrap(),
+        account.get_class()
+    );
+}
@@ -4,7 +4,7 @@ use starknet_api::core::{ClassHash, CompiledClassHash, ContractAddress, Nonce};
 use starknet_api::state::StorageKey;
 use starknet_types_core::felt::Felt;
 
-use super::cached_state::{ContractClassMapping, StateMaps};
+use super::cached_state::{ContractClassMapping, StateMaps, StorageEntry};
 use crate::abi::abi_utils::get_fee_token_var_address;
 use crate::abi::sierra_types::next_storage_key;
 use crate::execution::contract_class::ContractClass;
@@ -46,6 +46,25 @@ pub trait StateReader {
     /// Returns the compiled class hash of the given class hash.
     fn get_compiled_class_hash(&self, class_hash: ClassHash) -> StateResult<CompiledClassHash>;
 
+    /// Returns the storage values under the given keys, in order.
+    /// Readers with a per-read overhead (e.g., opening a DB transaction) should override this to
+    /// pay it once per batch.
+    fn get_storage_values(&self, storage_entries: &[StorageEntry]) -> StateResult<Vec<Felt>> {
+        storage_entries
+            .iter()
+            .map(|(contract_address, key)| self.get_storage_at(*contract_address, *key))
+            .collect()
+    }
+
+    /// Returns the nonces of the given contract instances, in order; see
+    /// [Self::get_storage_values].
+    fn get_nonces(&self, contract_addresses: &[ContractAddress]) -> StateResult<Vec<Nonce>> {
+        contract_addresses
+            .iter()
+            .map(|contract_address| self.get_nonce_at(*contract_address))
+            .collect()
+    }
+
     /// Returns the storage value representing the balance (in fee token) at the given address.
     // TODO(Dori, 1/7/2023): When a standard representation for large integers is set, change the
     //    return type to that.
@@ -58,13 +77,30 @@ pub trait StateReader {
     ) -> Result<(Felt, Felt), StateError> {
         let low_key = get_fee_token_var_address(contract_address);
         let high_key = next_storage_key(&low_key)?;
-        let low = self.get_storage_at(fee_token_address, low_key)?;
-        let high = self.get_storage_at(fee_token_address, high_key)?;
+        let values = self
+            .get_storage_values(&[(fee_token_address, low_key), (fee_token_address, high_key)])?;
 
-        Ok((low, high))
+        Ok((values[0], values[1]))
     }
 }
 
+/// A state reader that can also enumerate the state, rather than only answer point queries.
+/// Zero storage values are omitted, as they are indistinguishable from uninitialized storage.
+pub trait IterableStateReader: StateReader {
+    /// Returns the addresses of all contracts holding any state (a class hash, a nonce or
+    /// storage), sorted.
+    fn get_contract_addresses(&self) -> StateResult<Vec<ContractAddress>>;
+
+    /// Returns the storage entries of the given contract instance, sorted by key.
+    fn get_storage_entries(
+        &self,
+        contract_address: ContractAddress,
+    ) -> StateResult<Vec<(StorageKey, Felt)>>;
+
+    /// Returns the hashes of all declared classes, sorted.
+    fn get_declared_class_hashes(&self) -> StateResult<Vec<ClassHash>>;
+}
+
 /// A class defining the API for writing to Starknet global state.
 ///
 /// Reader functionality should be delegated to the associated type; which is passed in by
@@ -119,3 +155,49 @@ pub trait UpdatableState: StateReader {
         visited_pcs: &HashMap<ClassHash, HashSet<usize>>,
     );
 }
+
+/// Checks that a batch read returned a value per key; otherwise, the values cannot be matched to
+/// their keys.
+pub(crate) fn check_batch_read<V>(n_keys: usize, values: Vec<V>) -> StateResult<Vec<V>> {
+    if values.len() != n_keys {
+        return Err(StateError::StateReadError(format!(
+            "A batch read of {n_keys} keys returned {} values",
+            values.len()
+        )));
+    }
+    Ok(values)
+}
+
+/// Serves the given keys with `get_known` where possible, and reads the rest in one batch with
+/// `read_missing`; each value read in the batch is also passed to `on_read` (e.g., to cache it).
+pub(crate) fn read_batch_through<K: Copy, V: Copy>(
+    keys: &[K],
+    get_known: impl Fn(&K) -> Option<V>,
+    read_missing: impl FnOnce(&[K]) -> StateResult<Vec<V>>,
+    mut on_read: impl FnMut(&K, V),
+) -> StateResult<Vec<V>> {
+    let known_values: Vec<Option<V>> = keys.iter().map(get_known).collect();
+    let missing_keys: Vec<K> = keys
+        .iter()
+        .zip(&known_values)
+        .filter_map(|(key, known_value)| known_value.is_none().then_some(*key))
+        .collect();
+    let read_values = if missing_keys.is_empty() {
+        Vec::new()
+    } else {
+        check_batch_read(missing_keys.len(), read_missing(&missing_keys)?)?
+    };
+
+    let mut read_values = read_values.into_iter();
+    Ok(keys
+        .iter()
+        .zip(known_values)
+        .map(|(key, known_value)| {
+            known_value.unwrap_or_else(|| {
+                let value = read_values.next().expect("A value is read per missing key.");
+                on_read(key, value);
+                value
+            })
+        })
+        .collect())
+}
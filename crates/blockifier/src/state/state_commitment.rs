@@ -0,0 +1,194 @@
+use std::collections::{HashMap, HashSet};
+
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use starknet_api::core::{ClassHash, CompiledClassHash, ContractAddress, Nonce};
+use starknet_api::state::StorageKey;
+use starknet_types_core::felt::Felt;
+use starknet_types_core::hash::{Pedersen, Poseidon, StarkHash};
+
+use crate::state::cached_state::CommitmentStateDiff;
+use crate::state::patricia_tree::{PatriciaTree, ProofNode};
+use crate::state::state_api::{IterableStateReader, StateResult};
+
+#[cfg(test)]
+#[path = "state_commitment_test.rs"]
+mod test;
+
+/// The version of the contract state hash, as committed to by the OS.
+const CONTRACT_STATE_HASH_VERSION: Felt = Felt::ZERO;
+/// "CONTRACT_CLASS_LEAF_V0".
+const CONTRACT_CLASS_LEAF_V0: Felt =
+    Felt::from_hex_unchecked("0x434f4e54524143545f434c4153535f4c4541465f5630");
+/// "STARKNET_STATE_V0".
+const STARKNET_STATE_V0: Felt = Felt::from_hex_unchecked("0x535441524b4e45545f53544154455f5630");
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct StateRoots {
+    pub global_root: Felt,
+    pub contracts_trie_root: Felt,
+    pub classes_trie_root: Felt,
+}
+
+/// A proof of storage values of a contract, with respect to the global state root.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct StorageProof {
+    /// The path to the contract's leaf in the contracts trie.
+    pub contract_proof: Vec<ProofNode>,
+    /// The preimage of the contract's leaf.
+    pub class_hash: ClassHash,
+    pub nonce: Nonce,
+    pub storage_root: Felt,
+    /// The paths to the requested keys in the contract's storage trie, in order.
+    pub storage_proofs: Vec<Vec<ProofNode>>,
+}
+
+#[derive(Clone, Debug, Default)]
+struct ContractState {
+    class_hash: ClassHash,
+    nonce: Nonce,
+    storage: PatriciaTree<Pedersen>,
+}
+
+impl ContractState {
+    fn leaf_hash(&self) -> Felt {
+        contract_leaf_hash(self.class_hash, self.nonce, self.storage.root())
+    }
+}
+
+/// Computes a contract's leaf in the contracts trie, as done by the OS.
+pub fn contract_leaf_hash(class_hash: ClassHash, nonce: Nonce, storage_root: Felt) -> Felt {
+    let hash = Pedersen::hash(&class_hash.0, &storage_root);
+    let hash = Pedersen::hash(&hash, &nonce.0);
+    Pedersen::hash(&hash, &CONTRACT_STATE_HASH_VERSION)
+}
+
+/// Computes a class' leaf in the classes trie, as done by the OS.
+pub fn class_leaf_hash(compiled_class_hash: CompiledClassHash) -> Felt {
+    Poseidon::hash(&CONTRACT_CLASS_LEAF_V0, &compiled_class_hash.0)
+}
+
+/// An in-memory image of Starknet's state tries: the contracts trie (whose leaves commit to each
+/// contract's class hash, nonce and storage trie) and the classes trie (whose leaves commit to
+/// compiled class hashes). Contract tries use Pedersen, and the classes trie uses Poseidon.
+#[derive(Clone, Debug, Default)]
+pub struct StateTries {
+    contracts: HashMap<ContractAddress, ContractState>,
+    contracts_trie: PatriciaTree<Pedersen>,
+    classes_trie: PatriciaTree<Poseidon>,
+}
+
+impl StateTries {
+    /// Builds the tries of the entire given state.
+    pub fn from_state_reader(state_reader: &impl IterableStateReader) -> StateResult<Self> {
+        let mut state_diff = CommitmentStateDiff {
+            address_to_class_hash: IndexMap::new(),
+            address_to_nonce: IndexMap::new(),
+            storage_updates: IndexMap::new(),
+            class_hash_to_compiled_class_hash: IndexMap::new(),
+        };
+        for contract_address in state_reader.get_contract_addresses()? {
+            state_diff
+                .address_to_class_hash
+                .insert(contract_address, state_reader.get_class_hash_at(contract_address)?);
+            state_diff
+                .address_to_nonce
+                .insert(contract_address, state_reader.get_nonce_at(contract_address)?);
+            state_diff.storage_updates.insert(
+                contract_address,
+                state_reader.get_storage_entries(contract_address)?.into_iter().collect(),
+            );
+        }
+        for class_hash in state_reader.get_declared_class_hashes()? {
+            state_diff
+                .class_hash_to_compiled_class_hash
+                .insert(class_hash, state_reader.get_compiled_class_hash(class_hash)?);
+        }
+
+        let mut state_tries = Self::default();
+        state_tries.apply_state_diff(&state_diff);
+        Ok(state_tries)
+    }
+
+    /// Applies the given state diff (e.g., of a finalized block), and returns the new roots.
+    pub fn apply_state_diff(&mut self, state_diff: &CommitmentStateDiff) -> StateRoots {
+        let mut updated_contracts = HashSet::new();
+        for (contract_address, class_hash) in &state_diff.address_to_class_hash {
+            self.contracts.entry(*contract_address).or_default().class_hash = *class_hash;
+            updated_contracts.insert(*contract_address);
+        }
+        for (contract_address, nonce) in &state_diff.address_to_nonce {
+            self.contracts.entry(*contract_address).or_default().nonce = *nonce;
+            updated_contracts.insert(*contract_address);
+        }
+        for (contract_address, storage_updates) in &state_diff.storage_updates {
+            let contract_state = self.contracts.entry(*contract_address).or_default();
+            for (key, value) in storage_updates {
+                contract_state.storage.insert(*key.0.key(), *value);
+            }
+            updated_contracts.insert(*contract_address);
+        }
+        for contract_address in updated_contracts {
+            let leaf_hash = self.contracts[&contract_address].leaf_hash();
+            self.contracts_trie.insert(*contract_address.0.key(), leaf_hash);
+        }
+
+        for (class_hash, compiled_class_hash) in &state_diff.class_hash_to_compiled_class_hash {
+            // Cairo 0 classes have no compiled class hash, and are not committed to.
+            let leaf_hash = if compiled_class_hash.0 == Felt::ZERO {
+                Felt::ZERO
+            } else {
+                class_leaf_hash(*compiled_class_hash)
+            };
+            self.classes_trie.insert(class_hash.0, leaf_hash);
+        }
+
+        self.roots()
+    }
+
+    pub fn roots(&self) -> StateRoots {
+        let contracts_trie_root = self.contracts_trie.root();
+        let classes_trie_root = self.classes_trie.root();
+        // Before the classes trie existed, the global root was the contracts trie root.
+        let global_root = if classes_trie_root == Felt::ZERO {
+            contracts_trie_root
+        } else {
+            Poseidon::hash_array(&[STARKNET_STATE_V0, contracts_trie_root, classes_trie_root])
+        };
+
+        StateRoots { global_root, contracts_trie_root, classes_trie_root }
+    }
+
+    /// Returns the root of the storage trie of the given contract; zero if it has no storage.
+    pub fn storage_root(&self, contract_address: ContractAddress) -> Felt {
+        self.contracts
+            .get(&contract_address)
+            .map(|contract_state| contract_state.storage.root())
+            .unwrap_or_default()
+    }
+
+    /// Returns a proof of the values of the given keys in the given contract's storage.
+    pub fn get_storage_proof(
+        &self,
+        contract_address: ContractAddress,
+        keys: &[StorageKey],
+    ) -> StorageProof {
+        let default_contract_state = ContractState::default();
+        let contract_state =
+            self.contracts.get(&contract_address).unwrap_or(&default_contract_state);
+        let contract_proof = self
+            .contracts_trie
+            .proofs(&[*contract_address.0.key()])
+            .pop()
+            .expect("A proof is returned per key.");
+        let keys: Vec<Felt> = keys.iter().map(|key| *key.0.key()).collect();
+
+        StorageProof {
+            contract_proof,
+            class_hash: contract_state.class_hash,
+            nonce: contract_state.nonce,
+            storage_root: contract_state.storage.root(),
+            storage_proofs: contract_state.storage.proofs(&keys),
+        }
+    }
+}
@@ -0,0 +1,271 @@
+use pretty_assertions::assert_eq;
+use starknet_api::core::{ContractAddress, PatriciaKey};
+use starknet_api::{class_hash, contract_address, felt, patricia_key};
+use starknet_types_core::felt::Felt;
+use starknet_types_core::hash::{Pedersen, Poseidon, StarkHash};
+
+use crate::state::cached_state::{CommitmentStateDiff, StateMaps};
+use crate::state::patricia_tree::{PatriciaTree, ProofNode, TREE_HEIGHT};
+use crate::state::state_commitment::{
+    class_leaf_hash, contract_leaf_hash, StateRoots, StateTries, STARKNET_STATE_V0,
+};
+use crate::test_utils::contracts::FeatureContract;
+use crate::test_utils::dict_state_reader::DictStateReader;
+use crate::test_utils::CairoVersion;
+use crate::{compiled_class_hash, nonce, storage_key};
+
+#[test]
+fn test_patricia_tree_root() {
+    let mut tree = PatriciaTree::<Pedersen>::default();
+    assert_eq!(tree.root(), Felt::ZERO);
+
+    // A single leaf is reached by an edge spanning the entire key.
+    tree.insert(felt!(2_u8), felt!(7_u8));
+    let height = Felt::from(TREE_HEIGHT as u64);
+    assert_eq!(tree.root(), Pedersen::hash(&felt!(7_u8), &felt!(2_u8)) + height);
+
+    // Sibling leaves split at the last bit.
+    tree.insert(felt!(3_u8), felt!(8_u8));
+    let binary_node = Pedersen::hash(&felt!(7_u8), &felt!(8_u8));
+    assert_eq!(
+        tree.root(),
+        Pedersen::hash(&binary_node, &felt!(1_u8)) + Felt::from(TREE_HEIGHT as u64 - 1)
+    );
+
+    // Zero leaves are removed.
+    tree.insert(felt!(3_u8), Felt::ZERO);
+    assert_eq!(tree.root(), Pedersen::hash(&felt!(7_u8), &felt!(2_u8)) + height);
+}
+
+// The expected roots were computed by an independent implementation of the commitment scheme,
+// whose Pedersen hash matches StarkWare's published test vector.
+#[test]
+fn test_patricia_tree_known_root() {
+    let mut tree = PatriciaTree::<Pedersen>::default();
+    for (key, value) in [(0x10_u128, 1_u8), (0x11, 2), (0x400, 3), (1 << 100, 4)] {
+        tree.insert(Felt::from(key), Felt::from(value));
+    }
+    assert_eq!(
+        tree.root(),
+        felt!("0x6133f1b709e96a8c2622061c5b73a491dececa09991612cd4befb4019f20bc2")
+    );
+}
+
+#[test]
+fn test_patricia_tree_updates() {
+    let leaves = |range: std::ops::Range<u128>| {
+        range.map(|i| (Felt::from(i * 0x9e3779b97f4a7c15), Felt::from(i + 1)))
+    };
+    let mut tree = PatriciaTree::<Pedersen>::default();
+    for (key, value) in leaves(0..40) {
+        tree.insert(key, value);
+    }
+    let root = tree.root();
+
+    // Updates on top of cached hashes yield the same root as building the resulting tree at once.
+    for (key, _) in leaves(0..20) {
+        tree.insert(key, Felt::ZERO);
+    }
+    tree.insert(Felt::ONE, felt!(7_u8));
+    let mut expected_tree = PatriciaTree::<Pedersen>::default();
+    for (key, value) in leaves(20..40).rev().chain([(Felt::ONE, felt!(7_u8))]) {
+        expected_tree.insert(key, value);
+    }
+    assert_eq!(tree.root(), expected_tree.root());
+    assert_eq!(tree.get(&Felt::ONE), felt!(7_u8));
+    assert_eq!(tree.get(&leaves(0..1).next().unwrap().0), Felt::ZERO);
+
+    // Reverting the updates restores the root.
+    tree.insert(Felt::ONE, Felt::ZERO);
+    for (key, value) in leaves(0..20) {
+        tree.insert(key, value);
+    }
+    assert_eq!(tree.root(), root);
+
+    // Removing all leaves empties the tree.
+    for (key, _) in leaves(0..40) {
+        tree.insert(key, Felt::ZERO);
+    }
+    assert!(tree.is_empty());
+    assert_eq!(tree.root(), Felt::ZERO);
+}
+
+#[test]
+fn test_patricia_tree_proofs() {
+    let mut tree = PatriciaTree::<Poseidon>::default();
+    for (key, value) in [(0x10_u128, 1_u8), (0x11, 2), (0x400, 3), (1 << 100, 4)] {
+        tree.insert(Felt::from(key), Felt::from(value));
+    }
+    let root = tree.root();
+    let keys = [felt!(0x11_u8), felt!(0x400_u16), felt!(0x12_u8), felt!(0x1000_u16)];
+    let proofs = tree.proofs(&keys);
+
+    for (key, proof) in keys.iter().zip(&proofs) {
+        assert_eq!(PatriciaTree::<Poseidon>::verify_proof(root, *key, proof), Some(tree.get(key)));
+    }
+    // Proofs do not hold for other roots, keys or hash functions.
+    assert_eq!(PatriciaTree::<Poseidon>::verify_proof(root + Felt::ONE, keys[0], &proofs[0]), None);
+    assert_eq!(PatriciaTree::<Poseidon>::verify_proof(root, keys[1], &proofs[0]), None);
+    assert_eq!(PatriciaTree::<Pedersen>::verify_proof(root, keys[0], &proofs[0]), None);
+    // Proofs of missing keys cannot be extended.
+    let mut extended_proof = proofs[2].clone();
+    extended_proof.push(ProofNode::Binary { left: Felt::ZERO, right: Felt::ZERO });
+    assert_eq!(PatriciaTree::<Poseidon>::verify_proof(root, keys[2], &extended_proof), None);
+}
+
+fn state_diff() -> CommitmentStateDiff {
+    let contract_address = contract_address!("0x100");
+    StateMaps {
+        storage: [
+            ((contract_address, storage_key!("0x1")), felt!("0x11")),
+            ((contract_address, storage_key!("0x2")), felt!("0x12")),
+            ((contract_address!("0x200"), storage_key!("0x1")), felt!("0x21")),
+        ]
+        .into(),
+        nonces: [(contract_address, nonce!(1_u8))].into(),
+        class_hashes: [(contract_address, class_hash!("0x5"))].into(),
+        compiled_class_hashes: [(class_hash!("0x5"), compiled_class_hash!(6_u8))].into(),
+        ..Default::default()
+    }
+    .into()
+}
+
+#[test]
+fn test_state_roots() {
+    assert_eq!(StateTries::default().roots(), StateRoots::default());
+
+    let mut state_tries = StateTries::default();
+    let state_roots = state_tries.apply_state_diff(&state_diff());
+
+    let contract_address = contract_address!("0x100");
+    let mut storage_trie = PatriciaTree::<Pedersen>::default();
+    storage_trie.insert(felt!("0x1"), felt!("0x11"));
+    storage_trie.insert(felt!("0x2"), felt!("0x12"));
+    let storage_root = storage_trie.root();
+    assert_eq!(state_tries.storage_root(contract_address), storage_root);
+
+    let mut contracts_trie = PatriciaTree::<Pedersen>::default();
+    contracts_trie.insert(
+        *contract_address.0.key(),
+        contract_leaf_hash(class_hash!("0x5"), nonce!(1_u8), storage_root),
+    );
+    contracts_trie.insert(
+        felt!("0x200"),
+        contract_leaf_hash(
+            class_hash!("0x0"),
+            nonce!(0_u8),
+            state_tries.storage_root(contract_address!("0x200")),
+        ),
+    );
+    let mut classes_trie = PatriciaTree::<Poseidon>::default();
+    classes_trie.insert(felt!("0x5"), class_leaf_hash(compiled_class_hash!(6_u8)));
+    let (contracts_trie_root, classes_trie_root) = (contracts_trie.root(), classes_trie.root());
+    assert_eq!(
+        state_roots,
+        StateRoots {
+            global_root: Poseidon::hash_array(&[
+                STARKNET_STATE_V0,
+                contracts_trie_root,
+                classes_trie_root
+            ]),
+            contracts_trie_root,
+            classes_trie_root,
+        }
+    );
+
+    // Applying a diff on top of a base state yields the same roots as building the resulting
+    // state from scratch.
+    let mut update: CommitmentStateDiff = StateMaps {
+        storage: [((contract_address, storage_key!("0x2")), Felt::ZERO)].into(),
+        ..Default::default()
+    }
+    .into();
+    update.storage_updates[&contract_address].insert(storage_key!("0x3"), felt!("0x13"));
+    let updated_roots = state_tries.apply_state_diff(&update);
+
+    let mut full_state_diff = state_diff();
+    full_state_diff.storage_updates[&contract_address]
+        .extend(update.storage_updates[&contract_address].clone());
+    let mut dict_state_reader = DictStateReader {
+        address_to_nonce: full_state_diff.address_to_nonce.into_iter().collect(),
+        address_to_class_hash: full_state_diff.address_to_class_hash.into_iter().collect(),
+        class_hash_to_compiled_class_hash: full_state_diff
+            .class_hash_to_compiled_class_hash
+            .into_iter()
+            .collect(),
+        class_hash_to_class: [(
+            class_hash!("0x5"),
+            FeatureContract::Empty(CairoVersion::Cairo1).get_class(),
+        )]
+        .into(),
+        ..Default::default()
+    };
+    for (contract_address, storage_updates) in full_state_diff.storage_updates {
+        for (key, value) in storage_updates {
+            dict_state_reader.storage_view.insert((contract_address, key), value);
+        }
+    }
+    let rebuilt_state_tries = StateTries::from_state_reader(&dict_state_reader).unwrap();
+    assert_eq!(rebuilt_state_tries.roots(), updated_roots);
+    assert_ne!(updated_roots, state_roots);
+}
+
+// Without declared classes, the global root is the contracts trie root, which is Pedersen-based
+// throughout; see `test_patricia_tree_known_root` for the source of the expected values.
+#[test]
+fn test_known_state_roots() {
+    let mut state_diff = state_diff();
+    state_diff.class_hash_to_compiled_class_hash.clear();
+    let mut state_tries = StateTries::default();
+    let state_roots = state_tries.apply_state_diff(&state_diff);
+
+    assert_eq!(
+        state_tries.storage_root(contract_address!("0x100")),
+        felt!("0x7c892c7f2657095dc29ca7c26db389a0f328c9d7e49b763e479df712871eb8c")
+    );
+    assert_eq!(
+        state_tries.storage_root(contract_address!("0x200")),
+        felt!("0x4c6fadb010cb3311bed92474569e3e1ec6ee694276f5bc20718b6f2073b1f21")
+    );
+
+    let contracts_trie_root =
+        felt!("0x6034204523d664179a1e2778a3f2526c169a2486d1e66d353e1ad711e2754ef");
+    assert_eq!(
+        state_roots,
+        StateRoots {
+            global_root: contracts_trie_root,
+            contracts_trie_root,
+            classes_trie_root: Felt::ZERO
+        }
+    );
+}
+
+#[test]
+fn test_storage_proof() {
+    let mut state_tries = StateTries::default();
+    let state_roots = state_tries.apply_state_diff(&state_diff());
+    let contract_address = contract_address!("0x100");
+    let keys = [storage_key!("0x2"), storage_key!("0x7")];
+
+    let storage_proof = state_tries.get_storage_proof(contract_address, &keys);
+    assert_eq!(
+        PatriciaTree::<Pedersen>::verify_proof(
+            state_roots.contracts_trie_root,
+            *contract_address.0.key(),
+            &storage_proof.contract_proof
+        ),
+        Some(contract_leaf_hash(
+            storage_proof.class_hash,
+            storage_proof.nonce,
+            storage_proof.storage_root
+        ))
+    );
+    let proven_values: Vec<Option<Felt>> = keys
+        .iter()
+        .zip(&storage_proof.storage_proofs)
+        .map(|(key, proof)| {
+            PatriciaTree::<Pedersen>::verify_proof(storage_proof.storage_root, *key.0.key(), proof)
+        })
+        .collect();
+    assert_eq!(proven_values, vec![Some(felt!("0x12")), Some(Felt::ZERO)]);
+}
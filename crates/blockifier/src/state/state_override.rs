@@ -0,0 +1,121 @@
+use std::collections::HashMap;
+
+use starknet_api::core::{ClassHash, CompiledClassHash, ContractAddress, Nonce};
+use starknet_api::state::StorageKey;
+use starknet_types_core::felt::Felt;
+
+use crate::abi::abi_utils::get_fee_token_var_address;
+use crate::abi::sierra_types::next_storage_key;
+use crate::execution::contract_class::ContractClass;
+use crate::state::cached_state::StorageEntry;
+use crate::state::state_api::{read_batch_through, StateReader, StateResult};
+
+#[cfg(test)]
+#[path = "state_override_test.rs"]
+mod test;
+
+/// Values to read instead of the ones held by the underlying state (e.g., for eth_call-style
+/// simulations).
+#[derive(Clone, Debug, Default)]
+pub struct StateOverrides {
+    pub storage: HashMap<(ContractAddress, StorageKey), Felt>,
+    pub nonces: HashMap<ContractAddress, Nonce>,
+    pub class_hashes: HashMap<ContractAddress, ClassHash>,
+    pub compiled_class_hashes: HashMap<ClassHash, CompiledClassHash>,
+    /// Classes to consider as declared, regardless of the underlying state.
+    pub declared_classes: HashMap<ClassHash, ContractClass>,
+}
+
+impl StateOverrides {
+    /// Overrides the balance of the given contract in the given fee token, as read by
+    /// `StateReader::get_fee_token_balance`.
+    pub fn override_fee_token_balance(
+        &mut self,
+        contract_address: ContractAddress,
+        fee_token_address: ContractAddress,
+        (low, high): (Felt, Felt),
+    ) -> StateResult<()> {
+        let low_key = get_fee_token_var_address(contract_address);
+        let high_key = next_storage_key(&low_key)?;
+        self.storage.insert((fee_token_address, low_key), low);
+        self.storage.insert((fee_token_address, high_key), high);
+
+        Ok(())
+    }
+}
+
+/// A state reader that serves overridden values, and delegates all other reads to the wrapped
+/// reader. The wrapped reader itself is never modified.
+pub struct StateOverrideReader<S: StateReader> {
+    pub state_reader: S,
+    overrides: StateOverrides,
+}
+
+impl<S: StateReader> StateOverrideReader<S> {
+    pub fn new(state_reader: S, overrides: StateOverrides) -> Self {
+        Self { state_reader, overrides }
+    }
+
+    pub fn overrides(&self) -> &StateOverrides {
+        &self.overrides
+    }
+}
+
+impl<S: StateReader> StateReader for StateOverrideReader<S> {
+    fn get_storage_at(
+        &self,
+        contract_address: ContractAddress,
+        key: StorageKey,
+    ) -> StateResult<Felt> {
+        match self.overrides.storage.get(&(contract_address, key)) {
+            Some(value) => Ok(*value),
+            None => self.state_reader.get_storage_at(contract_address, key),
+        }
+    }
+
+    fn get_nonce_at(&self, contract_address: ContractAddress) -> StateResult<Nonce> {
+        match self.overrides.nonces.get(&contract_address) {
+            Some(nonce) => Ok(*nonce),
+            None => self.state_reader.get_nonce_at(contract_address),
+        }
+    }
+
+    fn get_storage_values(&self, storage_entries: &[StorageEntry]) -> StateResult<Vec<Felt>> {
+        read_batch_through(
+            storage_entries,
+            |storage_entry| self.overrides.storage.get(storage_entry).copied(),
+            |missing_entries| self.state_reader.get_storage_values(missing_entries),
+            |_, _| {},
+        )
+    }
+
+    fn get_nonces(&self, contract_addresses: &[ContractAddress]) -> StateResult<Vec<Nonce>> {
+        read_batch_through(
+            contract_addresses,
+            |contract_address| self.overrides.nonces.get(contract_address).copied(),
+            |missing_addresses| self.state_reader.get_nonces(missing_addresses),
+            |_, _| {},
+        )
+    }
+
+    fn get_class_hash_at(&self, contract_address: ContractAddress) -> StateResult<ClassHash> {
+        match self.overrides.class_hashes.get(&contract_address) {
+            Some(class_hash) => Ok(*class_hash),
+            None => self.state_reader.get_class_hash_at(contract_address),
+        }
+    }
+
+    fn get_compiled_contract_class(&self, class_hash: ClassHash) -> StateResult<ContractClass> {
+        match self.overrides.declared_classes.get(&class_hash) {
+            Some(contract_class) => Ok(contract_class.clone()),
+            None => self.state_reader.get_compiled_contract_class(class_hash),
+        }
+    }
+
+    fn get_compiled_class_hash(&self, class_hash: ClassHash) -> StateResult<CompiledClassHash> {
+        match self.overrides.compiled_class_hashes.get(&class_hash) {
+            Some(compiled_class_hash) => Ok(*compiled_class_hash),
+            None => self.state_reader.get_compiled_class_hash(class_hash),
+        }
+    }
+}
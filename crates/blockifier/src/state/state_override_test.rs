@@ -0,0 +1,156 @@
+use assert_matches::assert_matches;
+use pretty_assertions::assert_eq;
+use rstest::rstest;
+use starknet_api::core::{ClassHash, ContractAddress, Nonce, PatriciaKey};
+use starknet_api::transaction::TransactionVersion;
+use starknet_api::{class_hash, contract_address, felt, patricia_key};
+use starknet_types_core::felt::Felt;
+
+use crate::blockifier::config::TransactionExecutorConfig;
+use crate::blockifier::transaction_executor::{TransactionExecutor, TransactionExecutorError};
+use crate::context::BlockContext;
+use crate::state::state_api::StateReader;
+use crate::state::state_override::{StateOverrideReader, StateOverrides};
+use crate::test_utils::contracts::FeatureContract;
+use crate::test_utils::dict_state_reader::DictStateReader;
+use crate::test_utils::initial_test_state::test_state;
+use crate::test_utils::{create_trivial_calldata, CairoVersion, BALANCE};
+use crate::transaction::objects::FeeType;
+use crate::transaction::test_utils::{account_invoke_tx, block_context, max_resource_bounds};
+use crate::transaction::transaction_execution::Transaction;
+use crate::{compiled_class_hash, invoke_tx_args, nonce, storage_key};
+
+#[test]
+fn test_overrides_take_precedence() {
+    let contract_address = contract_address!("0x100");
+    let other_contract_address = contract_address!("0x200");
+    let key = storage_key!("0x10");
+    let class_hash = class_hash!("0x11");
+    let mut state_reader = DictStateReader::default();
+    state_reader.storage_view.insert((contract_address, key), felt!("0x1"));
+    state_reader.storage_view.insert((other_contract_address, key), felt!("0x2"));
+    state_reader.address_to_nonce.insert(contract_address, nonce!(1_u8));
+    state_reader.address_to_class_hash.insert(contract_address, class_hash);
+    state_reader.class_hash_to_compiled_class_hash.insert(class_hash, compiled_class_hash!(1_u8));
+
+    let overrides = StateOverrides {
+        storage: [((contract_address, key), felt!("0x3"))].into(),
+        nonces: [(contract_address, nonce!(2_u8))].into(),
+        class_hashes: [(other_contract_address, class_hash)].into(),
+        compiled_class_hashes: [(class_hash, compiled_class_hash!(2_u8))].into(),
+        declared_classes: [(class_hash, FeatureContract::Empty(CairoVersion::Cairo0).get_class())]
+            .into(),
+    };
+    let override_reader = StateOverrideReader::new(state_reader, overrides);
+
+    // Overridden values.
+    assert_eq!(override_reader.get_storage_at(contract_address, key).unwrap(), felt!("0x3"));
+    assert_eq!(override_reader.get_nonce_at(contract_address).unwrap(), nonce!(2_u8));
+    assert_eq!(override_reader.get_class_hash_at(other_contract_address).unwrap(), class_hash);
+    assert_eq!(
+        override_reader.get_compiled_class_hash(class_hash).unwrap(),
+        compiled_class_hash!(2_u8)
+    );
+    assert!(override_reader.get_compiled_contract_class(class_hash).is_ok());
+
+    // Values that are not overridden are read from the underlying reader.
+    assert_eq!(override_reader.get_storage_at(other_contract_address, key).unwrap(), felt!("0x2"));
+    assert_eq!(override_reader.get_nonce_at(other_contract_address).unwrap(), Nonce::default());
+    assert_eq!(override_reader.get_class_hash_at(contract_address).unwrap(), class_hash);
+    assert_eq!(
+        override_reader.get_class_hash_at(contract_address!("0x300")).unwrap(),
+        ClassHash::default()
+    );
+
+    // The underlying reader is untouched.
+    let state_reader = &override_reader.state_reader;
+    assert_eq!(state_reader.get_storage_at(contract_address, key).unwrap(), felt!("0x1"));
+    assert_eq!(state_reader.get_nonce_at(contract_address).unwrap(), nonce!(1_u8));
+    assert!(state_reader.get_compiled_contract_class(class_hash).is_err());
+}
+
+#[test]
+fn test_fee_token_balance_override() {
+    let account_address = ContractAddress::from(1_u128);
+    let fee_token_address = ContractAddress::from(2_u128);
+    let mut overrides = StateOverrides::default();
+    overrides
+        .override_fee_token_balance(account_address, fee_token_address, (felt!(7_u8), felt!(8_u8)))
+        .unwrap();
+
+    let mut override_reader = StateOverrideReader::new(DictStateReader::default(), overrides);
+    assert_eq!(
+        override_reader.get_fee_token_balance(account_address, fee_token_address).unwrap(),
+        (felt!(7_u8), felt!(8_u8))
+    );
+    assert_eq!(
+        override_reader.get_fee_token_balance(fee_token_address, fee_token_address).unwrap(),
+        (Felt::ZERO, Felt::ZERO)
+    );
+}
+
+#[rstest]
+fn test_executor_with_state_overrides(
+    block_context: BlockContext,
+    #[values(CairoVersion::Cairo0, CairoVersion::Cairo1)] cairo_version: CairoVersion,
+) {
+    let account = FeatureContract::AccountWithoutValidations(cairo_version);
+    let test_contract = FeatureContract::TestContract(cairo_version);
+    let account_address = account.get_instance_address(0);
+    // An unfunded account.
+    let state_reader =
+        test_state(&block_context.chain_info, 0, &[(account, 1), (test_contract, 1)]).state;
+    let tx = Transaction::AccountTransaction(account_invoke_tx(invoke_tx_args! {
+        sender_address: account_address,
+        calldata: create_trivial_calldata(test_contract.get_instance_address(0)),
+        resource_bounds: max_resource_bounds(),
+        version: TransactionVersion::THREE,
+    }));
+    let fee_token_address = block_context.chain_info.fee_token_address(&FeeType::Strk);
+
+    // Without overrides, the account cannot afford the transaction.
+    let mut tx_executor = TransactionExecutor::new_with_state_overrides(
+        state_reader.clone(),
+        StateOverrides::default(),
+        block_context.clone(),
+        TransactionExecutorConfig::default(),
+    );
+    assert_matches!(
+        tx_executor.execute(&tx),
+        Err(TransactionExecutorError::TransactionExecutionError(_))
+    );
+
+    // Override the account's balance.
+    let mut overrides = StateOverrides::default();
+    overrides
+        .override_fee_token_balance(
+            account_address,
+            fee_token_address,
+            (felt!(BALANCE), Felt::ZERO),
+        )
+        .unwrap();
+    let mut tx_executor = TransactionExecutor::new_with_state_overrides(
+        state_reader,
+        overrides,
+        block_context,
+        TransactionExecutorConfig::default(),
+    );
+    let tx_execution_info = tx_executor.execute(&tx).unwrap();
+    assert!(!tx_execution_info.is_reverted());
+
+    let block_state = tx_executor.block_state.as_mut().unwrap();
+    let actual_fee = tx_execution_info.transaction_receipt.fee.0;
+    assert_eq!(
+        block_state.get_fee_token_balance(account_address, fee_token_address).unwrap(),
+        (Felt::from(BALANCE - actual_fee), Felt::ZERO)
+    );
+    // The wrapped reader is untouched.
+    assert_eq!(
+        block_state
+            .state
+            .state_reader
+            .get_fee_token_balance(account_address, fee_token_address)
+            .unwrap(),
+        (Felt::ZERO, Felt::ZERO)
+    );
+}
@@ -6,8 +6,9 @@ use starknet_api::StarknetApiError;
 use starknet_types_core::felt::Felt;
 
 use crate::execution::contract_class::ContractClass;
+use crate::state::cached_state::StorageEntry;
 use crate::state::errors::StateError;
-use crate::state::state_api::{State, StateReader, StateResult};
+use crate::state::state_api::{read_batch_through, State, StateReader, StateResult};
 
 #[cfg(test)]
 #[path = "state_wrapper_test.rs"]
@@ -118,6 +119,15 @@ impl StateReader for DynStateWrapper<'_> {
         }
     }
 
+    fn get_storage_values(&self, storage_entries: &[StorageEntry]) -> StateResult<Vec<Felt>> {
+        read_batch_through(
+            storage_entries,
+            |storage_entry| self.storage_updates.get(storage_entry).copied(),
+            |missing_entries| self.state.get_storage_values(missing_entries),
+            |_, _| {},
+        )
+    }
+
     fn get_class_hash_at(&self, contract_address: ContractAddress) -> StateResult<ClassHash> {
         Ok(self
             .class_hashes
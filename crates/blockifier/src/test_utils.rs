@@ -1,3 +1,4 @@
+#[cfg(feature = "native")]
 pub mod cached_state;
 pub mod contracts;
 pub mod declare;
@@ -11,41 +12,57 @@ pub mod transfers_generator;
 use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
+#[cfg(feature = "native")]
 use std::sync::Arc;
 
+#[cfg(feature = "native")]
 use cairo_native::starknet::SyscallResult;
 use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
-use starknet_api::core::{
-    calculate_contract_address, ClassHash, ContractAddress, Nonce, PatriciaKey,
-};
+#[cfg(feature = "native")]
+use starknet_api::core::{calculate_contract_address, PatriciaKey};
+use starknet_api::core::{ClassHash, ContractAddress, Nonce};
 use starknet_api::state::StorageKey;
 use starknet_api::transaction::{
     Calldata, ContractAddressSalt, Resource, ResourceBounds, ResourceBoundsMapping,
     TransactionVersion,
 };
-use starknet_api::{class_hash, contract_address, felt, patricia_key};
+#[cfg(feature = "native")]
+use starknet_api::{class_hash, patricia_key};
+use starknet_api::{contract_address, felt};
 use starknet_types_core::felt::Felt;
 
 use self::dict_state_reader::DictStateReader;
 use crate::abi::abi_utils::{get_fee_token_var_address, selector_from_name};
-use crate::context::{BlockContext, TransactionContext};
-use crate::execution::call_info::{CallInfo, OrderedEvent};
+use crate::context::BlockContext;
+#[cfg(feature = "native")]
+use crate::context::TransactionContext;
+use crate::execution::call_info::CallInfo;
+#[cfg(feature = "native")]
+use crate::execution::call_info::OrderedEvent;
+#[cfg(feature = "native")]
 use crate::execution::common_hints::ExecutionMode;
 use crate::execution::deprecated_syscalls::hint_processor::SyscallCounter;
-use crate::execution::entry_point::{
-    CallEntryPoint, ConstructorContext, EntryPointExecutionContext,
-};
+use crate::execution::entry_point::CallEntryPoint;
+#[cfg(feature = "native")]
+use crate::execution::entry_point::{ConstructorContext, EntryPointExecutionContext};
+#[cfg(feature = "native")]
 use crate::execution::execution_utils::execute_deployment;
+#[cfg(feature = "native")]
 use crate::execution::native::utils::{
     contract_address_to_native_felt, decode_felts_as_str, encode_str_as_felts,
 };
+#[cfg(feature = "native")]
 use crate::execution::syscalls::hint_processor::FAILED_TO_CALCULATE_CONTRACT_ADDRESS;
 use crate::execution::syscalls::SyscallSelector;
 use crate::state::cached_state::{CachedState, StateChangesCount};
+#[cfg(feature = "native")]
 use crate::state::state_api::State;
+#[cfg(feature = "native")]
 use crate::test_utils::cached_state::get_erc20_class_hash_mapping;
 use crate::test_utils::contracts::FeatureContract;
-use crate::transaction::objects::{StarknetResources, TransactionInfo};
+use crate::transaction::objects::StarknetResources;
+#[cfg(feature = "native")]
+use crate::transaction::objects::TransactionInfo;
 use crate::transaction::transaction_types::TransactionType;
 use crate::utils::{const_max, u128_from_usize};
 use crate::versioned_constants::VersionedConstants;
@@ -390,6 +407,41 @@ pub fn get_tx_resources(tx_type: TransactionType) -> ExecutionResources {
     versioned_constants.get_additional_os_tx_resources(tx_type, &starknet_resources, false).unwrap()
 }
 
+/// Executes the given call after replacing the native classes in the state with their Casm
+/// equivalents (see [FeatureContract::get_casm_equivalent_class]). Used to derive the gas native
+/// execution is expected to consume.
+pub fn execute_with_casm_equivalent_classes(
+    mut state: CachedState<DictStateReader>,
+    entry_point_call: CallEntryPoint,
+) -> CallInfo {
+    for native_contract in
+        [FeatureContract::SierraTestContract, FeatureContract::SierraExecutionInfoV1Contract]
+    {
+        if let Some(contract_class) =
+            state.state.class_hash_to_class.get_mut(&native_contract.get_class_hash())
+        {
+            *contract_class = native_contract.get_casm_equivalent_class();
+        }
+    }
+    entry_point_call.execute_directly(&mut state).unwrap()
+}
+
+/// Returns the given expected gas if any; otherwise (typically, in native test cases), returns the
+/// gas consumed by the given call when executed on the Casm equivalents of the state's classes
+/// (see [execute_with_casm_equivalent_classes]), as native execution is charged as the Casm class
+/// compiled from the same Sierra.
+pub fn expected_gas_or_casm_equivalent(
+    expected_gas: Option<u64>,
+    create_state: impl FnOnce() -> CachedState<DictStateReader>,
+    entry_point_call: &CallEntryPoint,
+) -> u64 {
+    expected_gas.unwrap_or_else(|| {
+        execute_with_casm_equivalent_classes(create_state(), entry_point_call.clone())
+            .execution
+            .gas_consumed
+    })
+}
+
 /// Creates the calldata for the Cairo function "test_deploy" in the featured contract TestContract.
 /// The format of the calldata is:
 /// [
@@ -471,6 +523,7 @@ pub fn update_json_value(base: &mut serde_json::Value, update: serde_json::Value
     }
 }
 
+#[cfg(feature = "native")]
 pub fn create_erc20_deploy_test_state() -> CachedState<DictStateReader> {
     let address_to_class_hash: HashMap<ContractAddress, ClassHash> = HashMap::from([(
         contract_address!(TEST_ERC20_FULL_CONTRACT_ADDRESS),
@@ -484,6 +537,7 @@ pub fn create_erc20_deploy_test_state() -> CachedState<DictStateReader> {
     })
 }
 
+#[cfg(feature = "native")]
 pub fn deploy_contract(
     state: &mut dyn State,
     class_hash: Felt,
@@ -535,6 +589,7 @@ pub fn deploy_contract(
     Ok((contract_address_felt, return_data))
 }
 
+#[cfg(feature = "native")]
 pub fn prepare_erc20_deploy_test_state() -> (ContractAddress, CachedState<DictStateReader>) {
     let mut state = create_erc20_deploy_test_state();
 
@@ -556,6 +611,7 @@ pub fn prepare_erc20_deploy_test_state() -> (ContractAddress, CachedState<DictSt
     (contract_address, state)
 }
 
+#[cfg(feature = "native")]
 #[derive(Debug, Clone, Copy)]
 pub enum Signers {
     Alice,
@@ -563,6 +619,7 @@ pub enum Signers {
     Charlie,
 }
 
+#[cfg(feature = "native")]
 impl Signers {
     pub fn get_address(&self) -> ContractAddress {
         match self {
@@ -573,24 +630,28 @@ impl Signers {
     }
 }
 
+#[cfg(feature = "native")]
 impl From<Signers> for ContractAddress {
     fn from(val: Signers) -> ContractAddress {
         val.get_address()
     }
 }
 
+#[cfg(feature = "native")]
 impl From<Signers> for Felt {
     fn from(val: Signers) -> Felt {
         contract_address_to_native_felt(val.get_address())
     }
 }
 
+#[cfg(feature = "native")]
 #[derive(Debug, Clone)]
 pub struct TestEvent {
     pub data: Vec<Felt>,
     pub keys: Vec<Felt>,
 }
 
+#[cfg(feature = "native")]
 impl From<OrderedEvent> for TestEvent {
     fn from(value: OrderedEvent) -> Self {
         let event_data = value.event.data.0;
@@ -599,6 +660,7 @@ impl From<OrderedEvent> for TestEvent {
     }
 }
 
+#[cfg(feature = "native")]
 pub struct TestContext {
     pub contract_address: ContractAddress,
     pub state: CachedState<DictStateReader>,
@@ -606,6 +668,7 @@ pub struct TestContext {
     pub events: Vec<TestEvent>,
 }
 
+#[cfg(feature = "native")]
 impl Default for TestContext {
     fn default() -> Self {
         let (contract_address, state) = prepare_erc20_deploy_test_state();
@@ -613,6 +676,7 @@ impl Default for TestContext {
     }
 }
 
+#[cfg(feature = "native")]
 impl TestContext {
     pub fn new() -> Self {
         Self::default()
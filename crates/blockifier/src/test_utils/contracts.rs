@@ -1,9 +1,12 @@
+use cairo_lang_starknet_classes::casm_contract_class::CasmContractClass;
+use cairo_lang_starknet_classes::contract_class::ContractClass as SierraContractClass;
 use starknet_api::core::{
     ClassHash, CompiledClassHash, ContractAddress, EntryPointSelector, PatriciaKey,
 };
 use starknet_api::deprecated_contract_class::{
     ContractClass as DeprecatedContractClass, EntryPointOffset, EntryPointType,
 };
+use starknet_api::state::ContractClass as SnApiContractClass;
 use starknet_api::{class_hash, contract_address, felt, patricia_key};
 use starknet_types_core::felt::Felt;
 use strum::IntoEnumIterator;
@@ -11,9 +14,9 @@ use strum_macros::EnumIter;
 
 use crate::abi::abi_utils::selector_from_name;
 use crate::abi::constants::CONSTRUCTOR_ENTRY_POINT_NAME;
-use crate::execution::contract_class::{
-    ContractClass, ContractClassV0, ContractClassV1, NativeContractClassV1,
-};
+#[cfg(feature = "native")]
+use crate::execution::contract_class::NativeContractClassV1;
+use crate::execution::contract_class::{ContractClass, ContractClassV0, ContractClassV1};
 use crate::test_utils::{get_raw_contract_class, CairoVersion};
 
 // This file contains featured contracts, used for tests. Use the function 'test_state' in
@@ -163,6 +166,21 @@ impl FeatureContract {
             .expect("DeprecatedContractClass is not supported for this contract.")
     }
 
+    /// Returns the SN API (storage) representation of a Sierra feature contract.
+    pub fn get_sn_api_sierra_contract_class(&self) -> SnApiContractClass {
+        let mut raw_contract_class: serde_json::Value =
+            serde_json::from_str(&self.get_raw_class()).unwrap();
+
+        // The ABI is not required for execution, and is represented as a string in SN API.
+        raw_contract_class
+            .as_object_mut()
+            .expect("A Sierra contract class must be a JSON object.")
+            .insert("abi".to_string(), serde_json::Value::String(String::default()));
+
+        serde_json::from_value(raw_contract_class)
+            .expect("SN API ContractClass is not supported for this contract.")
+    }
+
     pub fn get_raw_class(&self) -> String {
         get_raw_contract_class(&self.get_compiled_path())
     }
@@ -259,6 +277,7 @@ impl FeatureContract {
                     .unwrap()
                     .offset
             }
+            #[cfg(feature = "native")]
             ContractClass::V1Native(_) => {
                 panic!("Not implemented for cairo native contracts")
             }
@@ -274,10 +293,15 @@ impl FeatureContract {
 
     pub fn get_class(&self) -> ContractClass {
         match self {
-            // TODO replace once vm/native is a flag
+            #[cfg(feature = "native")]
             Self::SierraTestContract | Self::SierraExecutionInfoV1Contract => {
                 NativeContractClassV1::from_file(&self.get_compiled_path()).into()
             }
+            // Without native support, Sierra contracts are executed on the VM.
+            #[cfg(not(feature = "native"))]
+            Self::SierraTestContract | Self::SierraExecutionInfoV1Contract => {
+                self.get_casm_equivalent_class()
+            }
             _ => match self.cairo_version() {
                 CairoVersion::Cairo0 => {
                     ContractClassV0::from_file(&self.get_compiled_path()).into()
@@ -289,6 +313,18 @@ impl FeatureContract {
         }
     }
 
+    /// Returns the Casm class compiled from the Sierra program of a native feature contract.
+    /// Native execution of the contract is expected to be charged exactly as the VM execution of
+    /// this class.
+    pub fn get_casm_equivalent_class(&self) -> ContractClass {
+        let sierra_contract_class: SierraContractClass =
+            serde_json::from_str(&self.get_raw_class()).unwrap();
+        let casm_contract_class =
+            CasmContractClass::from_contract_class(sierra_contract_class, false, usize::MAX)
+                .unwrap();
+        ContractClassV1::try_from(casm_contract_class).unwrap().into()
+    }
+
     pub fn get_ctor_offset(
         &self,
         entry_point_selector: Option<EntryPointSelector>,
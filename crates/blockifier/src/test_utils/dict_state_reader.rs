@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 
 use starknet_api::core::{ClassHash, CompiledClassHash, ContractAddress, Nonce};
 use starknet_api::state::StorageKey;
@@ -7,7 +7,7 @@ use starknet_types_core::felt::Felt;
 use crate::execution::contract_class::ContractClass;
 use crate::state::cached_state::StorageEntry;
 use crate::state::errors::StateError;
-use crate::state::state_api::{StateReader, StateResult};
+use crate::state::state_api::{IterableStateReader, StateReader, StateResult};
 
 /// A simple implementation of `StateReader` using `HashMap`s as storage.
 #[derive(Clone, Debug, Default)]
@@ -58,3 +58,35 @@ impl StateReader for DictStateReader {
         Ok(compiled_class_hash)
     }
 }
+
+impl IterableStateReader for DictStateReader {
+    fn get_contract_addresses(&self) -> StateResult<Vec<ContractAddress>> {
+        let contract_addresses: BTreeSet<ContractAddress> = self
+            .address_to_class_hash
+            .keys()
+            .chain(self.address_to_nonce.keys())
+            .chain(self.storage_view.keys().map(|(contract_address, _)| contract_address))
+            .copied()
+            .collect();
+        Ok(contract_addresses.into_iter().collect())
+    }
+
+    fn get_storage_entries(
+        &self,
+        contract_address: ContractAddress,
+    ) -> StateResult<Vec<(StorageKey, Felt)>> {
+        let storage_entries: BTreeMap<StorageKey, Felt> = self
+            .storage_view
+            .iter()
+            .filter(|((address, _), value)| *address == contract_address && **value != Felt::ZERO)
+            .map(|((_, key), value)| (*key, *value))
+            .collect();
+        Ok(storage_entries.into_iter().collect())
+    }
+
+    fn get_declared_class_hashes(&self) -> StateResult<Vec<ClassHash>> {
+        let mut class_hashes: Vec<ClassHash> = self.class_hash_to_class.keys().copied().collect();
+        class_hashes.sort();
+        Ok(class_hashes)
+    }
+}
@@ -1,6 +1,5 @@
 use std::sync::Arc;
 
-use cairo_native::executor::AotNativeExecutor;
 use cairo_vm::vm::runners::cairo_runner::ExecutionResources;
 use serde_json::Value;
 use starknet_api::block::{BlockNumber, BlockTimestamp};
@@ -10,13 +9,19 @@ use starknet_api::{contract_address, felt, patricia_key};
 
 use super::update_json_value;
 use crate::blockifier::block::{BlockInfo, GasPrices};
+#[cfg(feature = "native")]
+use crate::blockifier::config::NativeExecutionConfig;
 use crate::bouncer::{BouncerConfig, BouncerWeights};
 use crate::context::{BlockContext, ChainInfo, FeeTokenAddresses, TransactionContext};
 use crate::execution::call_info::{CallExecution, CallInfo, Retdata};
-use crate::execution::contract_class::{ContractClassV0, ContractClassV1, NativeContractClassV1};
+#[cfg(feature = "native")]
+use crate::execution::contract_class::NativeContractClassV1;
+use crate::execution::contract_class::{ContractClassV0, ContractClassV1};
 use crate::execution::entry_point::{
     CallEntryPoint, EntryPointExecutionContext, EntryPointExecutionResult,
 };
+#[cfg(feature = "native")]
+use crate::execution::native::compiler::compile_sierra_to_native;
 use crate::fee::fee_utils::get_fee_by_gas_vector;
 use crate::state::state_api::State;
 use crate::test_utils::{
@@ -162,6 +167,14 @@ impl BlockContext {
             chain_info: ChainInfo::create_for_testing(),
             versioned_constants: VersionedConstants::create_for_testing(),
             bouncer_config: BouncerConfig::max(),
+            syscall_interceptor: None,
+            execution_recorder: None,
+            #[cfg(feature = "native")]
+            divergence_reporter: None,
+            #[cfg(feature = "native")]
+            native_execution_config: NativeExecutionConfig::default(),
+            #[cfg(feature = "native")]
+            native_worker: None,
         }
     }
 
@@ -171,6 +184,14 @@ impl BlockContext {
             chain_info: ChainInfo::create_for_testing(),
             versioned_constants: VersionedConstants::create_for_account_testing(),
             bouncer_config: BouncerConfig::max(),
+            syscall_interceptor: None,
+            execution_recorder: None,
+            #[cfg(feature = "native")]
+            divergence_reporter: None,
+            #[cfg(feature = "native")]
+            native_execution_config: NativeExecutionConfig::default(),
+            #[cfg(feature = "native")]
+            native_worker: None,
         }
     }
 
@@ -216,35 +237,15 @@ impl ContractClassV1 {
     }
 }
 
+#[cfg(feature = "native")]
 impl NativeContractClassV1 {
     /// Convenience function to construct a NativeContractClassV1 from a raw contract class.
     /// If control over the compilation is desired use [Self::new] instead.
     fn try_from_json_string(raw_contract_class: &str) -> Result<Self, Box<dyn std::error::Error>> {
-        // Compile the Sierra Program to native code and loads it into the process'
-        // memory space.
-        fn compile_and_load(
-            sierra_program: &cairo_lang_sierra::program::Program,
-        ) -> Result<AotNativeExecutor, cairo_native::error::Error> {
-            let native_context = cairo_native::context::NativeContext::new();
-            let native_program = native_context.compile(sierra_program)?;
-            Ok(AotNativeExecutor::from_native_module(
-                native_program,
-                cairo_native::OptLevel::Default,
-            ))
-        }
-
         let sierra_contract_class: cairo_lang_starknet_classes::contract_class::ContractClass =
             serde_json::from_str(raw_contract_class)?;
 
-        // todo(rodro): we are having two instances of a sierra program, one it's object form
-        // and another in its felt encoded form. This can be avoided by either:
-        //   1. Having access to the encoding/decoding functions
-        //   2. Refactoring the code on the Cairo mono-repo
-
-        let sierra_program = sierra_contract_class.extract_sierra_program()?;
-        let executor = compile_and_load(&sierra_program)?;
-
-        Ok(Self::new(executor, sierra_contract_class)?)
+        Ok(compile_sierra_to_native(sierra_contract_class, cairo_native::OptLevel::Default)?)
     }
 
     pub fn from_file(contract_path: &str) -> Self {
@@ -27,7 +27,7 @@ use crate::transaction::errors::{
     TransactionExecutionError, TransactionFeeError, TransactionPreValidationError,
 };
 use crate::transaction::objects::{
-    DeprecatedTransactionInfo, HasRelatedFeeType, TransactionExecutionInfo,
+    DeprecatedTransactionInfo, FeeBounds, HasRelatedFeeType, TransactionExecutionInfo,
     TransactionExecutionResult, TransactionInfo, TransactionInfoCreator,
     TransactionPreValidationResult,
 };
@@ -51,7 +51,7 @@ mod flavors_test;
 mod post_execution_test;
 
 /// Represents a paid Starknet transaction.
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub enum AccountTransaction {
     Declare(DeclareTransaction),
     DeployAccount(DeployAccountTransaction),
@@ -113,6 +113,53 @@ impl AccountTransaction {
         }
     }
 
+    /// Sets the fee bounds the transaction commits to; note that its hash is left unchanged.
+    /// Panics if the kind of bounds does not match the transaction version.
+    pub(crate) fn set_fee_bounds(&mut self, fee_bounds: FeeBounds) {
+        let version = self.version();
+        let (max_fee, resource_bounds) = match self {
+            Self::Declare(tx) => match &mut tx.tx {
+                starknet_api::transaction::DeclareTransaction::V0(tx)
+                | starknet_api::transaction::DeclareTransaction::V1(tx) => {
+                    (Some(&mut tx.max_fee), None)
+                }
+                starknet_api::transaction::DeclareTransaction::V2(tx) => {
+                    (Some(&mut tx.max_fee), None)
+                }
+                starknet_api::transaction::DeclareTransaction::V3(tx) => {
+                    (None, Some(&mut tx.resource_bounds))
+                }
+            },
+            Self::DeployAccount(tx) => match &mut tx.tx {
+                starknet_api::transaction::DeployAccountTransaction::V1(tx) => {
+                    (Some(&mut tx.max_fee), None)
+                }
+                starknet_api::transaction::DeployAccountTransaction::V3(tx) => {
+                    (None, Some(&mut tx.resource_bounds))
+                }
+            },
+            Self::Invoke(tx) => match &mut tx.tx {
+                starknet_api::transaction::InvokeTransaction::V0(tx) => {
+                    (Some(&mut tx.max_fee), None)
+                }
+                starknet_api::transaction::InvokeTransaction::V1(tx) => {
+                    (Some(&mut tx.max_fee), None)
+                }
+                starknet_api::transaction::InvokeTransaction::V3(tx) => {
+                    (None, Some(&mut tx.resource_bounds))
+                }
+            },
+        };
+
+        match (fee_bounds, max_fee, resource_bounds) {
+            (FeeBounds::MaxFee(fee), Some(max_fee), None) => *max_fee = fee,
+            (FeeBounds::ResourceBounds(bounds), None, Some(resource_bounds)) => {
+                *resource_bounds = bounds
+            }
+            _ => panic!("Fee bounds of the wrong kind for transaction version {version:?}."),
+        }
+    }
+
     pub fn calldata_length(&self) -> usize {
         let calldata = match self {
             Self::Declare(_tx) => calldata![],
@@ -360,6 +407,8 @@ impl AccountTransaction {
         };
 
         let mut context = EntryPointExecutionContext::new_invoke(tx_context, true)?;
+        // The fee transfer is observed as a whole, once final.
+        context.execution_recorder = None;
 
         Ok(fee_transfer_call
             .execute(state, &mut ExecutionResources::default(), &mut context)
@@ -158,6 +158,14 @@ pub struct DeprecatedTransactionInfo {
     pub max_fee: Fee,
 }
 
+/// The fee bounds a transaction commits to: a max fee for deprecated transactions, and resource
+/// bounds for current ones.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum FeeBounds {
+    MaxFee(Fee),
+    ResourceBounds(ResourceBoundsMapping),
+}
+
 #[derive(
     derive_more::Add, derive_more::Sum, Clone, Copy, Debug, Default, Eq, PartialEq, Serialize,
 )]
@@ -564,7 +572,7 @@ pub trait HasRelatedFeeType {
     }
 }
 
-#[derive(Clone, Copy, Hash, EnumIter, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Hash, EnumIter, Eq, PartialEq)]
 pub enum FeeType {
     Strk,
     Eth,
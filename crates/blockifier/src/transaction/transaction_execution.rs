@@ -91,6 +91,20 @@ impl Transaction {
     }
 }
 
+impl Transaction {
+    /// Marks the transaction as a query, as if its version had the query bit set (see
+    /// [crate::transaction::objects::TransactionInfo::signed_version]). L1 handlers are left
+    /// as is.
+    pub(crate) fn set_only_query(&mut self) {
+        match self {
+            Self::AccountTransaction(AccountTransaction::Declare(tx)) => tx.set_only_query(),
+            Self::AccountTransaction(AccountTransaction::DeployAccount(tx)) => tx.only_query = true,
+            Self::AccountTransaction(AccountTransaction::Invoke(tx)) => tx.only_query = true,
+            Self::L1HandlerTransaction(_) => {}
+        }
+    }
+}
+
 impl TransactionInfoCreator for Transaction {
     fn create_tx_info(&self) -> TransactionInfo {
         match self {
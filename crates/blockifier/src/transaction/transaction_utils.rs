@@ -36,6 +36,7 @@ pub fn verify_contract_class_version(
                 cairo_version: 1,
             })
         }
+        #[cfg(feature = "native")]
         ContractClass::V1Native(_) => todo!("Sierra verify contract class version"),
     }
 }
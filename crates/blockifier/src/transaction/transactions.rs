@@ -112,7 +112,7 @@ pub trait ValidatableTransaction {
     ) -> TransactionExecutionResult<Option<CallInfo>>;
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct DeclareTransaction {
     pub tx: starknet_api::transaction::DeclareTransaction,
     pub tx_hash: TransactionHash,
@@ -167,6 +167,10 @@ impl DeclareTransaction {
         self.only_query
     }
 
+    pub(crate) fn set_only_query(&mut self) {
+        self.only_query = true;
+    }
+
     fn try_declare<S: State>(
         &self,
         state: &mut S,
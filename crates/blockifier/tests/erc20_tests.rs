@@ -7,8 +7,11 @@ use starknet_types_core::felt::Felt;
 pub const TOTAL_SUPPLY: u128 = 10_000_000_000_000_000_000_000u128;
 pub const BALANCE_TO_TRANSFER: u128 = 10u128;
 pub const BALANCE_AFTER_TRANSFER: u128 = TOTAL_SUPPLY - BALANCE_TO_TRANSFER;
-pub const U256_SUB_OVERFLOW: &str = "Native execution error: u256_sub Overflow";
-pub const CALLER_IS_NOT_THE_OWNER: &str = "Native execution error: Caller is not the owner";
+pub const U256_SUB_OVERFLOW: &str =
+    "Execution failed. Failure reason: 0x753235365f737562204f766572666c6f77 ('u256_sub Overflow').";
+pub const CALLER_IS_NOT_THE_OWNER: &str = "Execution failed. Failure reason: \
+                                           0x43616c6c6572206973206e6f7420746865206f776e6572 \
+                                           ('Caller is not the owner').";
 
 pub const NAME: &str = "Native";
 pub const SYMBOL: &str = "MTK";
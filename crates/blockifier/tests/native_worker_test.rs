@@ -0,0 +1,106 @@
+// run with:
+// cargo test --test native_worker_test --features native,testing
+#![cfg(feature = "native")]
+
+use std::path::Path;
+use std::time::Duration;
+
+use blockifier::abi::abi_utils::selector_from_name;
+use blockifier::blockifier::config::{
+    NativeArtifactCacheConfig, NativeWorkerConfig, NativeWorkerSandboxConfig,
+    TransactionExecutorConfig,
+};
+use blockifier::blockifier::transaction_executor::TransactionExecutor;
+use blockifier::execution::call_info::CallInfo;
+use blockifier::execution::native::worker::NativeWorker;
+use blockifier::invoke_tx_args;
+use blockifier::test_utils::contracts::FeatureContract;
+use blockifier::test_utils::initial_test_state::test_state;
+use blockifier::test_utils::{create_calldata, CairoVersion, BALANCE};
+use blockifier::transaction::test_utils::{account_invoke_tx, block_context};
+use blockifier::transaction::transaction_execution::Transaction;
+use starknet_api::felt;
+use starknet_api::transaction::TransactionVersion;
+use starknet_types_core::felt::Felt;
+
+const ACCOUNT: FeatureContract = FeatureContract::AccountWithoutValidations(CairoVersion::Cairo1);
+const TEST_CONTRACT: FeatureContract = FeatureContract::SierraTestContract;
+
+fn native_worker(artifact_cache_dir: &Path) -> NativeWorker {
+    NativeWorker::new(NativeWorkerConfig {
+        executable_path: env!("CARGO_BIN_EXE_native_executor_worker").into(),
+        opt_level: 0,
+        artifact_cache_config: NativeArtifactCacheConfig {
+            path: artifact_cache_dir.to_path_buf(),
+            max_size: u64::MAX,
+        },
+        response_timeout: Duration::from_secs(60),
+        sandbox_config: NativeWorkerSandboxConfig::default(),
+    })
+}
+
+/// Invokes the given function of the test contract, and returns the call info of its execution.
+fn invoke(
+    native_worker: Option<NativeWorker>,
+    entry_point_name: &str,
+    entry_point_args: &[Felt],
+) -> CallInfo {
+    let block_context = block_context();
+    let state =
+        test_state(block_context.chain_info(), BALANCE, &[(ACCOUNT, 1), (TEST_CONTRACT, 2)]);
+    let config = TransactionExecutorConfig { native_worker, ..Default::default() };
+    let mut tx_executor = TransactionExecutor::new(state, block_context, config);
+
+    let calldata =
+        create_calldata(TEST_CONTRACT.get_instance_address(0), entry_point_name, entry_point_args);
+    let tx = Transaction::AccountTransaction(account_invoke_tx(invoke_tx_args! {
+        sender_address: ACCOUNT.get_instance_address(0),
+        calldata,
+        version: TransactionVersion::ZERO,
+    }));
+    let tx_execution_info = tx_executor.execute(&tx).unwrap();
+    assert!(!tx_execution_info.is_reverted(), "{:?}", tx_execution_info.revert_error);
+
+    tx_execution_info.execute_call_info.unwrap()
+}
+
+#[test]
+fn test_storage_read_write() {
+    let artifact_cache_dir = tempfile::tempdir().unwrap();
+    let args = [felt!(1234_u16), felt!(18_u8)];
+
+    assert_eq!(
+        invoke(Some(native_worker(artifact_cache_dir.path())), "test_storage_read_write", &args),
+        invoke(None, "test_storage_read_write", &args)
+    );
+}
+
+#[test]
+fn test_nested_call_contract() {
+    let artifact_cache_dir = tempfile::tempdir().unwrap();
+    // test_call_contract -> test_storage_read_write, on another instance.
+    let args = [
+        *TEST_CONTRACT.get_instance_address(1).0.key(),
+        selector_from_name("test_storage_read_write").0,
+        felt!(2_u8),
+        felt!(1234_u16),
+        felt!(18_u8),
+    ];
+
+    assert_eq!(
+        invoke(Some(native_worker(artifact_cache_dir.path())), "test_call_contract", &args),
+        invoke(None, "test_call_contract", &args)
+    );
+}
+
+#[test]
+fn test_worker_reuse() {
+    let artifact_cache_dir = tempfile::tempdir().unwrap();
+    let native_worker = native_worker(artifact_cache_dir.path());
+    let args = [felt!(1234_u16), felt!(18_u8)];
+
+    let first = invoke(Some(native_worker.clone()), "test_storage_read_write", &args);
+    // Served by the same process, which has the class loaded already.
+    let second = invoke(Some(native_worker), "test_storage_read_write", &args);
+    assert_eq!(first, second);
+}
@@ -1,13 +1,22 @@
 use std::collections::HashMap;
+use std::time::Duration;
 
 use blockifier::blockifier::block::pre_process_block;
-use blockifier::blockifier::config::TransactionExecutorConfig;
+use blockifier::blockifier::config::{
+    DifferentialExecutionConfig, NativeCompilationConfig, NativeExecutionConfig,
+    NativeWorkerConfig, NativeWorkerSandboxConfig, TransactionExecutorConfig,
+};
 use blockifier::blockifier::transaction_executor::{TransactionExecutor, TransactionExecutorError};
 use blockifier::bouncer::BouncerConfig;
 use blockifier::context::{BlockContext, ChainInfo, FeeTokenAddresses};
 use blockifier::execution::call_info::CallInfo;
-use blockifier::state::cached_state::CachedState;
-use blockifier::state::global_cache::GlobalContractCache;
+use blockifier::execution::native::artifact_cache::NativeArtifactCache;
+use blockifier::execution::native::compilation_pool::NativeCompilationPool;
+use blockifier::execution::native::worker::NativeWorker;
+use blockifier::state::cached_state::{CachedState, CommitmentStateDiff};
+use blockifier::state::global_cache::{
+    GlobalContractCache, GlobalStateCache, GlobalStateCacheReader,
+};
 use blockifier::transaction::objects::{GasVector, ResourcesMapping, TransactionExecutionInfo};
 use blockifier::transaction::transaction_execution::Transaction;
 use blockifier::versioned_constants::VersionedConstants;
@@ -16,12 +25,14 @@ use pyo3::types::{PyBytes, PyList};
 use pyo3::{FromPyObject, PyAny, Python};
 use serde::Serialize;
 use starknet_api::block::BlockNumber;
-use starknet_api::core::{ChainId, ContractAddress};
+use starknet_api::core::{ChainId, ClassHash, ContractAddress};
 use starknet_api::transaction::Fee;
 use starknet_types_core::felt::Felt;
 
 use crate::errors::{NativeBlockifierError, NativeBlockifierResult};
-use crate::py_objects::{PyBouncerConfig, PyConcurrencyConfig, PyVersionedConstantsOverrides};
+use crate::py_objects::{
+    PyBouncerConfig, PyConcurrencyConfig, PyNativeCompilationConfig, PyVersionedConstantsOverrides,
+};
 use crate::py_state_diff::{PyBlockInfo, PyStateDiff};
 use crate::py_transaction::{py_tx, PyClassInfo, PY_TX_PARSING_ERR};
 use crate::py_utils::{int_to_chain_id, into_block_number_hash_pair, PyFelt};
@@ -82,16 +93,20 @@ pub struct PyBlockExecutor {
     pub tx_executor_config: TransactionExecutorConfig,
     pub chain_info: ChainInfo,
     pub versioned_constants: VersionedConstants,
-    pub tx_executor: Option<TransactionExecutor<PapyrusReader>>,
+    pub tx_executor: Option<TransactionExecutor<GlobalStateCacheReader<PapyrusReader>>>,
     /// `Send` trait is required for `pyclass` compatibility as Python objects must be threadsafe.
     pub storage: Box<dyn Storage + Send>,
     pub global_contract_cache: GlobalContractCache,
+    // If set, storage values and nonces are cached across blocks.
+    pub global_state_cache: Option<GlobalStateCache>,
+    // Shared by the readers of all blocks, so that compilation outlives a single block.
+    pub native_compilation_pool: Option<NativeCompilationPool>,
 }
 
 #[pymethods]
 impl PyBlockExecutor {
     #[new]
-    #[pyo3(signature = (bouncer_config, concurrency_config, general_config, global_contract_cache_size, target_storage_config, py_versioned_constants_overrides))]
+    #[pyo3(signature = (bouncer_config, concurrency_config, general_config, global_contract_cache_size, target_storage_config, py_versioned_constants_overrides, global_state_cache_size=None, global_contract_cache_max_bytes=None, native_compilation_config=None))]
     pub fn create(
         bouncer_config: PyBouncerConfig,
         concurrency_config: PyConcurrencyConfig,
@@ -99,24 +114,82 @@ impl PyBlockExecutor {
         global_contract_cache_size: usize,
         target_storage_config: StorageConfig,
         py_versioned_constants_overrides: PyVersionedConstantsOverrides,
+        global_state_cache_size: Option<usize>,
+        global_contract_cache_max_bytes: Option<usize>,
+        native_compilation_config: Option<PyNativeCompilationConfig>,
     ) -> Self {
         log::debug!("Initializing Block Executor...");
         let storage =
             PapyrusStorage::new(target_storage_config).expect("Failed to initialize storage.");
         let versioned_constants =
             VersionedConstants::get_versioned_constants(py_versioned_constants_overrides.into());
+        let global_contract_cache = GlobalContractCache::new_with_max_bytes(
+            global_contract_cache_size,
+            global_contract_cache_max_bytes,
+        );
+        let global_state_cache = global_state_cache_size.map(GlobalStateCache::new);
+        // Native compilation is disabled by default.
+        let native_compilation_config = native_compilation_config.unwrap_or_default();
+        let differential_execution_config = DifferentialExecutionConfig {
+            enabled: native_compilation_config.differential_execution,
+        };
+        let native_execution_config = NativeExecutionConfig {
+            max_execution_time: native_compilation_config
+                .max_execution_time_ms
+                .map(Duration::from_millis),
+        };
+        let native_worker_path = native_compilation_config.native_worker_path.clone();
+        let native_worker_sandbox_config = NativeWorkerSandboxConfig {
+            max_memory: native_compilation_config.native_worker_max_memory,
+            user: native_compilation_config.native_worker_user,
+        };
+        let response_timeout =
+            Duration::from_millis(native_compilation_config.native_worker_response_timeout_ms);
+        let native_compilation_config: NativeCompilationConfig = native_compilation_config.into();
+        let native_worker = native_worker_path.map(|path| {
+            NativeWorker::new(NativeWorkerConfig {
+                executable_path: path,
+                opt_level: native_compilation_config.opt_level,
+                artifact_cache_config: native_compilation_config
+                    .artifact_cache_config
+                    .clone()
+                    .expect("Native workers require a native artifact cache."),
+                response_timeout,
+                sandbox_config: native_worker_sandbox_config,
+            })
+        });
+        let native_compilation_pool = match &native_compilation_config {
+            NativeCompilationConfig {
+                enabled: true,
+                opt_level,
+                artifact_cache_config,
+                background_compilation_config: Some(background_compilation_config),
+            } => Some(NativeCompilationPool::new(
+                background_compilation_config.clone(),
+                *opt_level,
+                artifact_cache_config.clone().map(NativeArtifactCache::new),
+                global_contract_cache.clone(),
+            )),
+            _ => None,
+        };
         log::debug!("Initialized Block Executor.");
 
         Self {
             bouncer_config: bouncer_config.try_into().expect("Failed to parse bouncer config."),
             tx_executor_config: TransactionExecutorConfig {
                 concurrency_config: concurrency_config.into(),
+                native_compilation_config,
+                differential_execution_config,
+                native_execution_config,
+                native_worker,
             },
             chain_info: general_config.starknet_os_config.into_chain_info(),
             versioned_constants,
             tx_executor: None,
             storage: Box::new(storage),
-            global_contract_cache: GlobalContractCache::new(global_contract_cache_size),
+            global_contract_cache,
+            global_state_cache,
+            native_compilation_pool,
         }
     }
 
@@ -279,6 +352,14 @@ impl PyBlockExecutor {
         declared_class_hash_to_class: HashMap<PyFelt, (PyFelt, String)>,
         deprecated_declared_class_hash_to_class: HashMap<PyFelt, String>,
     ) -> NativeBlockifierResult<()> {
+        // The global state cache follows the committed chain; hence, it is updated with the state
+        // diffs of appended blocks only, rather than of finalized ones.
+        let block_number = BlockNumber(py_block_info.block_number);
+        let commitment_state_diff = match &self.global_state_cache {
+            Some(_) => Some(CommitmentStateDiff::try_from(&py_state_diff)?),
+            None => None,
+        };
+
         self.storage.append_block(
             block_id,
             previous_block_id,
@@ -286,7 +367,14 @@ impl PyBlockExecutor {
             py_state_diff,
             declared_class_hash_to_class,
             deprecated_declared_class_hash_to_class,
-        )
+        )?;
+
+        if let (Some(global_state_cache), Some(commitment_state_diff)) =
+            (&self.global_state_cache, &commitment_state_diff)
+        {
+            global_state_cache.apply_state_diff(block_number, commitment_state_diff);
+        }
+        Ok(())
     }
 
     /// Returns the next block number, for which block header was not yet appended.
@@ -320,9 +408,39 @@ impl PyBlockExecutor {
     pub fn revert_block(&mut self, block_number: u64) -> NativeBlockifierResult<()> {
         // Clear global class cache, to peroperly revert classes declared in the reverted block.
         self.global_contract_cache.clear();
+        // Likewise for the global state cache, which might hold values written in that block.
+        if let Some(global_state_cache) = &self.global_state_cache {
+            global_state_cache.clear();
+        }
         self.storage.revert_block(block_number)
     }
 
+    // Global Contract Cache API.
+
+    /// Pins the classes of the given class hashes in the global contract cache; pinned classes are
+    /// never evicted.
+    #[pyo3(signature = (class_hashes))]
+    pub fn pin_classes(&self, class_hashes: Vec<PyFelt>) {
+        for class_hash in class_hashes {
+            self.global_contract_cache.pin(ClassHash(class_hash.0));
+        }
+    }
+
+    #[pyo3(signature = (class_hashes))]
+    pub fn unpin_classes(&self, class_hashes: Vec<PyFelt>) {
+        for class_hash in class_hashes {
+            self.global_contract_cache.unpin(&ClassHash(class_hash.0));
+        }
+    }
+
+    /// Returns the hit, miss and eviction counters of the global contract cache, along with its
+    /// current size, serialized as JSON.
+    pub fn get_global_contract_cache_metrics(&self) -> Py<PyBytes> {
+        let serialized_metrics = serde_json::to_vec(&self.global_contract_cache.metrics())
+            .expect("Failed serializing global contract cache metrics.");
+        Python::with_gil(|py| PyBytes::new(py, &serialized_metrics).into())
+    }
+
     /// Deallocate the transaction executor and close storage connections.
     pub fn close(&mut self) {
         log::debug!("Closing Block Executor.");
@@ -356,6 +474,7 @@ impl PyBlockExecutor {
             },
             tx_executor_config: TransactionExecutorConfig {
                 concurrency_config: concurrency_config.into(),
+                ..Default::default()
             },
             storage: Box::new(PapyrusStorage::new_for_testing(
                 path,
@@ -365,22 +484,36 @@ impl PyBlockExecutor {
             versioned_constants,
             tx_executor: None,
             global_contract_cache: GlobalContractCache::new(GLOBAL_CONTRACT_CACHE_SIZE_FOR_TEST),
+            global_state_cache: None,
+            native_compilation_pool: None,
         }
     }
 }
 
 impl PyBlockExecutor {
-    pub fn tx_executor(&mut self) -> &mut TransactionExecutor<PapyrusReader> {
+    pub fn tx_executor(
+        &mut self,
+    ) -> &mut TransactionExecutor<GlobalStateCacheReader<PapyrusReader>> {
         self.tx_executor.as_mut().expect("Transaction executor should be initialized")
     }
 
-    fn get_aligned_reader(&self, next_block_number: BlockNumber) -> PapyrusReader {
+    fn get_aligned_reader(
+        &self,
+        next_block_number: BlockNumber,
+    ) -> GlobalStateCacheReader<PapyrusReader> {
         // Full-node storage must be aligned to the Python storage before initializing a reader.
         self.storage.validate_aligned(next_block_number.0);
-        PapyrusReader::new(
+        let papyrus_reader = PapyrusReader::new(
             self.storage.reader().clone(),
             next_block_number,
             self.global_contract_cache.clone(),
+            self.tx_executor_config.native_compilation_config.clone(),
+            self.native_compilation_pool.clone(),
+        );
+        GlobalStateCacheReader::new(
+            papyrus_reader,
+            next_block_number,
+            self.global_state_cache.clone(),
         )
     }
 
@@ -395,6 +528,8 @@ impl PyBlockExecutor {
             versioned_constants: VersionedConstants::latest_constants().clone(),
             tx_executor: None,
             global_contract_cache: GlobalContractCache::new(GLOBAL_CONTRACT_CACHE_SIZE_FOR_TEST),
+            global_state_cache: None,
+            native_compilation_pool: None,
         }
     }
 
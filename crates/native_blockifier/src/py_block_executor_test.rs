@@ -2,12 +2,14 @@ use std::collections::HashMap;
 
 use blockifier::blockifier::transaction_executor::BLOCK_STATE_ACCESS_ERR;
 use blockifier::execution::contract_class::{ContractClass, ContractClassV1};
+use blockifier::state::global_cache::{GlobalStateCache, GLOBAL_CONTRACT_CACHE_SIZE_FOR_TEST};
 use blockifier::state::state_api::StateReader;
-use cached::Cached;
+use blockifier::storage_key;
 use cairo_lang_starknet_classes::casm_contract_class::CasmContractClass;
 use pretty_assertions::assert_eq;
+use starknet_api::block::BlockNumber;
 use starknet_api::core::ClassHash;
-use starknet_api::{class_hash, felt};
+use starknet_api::{class_hash, contract_address, felt};
 use starknet_types_core::felt::Felt;
 
 use crate::py_block_executor::{PyBlockExecutor, PyGeneralConfig};
@@ -52,7 +54,7 @@ fn global_contract_cache_update() {
         )
         .unwrap();
 
-    assert_eq!(block_executor.global_contract_cache.lock().cache_size(), 0);
+    assert_eq!(block_executor.global_contract_cache.lock().len(), 0);
 
     let queried_contract_class = block_executor
         .tx_executor()
@@ -63,7 +65,44 @@ fn global_contract_cache_update() {
         .unwrap();
 
     assert_eq!(queried_contract_class, contract_class);
-    assert_eq!(block_executor.global_contract_cache.lock().cache_size(), 1);
+    assert_eq!(block_executor.global_contract_cache.lock().len(), 1);
+}
+
+#[test]
+fn global_state_cache_update() {
+    let temp_storage_path = tempfile::tempdir().unwrap().into_path();
+    let mut block_executor = PyBlockExecutor::create_for_testing(
+        PyConcurrencyConfig::default(),
+        PyGeneralConfig::default(),
+        temp_storage_path,
+        4000,
+    );
+    let global_state_cache = GlobalStateCache::new(GLOBAL_CONTRACT_CACHE_SIZE_FOR_TEST);
+    block_executor.global_state_cache = Some(global_state_cache.clone());
+    let (contract_address, key) = (contract_address!("0x100"), storage_key!("0x7"));
+
+    // The state diff of a block is cached for the next block once the block is appended, rather
+    // than finalized.
+    block_executor
+        .append_block(
+            0,
+            None,
+            PyBlockInfo::default(),
+            PyStateDiff {
+                storage_updates: HashMap::from([(
+                    PyFelt::from(contract_address),
+                    HashMap::from([(PyFelt(*key.0.key()), PyFelt::from(5_u8))]),
+                )]),
+                ..PyStateDiff::default()
+            },
+            HashMap::default(),
+            HashMap::default(),
+        )
+        .unwrap();
+    assert_eq!(
+        global_state_cache.get_storage_at(BlockNumber(1), contract_address, key),
+        Some(felt!(5_u8))
+    );
 }
 
 #[test]
@@ -1,7 +1,11 @@
 use std::collections::HashMap;
+use std::path::PathBuf;
 
 use blockifier::abi::constants;
-use blockifier::blockifier::config::ConcurrencyConfig;
+use blockifier::blockifier::config::{
+    BackgroundCompilationConfig, ConcurrencyConfig, NativeArtifactCacheConfig,
+    NativeCompilationConfig,
+};
 use blockifier::bouncer::{BouncerConfig, BouncerWeights, BuiltinCount, HashMapWrapper};
 use blockifier::versioned_constants::{VersionedConstants, VersionedConstantsOverrides};
 use cairo_vm::types::builtin_name::BuiltinName;
@@ -167,3 +171,48 @@ impl From<PyConcurrencyConfig> for ConcurrencyConfig {
         }
     }
 }
+
+#[derive(Debug, Default, FromPyObject)]
+pub struct PyNativeCompilationConfig {
+    pub enabled: bool,
+    pub opt_level: u8,
+    pub artifact_cache_path: Option<PathBuf>,
+    pub artifact_cache_max_size: u64,
+    pub background_compilation: bool,
+    pub n_compilation_workers: usize,
+    pub compilation_queue_size: usize,
+    // Also execute natively compiled classes on the VM, and report divergences.
+    pub differential_execution: bool,
+    // If set, bounds the wall-clock time of native execution in each transaction phase.
+    pub max_execution_time_ms: Option<u64>,
+    // If set, native classes are executed in isolated worker processes, running this executable.
+    // The workers load the classes from the artifact cache, which must then be set.
+    pub native_worker_path: Option<PathBuf>,
+    // Maximal time to wait for a message from a worker.
+    pub native_worker_response_timeout_ms: u64,
+    // If set, bounds the address space of each worker, in bytes.
+    pub native_worker_max_memory: Option<u64>,
+    // If set, workers run as the given user and group IDs.
+    pub native_worker_user: Option<(u32, u32)>,
+}
+
+impl From<PyNativeCompilationConfig> for NativeCompilationConfig {
+    fn from(py_native_compilation_config: PyNativeCompilationConfig) -> Self {
+        NativeCompilationConfig {
+            enabled: py_native_compilation_config.enabled,
+            opt_level: py_native_compilation_config.opt_level,
+            artifact_cache_config: py_native_compilation_config.artifact_cache_path.map(|path| {
+                NativeArtifactCacheConfig {
+                    path,
+                    max_size: py_native_compilation_config.artifact_cache_max_size,
+                }
+            }),
+            background_compilation_config: py_native_compilation_config
+                .background_compilation
+                .then_some(BackgroundCompilationConfig {
+                    n_workers: py_native_compilation_config.n_compilation_workers,
+                    queue_size: py_native_compilation_config.compilation_queue_size,
+                }),
+        }
+    }
+}
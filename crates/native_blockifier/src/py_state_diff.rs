@@ -11,7 +11,7 @@ use indexmap::IndexMap;
 use pyo3::prelude::*;
 use pyo3::FromPyObject;
 use starknet_api::block::{BlockNumber, BlockTimestamp};
-use starknet_api::core::{ClassHash, ContractAddress, Nonce};
+use starknet_api::core::{ClassHash, CompiledClassHash, ContractAddress, Nonce};
 use starknet_api::state::{StateDiff, StorageKey};
 
 use crate::errors::{
@@ -77,6 +77,47 @@ impl TryFrom<PyStateDiff> for StateDiff {
     }
 }
 
+impl TryFrom<&PyStateDiff> for CommitmentStateDiff {
+    type Error = NativeBlockifierError;
+
+    fn try_from(state_diff: &PyStateDiff) -> NativeBlockifierResult<Self> {
+        let mut address_to_class_hash = IndexMap::new();
+        for (address, class_hash) in &state_diff.address_to_class_hash {
+            address_to_class_hash
+                .insert(ContractAddress::try_from(address.0)?, ClassHash(class_hash.0));
+        }
+
+        let mut address_to_nonce = IndexMap::new();
+        for (address, nonce) in &state_diff.address_to_nonce {
+            address_to_nonce.insert(ContractAddress::try_from(address.0)?, Nonce(nonce.0));
+        }
+
+        let mut storage_updates = IndexMap::new();
+        for (address, storage_mapping) in &state_diff.storage_updates {
+            let mut storage_diff = IndexMap::new();
+            for (key, value) in storage_mapping {
+                storage_diff.insert(StorageKey::try_from(key.0)?, value.0);
+            }
+            storage_updates.insert(ContractAddress::try_from(address.0)?, storage_diff);
+        }
+
+        let class_hash_to_compiled_class_hash = state_diff
+            .class_hash_to_compiled_class_hash
+            .iter()
+            .map(|(class_hash, compiled_class_hash)| {
+                (ClassHash(class_hash.0), CompiledClassHash(compiled_class_hash.0))
+            })
+            .collect();
+
+        Ok(Self {
+            address_to_class_hash,
+            address_to_nonce,
+            storage_updates,
+            class_hash_to_compiled_class_hash,
+        })
+    }
+}
+
 impl From<CommitmentStateDiff> for PyStateDiff {
     fn from(state_diff: CommitmentStateDiff) -> Self {
         // State commitment.
@@ -1,14 +1,26 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use blockifier::blockifier::config::NativeCompilationConfig;
 use blockifier::execution::contract_class::{ContractClass, ContractClassV0, ContractClassV1};
+use blockifier::execution::native::artifact_cache::NativeArtifactCache;
+use blockifier::execution::native::compilation_pool::{
+    CompilationRequestStatus, NativeCompilationPool,
+};
+use blockifier::execution::native::compiler::{
+    compile_sierra_to_native, native_opt_level, sn_api_to_sierra_contract_class,
+};
+use blockifier::state::cached_state::StorageEntry;
 use blockifier::state::errors::StateError;
 use blockifier::state::global_cache::GlobalContractCache;
-use blockifier::state::state_api::{StateReader, StateResult};
+use blockifier::state::state_api::{IterableStateReader, StateReader, StateResult};
+use cairo_lang_starknet_classes::contract_class::ContractClass as SierraContractClass;
 use papyrus_storage::compiled_class::CasmStorageReader;
 use papyrus_storage::db::RO;
 use papyrus_storage::state::StateStorageReader;
 use papyrus_storage::StorageReader;
 use starknet_api::block::BlockNumber;
 use starknet_api::core::{ClassHash, CompiledClassHash, ContractAddress, Nonce};
-use starknet_api::state::{StateNumber, StorageKey};
+use starknet_api::state::{StateNumber, StorageKey, ThinStateDiff};
 use starknet_types_core::felt::Felt;
 
 #[cfg(test)]
@@ -21,6 +33,10 @@ pub struct PapyrusReader {
     storage_reader: StorageReader,
     latest_block: BlockNumber,
     global_class_hash_to_class: GlobalContractCache,
+    native_compilation_config: NativeCompilationConfig,
+    native_artifact_cache: Option<NativeArtifactCache>,
+    // If set, classes are compiled to native code in the background.
+    native_compilation_pool: Option<NativeCompilationPool>,
 }
 
 impl PapyrusReader {
@@ -28,8 +44,19 @@ impl PapyrusReader {
         storage_reader: StorageReader,
         latest_block: BlockNumber,
         global_class_hash_to_class: GlobalContractCache,
+        native_compilation_config: NativeCompilationConfig,
+        native_compilation_pool: Option<NativeCompilationPool>,
     ) -> Self {
-        Self { storage_reader, latest_block, global_class_hash_to_class }
+        let native_artifact_cache =
+            native_compilation_config.artifact_cache_config.clone().map(NativeArtifactCache::new);
+        Self {
+            storage_reader,
+            latest_block,
+            global_class_hash_to_class,
+            native_compilation_config,
+            native_artifact_cache,
+            native_compilation_pool,
+        }
     }
 
     fn reader(&self) -> StateResult<RawPapyrusReader<'_>> {
@@ -40,6 +67,9 @@ impl PapyrusReader {
 
     /// Returns a V1 contract if found, or a V0 contract if a V1 contract is not
     /// found, or an `Error` otherwise.
+    /// If native compilation is enabled, V1 contracts are compiled from their Sierra representation
+    /// to native code; on failure, the Casm representation is returned instead.
+    /// In background compilation mode, the Casm representation is always returned.
     fn get_compiled_contract_class_inner(
         &self,
         class_hash: ClassHash,
@@ -63,6 +93,14 @@ impl PapyrusReader {
                      inconsistent.",
                 );
 
+            if self.native_compilation_config.enabled && self.native_compilation_pool.is_none() {
+                if let Some(native_contract_class) =
+                    self.get_native_contract_class(class_hash, state_number)?
+                {
+                    return Ok(native_contract_class);
+                }
+            }
+
             return Ok(ContractClass::V1(ContractClassV1::try_from(casm_contract_class)?));
         }
 
@@ -79,6 +117,91 @@ impl PapyrusReader {
             None => Err(StateError::UndeclaredClassHash(class_hash)),
         }
     }
+
+    /// Compiles the Sierra class of the given (declared) class hash to native code, or loads it
+    /// from the native artifact cache, if configured.
+    /// Returns `None` if the Sierra class is unavailable or fails to compile, in which case the
+    /// caller should fall back to the Casm class.
+    fn get_native_contract_class(
+        &self,
+        class_hash: ClassHash,
+        state_number: StateNumber,
+    ) -> StateResult<Option<ContractClass>> {
+        let Some(sierra_contract_class) =
+            self.get_sierra_contract_class(class_hash, state_number)?
+        else {
+            return Ok(None);
+        };
+
+        let opt_level = self.native_compilation_config.opt_level;
+        let compilation_result = match &self.native_artifact_cache {
+            Some(native_artifact_cache) => {
+                native_artifact_cache.get_or_compile(class_hash, sierra_contract_class, opt_level)
+            }
+            None => compile_sierra_to_native(sierra_contract_class, native_opt_level(opt_level)),
+        };
+
+        match compilation_result {
+            Ok(native_contract_class) => Ok(Some(ContractClass::V1Native(native_contract_class))),
+            Err(error) => {
+                log::warn!(
+                    "Failed to compile class {class_hash} to native code; falling back to Casm. \
+                     Error: {error}"
+                );
+                Ok(None)
+            }
+        }
+    }
+
+    /// Requests the background compilation of the given (declared) class, whose Casm
+    /// representation was just inserted into the global contract cache.
+    fn request_native_compilation(
+        &self,
+        native_compilation_pool: &NativeCompilationPool,
+        class_hash: ClassHash,
+    ) -> StateResult<()> {
+        if native_compilation_pool.is_blacklisted(&class_hash) {
+            return Ok(());
+        }
+        let Some(sierra_contract_class) =
+            self.get_sierra_contract_class(class_hash, StateNumber(self.latest_block))?
+        else {
+            return Ok(());
+        };
+
+        let status = native_compilation_pool.request_compilation(class_hash, sierra_contract_class);
+        if status == CompilationRequestStatus::QueueFull {
+            // Do not cache the Casm class, so that compilation is requested again upon the next
+            // read.
+            log::debug!("Native compilation queue is full; class {class_hash} was not enqueued.");
+            self.global_class_hash_to_class.remove(&class_hash);
+        }
+
+        Ok(())
+    }
+
+    /// Returns the Sierra class of the given (declared) class hash, if available.
+    fn get_sierra_contract_class(
+        &self,
+        class_hash: ClassHash,
+        state_number: StateNumber,
+    ) -> StateResult<Option<SierraContractClass>> {
+        let sierra_contract_class = self
+            .reader()?
+            .get_state_reader()
+            .and_then(|sr| sr.get_class_definition_at(state_number, &class_hash))
+            .map_err(|err| StateError::StateReadError(err.to_string()))?;
+        // Classes appended without their Sierra representation are stored with an empty program.
+        match sierra_contract_class.filter(|class| !class.sierra_program.is_empty()) {
+            Some(sierra_contract_class) => {
+                Ok(Some(sn_api_to_sierra_contract_class(sierra_contract_class)))
+            }
+            None => {
+                log::debug!("Sierra class {class_hash} is unavailable; falling back to Casm.");
+                Ok(None)
+            }
+        }
+    }
 }
 
 // Currently unused - will soon replace the same `impl` for `PapyrusStateReader`.
@@ -108,6 +231,45 @@ impl StateReader for PapyrusReader {
         }
     }
 
+    fn get_storage_values(&self, storage_entries: &[StorageEntry]) -> StateResult<Vec<Felt>> {
+        if storage_entries.is_empty() {
+            return Ok(vec![]);
+        }
+        let state_number = StateNumber(self.latest_block);
+        let reader = self.reader()?;
+        let state_reader = reader
+            .get_state_reader()
+            .map_err(|error| StateError::StateReadError(error.to_string()))?;
+        storage_entries
+            .iter()
+            .map(|(contract_address, key)| {
+                state_reader
+                    .get_storage_at(state_number, contract_address, key)
+                    .map_err(|error| StateError::StateReadError(error.to_string()))
+            })
+            .collect()
+    }
+
+    fn get_nonces(&self, contract_addresses: &[ContractAddress]) -> StateResult<Vec<Nonce>> {
+        if contract_addresses.is_empty() {
+            return Ok(vec![]);
+        }
+        let state_number = StateNumber(self.latest_block);
+        let reader = self.reader()?;
+        let state_reader = reader
+            .get_state_reader()
+            .map_err(|error| StateError::StateReadError(error.to_string()))?;
+        contract_addresses
+            .iter()
+            .map(|contract_address| {
+                match state_reader.get_nonce_at(state_number, contract_address) {
+                    Ok(nonce) => Ok(nonce.unwrap_or_default()),
+                    Err(err) => Err(StateError::StateReadError(err.to_string())),
+                }
+            })
+            .collect()
+    }
+
     fn get_class_hash_at(&self, contract_address: ContractAddress) -> StateResult<ClassHash> {
         let state_number = StateNumber(self.latest_block);
         match self
@@ -131,6 +293,11 @@ impl StateReader for PapyrusReader {
                 let contract_class_from_db = self.get_compiled_contract_class_inner(class_hash)?;
                 // The class was declared in a previous (finalized) state; update the global cache.
                 self.global_class_hash_to_class.set(class_hash, contract_class_from_db.clone());
+                if let (ContractClass::V1(_), Some(native_compilation_pool)) =
+                    (&contract_class_from_db, &self.native_compilation_pool)
+                {
+                    self.request_native_compilation(native_compilation_pool, class_hash)?;
+                }
                 Ok(contract_class_from_db)
             }
         }
@@ -140,3 +307,61 @@ impl StateReader for PapyrusReader {
         todo!()
     }
 }
+
+impl PapyrusReader {
+    /// Applies the given function to the state diffs of all blocks preceding the pinned block, in
+    /// order. Fails if any of them is missing, as the enumerated state would be incomplete.
+    fn for_each_state_diff(&self, mut f: impl FnMut(ThinStateDiff)) -> StateResult<()> {
+        let reader = self.reader()?;
+        for block_number in 0..self.latest_block.0 {
+            let state_diff = reader
+                .get_state_diff(BlockNumber(block_number))
+                .map_err(|error| StateError::StateReadError(error.to_string()))?
+                .ok_or_else(|| {
+                    StateError::StateReadError(format!(
+                        "Missing state diff of block {block_number}."
+                    ))
+                })?;
+            f(state_diff);
+        }
+        Ok(())
+    }
+}
+
+// Note: enumeration replays the state diffs of the entire chain, and is meant for offline use
+// (e.g., state export), rather than for execution.
+impl IterableStateReader for PapyrusReader {
+    fn get_contract_addresses(&self) -> StateResult<Vec<ContractAddress>> {
+        let mut contract_addresses = BTreeSet::new();
+        self.for_each_state_diff(|state_diff| {
+            contract_addresses.extend(state_diff.deployed_contracts.keys());
+            contract_addresses.extend(state_diff.replaced_classes.keys());
+            contract_addresses.extend(state_diff.nonces.keys());
+            contract_addresses.extend(state_diff.storage_diffs.keys());
+        })?;
+        Ok(contract_addresses.into_iter().collect())
+    }
+
+    fn get_storage_entries(
+        &self,
+        contract_address: ContractAddress,
+    ) -> StateResult<Vec<(StorageKey, Felt)>> {
+        // Later diffs override earlier ones.
+        let mut storage_entries = BTreeMap::new();
+        self.for_each_state_diff(|state_diff| {
+            if let Some(storage_diff) = state_diff.storage_diffs.get(&contract_address) {
+                storage_entries.extend(storage_diff);
+            }
+        })?;
+        Ok(storage_entries.into_iter().filter(|(_, value)| *value != Felt::ZERO).collect())
+    }
+
+    fn get_declared_class_hashes(&self) -> StateResult<Vec<ClassHash>> {
+        let mut class_hashes = BTreeSet::new();
+        self.for_each_state_diff(|state_diff| {
+            class_hashes.extend(state_diff.declared_classes.keys());
+            class_hashes.extend(state_diff.deprecated_declared_classes);
+        })?;
+        Ok(class_hashes.into_iter().collect())
+    }
+}
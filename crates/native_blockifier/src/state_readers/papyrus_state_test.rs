@@ -1,20 +1,28 @@
 use std::collections::HashMap;
+use std::thread;
+use std::time::{Duration, Instant};
 
 use blockifier::abi::abi_utils::selector_from_name;
+use blockifier::blockifier::config::{BackgroundCompilationConfig, NativeCompilationConfig};
 use blockifier::execution::call_info::{CallExecution, Retdata};
+use blockifier::execution::contract_class::ContractClass;
 use blockifier::execution::entry_point::CallEntryPoint;
-use blockifier::retdata;
+use blockifier::execution::native::compilation_pool::NativeCompilationPool;
 use blockifier::state::cached_state::CachedState;
 use blockifier::state::global_cache::{GlobalContractCache, GLOBAL_CONTRACT_CACHE_SIZE_FOR_TEST};
-use blockifier::state::state_api::StateReader;
+use blockifier::state::state_api::{IterableStateReader, StateReader};
 use blockifier::test_utils::contracts::FeatureContract;
 use blockifier::test_utils::{trivial_external_entry_point_new, CairoVersion};
+use blockifier::{nonce, retdata};
+use cairo_lang_starknet_classes::casm_contract_class::CasmContractClass;
+use cairo_lang_starknet_classes::contract_class::ContractClass as SierraContractClass;
 use indexmap::IndexMap;
 use papyrus_storage::class::ClassStorageWriter;
+use papyrus_storage::compiled_class::CasmStorageWriter;
 use papyrus_storage::state::StateStorageWriter;
 use starknet_api::block::BlockNumber;
 use starknet_api::deprecated_contract_class::ContractClass as DeprecatedContractClass;
-use starknet_api::state::{StateDiff, StorageKey};
+use starknet_api::state::{ContractClass as SnApiContractClass, StateDiff, StorageKey};
 use starknet_api::transaction::Calldata;
 use starknet_api::{calldata, felt};
 use starknet_types_core::felt::Felt;
@@ -55,6 +63,8 @@ fn test_entry_point_with_papyrus_state() -> papyrus_storage::StorageResult<()> {
         storage_reader,
         block_number,
         GlobalContractCache::new(GLOBAL_CONTRACT_CACHE_SIZE_FOR_TEST),
+        NativeCompilationConfig::default(),
+        None,
     );
     let mut state = CachedState::from(papyrus_reader);
 
@@ -81,6 +91,215 @@ fn test_entry_point_with_papyrus_state() -> papyrus_storage::StorageResult<()> {
     Ok(())
 }
 
+#[test]
+fn test_iterable_papyrus_state() -> papyrus_storage::StorageResult<()> {
+    let ((storage_reader, mut storage_writer), _) = papyrus_storage::test_utils::get_test_storage();
+
+    let test_contract = FeatureContract::TestContract(CairoVersion::Cairo0);
+    let test_class_hash = test_contract.get_class_hash();
+    let test_class = test_contract.get_deprecated_contract_class();
+    let contract_address = test_contract.get_instance_address(0);
+    let (key_0, key_1) = (StorageKey::from(1_u128), StorageKey::from(2_u128));
+    let state_diff_0 = StateDiff {
+        deployed_contracts: IndexMap::from([(contract_address, test_class_hash)]),
+        storage_diffs: IndexMap::from([(
+            contract_address,
+            IndexMap::from([(key_0, felt!(1_u8)), (key_1, felt!(2_u8))]),
+        )]),
+        deprecated_declared_classes: IndexMap::from([(test_class_hash, test_class.clone())]),
+        ..Default::default()
+    };
+    // Overrides the first key, and zeroes the second one.
+    let state_diff_1 = StateDiff {
+        storage_diffs: IndexMap::from([(
+            contract_address,
+            IndexMap::from([(key_0, felt!(3_u8)), (key_1, Felt::ZERO)]),
+        )]),
+        ..Default::default()
+    };
+    storage_writer
+        .begin_rw_txn()?
+        .append_state_diff(BlockNumber(0), state_diff_0.into())?
+        .append_classes(BlockNumber(0), Default::default(), &[(test_class_hash, &test_class)])?
+        .append_state_diff(BlockNumber(1), state_diff_1.into())?
+        .append_classes(BlockNumber(1), Default::default(), &[])?
+        .commit()?;
+
+    let reader_at = |block_number: u64| {
+        PapyrusReader::new(
+            storage_reader.clone(),
+            BlockNumber(block_number),
+            GlobalContractCache::new(GLOBAL_CONTRACT_CACHE_SIZE_FOR_TEST),
+            NativeCompilationConfig::default(),
+            None,
+        )
+    };
+
+    let papyrus_reader = reader_at(2);
+    assert_eq!(papyrus_reader.get_contract_addresses().unwrap(), vec![contract_address]);
+    assert_eq!(
+        papyrus_reader.get_storage_entries(contract_address).unwrap(),
+        vec![(key_0, felt!(3_u8))]
+    );
+    assert_eq!(papyrus_reader.get_declared_class_hashes().unwrap(), vec![test_class_hash]);
+
+    // Enumeration is done at the pinned block.
+    assert_eq!(
+        reader_at(1).get_storage_entries(contract_address).unwrap(),
+        vec![(key_0, felt!(1_u8)), (key_1, felt!(2_u8))]
+    );
+    assert!(reader_at(0).get_contract_addresses().unwrap().is_empty());
+
+    // A missing state diff fails the enumeration, rather than being skipped.
+    assert!(reader_at(3).get_contract_addresses().is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_batch_reads_with_papyrus_state() -> papyrus_storage::StorageResult<()> {
+    let ((storage_reader, mut storage_writer), _) = papyrus_storage::test_utils::get_test_storage();
+
+    let contract_address =
+        FeatureContract::TestContract(CairoVersion::Cairo0).get_instance_address(0);
+    let (key_0, key_1) = (StorageKey::from(1_u128), StorageKey::from(2_u128));
+    let state_diff = StateDiff {
+        storage_diffs: IndexMap::from([(
+            contract_address,
+            IndexMap::from([(key_0, felt!(1_u8)), (key_1, felt!(2_u8))]),
+        )]),
+        nonces: IndexMap::from([(contract_address, nonce!(3_u8))]),
+        ..Default::default()
+    };
+    storage_writer
+        .begin_rw_txn()?
+        .append_state_diff(BlockNumber(0), state_diff.into())?
+        .append_classes(BlockNumber(0), Default::default(), &[])?
+        .commit()?;
+    let papyrus_reader = PapyrusReader::new(
+        storage_reader,
+        BlockNumber(1),
+        GlobalContractCache::new(GLOBAL_CONTRACT_CACHE_SIZE_FOR_TEST),
+        NativeCompilationConfig::default(),
+        None,
+    );
+
+    let storage_entries = [
+        (contract_address, key_1),
+        (contract_address, StorageKey::from(3_u128)),
+        (contract_address, key_0),
+    ];
+    assert_eq!(
+        papyrus_reader.get_storage_values(&storage_entries).unwrap(),
+        vec![felt!(2_u8), Felt::ZERO, felt!(1_u8)]
+    );
+    let other_address = FeatureContract::TestContract(CairoVersion::Cairo0).get_instance_address(1);
+    assert_eq!(
+        papyrus_reader.get_nonces(&[other_address, contract_address]).unwrap(),
+        vec![nonce!(0_u8), nonce!(3_u8)]
+    );
+    assert!(papyrus_reader.get_storage_values(&[]).unwrap().is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn test_native_compilation_with_papyrus_state() -> papyrus_storage::StorageResult<()> {
+    let ((storage_reader, mut storage_writer), _) = papyrus_storage::test_utils::get_test_storage();
+
+    // Classes appended through the Python storage are stored without their Sierra representation.
+    let contract_with_sierra = FeatureContract::SierraTestContract;
+    let contract_without_sierra = FeatureContract::SierraExecutionInfoV1Contract;
+    let declared_classes = IndexMap::from([
+        (
+            contract_with_sierra.get_class_hash(),
+            (
+                contract_with_sierra.get_compiled_class_hash(),
+                contract_with_sierra.get_sn_api_sierra_contract_class(),
+            ),
+        ),
+        (
+            contract_without_sierra.get_class_hash(),
+            (contract_without_sierra.get_compiled_class_hash(), SnApiContractClass::default()),
+        ),
+    ]);
+    let state_diff = StateDiff { declared_classes: declared_classes.clone(), ..Default::default() };
+
+    let block_number = BlockNumber::default();
+    let mut append_txn = storage_writer.begin_rw_txn()?;
+    for feature_contract in [contract_with_sierra, contract_without_sierra] {
+        let sierra_contract_class: SierraContractClass =
+            serde_json::from_str(&feature_contract.get_raw_class()).unwrap();
+        let casm_contract_class =
+            CasmContractClass::from_contract_class(sierra_contract_class, false, usize::MAX)
+                .unwrap();
+        append_txn =
+            append_txn.append_casm(&feature_contract.get_class_hash(), &casm_contract_class)?;
+    }
+    append_txn
+        .append_state_diff(block_number, state_diff.into())?
+        .append_classes(
+            block_number,
+            &declared_classes
+                .iter()
+                .map(|(class_hash, (_, contract_class))| (*class_hash, contract_class))
+                .collect::<Vec<_>>(),
+            &[],
+        )?
+        .commit()?;
+
+    let native_compilation_config = NativeCompilationConfig { enabled: true, ..Default::default() };
+    let papyrus_reader = PapyrusReader::new(
+        storage_reader.clone(),
+        BlockNumber(1),
+        GlobalContractCache::new(GLOBAL_CONTRACT_CACHE_SIZE_FOR_TEST),
+        native_compilation_config.clone(),
+        None,
+    );
+    assert!(matches!(
+        papyrus_reader.get_compiled_contract_class(contract_with_sierra.get_class_hash()).unwrap(),
+        ContractClass::V1Native(_)
+    ));
+    // Fallback to Casm.
+    assert!(matches!(
+        papyrus_reader
+            .get_compiled_contract_class(contract_without_sierra.get_class_hash())
+            .unwrap(),
+        ContractClass::V1(_)
+    ));
+
+    // Background compilation: the Casm class is served until compilation is done.
+    let global_contract_cache = GlobalContractCache::new(GLOBAL_CONTRACT_CACHE_SIZE_FOR_TEST);
+    let native_compilation_pool = NativeCompilationPool::new(
+        BackgroundCompilationConfig { n_workers: 1, queue_size: 1 },
+        native_compilation_config.opt_level,
+        None,
+        global_contract_cache.clone(),
+    );
+    let papyrus_reader = PapyrusReader::new(
+        storage_reader,
+        BlockNumber(1),
+        global_contract_cache,
+        native_compilation_config,
+        Some(native_compilation_pool),
+    );
+    let class_hash = contract_with_sierra.get_class_hash();
+    assert!(matches!(
+        papyrus_reader.get_compiled_contract_class(class_hash).unwrap(),
+        ContractClass::V1(_)
+    ));
+    let start = Instant::now();
+    while !matches!(
+        papyrus_reader.get_compiled_contract_class(class_hash).unwrap(),
+        ContractClass::V1Native(_)
+    ) {
+        assert!(start.elapsed() < Duration::from_secs(60), "Timed out waiting for compilation.");
+        thread::sleep(Duration::from_millis(10));
+    }
+
+    Ok(())
+}
+
 #[test]
 /// Edge case: adding a large contract to the global contract cache.
 fn global_contract_cache_update_large_contract() {